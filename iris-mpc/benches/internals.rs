@@ -241,7 +241,7 @@ fn criterion_benchmark_iriscodearray<T: Sharable>(c: &mut Criterion) {
     let b = vec![Aby3Share::<T>::zero(); IrisCode::IRIS_CODE_SIZE];
 
     {
-        let mut group = c.benchmark_group(&format!("filter_reduce_add_twice, {}bit", T::Share::K));
+        let mut group = c.benchmark_group(format!("filter_reduce_add_twice, {}bit", T::Share::K));
 
         group.bench_function("combined", |bench| {
             bench.iter(|| variant_combined(&a, &b, &mask));
@@ -301,7 +301,7 @@ fn criterion_benchmark_iriscodearray<T: Sharable>(c: &mut Criterion) {
     let a_mac = vec![Aby3Share::<T>::zero(); IrisCode::IRIS_CODE_SIZE];
     let b_mac = vec![Aby3Share::<T>::zero(); IrisCode::IRIS_CODE_SIZE];
 
-    let mut group2 = c.benchmark_group(&format!("filter_reduce_add_4times, {}bit", T::Share::K));
+    let mut group2 = c.benchmark_group(format!("filter_reduce_add_4times, {}bit", T::Share::K));
     group2.bench_function("combined", |bench| {
         bench.iter(|| variant_4_combined(&a, &a_mac, &b, &b_mac, &mask));
     });
@@ -1,4 +1,4 @@
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
 use iris_mpc::prelude::{
     Aby3, Aby3Share, IrisProtocol, MpcTrait, PartyTestNetwork, Sharable, TestNetwork3p,
 };
@@ -11,6 +11,7 @@ use rand_chacha::ChaCha12Rng;
 use std::ops::Mul;
 use tokio::runtime;
 const CHUNK_SIZE: usize = 128;
+const THREADS: [usize; 3] = [1, 2, 4];
 
 async fn iris_aby3_task<T: Sharable>(
     net: PartyTestNetwork,
@@ -18,6 +19,7 @@ async fn iris_aby3_task<T: Sharable>(
     mask: IrisCodeArray,
     shared_db: Vec<Vec<Aby3Share<T>>>,
     masks: Vec<IrisCodeArray>,
+    threads: usize,
 ) -> bool
 where
     Standard: Distribution<T::Share>,
@@ -25,7 +27,8 @@ where
     Aby3Share<T>: Mul<T::Share, Output = Aby3Share<T>>,
     <T as std::convert::TryFrom<usize>>::Error: std::fmt::Debug,
 {
-    let protocol = Aby3::<PartyTestNetwork>::new(net);
+    let mut protocol = Aby3::<PartyTestNetwork>::new(net);
+    protocol.set_threads(threads).unwrap();
     let mut iris = IrisProtocol::new(protocol).unwrap();
 
     iris.preprocessing().await.unwrap();
@@ -42,7 +45,7 @@ where
 fn iris_aby3<T: Sharable, R: Rng>(
     c: &mut Criterion,
     shared_code: &[Vec<Vec<Aby3Share<T>>>],
-    masks: &Vec<IrisCodeArray>,
+    masks: &[IrisCodeArray],
     rng: &mut R,
 ) where
     Standard: Distribution<T::Share>,
@@ -76,34 +79,58 @@ fn iris_aby3<T: Sharable, R: Rng>(
         code_b.push(shares[1].to_owned());
         code_c.push(shares[2].to_owned());
     }
-    let shares = vec![code_a, code_b, code_c];
+    let shares = [code_a, code_b, code_c];
     let mask = iris.mask;
 
-    c.bench_function(
-        format!("Iris_matcher aby3 (DB: {db_size}, 3 parties)").as_str(),
-        move |bench| {
-            bench.to_async(&rt).iter(|| async {
-                let network = TestNetwork3p::new();
-                let net = network.get_party_networks();
-
-                let mut parties = Vec::with_capacity(3);
-                for (i, n) in net.into_iter().enumerate() {
-                    parties.push(tokio::spawn(iris_aby3_task(
-                        black_box(n),
-                        black_box(shares[i].to_owned()),
-                        black_box(mask),
-                        black_box(shared_code[i].to_owned()),
-                        black_box(masks.to_owned()),
-                    )));
-                }
-
-                for party in parties {
-                    party.await.unwrap();
-                    black_box(())
-                }
-            });
-        },
-    );
+    // Scaling with the number of threads for the local computation
+    let mut group = c.benchmark_group(format!(
+        "Iris_matcher aby3 (DB: {db_size}, 3 parties, threads)"
+    ));
+    for threads in THREADS {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(threads),
+            &threads,
+            |bench, threads| {
+                bench
+                    .to_async(&rt)
+                    .iter(|| iris_aby3_parties(&shares, mask, shared_code, masks, *threads));
+            },
+        );
+    }
+    group.finish();
+}
+
+async fn iris_aby3_parties<T: Sharable>(
+    shares: &[Vec<Aby3Share<T>>],
+    mask: IrisCodeArray,
+    shared_code: &[Vec<Vec<Aby3Share<T>>>],
+    masks: &[IrisCodeArray],
+    threads: usize,
+) where
+    Standard: Distribution<T::Share>,
+    Aby3Share<T>: Mul<Output = Aby3Share<T>>,
+    Aby3Share<T>: Mul<T::Share, Output = Aby3Share<T>>,
+    <T as std::convert::TryFrom<usize>>::Error: std::fmt::Debug,
+{
+    let network = TestNetwork3p::new();
+    let net = network.get_party_networks();
+
+    let mut parties = Vec::with_capacity(3);
+    for (i, n) in net.into_iter().enumerate() {
+        parties.push(tokio::spawn(iris_aby3_task(
+            black_box(n),
+            black_box(shares[i].to_owned()),
+            black_box(mask),
+            black_box(shared_code[i].to_owned()),
+            black_box(masks.to_owned()),
+            threads,
+        )));
+    }
+
+    for party in parties {
+        party.await.unwrap();
+        black_box(())
+    }
 }
 
 fn create_db<R: Rng>(num_items: usize, rng: &mut R) -> Vec<IrisCode> {
@@ -99,7 +99,7 @@ fn iris_aby3_mal<T: Sharable, R: Rng>(
         code_b.push(shares[1].to_owned());
         code_c.push(shares[2].to_owned());
     }
-    let shares = vec![code_a, code_b, code_c];
+    let shares = [code_a, code_b, code_c];
     let mask = iris.mask;
 
     c.bench_function(
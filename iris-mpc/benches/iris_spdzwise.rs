@@ -1,7 +1,7 @@
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
 use iris_mpc::prelude::{
-    Aby3, Aby3Share, IrisSpdzWise, MpcTrait, PartyTestNetwork, Sharable, SpdzWise, SpdzWiseShare,
-    SpdzWiseVecShare, TestNetwork3p, VecShareTrait,
+    Aby3, Aby3Share, BinaryVerification, IrisSpdzWise, MpcTrait, PartyTestNetwork, Sharable,
+    SpdzWise, SpdzWiseShare, SpdzWiseVecShare, TestNetwork3p, VecShareTrait,
 };
 use plain_reference::{IrisCode, IrisCodeArray};
 use rand::{
@@ -12,12 +12,13 @@ use rand_chacha::ChaCha12Rng;
 use std::ops::Mul;
 use tokio::runtime;
 const CHUNK_SIZE: usize = 128;
+const THREADS: [usize; 3] = [1, 2, 4];
 
 #[allow(type_alias_bounds)]
 pub(crate) type UShare<T: Sharable> = <T::VerificationShare as Sharable>::Share;
 
 async fn spdzwise_task<T: Sharable>(
-    net: PartyTestNetwork,
+    protocol: SpdzWise<PartyTestNetwork, T::VerificationShare>,
     mac_key: SpdzWiseShare<T::VerificationShare>,
     code: SpdzWiseVecShare<T::VerificationShare>,
     mask: IrisCodeArray,
@@ -31,7 +32,6 @@ where
     Aby3Share<T>: Mul<T::Share, Output = Aby3Share<T>>,
     <T as std::convert::TryFrom<usize>>::Error: std::fmt::Debug,
 {
-    let protocol = SpdzWise::<PartyTestNetwork, T::VerificationShare>::new(net);
     let mut iris = IrisSpdzWise::<T, _>::new(protocol).unwrap();
 
     iris.preprocessing().await.unwrap();
@@ -86,7 +86,7 @@ fn iris_spdzwise<T: Sharable, R: Rng>(
         code_b.push(shares[1].to_owned());
         code_c.push(shares[2].to_owned());
     }
-    let shares = vec![code_a, code_b, code_c];
+    let shares = [code_a, code_b, code_c];
     let mask = iris.mask;
 
     // We have to share the mac key as well and give it to the parties
@@ -99,32 +99,52 @@ fn iris_spdzwise<T: Sharable, R: Rng>(
     .map(|m| SpdzWiseShare::new(Aby3Share::default(), m))
     .collect::<Vec<_>>();
 
-    c.bench_function(
-        format!("Iris_matcher spdzwise (DB: {db_size}, 3 parties)").as_str(),
-        move |bench| {
-            bench.to_async(&rt).iter(|| async {
-                let network = TestNetwork3p::new();
-                let net = network.get_party_networks();
-
-                let mut parties = Vec::with_capacity(3);
-                for (i, n) in net.into_iter().enumerate() {
-                    parties.push(tokio::spawn(spdzwise_task::<T>(
-                        black_box(n),
-                        black_box(mac_keys[i].to_owned()),
-                        black_box(shares[i].to_owned()),
-                        black_box(mask),
-                        black_box(shared_code[i].to_owned()),
-                        black_box(masks.to_owned()),
-                    )));
-                }
-
-                for party in parties {
-                    party.await.unwrap();
-                    black_box(())
-                }
-            });
-        },
-    );
+    for (binary_verification, name) in [
+        (BinaryVerification::CutAndChoose, "cut-and-choose"),
+        (BinaryVerification::Gf2p64Mac, "GF(2^64) MACs"),
+    ] {
+        // Scaling with the number of threads for the local computation
+        let mut group = c.benchmark_group(format!(
+            "Iris_matcher spdzwise {name} (DB: {db_size}, 3 parties, threads)"
+        ));
+        for threads in THREADS {
+            group.bench_with_input(
+                BenchmarkId::from_parameter(threads),
+                &threads,
+                |bench, threads| {
+                    bench.to_async(&rt).iter(|| async {
+                        let network = TestNetwork3p::new();
+                        let net = network.get_party_networks();
+
+                        let mut parties = Vec::with_capacity(3);
+                        for (i, n) in net.into_iter().enumerate() {
+                            let mut protocol = SpdzWise::<
+                                PartyTestNetwork,
+                                T::VerificationShare,
+                            >::with_binary_verification(
+                                n, binary_verification
+                            );
+                            protocol.set_threads(*threads).unwrap();
+                            parties.push(tokio::spawn(spdzwise_task::<T>(
+                                black_box(protocol),
+                                black_box(mac_keys[i].to_owned()),
+                                black_box(shares[i].to_owned()),
+                                black_box(mask),
+                                black_box(shared_code[i].to_owned()),
+                                black_box(masks.to_owned()),
+                            )));
+                        }
+
+                        for party in parties {
+                            party.await.unwrap();
+                            black_box(())
+                        }
+                    });
+                },
+            );
+        }
+        group.finish();
+    }
 }
 
 fn create_db<R: Rng>(num_items: usize, rng: &mut R) -> Vec<IrisCode> {
@@ -0,0 +1,218 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use iris_mpc::prelude::{
+    Aby3, Aby3Share, BinaryVerification, Bit, MalAby3, MpcTrait, MsbVariant, PartyTestNetwork,
+    Sharable, SpdzWise, SpdzWiseShare, TestNetwork3p,
+};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+use std::time::{Duration, Instant};
+use tokio::runtime;
+
+type T = u16;
+type V = <T as Sharable>::VerificationShare;
+
+// Only the online phase (MSB extraction and verification) is measured, the edaBits are
+// precomputed beforehand.
+async fn mal_aby3_task(
+    net: PartyTestNetwork,
+    shares: Vec<Aby3Share<T>>,
+    variant: MsbVariant,
+) -> Duration {
+    let mut protocol = MalAby3::<PartyTestNetwork>::new(net);
+    MpcTrait::<T, Aby3Share<T>, Aby3Share<Bit>>::preprocess(&mut protocol)
+        .await
+        .unwrap();
+    protocol.set_msb_variant(variant).unwrap();
+    if variant == MsbVariant::EdaBits {
+        protocol
+            .precompute_edabits::<T>(shares.len())
+            .await
+            .unwrap();
+    }
+
+    let start = Instant::now();
+    let msbs = protocol.get_msb_many(shares).await.unwrap();
+    MpcTrait::<T, Aby3Share<T>, Aby3Share<Bit>>::verify(&mut protocol)
+        .await
+        .unwrap();
+    let elapsed = start.elapsed();
+    black_box(msbs);
+
+    MpcTrait::<T, Aby3Share<T>, Aby3Share<Bit>>::finish(protocol)
+        .await
+        .unwrap();
+    elapsed
+}
+
+async fn spdzwise_task(
+    net: PartyTestNetwork,
+    mac_key: SpdzWiseShare<V>,
+    shares: Vec<SpdzWiseShare<V>>,
+    variant: MsbVariant,
+) -> Duration {
+    // GF(2^64) MACs, such that no AND triples have to be precomputed
+    let mut protocol = SpdzWise::<PartyTestNetwork, V>::with_binary_verification(
+        net,
+        BinaryVerification::Gf2p64Mac,
+    );
+    MpcTrait::<T, SpdzWiseShare<V>, Aby3Share<Bit>>::preprocess(&mut protocol)
+        .await
+        .unwrap();
+    MpcTrait::<T, SpdzWiseShare<V>, Aby3Share<Bit>>::set_mac_key(&mut protocol, mac_key);
+    protocol.set_msb_variant(variant).unwrap();
+    if variant == MsbVariant::EdaBits {
+        protocol
+            .precompute_edabits::<T>(shares.len())
+            .await
+            .unwrap();
+    }
+
+    let start = Instant::now();
+    let msbs = MpcTrait::<T, SpdzWiseShare<V>, Aby3Share<Bit>>::get_msb_many(&mut protocol, shares)
+        .await
+        .unwrap();
+    MpcTrait::<T, SpdzWiseShare<V>, Aby3Share<Bit>>::verify(&mut protocol)
+        .await
+        .unwrap();
+    let elapsed = start.elapsed();
+    black_box(msbs);
+
+    MpcTrait::<T, SpdzWiseShare<V>, Aby3Share<Bit>>::finish(protocol)
+        .await
+        .unwrap();
+    elapsed
+}
+
+fn msb_mal_aby3<R: Rng>(c: &mut Criterion, num: usize, rng: &mut R) {
+    let rt = runtime::Builder::new_multi_thread()
+        .worker_threads(3)
+        .build()
+        .unwrap();
+
+    let mut shares = (0..3).map(|_| Vec::with_capacity(num)).collect::<Vec<_>>();
+    for _ in 0..num {
+        let share = MalAby3::<PartyTestNetwork>::share(rng.gen::<T>(), V::default(), rng);
+        for (s, share_) in shares.iter_mut().zip(share) {
+            s.push(share_);
+        }
+    }
+
+    for (variant, name) in [
+        (MsbVariant::Adder, "adder"),
+        (MsbVariant::EdaBits, "edaBits"),
+    ] {
+        c.bench_function(
+            format!("MSB aby3 malicious {name} (n: {num}, 3 parties)").as_str(),
+            |bench| {
+                bench.to_async(&rt).iter_custom(|iters| {
+                    let shares = shares.to_owned();
+                    async move {
+                        let mut total = Duration::ZERO;
+                        for _ in 0..iters {
+                            let network = TestNetwork3p::new();
+                            let net = network.get_party_networks();
+
+                            let mut parties = Vec::with_capacity(3);
+                            for (i, n) in net.into_iter().enumerate() {
+                                parties.push(tokio::spawn(mal_aby3_task(
+                                    black_box(n),
+                                    black_box(shares[i].to_owned()),
+                                    black_box(variant),
+                                )));
+                            }
+
+                            let mut elapsed = Duration::ZERO;
+                            for party in parties {
+                                elapsed = elapsed.max(party.await.unwrap());
+                            }
+                            total += elapsed;
+                        }
+                        total
+                    }
+                });
+            },
+        );
+    }
+}
+
+fn msb_spdzwise<R: Rng>(c: &mut Criterion, num: usize, rng: &mut R) {
+    let rt = runtime::Builder::new_multi_thread()
+        .worker_threads(3)
+        .build()
+        .unwrap();
+
+    let mac_key = rng.gen::<V>();
+    let mut shares = (0..3).map(|_| Vec::with_capacity(num)).collect::<Vec<_>>();
+    for _ in 0..num {
+        let share = SpdzWise::<PartyTestNetwork, V>::share(rng.gen::<T>(), mac_key, rng);
+        for (s, share_) in shares.iter_mut().zip(share) {
+            s.push(share_);
+        }
+    }
+
+    // We have to share the mac key as well and give it to the parties
+    let mac_keys = Aby3::<PartyTestNetwork>::share(
+        mac_key,
+        <V as Sharable>::VerificationShare::default(),
+        rng,
+    )
+    .into_iter()
+    .map(|m| SpdzWiseShare::new(Aby3Share::default(), m))
+    .collect::<Vec<_>>();
+
+    for (variant, name) in [
+        (MsbVariant::Adder, "adder"),
+        (MsbVariant::EdaBits, "edaBits"),
+    ] {
+        c.bench_function(
+            format!("MSB spdzwise {name} (n: {num}, 3 parties)").as_str(),
+            |bench| {
+                bench.to_async(&rt).iter_custom(|iters| {
+                    let shares = shares.to_owned();
+                    let mac_keys = mac_keys.to_owned();
+                    async move {
+                        let mut total = Duration::ZERO;
+                        for _ in 0..iters {
+                            let network = TestNetwork3p::new();
+                            let net = network.get_party_networks();
+
+                            let mut parties = Vec::with_capacity(3);
+                            for (i, n) in net.into_iter().enumerate() {
+                                parties.push(tokio::spawn(spdzwise_task(
+                                    black_box(n),
+                                    black_box(mac_keys[i].to_owned()),
+                                    black_box(shares[i].to_owned()),
+                                    black_box(variant),
+                                )));
+                            }
+
+                            let mut elapsed = Duration::ZERO;
+                            for party in parties {
+                                elapsed = elapsed.max(party.await.unwrap());
+                            }
+                            total += elapsed;
+                        }
+                        total
+                    }
+                });
+            },
+        );
+    }
+}
+
+fn criterion_benchmark_msb(c: &mut Criterion) {
+    let mut rng = ChaCha12Rng::from_entropy();
+    let sizes = [1000];
+
+    for s in sizes {
+        msb_mal_aby3(c, s, &mut rng);
+        msb_spdzwise(c, s, &mut rng);
+    }
+}
+
+criterion_group!(
+    name = benches;
+    config = Criterion::default();
+    targets = criterion_benchmark_msb
+);
+criterion_main!(benches);
@@ -1,6 +1,11 @@
 use clap::Parser;
 use color_eyre::{eyre::Context, Result};
-use iris_mpc::prelude::{Aby3, Aby3Network, Aby3Share, Error, IrisAby3, MpcTrait, Sharable};
+use futures::stream;
+use iris_mpc::prelude::{
+    aby3_party_db_chunks, party_db_path, party_seeds_path, read_aby3_party_db, read_compressed_db,
+    read_party_seeds, read_truth_code, truth_db_path, Aby3, Aby3Network, Aby3Share, DbRoots, Error,
+    IrisAby3, MpcTrait, Sharable, ShareFile,
+};
 use mpc_net::config::{NetworkConfig, NetworkParty};
 use plain_reference::{IrisCode, IrisCodeArray};
 use rand::{
@@ -59,6 +64,28 @@ struct Args {
     /// Number of protocol runs
     #[arg(short, long, default_value = "1")]
     num_runs: usize,
+
+    /// Number of concurrent sessions the chunks are distributed over
+    #[arg(long, default_value = "1")]
+    sessions: usize,
+
+    /// Set to true if the database was created with compressed shares. Reads the database and the
+    /// seed file of this party
+    #[arg(long, default_value = "false")]
+    compressed: bool,
+
+    /// Read the shares of this party from a share file instead of the database
+    #[arg(long, value_name = "FILE")]
+    share_file: Option<PathBuf>,
+
+    /// Read the shares of this party from its own database, created with --per-party
+    #[arg(long, default_value = "false")]
+    per_party: bool,
+
+    /// Stream the shares chunk by chunk from the share file or the database of this party,
+    /// instead of reading the whole database before matching
+    #[arg(long, default_value = "false")]
+    stream: bool,
 }
 
 fn print_stats<T: Sharable>(iris: &IrisAby3<T, Aby3<Aby3Network>>) -> Result<()>
@@ -96,9 +123,10 @@ async fn setup_network(args: Args, port_offset: u16) -> Result<Aby3Network> {
     Ok(network)
 }
 
+// The records are either read into memory or borrowed from a mapped share file
 #[derive(Default)]
-struct SharedDB<T: Sharable> {
-    shares: Vec<Vec<Aby3Share<T>>>,
+struct SharedDB<D> {
+    shares: Vec<D>,
     masks: Vec<IrisCodeArray>,
 }
 
@@ -114,7 +142,43 @@ fn open_database(database_file: &PathBuf) -> Result<Connection> {
     Ok(conn)
 }
 
-fn read_db<T: Sharable>(args: Args) -> Result<SharedDB<T>> {
+fn open_share_file<T: Sharable>(path: &PathBuf, party: usize) -> Result<ShareFile<Aby3Share<T>>> {
+    let file = ShareFile::open(path)?;
+    if file.get_party() != party {
+        Err(Error::IdError(file.get_party()))?;
+    }
+    Ok(file)
+}
+
+fn read_compressed<T: Sharable>(args: Args) -> Result<SharedDB<Vec<Aby3Share<T>>>>
+where
+    Standard: Distribution<T::Share>,
+{
+    let seeds_conn = open_database(&party_seeds_path(&args.database, args.party))?;
+    let seeds = read_party_seeds(&seeds_conn, args.party)?;
+    let conn = open_database(&party_db_path(&args.database, args.party))?;
+    let db = read_compressed_db(&conn, &seeds)?;
+    Ok(SharedDB {
+        shares: db.shares,
+        masks: db.masks,
+    })
+}
+
+fn read_db<T: Sharable>(args: Args) -> Result<SharedDB<Vec<Aby3Share<T>>>>
+where
+    Standard: Distribution<T::Share>,
+{
+    if args.per_party {
+        let conn = open_database(&party_db_path(&args.database, args.party))?;
+        let db = read_aby3_party_db(&conn, args.party)?;
+        return Ok(SharedDB {
+            shares: db.shares,
+            masks: db.masks,
+        });
+    }
+    if args.compressed {
+        return read_compressed(args);
+    }
     let conn = open_database(&args.database)?;
 
     // read the codes from the database using rusqlite and iterate over them
@@ -125,7 +189,7 @@ fn read_db<T: Sharable>(args: Args) -> Result<SharedDB<T>> {
         i => Err(Error::IdError(i))?,
     };
 
-    let mut res = SharedDB::<T>::default();
+    let mut res = SharedDB::default();
     let mut rows = stmt.query([])?;
 
     while let Some(row) = rows.next()? {
@@ -150,32 +214,78 @@ fn read_db<T: Sharable>(args: Args) -> Result<SharedDB<T>> {
     Ok(res)
 }
 
+async fn iris_in_db_stream<T: Sharable>(
+    iris: &mut IrisAby3<T, Aby3<Aby3Network>>,
+    args: &Args,
+    shares: &SharedIris<T>,
+) -> Result<bool>
+where
+    Aby3Share<T>: Mul<T::Share, Output = Aby3Share<T>>,
+    <T as std::convert::TryFrom<usize>>::Error: std::fmt::Debug,
+    Standard: Distribution<T::Share>,
+{
+    // Enough codes per read to keep all sessions busy
+    let read_size = args.chunk_size * args.sessions.max(1);
+    if let Some(path) = &args.share_file {
+        let file = open_share_file::<T>(path, args.party)?;
+        let db = stream::iter(file.chunks(read_size).map(Ok));
+        let res = iris
+            .iris_in_db_stream(&shares.shares, &shares.mask, db, args.chunk_size)
+            .await?;
+        return Ok(res);
+    }
+    if !args.per_party {
+        Err(Error::ValueError(
+            "Streaming requires --share-file or --per-party".to_owned(),
+        ))?;
+    }
+    let conn = open_database(&party_db_path(&args.database, args.party))?;
+    let db = stream::iter(aby3_party_db_chunks(&conn, args.party, read_size)?);
+    let res = iris
+        .iris_in_db_stream(&shares.shares, &shares.mask, db, args.chunk_size)
+        .await?;
+    Ok(res)
+}
+
+// Computes the roots over the same database which iris_in_db_stream matches
+fn stream_db_roots<T: Sharable>(args: &Args) -> Result<DbRoots> {
+    if let Some(path) = &args.share_file {
+        let file = open_share_file::<T>(path, args.party)?;
+        let roots =
+            DbRoots::from_chunks::<Aby3Share<T>, _, _>(file.chunks(args.chunk_size).map(Ok))?;
+        return Ok(roots);
+    }
+    let conn = open_database(&party_db_path(&args.database, args.party))?;
+    let chunks = aby3_party_db_chunks::<T>(&conn, args.party, args.chunk_size)?;
+    Ok(DbRoots::from_chunks::<Aby3Share<T>, _, _>(chunks)?)
+}
+
 fn get_iris_share<T: Sharable>(args: Args) -> Result<SharedIris<T>>
 where
     Aby3Share<T>: Mul<T::Share, Output = Aby3Share<T>>,
     Standard: Distribution<T::Share>,
 {
     let mut rng = ChaCha12Rng::seed_from_u64(args.iris_seed);
-    let iris = if args.should_match {
+    let iris = if args.should_match && (args.per_party || args.compressed) {
+        let conn = open_database(&truth_db_path(&args.database))?;
+        read_truth_code(&conn, 1)?.get_similar_iris(&mut rng)
+    } else if args.should_match {
         let conn = open_database(&args.database)?;
+        let query = "SELECT code, mask from iris_codes WHERE id = 1;";
         // read the codes from the database using rusqlite and iterate over them
-        conn.query_row(
-            "SELECT code, mask from iris_codes WHERE id = 1;",
-            [],
-            |row| {
-                let mut res = IrisCode::default();
-                res.code
-                    .as_raw_mut_slice()
-                    .copy_from_slice(&row.get::<_, Vec<u8>>(0)?);
-                res.mask
-                    .as_raw_mut_slice()
-                    .copy_from_slice(&row.get::<_, Vec<u8>>(1)?);
-
-                res = res.get_similar_iris(&mut rng);
-
-                Ok(res)
-            },
-        )?
+        conn.query_row(query, [], |row| {
+            let mut res = IrisCode::default();
+            res.code
+                .as_raw_mut_slice()
+                .copy_from_slice(&row.get::<_, Vec<u8>>(0)?);
+            res.mask
+                .as_raw_mut_slice()
+                .copy_from_slice(&row.get::<_, Vec<u8>>(1)?);
+
+            res = res.get_similar_iris(&mut rng);
+
+            Ok(res)
+        })?
     } else {
         IrisCode::random_rng(&mut rng)
     };
@@ -206,11 +316,30 @@ async fn main() -> Result<()> {
     let args = Args::parse();
     let id = args.party;
 
-    println0!(id, "Reading database:");
-    let start = Instant::now();
-    let db = read_db::<u16>(args.to_owned())?;
-    let duration = start.elapsed();
-    println0!(id, "...done, took {} ms\n", duration.as_millis());
+    // The shares of a share file are matched directly from the mapped file
+    let share_file = match &args.share_file {
+        Some(path) if !args.stream => {
+            println0!(id, "Mapping share file:");
+            let start = Instant::now();
+            let file = open_share_file::<u16>(path, args.party)?;
+            let duration = start.elapsed();
+            println0!(id, "...done, took {} ms\n", duration.as_millis());
+            Some(file)
+        }
+        _ => None,
+    };
+    let file_db = share_file.as_ref().map(|file| file.records());
+
+    let db = if args.stream || file_db.is_some() {
+        SharedDB::default()
+    } else {
+        println0!(id, "Reading database:");
+        let start = Instant::now();
+        let db = read_db::<u16>(args.to_owned())?;
+        let duration = start.elapsed();
+        println0!(id, "...done, took {} ms\n", duration.as_millis());
+        db
+    };
 
     println0!(id, "Get shares:");
     let start = Instant::now();
@@ -218,6 +347,26 @@ async fn main() -> Result<()> {
     let duration = start.elapsed();
     println0!(id, "...done, took {} ms\n", duration.as_millis());
 
+    // The roots are computed from the shares which are matched. They are cross-checked with the
+    // other parties in the preprocessing, such that all parties match against the same version of
+    // the database, and again after matching, such that no party can match other shares
+    let roots = if args.per_party {
+        println0!(id, "Computing database roots:");
+        let start = Instant::now();
+        let roots = if args.stream {
+            stream_db_roots::<u16>(&args)?
+        } else if let Some(file_db) = &file_db {
+            DbRoots::from_shares::<Aby3Share<u16>, _>(&file_db.shares, &file_db.masks)?
+        } else {
+            DbRoots::from_shares::<Aby3Share<u16>, _>(&db.shares, &db.masks)?
+        };
+        let duration = start.elapsed();
+        println0!(id, "...done, took {} ms\n", duration.as_millis());
+        Some(roots)
+    } else {
+        None
+    };
+
     for i in 0..args.num_runs {
         println0!(id, "Run {}:", i);
         println0!(id, "Setting up network:");
@@ -230,6 +379,10 @@ async fn main() -> Result<()> {
         let start = Instant::now();
         let protocol = Aby3::new(network);
         let mut iris = IrisAby3::<u16, _>::new(protocol)?;
+        if let Some(roots) = &roots {
+            iris.set_db_roots(roots.to_owned());
+        }
+        iris.set_sessions(args.sessions)?;
         let duration = start.elapsed();
         println0!(id, "...done, took {} ms\n", duration.as_millis());
         print_stats(&iris)?;
@@ -243,15 +396,27 @@ async fn main() -> Result<()> {
 
         println0!(id, "\nMPC matching:");
         let start = Instant::now();
-        let res = iris
-            .iris_in_db(
+        let res = if args.stream {
+            iris_in_db_stream(&mut iris, &args, &shares).await?
+        } else if let Some(file_db) = &file_db {
+            iris.iris_in_db(
+                &shares.shares,
+                &file_db.shares,
+                &shares.mask,
+                &file_db.masks,
+                args.chunk_size,
+            )
+            .await?
+        } else {
+            iris.iris_in_db(
                 &shares.shares,
                 &db.shares,
                 &shares.mask,
                 &db.masks,
                 args.chunk_size,
             )
-            .await?;
+            .await?
+        };
         let duration = start.elapsed();
         println0!(id, "...done, took {} ms", duration.as_millis());
         println0!(id, "Result is {res}\n");
@@ -1,6 +1,10 @@
 use clap::Parser;
 use color_eyre::{eyre::Context, Result};
-use iris_mpc::prelude::{Aby3Network, Aby3Share, Error, IrisAby3, MalAby3, MpcTrait, Sharable};
+use futures::stream;
+use iris_mpc::prelude::{
+    aby3_party_db_chunks, party_db_path, read_aby3_party_db, read_truth_code, truth_db_path,
+    Aby3Network, Aby3Share, DbRoots, Error, IrisAby3, MalAby3, MpcTrait, Sharable,
+};
 use mpc_net::config::{NetworkConfig, NetworkParty};
 use plain_reference::{IrisCode, IrisCodeArray};
 use rand::{
@@ -59,6 +63,15 @@ struct Args {
     /// Size of the chunks that are handled at once to batch networking
     #[arg(short, long, default_value = "1024")]
     chunk_size: usize,
+
+    /// Read the shares of this party from its own database, created with --per-party
+    #[arg(long, default_value = "false")]
+    per_party: bool,
+
+    /// Stream the shares chunk by chunk from the database of this party, instead of reading the
+    /// whole database before matching. Requires --per-party
+    #[arg(long, default_value = "false", requires = "per_party")]
+    stream: bool,
 }
 
 fn print_stats<T: Sharable>(iris: &IrisAby3<T, MalAby3<Aby3Network>>) -> Result<()>
@@ -120,6 +133,14 @@ fn open_database(database_file: &PathBuf) -> Result<Connection> {
 }
 
 fn read_db<T: Sharable>(args: Args) -> Result<SharedDB<T>> {
+    if args.per_party {
+        let conn = open_database(&party_db_path(&args.database, args.party))?;
+        let db = read_aby3_party_db(&conn, args.party)?;
+        return Ok(SharedDB {
+            shares: db.shares,
+            masks: db.masks,
+        });
+    }
     let conn = open_database(&args.database)?;
 
     // read the codes from the database using rusqlite and iterate over them
@@ -171,7 +192,10 @@ where
         for<'a> MulAssign<&'a <T::VerificationShare as Sharable>::Share>,
 {
     let mut rng = ChaCha12Rng::seed_from_u64(args.iris_seed);
-    let iris = if args.should_match {
+    let iris = if args.should_match && args.per_party {
+        let conn = open_database(&truth_db_path(&args.database))?;
+        read_truth_code(&conn, 1)?.get_similar_iris(&mut rng)
+    } else if args.should_match {
         let conn = open_database(&args.database)?;
         // read the codes from the database using rusqlite and iterate over them
         conn.query_row(
@@ -221,11 +245,16 @@ async fn main() -> Result<()> {
     let args = Args::parse();
     let id = args.party;
 
-    println0!(id, "Reading database:");
-    let start = Instant::now();
-    let db = read_db::<u16>(args.to_owned())?;
-    let duration = start.elapsed();
-    println0!(id, "...done, took {} ms\n", duration.as_millis());
+    let db = if args.stream {
+        SharedDB::default()
+    } else {
+        println0!(id, "Reading database:");
+        let start = Instant::now();
+        let db = read_db::<u16>(args.to_owned())?;
+        let duration = start.elapsed();
+        println0!(id, "...done, took {} ms\n", duration.as_millis());
+        db
+    };
 
     println0!(id, "Get shares:");
     let start = Instant::now();
@@ -233,6 +262,26 @@ async fn main() -> Result<()> {
     let duration = start.elapsed();
     println0!(id, "...done, took {} ms\n", duration.as_millis());
 
+    // The roots are computed from the shares which are matched. They are cross-checked with the
+    // other parties in the preprocessing, such that all parties match against the same version of
+    // the database, and again after matching, such that no party can match other shares
+    let roots = if args.per_party {
+        println0!(id, "Computing database roots:");
+        let start = Instant::now();
+        let roots = if args.stream {
+            let conn = open_database(&party_db_path(&args.database, args.party))?;
+            let chunks = aby3_party_db_chunks::<u16>(&conn, args.party, args.chunk_size)?;
+            DbRoots::from_chunks::<Aby3Share<u16>, _, _>(chunks)?
+        } else {
+            DbRoots::from_shares::<Aby3Share<u16>, _>(&db.shares, &db.masks)?
+        };
+        let duration = start.elapsed();
+        println0!(id, "...done, took {} ms\n", duration.as_millis());
+        Some(roots)
+    } else {
+        None
+    };
+
     println0!(id, "Setting up network:");
     let start = Instant::now();
     let network = setup_network(args.to_owned()).await?;
@@ -243,6 +292,9 @@ async fn main() -> Result<()> {
     let start = Instant::now();
     let protocol = MalAby3::new(network);
     let mut iris = IrisAby3::<u16, _>::new(protocol)?;
+    if let Some(roots) = &roots {
+        iris.set_db_roots(roots.to_owned());
+    }
     let duration = start.elapsed();
     println0!(id, "...done, took {} ms\n", duration.as_millis());
     print_stats(&iris)?;
@@ -256,15 +308,25 @@ async fn main() -> Result<()> {
 
     println0!(id, "\nMPC matching:");
     let start = Instant::now();
-    let res = iris
-        .iris_in_db(
+    let res = if args.stream {
+        let conn = open_database(&party_db_path(&args.database, args.party))?;
+        let db = stream::iter(aby3_party_db_chunks::<u16>(
+            &conn,
+            args.party,
+            args.chunk_size,
+        )?);
+        iris.iris_in_db_stream(&shares.shares, &shares.mask, db, args.chunk_size)
+            .await?
+    } else {
+        iris.iris_in_db(
             &shares.shares,
             &db.shares,
             &shares.mask,
             &db.masks,
             args.chunk_size,
         )
-        .await?;
+        .await?
+    };
     let duration = start.elapsed();
     println0!(id, "...done, took {} ms", duration.as_millis());
     println0!(id, "Result is {res}\n");
@@ -0,0 +1,284 @@
+use clap::Parser;
+use color_eyre::{eyre::Context, Result};
+use iris_mpc::prelude::{
+    combine_shard_results, match_shard, party_db_len, party_db_path, read_aby3_party_db_range,
+    read_truth_code, shard_ranges, truth_db_path, Aby3, Aby3Network, Aby3Share, Error, IrisAby3,
+    MpcTrait, Sharable, ShardNetwork, ShardNetworkTrait,
+};
+use mpc_net::config::{NetworkConfig, NetworkParty};
+use plain_reference::{IrisCode, IrisCodeArray};
+use rand::{
+    distributions::{Distribution, Standard},
+    SeedableRng,
+};
+use rand_chacha::ChaCha12Rng;
+use rusqlite::Connection;
+use std::{fs::File, ops::Mul, path::PathBuf};
+use tokio::time::Instant;
+
+// Each party consists of a coordinator and one worker per shard. The workers of the same shard
+// form a triple, as do the coordinators. All processes of a party use the certificate of the
+// party and derive their ports from the address of the party in the config:
+//   triple of node k (coordinator: k = 0, worker of shard i: k = i + 1): port + 10 * k
+//   links between the coordinator and the workers of a party:             port + 900 + k
+const TRIPLE_PORT_STEP: u16 = 10;
+const SHARD_PORT_OFFSET: u16 = 900;
+
+macro_rules! println0  {
+    ($id:expr) => {
+        if $id == 0 {
+            println!();
+        }
+    };
+    ($id:expr, $($arg:tt)*) => {{
+        if $id == 0 {
+            println!($($arg)*);
+        }
+    }};
+
+}
+
+#[derive(Parser, Clone)]
+struct Args {
+    /// The config file path
+    #[clap(short, long, value_name = "FILE")]
+    config_file: PathBuf,
+
+    /// The path to the .der key file for our certificate
+    #[clap(short, long, value_name = "FILE")]
+    key_file: PathBuf,
+
+    /// The If of our party in the config
+    #[clap(short, long, value_name = "ID")]
+    party: usize,
+
+    /// path to the database file, the shares are read from the database of this party, created
+    /// with --per-party
+    #[arg(short, long, value_name = "FILE", required = true)]
+    database: PathBuf,
+
+    /// seed to generate the iris code to match
+    #[arg(short, long, value_name = "seed", required = true)]
+    iris_seed: u64,
+
+    /// Set to true if a image should be generated that matches an element in the database
+    #[arg(short, long, default_value = "false")]
+    should_match: bool,
+
+    /// Size of the chunks that are handled at once to batch networking
+    #[arg(long, default_value = "1024")]
+    chunk_size: usize,
+
+    /// Number of concurrent sessions the chunks of a shard are distributed over
+    #[arg(long, default_value = "1")]
+    sessions: usize,
+
+    /// Number of shards the database is split into
+    #[arg(short, long, value_name = "N")]
+    num_shards: usize,
+
+    /// The shard this process works on. If not given, this process is the coordinator
+    #[arg(long, value_name = "SHARD")]
+    shard: Option<usize>,
+}
+
+impl Args {
+    fn node(&self) -> usize {
+        self.shard
+            .map_or(ShardNetwork::COORDINATOR, |shard| shard + 1)
+    }
+}
+
+fn read_parties(args: &Args) -> Result<Vec<NetworkParty>> {
+    let parties =
+        serde_yaml::from_reader(File::open(&args.config_file).context("opening config file")?)
+            .context("parsing config file")?;
+    Ok(parties)
+}
+
+fn with_port_offset(mut party: NetworkParty, port_offset: u16) -> NetworkParty {
+    party
+        .bind_addr
+        .set_port(party.bind_addr.port() + port_offset);
+    party
+        .public_addr
+        .set_port(party.public_addr.port() + port_offset);
+    party
+}
+
+async fn setup_network(args: &Args) -> Result<Aby3Network> {
+    let port_offset = TRIPLE_PORT_STEP * args.node() as u16;
+    let parties = read_parties(args)?
+        .into_iter()
+        .map(|p| with_port_offset(p, port_offset))
+        .collect();
+
+    let config = NetworkConfig {
+        parties,
+        my_id: args.party,
+        key_path: args.key_file.to_owned(),
+    };
+
+    let network = Aby3Network::new(config).await?;
+
+    Ok(network)
+}
+
+async fn setup_shard_network(args: &Args) -> Result<ShardNetwork> {
+    let party = read_parties(args)?
+        .into_iter()
+        .find(|p| p.id == args.party)
+        .ok_or(Error::IdError(args.party))?;
+    let parties = (0..=args.num_shards)
+        .map(|node| {
+            let mut party = with_port_offset(party.to_owned(), SHARD_PORT_OFFSET + node as u16);
+            party.id = node;
+            party
+        })
+        .collect();
+
+    let config = NetworkConfig {
+        parties,
+        my_id: args.node(),
+        key_path: args.key_file.to_owned(),
+    };
+
+    let network = ShardNetwork::new(config).await?;
+
+    Ok(network)
+}
+
+#[derive(Default)]
+struct SharedIris<T: Sharable> {
+    shares: Vec<Aby3Share<T>>,
+    mask: IrisCodeArray,
+}
+
+fn open_database(database_file: &PathBuf) -> Result<Connection> {
+    let conn = Connection::open(database_file)?;
+    // Additional setup or configuration for the database connection can be done here
+    Ok(conn)
+}
+
+fn get_iris_share<T: Sharable>(args: &Args) -> Result<SharedIris<T>>
+where
+    Aby3Share<T>: Mul<T::Share, Output = Aby3Share<T>>,
+    Standard: Distribution<T::Share>,
+{
+    let mut rng = ChaCha12Rng::seed_from_u64(args.iris_seed);
+    let iris = if args.should_match {
+        let conn = open_database(&truth_db_path(&args.database))?;
+        read_truth_code(&conn, 1)?.get_similar_iris(&mut rng)
+    } else {
+        IrisCode::random_rng(&mut rng)
+    };
+
+    let mut res = SharedIris::<T>::default();
+    res.mask
+        .as_raw_mut_slice()
+        .copy_from_slice(iris.mask.as_raw_slice());
+
+    for i in 0..IrisCode::IRIS_CODE_SIZE {
+        // We simulate the parties already knowing the shares of the code.
+        let shares = Aby3::<Aby3Network>::share(
+            T::from(iris.code.get_bit(i)),
+            T::VerificationShare::default(),
+            &mut rng,
+        );
+        if args.party > 2 {
+            Err(Error::IdError(args.party))?;
+        }
+        res.shares.push(shares[args.party].to_owned());
+    }
+
+    Ok(res)
+}
+
+async fn run_worker(args: Args, shard: usize) -> Result<()> {
+    let id = args.party;
+
+    println0!(id, "Worker {shard}: Reading shard of the database:");
+    let start = Instant::now();
+    let conn = open_database(&party_db_path(&args.database, args.party))?;
+    let range = shard_ranges(party_db_len(&conn)?, args.num_shards)
+        .get(shard)
+        .ok_or(Error::IdError(shard))?
+        .to_owned();
+    let db = read_aby3_party_db_range::<u16>(&conn, args.party, range.to_owned())?;
+    let duration = start.elapsed();
+    println0!(
+        id,
+        "...done, read records {:?}, took {} ms\n",
+        range,
+        duration.as_millis()
+    );
+
+    let shares = get_iris_share::<u16>(&args)?;
+
+    let network = setup_network(&args).await?;
+    let mut shard_network = setup_shard_network(&args).await?;
+
+    let protocol = Aby3::new(network);
+    let mut iris = IrisAby3::<u16, _>::new(protocol)?;
+    iris.set_sessions(args.sessions)?;
+    iris.preprocessing().await?;
+
+    println0!(id, "Worker {shard}: MPC matching:");
+    let start = Instant::now();
+    match_shard(
+        &mut iris,
+        &mut shard_network,
+        &shares.shares,
+        &db.shares,
+        &shares.mask,
+        &db.masks,
+        args.chunk_size,
+    )
+    .await?;
+    let duration = start.elapsed();
+    println0!(id, "...done, took {} ms\n", duration.as_millis());
+
+    iris.finish().await?;
+    shard_network.shutdown().await?;
+    Ok(())
+}
+
+async fn run_coordinator(args: Args) -> Result<()> {
+    let id = args.party;
+
+    println0!(id, "Coordinator: Setting up network:");
+    let start = Instant::now();
+    let network = setup_network(&args).await?;
+    let mut shard_network = setup_shard_network(&args).await?;
+    let duration = start.elapsed();
+    println0!(id, "...done, took {} ms\n", duration.as_millis());
+
+    let protocol = Aby3::new(network);
+    let mut iris = IrisAby3::<u16, _>::new(protocol)?;
+    iris.preprocessing().await?;
+
+    println0!(id, "Coordinator: Combining the results of the shards:");
+    let start = Instant::now();
+    let res = combine_shard_results(&mut iris, &mut shard_network).await?;
+    let duration = start.elapsed();
+    println0!(id, "...done, took {} ms", duration.as_millis());
+    println0!(id, "Result is {res}\n");
+
+    if args.should_match && !res {
+        println0!(id, "ERROR: should match but doesn't");
+    }
+
+    iris.finish().await?;
+    shard_network.shutdown().await?;
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse();
+
+    match args.shard {
+        Some(shard) => run_worker(args, shard).await,
+        None => run_coordinator(args).await,
+    }
+}
@@ -0,0 +1,94 @@
+use clap::Parser;
+use color_eyre::{eyre::Report, Result};
+use iris_mpc::prelude::{AdderStrategy, Bit, DryRun, IrisProtocol};
+use plain_reference::IrisCode;
+use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+use tokio::time::Instant;
+
+/// Estimates the costs of the ABY3 example (examples/aby3.rs) for one party without running the
+/// parties. The printed connection stats can be compared with the ones of the example. Only ABY3
+/// with the adder-based MSB extraction is modelled.
+#[derive(Parser, Clone)]
+struct Args {
+    /// number of items in the database
+    #[arg(short, long, value_name = "NUM", required = true)]
+    items: usize,
+
+    /// seed to generate the iris codes
+    #[arg(short = 's', long, value_name = "seed", default_value = "0")]
+    iris_seed: u64,
+
+    /// Size of the chunks that are handled at once to batch networking
+    #[arg(short, long, default_value = "1024")]
+    chunk_size: usize,
+
+    /// Adder used for the MSB extraction (ripple-carry, kogge-stone, brent-kung, sklansky)
+    #[arg(short, long, default_value = "ripple-carry")]
+    adder: String,
+}
+
+fn parse_adder(adder: &str) -> Result<AdderStrategy> {
+    match adder.to_lowercase().as_str() {
+        "ripple-carry" => Ok(AdderStrategy::RippleCarry),
+        "kogge-stone" => Ok(AdderStrategy::KoggeStone),
+        "brent-kung" => Ok(AdderStrategy::BrentKung),
+        "sklansky" => Ok(AdderStrategy::Sklansky),
+        _ => Err(Report::msg("Invalid adder specified")),
+    }
+}
+
+fn iris_code_plain_type(code: &IrisCode) -> Vec<u16> {
+    code.code.bits().map(u16::from).collect()
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse();
+    let strategy = parse_adder(&args.adder)?;
+
+    // The costs do not depend on the values, so random codes are sufficient
+    println!("Generating database:");
+    let start = Instant::now();
+    let mut rng = ChaCha12Rng::seed_from_u64(args.iris_seed);
+    let mut db = Vec::with_capacity(args.items);
+    let mut masks = Vec::with_capacity(args.items);
+    for _ in 0..args.items {
+        let code = IrisCode::random_rng(&mut rng);
+        db.push(iris_code_plain_type(&code));
+        masks.push(code.mask);
+    }
+    let iris = IrisCode::random_rng(&mut rng);
+    let duration = start.elapsed();
+    println!("...done, took {} ms\n", duration.as_millis());
+
+    let mut protocol = DryRun::new(0)?;
+    protocol.set_adder_strategy(strategy);
+    let mut dry_run = IrisProtocol::<u16, u16, Bit, _>::new(protocol)?;
+
+    println!("\nPreprocessing:");
+    dry_run.preprocessing().await?;
+    println!("Stats: party 0");
+    dry_run.print_connection_stats(&mut std::io::stdout())?;
+
+    println!("\nMPC matching:");
+    let start = Instant::now();
+    let res = dry_run
+        .iris_in_db(
+            &iris_code_plain_type(&iris),
+            &db,
+            &iris.mask,
+            &masks,
+            args.chunk_size,
+        )
+        .await?;
+    let duration = start.elapsed();
+    println!("...done, took {} ms", duration.as_millis());
+    println!("Result is {res}\n");
+    println!("Stats: party 0");
+    print!("{}", dry_run.get_mpc_ref().report());
+
+    dry_run.finish().await?;
+
+    Ok(())
+}
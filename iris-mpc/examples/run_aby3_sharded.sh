@@ -0,0 +1,38 @@
+#!/usr/bin/env bash
+
+if [[ $# -gt 1 ]]; then
+    echo "Usage: run_aby3_sharded.sh [-s]"
+    exit -1
+fi
+
+args=""
+if [[ $# -eq 1 ]]; then
+    if [[ $1 != "-s" ]]; then
+        echo "Usage: run_aby3_sharded.sh [-s]"
+        exit -1
+    fi
+    args="-s"
+fi
+
+SHARDS=3
+
+mkdir -p data
+[[ -f data/db_sharded.party0.sqlite ]] || cargo run --release --bin create_sample_data -- -d data/db_sharded.sqlite -i 10000 -m aby3 --per-party
+
+[[ -f "data/key0.der" ]] || cargo run --bin gen_cert -- -k data/key0.der -c data/cert0.der -s localhost -s party0
+[[ -f "data/key1.der" ]] || cargo run --bin gen_cert -- -k data/key1.der -c data/cert1.der -s localhost -s party1
+[[ -f "data/key2.der" ]] || cargo run --bin gen_cert -- -k data/key2.der -c data/cert2.der -s localhost -s party2
+
+cargo build --release --example aby3_sharded
+BIN=../target/release/examples/aby3_sharded
+
+for party in 0 1 2; do
+    for ((shard = 0; shard < SHARDS; shard++)); do
+        $BIN -p $party -k data/key$party.der -c examples/config.yaml -d data/db_sharded.sqlite -i 0 -n $SHARDS --shard $shard $args &
+    done
+done
+
+$BIN -p 0 -k data/key0.der -c examples/config.yaml -d data/db_sharded.sqlite -i 0 -n $SHARDS $args &
+$BIN -p 1 -k data/key1.der -c examples/config.yaml -d data/db_sharded.sqlite -i 0 -n $SHARDS $args &
+$BIN -p 2 -k data/key2.der -c examples/config.yaml -d data/db_sharded.sqlite -i 0 -n $SHARDS $args
+wait
@@ -3,9 +3,12 @@ use color_eyre::{
     eyre::{Context, ContextCompat},
     Report, Result,
 };
+use futures::stream;
 use iris_mpc::prelude::{
-    Aby3Network, Aby3Share, Error, IrisSpdzWise, MpcTrait, Sharable, SpdzWise, SpdzWiseShare,
-    SpdzWiseVecShare, VecShareTrait,
+    party_db_path, read_spdzwise_party_db, read_spdzwise_party_mac_key, read_truth_code,
+    read_truth_mac_key, spdzwise_party_db_chunks, truth_db_path, Aby3Network, Aby3Share, DbRoots,
+    Error, IrisSpdzWise, MpcTrait, Sharable, SpdzWise, SpdzWiseShare, SpdzWiseVecShare,
+    VecShareTrait,
 };
 use mpc_net::config::{NetworkConfig, NetworkParty};
 use plain_reference::{IrisCode, IrisCodeArray};
@@ -68,6 +71,15 @@ struct Args {
     /// Number of protocol runs
     #[arg(short, long, default_value = "1")]
     num_runs: usize,
+
+    /// Read the shares of this party from its own database, created with --per-party
+    #[arg(long, default_value = "false")]
+    per_party: bool,
+
+    /// Stream the shares chunk by chunk from the database of this party, instead of reading the
+    /// whole database before matching. Requires --per-party
+    #[arg(long, default_value = "false", requires = "per_party")]
+    stream: bool,
 }
 
 fn print_stats<T: Sharable>(
@@ -128,7 +140,30 @@ fn open_database(database_file: &PathBuf) -> Result<Connection> {
     Ok(conn)
 }
 
+// Only reads the MAC keys of the database of this party, used for streaming the shares
+fn read_party_mac_keys<T: Sharable>(args: &Args) -> Result<SharedDB<T>> {
+    let conn = open_database(&party_db_path(&args.database, args.party))?;
+    let mac_key_share = read_spdzwise_party_mac_key::<T>(&conn, args.party)?;
+    // The plain mac key is only used to share the query iris
+    let truth = open_database(&truth_db_path(&args.database))?;
+    let mac_key = read_truth_mac_key::<T>(&truth)?;
+    Ok(SharedDB {
+        mac_key,
+        mac_key_share,
+        ..Default::default()
+    })
+}
+
 fn read_db<T: Sharable>(args: Args) -> Result<SharedDB<T>> {
+    if args.per_party {
+        let conn = open_database(&party_db_path(&args.database, args.party))?;
+        let db = read_spdzwise_party_db::<T>(&conn, args.party)?;
+        return Ok(SharedDB {
+            shares: db.shares,
+            masks: db.masks,
+            ..read_party_mac_keys(&args)?
+        });
+    }
     let conn = open_database(&args.database)?;
     let mut res = SharedDB::<T>::default();
 
@@ -214,7 +249,10 @@ where
     Aby3Share<T>: Mul<T::Share, Output = Aby3Share<T>>,
 {
     let mut rng = ChaCha12Rng::seed_from_u64(args.iris_seed);
-    let iris = if args.should_match {
+    let iris = if args.should_match && args.per_party {
+        let conn = open_database(&truth_db_path(&args.database))?;
+        read_truth_code(&conn, 1)?.get_similar_iris(&mut rng)
+    } else if args.should_match {
         let conn = open_database(&args.database)?;
         // read the codes from the database using rusqlite and iterate over them
         conn.query_row(
@@ -261,11 +299,16 @@ async fn main() -> Result<()> {
     let args = Args::parse();
     let id = args.party;
 
-    println0!(id, "Reading database:");
-    let start = Instant::now();
-    let db = read_db::<u16>(args.to_owned())?;
-    let duration = start.elapsed();
-    println0!(id, "...done, took {} ms\n", duration.as_millis());
+    let db = if args.stream {
+        read_party_mac_keys::<u16>(&args)?
+    } else {
+        println0!(id, "Reading database:");
+        let start = Instant::now();
+        let db = read_db::<u16>(args.to_owned())?;
+        let duration = start.elapsed();
+        println0!(id, "...done, took {} ms\n", duration.as_millis());
+        db
+    };
 
     println0!(id, "Get shares:");
     let start = Instant::now();
@@ -273,6 +316,30 @@ async fn main() -> Result<()> {
     let duration = start.elapsed();
     println0!(id, "...done, took {} ms\n", duration.as_millis());
 
+    // The roots are computed from the shares which are matched. They are cross-checked with the
+    // other parties in the preprocessing, such that all parties match against the same version of
+    // the database, and again after matching, such that no party can match other shares
+    let roots = if args.per_party {
+        println0!(id, "Computing database roots:");
+        let start = Instant::now();
+        let roots = if args.stream {
+            let conn = open_database(&party_db_path(&args.database, args.party))?;
+            let chunks = spdzwise_party_db_chunks::<u16>(&conn, args.party, args.chunk_size)?;
+            DbRoots::from_chunks::<SpdzWiseShare<<u16 as Sharable>::VerificationShare>, _, _>(
+                chunks,
+            )?
+        } else {
+            DbRoots::from_shares::<SpdzWiseShare<<u16 as Sharable>::VerificationShare>, _>(
+                &db.shares, &db.masks,
+            )?
+        };
+        let duration = start.elapsed();
+        println0!(id, "...done, took {} ms\n", duration.as_millis());
+        Some(roots)
+    } else {
+        None
+    };
+
     for i in 0..args.num_runs {
         println0!(id, "Run {}:", i);
         println0!(id, "Setting up network:");
@@ -285,6 +352,9 @@ async fn main() -> Result<()> {
         let start = Instant::now();
         let protocol = SpdzWise::new(network);
         let mut iris = IrisSpdzWise::<u16, _>::new(protocol)?;
+        if let Some(roots) = &roots {
+            iris.set_db_roots(roots.to_owned());
+        }
         let duration = start.elapsed();
         println0!(id, "...done, took {} ms\n", duration.as_millis());
         print_stats(&iris)?;
@@ -299,15 +369,25 @@ async fn main() -> Result<()> {
 
         println0!(id, "\nMPC matching:");
         let start = Instant::now();
-        let res = iris
-            .iris_in_db(
+        let res = if args.stream {
+            let conn = open_database(&party_db_path(&args.database, args.party))?;
+            let db = stream::iter(spdzwise_party_db_chunks::<u16>(
+                &conn,
+                args.party,
+                args.chunk_size,
+            )?);
+            iris.iris_in_db_stream(&shares.shares, &shares.mask, db, args.chunk_size)
+                .await?
+        } else {
+            iris.iris_in_db(
                 &shares.shares,
                 &db.shares,
                 &shares.mask,
                 &db.masks,
                 args.chunk_size,
             )
-            .await?;
+            .await?
+        };
         let duration = start.elapsed();
         println0!(id, "...done, took {} ms", duration.as_millis());
         println0!(id, "Result is {res}\n");
@@ -1,7 +1,9 @@
 use clap::Parser;
 use color_eyre::{eyre::Context, Result};
+use futures::stream;
 use iris_mpc::prelude::{
-    Error, IrisSwift3, MpcTrait, Sharable, Swift3, Swift3Network, Swift3Share,
+    party_db_path, read_swift3_party_db, read_truth_code, swift3_party_db_chunks, truth_db_path,
+    DbRoots, Error, IrisSwift3, MpcTrait, Sharable, Swift3, Swift3Network, Swift3Share,
 };
 use mpc_net::config::{NetworkConfig, NetworkParty};
 use plain_reference::{IrisCode, IrisCodeArray};
@@ -57,6 +59,15 @@ struct Args {
     /// Size of the chunks that are handled at once to batch networking
     #[arg(short, long, default_value = "1024")]
     chunk_size: usize,
+
+    /// Read the shares of this party from its own database, created with --per-party
+    #[arg(long, default_value = "false")]
+    per_party: bool,
+
+    /// Stream the shares chunk by chunk from the database of this party, instead of reading the
+    /// whole database before matching. Requires --per-party
+    #[arg(long, default_value = "false", requires = "per_party")]
+    stream: bool,
 }
 
 fn print_stats<T: Sharable>(iris: &IrisSwift3<T, Swift3<Swift3Network, T>>) -> Result<()>
@@ -108,6 +119,14 @@ fn open_database(database_file: &PathBuf) -> Result<Connection> {
 }
 
 fn read_db<T: Sharable>(args: Args) -> Result<SharedDB<T>> {
+    if args.per_party {
+        let conn = open_database(&party_db_path(&args.database, args.party))?;
+        let db = read_swift3_party_db(&conn, args.party)?;
+        return Ok(SharedDB {
+            shares: db.shares,
+            masks: db.masks,
+        });
+    }
     let conn = open_database(&args.database)?;
 
     // read the codes from the database using rusqlite and iterate over them
@@ -153,7 +172,10 @@ where
     Standard: Distribution<T::Share>,
 {
     let mut rng = ChaCha12Rng::seed_from_u64(args.iris_seed);
-    let iris = if args.should_match {
+    let iris = if args.should_match && args.per_party {
+        let conn = open_database(&truth_db_path(&args.database))?;
+        read_truth_code(&conn, 1)?.get_similar_iris(&mut rng)
+    } else if args.should_match {
         let conn = open_database(&args.database)?;
         // read the codes from the database using rusqlite and iterate over them
         conn.query_row(
@@ -204,11 +226,16 @@ async fn main() -> Result<()> {
     let id = args.party;
     tracing_subscriber::fmt::init();
 
-    println0!(id, "Reading database:");
-    let start = Instant::now();
-    let db = read_db::<u16>(args.to_owned())?;
-    let duration = start.elapsed();
-    println0!(id, "...done, took {} ms\n", duration.as_millis());
+    let db = if args.stream {
+        SharedDB::default()
+    } else {
+        println0!(id, "Reading database:");
+        let start = Instant::now();
+        let db = read_db::<u16>(args.to_owned())?;
+        let duration = start.elapsed();
+        println0!(id, "...done, took {} ms\n", duration.as_millis());
+        db
+    };
 
     println0!(id, "Get shares:");
     let start = Instant::now();
@@ -216,6 +243,26 @@ async fn main() -> Result<()> {
     let duration = start.elapsed();
     println0!(id, "...done, took {} ms\n", duration.as_millis());
 
+    // The roots are computed from the shares which are matched. They are cross-checked with the
+    // other parties in the preprocessing, such that all parties match against the same version of
+    // the database, and again after matching, such that no party can match other shares
+    let roots = if args.per_party {
+        println0!(id, "Computing database roots:");
+        let start = Instant::now();
+        let roots = if args.stream {
+            let conn = open_database(&party_db_path(&args.database, args.party))?;
+            let chunks = swift3_party_db_chunks::<u16>(&conn, args.party, args.chunk_size)?;
+            DbRoots::from_chunks::<Swift3Share<u16>, _, _>(chunks)?
+        } else {
+            DbRoots::from_shares::<Swift3Share<u16>, _>(&db.shares, &db.masks)?
+        };
+        let duration = start.elapsed();
+        println0!(id, "...done, took {} ms\n", duration.as_millis());
+        Some(roots)
+    } else {
+        None
+    };
+
     println0!(id, "Setting up network:");
     let start = Instant::now();
     let network = setup_network(args.to_owned()).await?;
@@ -226,6 +273,9 @@ async fn main() -> Result<()> {
     let start = Instant::now();
     let protocol = Swift3::new(network);
     let mut iris = IrisSwift3::<u16, _>::new(protocol)?;
+    if let Some(roots) = &roots {
+        iris.set_db_roots(roots.to_owned());
+    }
     let duration = start.elapsed();
     println0!(id, "...done, took {} ms\n", duration.as_millis());
     print_stats(&iris)?;
@@ -239,15 +289,25 @@ async fn main() -> Result<()> {
 
     println0!(id, "\nMPC matching:");
     let start = Instant::now();
-    let res = iris
-        .iris_in_db(
+    let res = if args.stream {
+        let conn = open_database(&party_db_path(&args.database, args.party))?;
+        let db = stream::iter(swift3_party_db_chunks::<u16>(
+            &conn,
+            args.party,
+            args.chunk_size,
+        )?);
+        iris.iris_in_db_stream(&shares.shares, &shares.mask, db, args.chunk_size)
+            .await?
+    } else {
+        iris.iris_in_db(
             &shares.shares,
             &db.shares,
             &shares.mask,
             &db.masks,
             args.chunk_size,
         )
-        .await?;
+        .await?
+    };
     let duration = start.elapsed();
     println0!(id, "...done, took {} ms", duration.as_millis());
     println0!(id, "Result is {res}\n");
@@ -1,4 +1,5 @@
 use std::io;
+use std::sync::Arc;
 
 use super::id::PartyID;
 use crate::error::Error;
@@ -8,11 +9,13 @@ use mpc_net::channel::ChannelHandle;
 use mpc_net::config::NetworkConfig;
 use mpc_net::MpcNetworkHandler;
 
+type Channel = ChannelHandle<Bytes, BytesMut>;
+
 pub struct Aby3Network {
-    handler: MpcNetworkHandler,
+    handler: Arc<MpcNetworkHandler>, // Shared by all sessions
     id: PartyID,
-    channel_send: ChannelHandle<Bytes, BytesMut>,
-    channel_recv: ChannelHandle<Bytes, BytesMut>,
+    channel_send: Channel,
+    channel_recv: Channel,
 }
 
 impl Aby3Network {
@@ -22,25 +25,40 @@ impl Aby3Network {
             return Err(Error::NumPartyError(config.parties.len()));
         }
 
-        let mut handler = MpcNetworkHandler::establish(config).await?;
-        let mut channels = handler.get_byte_channels().await?;
-
-        let next_id: usize = id.next_id().into();
-        let prev_id: usize = id.prev_id().into();
-
-        let channel_send = channels.remove(&next_id).ok_or(Error::ConfigError)?;
-        let channel_recv = channels.remove(&prev_id).ok_or(Error::ConfigError)?;
-
-        let channel_send = ChannelHandle::manage(channel_send);
-        let channel_recv = ChannelHandle::manage(channel_recv);
+        let handler = MpcNetworkHandler::establish(config).await?;
+        let (channel_send, channel_recv) = Self::open_channels(&handler, id)
+            .await?
+            .ok_or(Error::ConfigError)?;
 
         Ok(Self {
-            handler,
+            handler: Arc::new(handler),
             id,
             channel_send,
             channel_recv,
         })
     }
+
+    // Opens new streams to the next and the previous party
+    async fn open_channels(
+        handler: &MpcNetworkHandler,
+        id: PartyID,
+    ) -> io::Result<Option<(Channel, Channel)>> {
+        let mut channels = handler.get_byte_channels().await?;
+
+        let next_id: usize = id.next_id().into();
+        let prev_id: usize = id.prev_id().into();
+
+        let (Some(channel_send), Some(channel_recv)) =
+            (channels.remove(&next_id), channels.remove(&prev_id))
+        else {
+            return Ok(None);
+        };
+
+        Ok(Some((
+            ChannelHandle::manage(channel_send),
+            ChannelHandle::manage(channel_recv),
+        )))
+    }
 }
 
 impl NetworkTrait for Aby3Network {
@@ -67,7 +85,7 @@ impl NetworkTrait for Aby3Network {
             std::mem::drop(send_status);
             Ok(())
         } else {
-            Err(io::Error::new(io::ErrorKind::Other, "Invalid ID"))
+            Err(io::Error::other("Invalid ID"))
         };
         tracing::trace!("send_id {}->{}: done", self.id, id);
         res
@@ -96,7 +114,7 @@ impl NetworkTrait for Aby3Network {
         } else if id == usize::from(self.id.next_id()) {
             self.channel_send.recv().await.await
         } else {
-            return Err(io::Error::new(io::ErrorKind::Other, "Invalid ID"));
+            return Err(io::Error::other("Invalid ID"));
         };
         tracing::trace!("recv_id {}<-{}: done", self.id, id);
 
@@ -165,7 +183,23 @@ impl NetworkTrait for Aby3Network {
         drop(channel_recv);
         drop(channel_send);
 
-        handler.shutdown().await;
+        // The connections are closed with the last session
+        if let Ok(handler) = Arc::try_unwrap(handler) {
+            handler.shutdown().await;
+        }
         Ok(())
     }
+
+    async fn fork(&mut self) -> io::Result<Self> {
+        let (channel_send, channel_recv) = Self::open_channels(&self.handler, self.id)
+            .await?
+            .ok_or_else(|| io::Error::other("Missing connection"))?;
+
+        Ok(Self {
+            handler: self.handler.to_owned(),
+            id: self.id,
+            channel_send,
+            channel_recv,
+        })
+    }
 }
@@ -1,12 +1,19 @@
+use super::id::PartyID;
 use super::random::prf::{Prf, PrfSeed};
 use super::utils;
 use crate::aby3::share::Share;
+use crate::adder::{self, AdderStrategy};
+use crate::db::roots::{self, DbRoots};
+use crate::edabits::{self, EdaBits};
 use crate::error::Error;
+use crate::fss::msb::{self, EvaluatorMaterial, MsbMaterial, MsbVariant};
 use crate::traits::binary_trait::BinaryMpcTrait;
 use crate::traits::mpc_trait::MpcTrait;
 use crate::traits::network_trait::NetworkTrait;
 use crate::traits::security::SemiHonest;
+use crate::traits::share_trait::VecSlice;
 use crate::types::bit::Bit;
+use crate::types::packed_bits::PackedBits;
 use crate::types::ring_element::{RingElement, RingImpl};
 use crate::types::sharable::Sharable;
 use bytes::Bytes;
@@ -14,12 +21,23 @@ use itertools::Itertools;
 use num_traits::Zero;
 use plain_reference::IrisCodeArray;
 use rand::distributions::{Distribution, Standard};
-use rand::Rng;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::borrow::Borrow;
+use std::collections::{HashMap, VecDeque};
+use std::io;
 use std::ops::Mul;
+use std::sync::Arc;
 
 pub struct Aby3<N: NetworkTrait> {
     pub(crate) network: N,
     pub(crate) prf: Prf,
+    msb_variant: MsbVariant,
+    adder_strategy: AdderStrategy,
+    fss_material: HashMap<usize, VecDeque<MsbMaterial>>, // Indexed by the bitsize
+    edabits: EdaBits,
+    pool: Option<Arc<ThreadPool>>, // For the local computations, shared by all sessions
 }
 
 impl<N: NetworkTrait> SemiHonest for Aby3<N> {}
@@ -46,7 +64,235 @@ impl<N: NetworkTrait> Aby3<N> {
     pub fn new(network: N) -> Self {
         let prf = Prf::default();
 
-        Self { network, prf }
+        Self {
+            network,
+            prf,
+            msb_variant: MsbVariant::default(),
+            adder_strategy: AdderStrategy::default(),
+            fss_material: HashMap::new(),
+            edabits: EdaBits::default(),
+            pool: None,
+        }
+    }
+
+    /// Selects how the MSB is extracted in [`MpcTrait::get_msb_many`]. Has to be the same for all
+    /// parties.
+    pub fn set_msb_variant(&mut self, variant: MsbVariant) -> Result<(), Error> {
+        self.msb_variant = variant;
+        Ok(())
+    }
+
+    pub fn get_msb_variant(&self) -> MsbVariant {
+        self.msb_variant
+    }
+
+    /// Selects the adder of [`MsbVariant::Adder`]. Has to be the same for all parties.
+    pub fn set_adder_strategy(&mut self, strategy: AdderStrategy) {
+        self.adder_strategy = strategy;
+    }
+
+    pub fn get_adder_strategy(&self) -> AdderStrategy {
+        self.adder_strategy
+    }
+
+    /// Sets the number of threads for the local computation of [`MpcTrait::masked_dot_many`].
+    /// With a single thread (the default), everything is computed on the calling thread. While the
+    /// pool computes, the calling tokio worker is handed over with
+    /// [`tokio::task::block_in_place`], so tasks of other parties or sessions keep running on a
+    /// multi-threaded runtime. On a current-thread runtime, the whole runtime waits for the pool.
+    pub fn set_threads(&mut self, threads: usize) -> Result<(), Error> {
+        self.pool = match threads {
+            0 => return Err(Error::ConfigError),
+            1 => None,
+            _ => {
+                let pool = ThreadPoolBuilder::new()
+                    .num_threads(threads)
+                    .build()
+                    .map_err(|err| Error::Other(err.to_string()))?;
+                Some(Arc::new(pool))
+            }
+        };
+        Ok(())
+    }
+
+    pub fn get_threads(&self) -> usize {
+        self.pool
+            .as_ref()
+            .map_or(1, |pool| pool.current_num_threads())
+    }
+
+    pub(crate) fn get_pool(&self) -> Option<&ThreadPool> {
+        self.pool.as_deref()
+    }
+
+    /// Preprocessing for [`MsbVariant::Fss`]: P2 acts as dealer and sends `amount` DCF keys for
+    /// MSB extractions of `T` to P0 and P1.
+    pub async fn precompute_dcf_keys<T: Sharable>(&mut self, amount: usize) -> Result<(), Error> {
+        let k = T::Share::K;
+        let material = match self.network.get_id() {
+            0 | 1 => {
+                let response = if self.network.get_id() == 0 {
+                    self.network.receive_prev_id().await?
+                } else {
+                    self.network.receive_next_id().await?
+                };
+                let keys: Vec<EvaluatorMaterial> =
+                    bincode::deserialize(&response).map_err(|_| Error::SerializationError)?;
+                if keys.len() != amount || keys.iter().any(|m| m.key.input_len() + 1 != k) {
+                    return Err(Error::InvalidMessageSize);
+                }
+                keys.into_iter().map(MsbMaterial::Evaluator).collect_vec()
+            }
+            2 => {
+                let mut rng = ChaCha12Rng::from_entropy();
+                let mut masks = Vec::with_capacity(amount);
+                let mut keys0 = Vec::with_capacity(amount);
+                let mut keys1 = Vec::with_capacity(amount);
+                for _ in 0..amount {
+                    let (r, key0, key1) = msb::deal(k, &mut rng);
+                    masks.push(MsbMaterial::Dealer(r));
+                    keys0.push(key0);
+                    keys1.push(key1);
+                }
+                let keys0 = bincode::serialize(&keys0).map_err(|_| Error::SerializationError)?;
+                let keys1 = bincode::serialize(&keys1).map_err(|_| Error::SerializationError)?;
+                self.network.send_next_id(Bytes::from(keys0)).await?;
+                self.network.send_prev_id(Bytes::from(keys1)).await?;
+                masks
+            }
+            _ => unreachable!(),
+        };
+        self.fss_material.entry(k).or_default().extend(material);
+        Ok(())
+    }
+
+    /// Preprocessing for [`MsbVariant::EdaBits`]: Generates `amount` edaBits for `T`.
+    pub async fn precompute_edabits<T: Sharable>(&mut self, amount: usize) -> Result<(), Error>
+    where
+        Standard: Distribution<T::Share>,
+    {
+        let arithmetic = (0..amount).map(|_| self.prf.gen_rand::<T>()).collect_vec();
+        let binary = <Self as BinaryMpcTrait<T, Share<T>>>::arithmetic_to_binary_many(
+            self,
+            arithmetic.to_owned(),
+        )
+        .await?;
+        self.edabits.extend(arithmetic, binary);
+        Ok(())
+    }
+
+    async fn msb_edabits_many<T: Sharable>(
+        &mut self,
+        a: Vec<Share<T>>,
+    ) -> Result<Vec<Share<Bit>>, Error>
+    where
+        Standard: Distribution<T::Share>,
+    {
+        let len = a.len();
+        let available = self.edabits.len::<T>();
+        if available < len {
+            tracing::warn!(
+                "Party {}: Not enough edaBits, generating them inline",
+                self.network.get_id()
+            );
+            self.precompute_edabits::<T>(len - available).await?;
+        }
+        let (r, r_bits) = self.edabits.take::<T>(len)?;
+
+        // Network: open x + r
+        let masked = a.into_iter().zip(r).map(|(a_, r_)| a_ + r_).collect_vec();
+        let shares_b = masked.iter().map(|s| &s.b);
+        let shares_c = utils::send_iter_and_receive_iter(&mut self.network, shares_b).await?;
+        let c = masked
+            .iter()
+            .zip(shares_c)
+            .map(|(s, c)| c + &s.a + &s.b)
+            .collect();
+
+        let id = PartyID::try_from(self.network.get_id())?;
+        edabits::msb_from_edabits::<T, _>(self, id, c, r_bits).await
+    }
+
+    // The MSB of x is computed from y = x + r, which is opened to P0 and P1 only, by evaluating
+    // the DCF keys of the preprocessing. The resulting XOR shares of P0 and P1 are reshared.
+    async fn msb_fss_many<T: Sharable>(
+        &mut self,
+        a: Vec<Share<T>>,
+    ) -> Result<Vec<Share<Bit>>, Error>
+    where
+        Standard: Distribution<T::Share>,
+    {
+        let len = a.len();
+        let k = T::Share::K;
+        let available = self.fss_material.get(&k).map_or(0, |m| m.len());
+        if available < len {
+            tracing::warn!(
+                "Party {}: Not enough DCF keys, generating them inline",
+                self.network.get_id()
+            );
+            self.precompute_dcf_keys::<T>(len - available).await?;
+        }
+        let material = self
+            .fss_material
+            .get_mut(&k)
+            .expect("Material is present")
+            .drain(..len)
+            .collect_vec();
+
+        let id = self.network.get_id();
+        let mut res = Vec::with_capacity(len);
+        if id == 2 {
+            // y - x0 = x1 + x2 + r
+            let mut masked = Vec::with_capacity(len);
+            for (a_, m) in a.into_iter().zip(material) {
+                let r = match m {
+                    MsbMaterial::Dealer(r) => r,
+                    MsbMaterial::Evaluator(_) => unreachable!(),
+                };
+                let r = r
+                    .into_iter()
+                    .rev()
+                    .map(|b| RingElement(Bit::new(b)))
+                    .collect_vec();
+                masked.push(a_.a + a_.b + T::Share::from_bits(&r)?);
+                res.push(false);
+            }
+            utils::send_vec_next(&mut self.network, &masked).await?;
+            utils::send_vec_prev(&mut self.network, &masked).await?;
+        } else {
+            let masked: Vec<T::Share> = if id == 0 {
+                utils::receive_vec_prev(&mut self.network, len).await?
+            } else {
+                utils::receive_vec_next(&mut self.network, len).await?
+            };
+            for ((a_, m), c) in a.into_iter().zip(material).zip(masked) {
+                let m = match m {
+                    MsbMaterial::Evaluator(m) => m,
+                    MsbMaterial::Dealer(_) => unreachable!(),
+                };
+                let x0 = if id == 0 { a_.a } else { a_.b };
+                let y = (c + x0)
+                    .to_bits()
+                    .into_iter()
+                    .rev()
+                    .map(|b| b.convert().convert())
+                    .collect_vec();
+                res.push(m.eval(&y, id == 0));
+            }
+        }
+
+        // Reshare
+        let shares_a = res
+            .into_iter()
+            .map(|r| RingElement(Bit::new(r)) ^ self.prf.gen_binary_zero_share::<Bit>())
+            .collect_vec();
+        let shares_b = utils::send_slice_and_receive_iter(&mut self.network, &shares_a).await?;
+
+        Ok(shares_a
+            .into_iter()
+            .zip(shares_b)
+            .map(|(a_, b_)| Share::new(a_, b_))
+            .collect())
     }
 
     async fn setup_prf(&mut self) -> Result<(), Error> {
@@ -55,7 +301,7 @@ impl<N: NetworkTrait> Aby3<N> {
     }
 
     async fn setup_prf_from_seed(&mut self, seed: PrfSeed) -> Result<(), Error> {
-        let data = Bytes::from_iter(seed.into_iter());
+        let data = Bytes::from_iter(seed);
         let response = utils::send_and_receive(&mut self.network, data).await?;
         let their_seed = utils::bytes_to_seed(response)?;
         self.prf = Prf::new(seed, their_seed);
@@ -87,25 +333,6 @@ impl<N: NetworkTrait> Aby3<N> {
         (x1, x2, x3)
     }
 
-    fn pack<T: Sharable>(&self, a: Vec<Share<Bit>>) -> Vec<Share<T>> {
-        let outlen = (a.len() + T::Share::K - 1) / T::Share::K;
-        let mut out = Vec::with_capacity(outlen);
-
-        for a_ in a.chunks(T::Share::K) {
-            let mut share_a = T::Share::zero();
-            let mut share_b = T::Share::zero();
-            for (i, bit) in a_.iter().enumerate() {
-                let (bit_a, bit_b) = bit.to_owned().get_ab();
-                share_a |= T::Share::from(bit_a.convert().convert()) << (i as u32);
-                share_b |= T::Share::from(bit_b.convert().convert()) << (i as u32);
-            }
-            let share = Share::new(share_a, share_b);
-            out.push(share);
-        }
-
-        out
-    }
-
     reduce_or!(
         [u128, u64, reduce_or_u128, reduce_or_u64],
         [u64, u32, reduce_or_u64, reduce_or_u32],
@@ -171,85 +398,49 @@ impl<N: NetworkTrait> Aby3<N> {
         Ok(res)
     }
 
-    async fn transposed_pack_and(
-        &mut self,
-        x1: Vec<Vec<Share<u128>>>,
-        x2: Vec<Vec<Share<u128>>>,
-    ) -> Result<Vec<Vec<Share<u128>>>, Error> {
-        let len = x1.len();
-        debug_assert_eq!(len, x2.len());
-        let inner_len = x1[0].len();
-
-        let x3 = <Self as BinaryMpcTrait<u128, Share<u128>>>::and_many(
-            self,
-            &x1.into_iter().flatten().collect_vec(),
-            &x2.into_iter().flatten().collect_vec(),
-        )
-        .await?;
-
-        Ok(x3.chunks(inner_len).map(|x| x.to_owned()).collect_vec())
-    }
-
+    // Computes the MSBs with the adder circuit selected by the adder strategy
     async fn msb_adder_many<T: Sharable>(
         &mut self,
-        x1: Vec<Share<T>>,
-        x2: Vec<Share<T>>,
-        x3: Vec<Share<T>>,
-    ) -> Result<Vec<Share<Bit>>, Error> {
-        let len = x1.len();
-        if len != x2.len() || len != x3.len() {
-            return Err(Error::InvalidSizeError);
-        }
+        a: Vec<Share<T>>,
+    ) -> Result<PackedBits<Share<Bit>>, Error>
+    where
+        Standard: Distribution<T::Share>,
+    {
+        let len = a.len();
+        let mut x1 = Vec::with_capacity(len);
+        let mut x2 = Vec::with_capacity(len);
+        let mut x3 = Vec::with_capacity(len);
 
-        let x1 = utils::transpose_pack_u128::<T>(x1);
-        let x2 = utils::transpose_pack_u128::<T>(x2);
-        let mut x3 = utils::transpose_pack_u128::<T>(x3);
-
-        // Full adder to get 2 * c and s
-        let mut x2x3 = utils::transposed_pack_xor(&x2, &x3);
-        let s = utils::transposed_pack_xor(&x1, &x2x3);
-        let mut x1x3 = utils::transposed_pack_xor(&x1, &x3);
-        // 2 * c
-        x1x3.pop().expect("Enough elements present");
-        x2x3.pop().expect("Enough elements present");
-        x3.pop().expect("Enough elements present");
-        let mut c = self.transposed_pack_and(x1x3, x2x3).await?;
-        utils::transposed_pack_xor_assign(&mut c, &x3);
-
-        // Add 2c + s via a ripple carry adder
-        // LSB of c is 0
-        // First round: half adder can be skipped due to LSB of c being 0
-        let mut a = s;
-        let mut b = c;
-
-        // First full adder
-        let mut c =
-            <Self as BinaryMpcTrait<u128, Share<u128>>>::and_many(self, &a[1], &b[0]).await?;
-
-        // For last round
-        let a_msb = a.pop().expect("Enough elements present");
-        let b_msb = b.pop().expect("Enough elements present");
-
-        // 2 -> k-1
-        for (a_, b_) in a.into_iter().skip(2).zip(b.into_iter().skip(1)) {
-            let tmp_a = <Self as BinaryMpcTrait<u128, Share<u128>>>::xor_many(a_, c.to_owned())?;
-            let tmp_b = <Self as BinaryMpcTrait<u128, Share<u128>>>::xor_many(b_, c.to_owned())?;
-            let tmp_c =
-                <Self as BinaryMpcTrait<u128, Share<u128>>>::and_many(self, &tmp_a, &tmp_b).await?;
-            c = <Self as BinaryMpcTrait<u128, Share<u128>>>::xor_many(tmp_c, c)?;
+        for x_ in a {
+            let (x1_, x2_, x3_) = self.a2b_pre(x_);
+            x1.push(x1_);
+            x2.push(x2_);
+            x3.push(x3_);
         }
 
-        let res = <Self as BinaryMpcTrait<u128, Share<u128>>>::xor_many(a_msb, b_msb)?;
-        let res = <Self as BinaryMpcTrait<u128, Share<u128>>>::xor_many(res, c)?;
-
-        // Extract bits for outputs
-        let mut res = res
-            .into_iter()
-            .flat_map(|t| t.to_bits())
-            .collect::<Vec<_>>();
-        res.resize(len, Share::default());
+        let res = match self.adder_strategy {
+            AdderStrategy::KoggeStone => {
+                let sum =
+                    <Self as BinaryMpcTrait<T, Share<T>>>::binary_add_3_many(self, x1, x2, x3)
+                        .await?;
+                utils::transpose_pack_u128::<T>(sum)
+                    .pop()
+                    .expect("Enough elements present")
+            }
+            strategy => {
+                adder::msb_many(
+                    self,
+                    strategy,
+                    utils::transpose_pack_u128::<T>(x1),
+                    utils::transpose_pack_u128::<T>(x2),
+                    utils::transpose_pack_u128::<T>(x3),
+                )
+                .await?
+            }
+        };
 
-        Ok(res)
+        // The outputs are already bitsliced, the padding are shares of zero
+        Ok(PackedBits::from_words(res, len))
     }
 }
 
@@ -283,6 +474,35 @@ where
         Ok(self.network.print_connection_stats(out)?)
     }
 
+    async fn verify_db_roots(&mut self, roots: &DbRoots) -> Result<(), Error> {
+        roots::exchange_db_roots(&mut self.network, roots).await
+    }
+
+    fn supports_sessions(&self) -> bool {
+        true
+    }
+
+    async fn fork(&mut self) -> Result<Option<Self>, Error> {
+        let network = match self.network.fork().await {
+            Ok(network) => network,
+            Err(err) if err.kind() == io::ErrorKind::Unsupported => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+
+        // Preprocessed material stays with this session. The new one starts without DCF keys and
+        // edaBits and generates them inline when required, which costs additional rounds, so
+        // precompute them on the new session where this matters
+        Ok(Some(Self {
+            network,
+            prf: self.prf.fork(),
+            msb_variant: self.msb_variant,
+            adder_strategy: self.adder_strategy,
+            fss_material: HashMap::new(),
+            edabits: EdaBits::default(),
+            pool: self.pool.to_owned(),
+        }))
+    }
+
     async fn input(&mut self, input: Option<T>, id: usize) -> Result<Share<T>, Error> {
         if id >= self.network.get_num_parties() {
             return Err(Error::IdError(id));
@@ -303,6 +523,39 @@ where
         Ok(Share::new(share_a, share_b))
     }
 
+    async fn input_many(
+        &mut self,
+        input: Option<Vec<T>>,
+        len: usize,
+        id: usize,
+    ) -> Result<Vec<Share<T>>, Error> {
+        if id >= self.network.get_num_parties() {
+            return Err(Error::IdError(id));
+        }
+
+        let mut shares_a = (0..len)
+            .map(|_| self.prf.gen_zero_share::<T>())
+            .collect::<Vec<_>>();
+        if id == self.network.get_id() {
+            let input = input.ok_or(Error::ValueError("Cannot share None".to_string()))?;
+            if input.len() != len {
+                return Err(Error::InvalidSizeError);
+            }
+            for (share, value) in shares_a.iter_mut().zip(input) {
+                *share += value.to_sharetype();
+            }
+        }
+
+        // Network: reshare
+        let shares_b = utils::send_slice_and_receive_iter(&mut self.network, &shares_a).await?;
+
+        Ok(shares_a
+            .into_iter()
+            .zip(shares_b)
+            .map(|(a, b)| Share::new(a, b))
+            .collect())
+    }
+
     #[cfg(test)]
     async fn input_all(&mut self, input: T) -> Result<Vec<Share<T>>, Error> {
         let mut shares_a = Vec::with_capacity(3);
@@ -360,15 +613,17 @@ where
         Ok((share.a ^ share.b ^ c).convert().convert())
     }
 
-    async fn open_bit_many(&mut self, shares: Vec<Share<Bit>>) -> Result<Vec<bool>, Error> {
-        let shares_b = shares.iter().map(|s| &s.b);
+    async fn open_bit_many(&mut self, shares: PackedBits<Share<Bit>>) -> Result<Vec<bool>, Error> {
+        // Open the packed words
+        let words = shares.words();
+        let shares_b = words.iter().map(|s| &s.b);
         let shares_c = utils::send_iter_and_receive_iter(&mut self.network, shares_b).await?;
-        let res = shares
+        let res = words
             .iter()
             .zip(shares_c)
-            .map(|(s, c)| (c ^ &s.a ^ &s.b).convert().convert())
+            .map(|(s, c)| (c ^ &s.a ^ &s.b).convert())
             .collect();
-        Ok(res)
+        Ok(PackedBits::open_words(res, shares.len()))
     }
 
     fn add(&self, a: Share<T>, b: Share<T>) -> Share<T> {
@@ -421,7 +676,7 @@ where
 
         let rand = self.prf.gen_zero_share::<T>();
         let mut c = Share::new(rand, T::zero().to_sharetype());
-        for (a_, b_) in a.into_iter().zip(b.into_iter()) {
+        for (a_, b_) in a.into_iter().zip(b) {
             c += a_ * b_;
         }
 
@@ -465,31 +720,47 @@ where
         Ok(res)
     }
 
-    async fn masked_dot_many(
+    async fn mul_many(
         &mut self,
-        a: &Vec<Share<T>>,
-        b: &[Vec<Share<T>>],
-        masks: &[IrisCodeArray],
+        a: Vec<Share<T>>,
+        b: Vec<Share<T>>,
     ) -> Result<Vec<Share<T>>, Error> {
-        if a.len() != IrisCodeArray::IRIS_CODE_SIZE {
+        if a.len() != b.len() {
             return Err(Error::InvalidSizeError);
         }
+        Aby3::mul_many(self, a, b).await
+    }
 
-        let mut shares_a = Vec::with_capacity(a.len());
+    async fn masked_dot_many<D>(
+        &mut self,
+        a: &Vec<Share<T>>,
+        b: &[D],
+        masks: &[IrisCodeArray],
+    ) -> Result<Vec<Share<T>>, Error>
+    where
+        D: Borrow<VecSlice<Share<T>>> + Sync,
+    {
+        if a.len() != IrisCodeArray::IRIS_CODE_SIZE
+            || b.len() != masks.len()
+            || b.iter().any(|b_| b_.borrow().len() != a.len())
+        {
+            return Err(Error::InvalidSizeError);
+        }
 
-        for (b_, mask_) in b.iter().zip(masks.iter()) {
-            let mut rand = self.prf.gen_zero_share::<T>();
-            if a.len() != b_.len() {
-                return Err(Error::InvalidSizeError);
-            }
-            for ((a__, b__), bit) in a.iter().zip(b_.iter()).zip(mask_.bits()) {
+        // The randomness is drawn in order, the local dot products are computed in parallel
+        let rands = (0..b.len())
+            .map(|_| self.prf.gen_zero_share::<T>())
+            .collect_vec();
+        let shares_a = utils::par_map(self.pool.as_deref(), b.len(), |i| {
+            let mut rand = rands[i].to_owned();
+            for ((a__, b__), bit) in a.iter().zip(b[i].borrow().iter()).zip(masks[i].bits()) {
                 // only aggregate if mask is set
                 if bit {
                     rand += (a__.clone() * b__).a; // TODO: check if we can allow ref * ref ops in RingImpl
                 }
             }
-            shares_a.push(rand);
-        }
+            rand
+        });
 
         // Network: reshare
         let shares_b = utils::send_slice_and_receive_iter(&mut self.network, &shares_a).await?;
@@ -504,30 +775,40 @@ where
     }
 
     async fn get_msb(&mut self, a: Share<T>) -> Result<Share<Bit>, Error> {
-        let bits = self.arithmetic_to_binary(a).await?;
-        Ok(bits.get_msb())
+        match self.msb_variant {
+            MsbVariant::Fss => {
+                let mut res = self.msb_fss_many(vec![a]).await?;
+                return Ok(res.pop().expect("One output"));
+            }
+            MsbVariant::EdaBits => {
+                let mut res = self.msb_edabits_many(vec![a]).await?;
+                return Ok(res.pop().expect("One output"));
+            }
+            MsbVariant::Adder => {}
+        }
+        let res = self.msb_adder_many(vec![a]).await?;
+        Ok(res.into_iter().next().expect("One output"))
     }
 
-    async fn get_msb_many(&mut self, a: Vec<Share<T>>) -> Result<Vec<Share<Bit>>, Error> {
-        // TODO one can switch adder here
-
-        // let bits = self.arithmetic_to_binary_many(a).await?;
-        // let res = bits.into_iter().map(|a| a.get_msb()).collect();
-        // Ok(res)
-
-        let len = a.len();
-        let mut x1 = Vec::with_capacity(len);
-        let mut x2 = Vec::with_capacity(len);
-        let mut x3 = Vec::with_capacity(len);
-
-        for x_ in a {
-            let (x1_, x2_, x3_) = self.a2b_pre(x_);
-            x1.push(x1_);
-            x2.push(x2_);
-            x3.push(x3_);
+    async fn get_msb_many(&mut self, a: Vec<Share<T>>) -> Result<PackedBits<Share<Bit>>, Error> {
+        match self.msb_variant {
+            MsbVariant::Fss => return Ok(self.msb_fss_many(a).await?.into_iter().collect()),
+            MsbVariant::EdaBits => {
+                return Ok(self.msb_edabits_many(a).await?.into_iter().collect())
+            }
+            MsbVariant::Adder => {}
         }
 
-        self.msb_adder_many(x1, x2, x3).await
+        self.msb_adder_many(a).await
+    }
+
+    fn msb_and_gates(&self, amount: usize) -> usize {
+        let per_value = match self.msb_variant {
+            MsbVariant::Adder => self.adder_strategy.msb_and_gates(T::Share::K),
+            MsbVariant::Fss => 0,
+            MsbVariant::EdaBits => edabits::msb_and_gates(T::Share::K),
+        };
+        amount * per_value
     }
 
     async fn binary_or(&mut self, a: Share<Bit>, b: Share<Bit>) -> Result<Share<Bit>, Error> {
@@ -536,10 +817,10 @@ where
 
     async fn reduce_binary_or(
         &mut self,
-        a: Vec<Share<Bit>>,
+        a: PackedBits<Share<Bit>>,
         chunk_size: usize,
     ) -> Result<Share<Bit>, Error> {
-        let packed = self.pack(a);
+        let packed = a.into_words();
         let reduced = utils::or_tree::<u128, _, _>(self, packed, chunk_size).await?;
         self.reduce_or_u128(reduced).await
     }
@@ -592,11 +873,6 @@ where
         Ok(res)
     }
 
-    async fn arithmetic_to_binary(&mut self, x: Share<T>) -> Result<Share<T>, Error> {
-        let (x1, x2, x3) = self.a2b_pre(x);
-        self.binary_add_3(x1, x2, x3).await
-    }
-
     async fn arithmetic_to_binary_many(
         &mut self,
         x: Vec<Share<T>>,
@@ -27,6 +27,12 @@ impl Prf {
         }
     }
 
+    /// Derives the PRF of a new session. Since the PRFs of the parties are correlated, the derived
+    /// PRFs are correlated as well.
+    pub(crate) fn fork(&mut self) -> Self {
+        Self::new(self.my_prf.gen(), self.next_prf.gen())
+    }
+
     pub fn gen_seed() -> PrfSeed {
         let mut rng = ChaCha12Rng::from_entropy();
         rng.gen::<PrfSeed>()
@@ -1,9 +1,10 @@
 use super::id::PartyID;
 use crate::{
-    traits::share_trait::ShareTrait,
+    traits::share_trait::{BitShareTrait, ShareTrait},
     types::{
         bit::Bit,
         int_ring::IntRing2k,
+        packed_bits::{get_ring_bit, set_ring_bit},
         ring_element::{RingElement, RingImpl},
         sharable::Sharable,
     },
@@ -19,7 +20,9 @@ use std::{
 };
 
 // share x = x1 + x2 + x3 where party i has (xi, x{i-1})
+// The C layout is relied upon by `unsafe impl FileShare for Aby3Share` in db/share_file.rs
 #[derive(Clone, Debug, PartialEq, Default, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[repr(C)]
 pub struct Share<T: Sharable> {
     pub(crate) a: T::Share,
     pub(crate) b: T::Share,
@@ -30,6 +33,19 @@ impl<T: Sharable> ShareTrait for Share<T> {
     type VecShare = Vec<Self>;
 }
 
+impl BitShareTrait for Share<Bit> {
+    type Word = Share<u128>;
+
+    fn get_bit(word: &Share<u128>, index: usize) -> Self {
+        Share::new(get_ring_bit(&word.a, index), get_ring_bit(&word.b, index))
+    }
+
+    fn set_bit(word: &mut Share<u128>, index: usize, bit: Self) {
+        set_ring_bit(&mut word.a, index, bit.a);
+        set_ring_bit(&mut word.b, index, bit.b);
+    }
+}
+
 impl<T: Sharable> Share<T> {
     pub fn new(a: T::Share, b: T::Share) -> Self {
         Share {
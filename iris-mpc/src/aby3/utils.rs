@@ -14,10 +14,12 @@ use crate::{
 };
 use bytes::{Buf, Bytes, BytesMut};
 use num_traits::{AsPrimitive, One, Zero};
+use rayon::{prelude::*, ThreadPool};
 use std::{
     io::Error as IOError,
     ops::{BitXor, BitXorAssign},
 };
+use tokio::runtime::{Handle, RuntimeFlavor};
 
 pub(crate) fn bytes_to_seed(mut bytes: BytesMut) -> Result<PrfSeed, Error> {
     if bytes.len() != 32 {
@@ -31,6 +33,40 @@ pub(crate) fn bytes_to_seed(mut bytes: BytesMut) -> Result<PrfSeed, Error> {
     }
 }
 
+/// Computes `f(i)` for `i` in `0..len`, where each thread of `pool` handles a contiguous chunk of
+/// the indices. Without a pool, everything is computed on the calling thread.
+///
+/// Waiting for the pool blocks the calling thread. On a multi-threaded tokio runtime, the worker
+/// hands its other tasks to the remaining workers while it waits (see
+/// [`tokio::task::block_in_place`]), on a current-thread runtime the whole runtime waits.
+pub(crate) fn par_map<R: Send>(
+    pool: Option<&ThreadPool>,
+    len: usize,
+    f: impl Fn(usize) -> R + Send + Sync,
+) -> Vec<R> {
+    match pool {
+        Some(pool) if len > 1 => {
+            let chunk_size = len.div_ceil(pool.current_num_threads());
+            let compute = || {
+                pool.install(|| {
+                    (0..len)
+                        .into_par_iter()
+                        .with_min_len(chunk_size)
+                        .map(f)
+                        .collect()
+                })
+            };
+            match Handle::try_current() {
+                Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+                    tokio::task::block_in_place(compute)
+                }
+                _ => compute(),
+            }
+        }
+        _ => (0..len).map(f).collect(),
+    }
+}
+
 pub(crate) fn ceil_log2(x: usize) -> usize {
     let mut y = 0;
     let mut x = x - 1;
@@ -114,7 +150,7 @@ pub(crate) async fn send_slice_and_receive_bytes<N: NetworkTrait, R: RingImpl>(
 #[allow(unused)]
 pub(crate) async fn send_iter_and_receive_vec<'a, N: NetworkTrait, R: RingImpl + 'a>(
     network: &mut N,
-    values: impl Iterator<Item = &'a R> + ExactSizeIterator,
+    values: impl ExactSizeIterator<Item = &'a R>,
 ) -> Result<Vec<R>, Error> {
     let len = values.len();
     let response = send_and_receive(network, ring_iter_to_bytes(values)).await?;
@@ -123,7 +159,7 @@ pub(crate) async fn send_iter_and_receive_vec<'a, N: NetworkTrait, R: RingImpl +
 
 pub(crate) async fn send_iter_and_receive_iter<'a, N: NetworkTrait, R: RingImpl + 'a>(
     network: &mut N,
-    values: impl Iterator<Item = &'a R> + ExactSizeIterator,
+    values: impl ExactSizeIterator<Item = &'a R>,
 ) -> Result<RingBytesIter<R>, Error> {
     let len = values.len();
     let response = send_and_receive(network, ring_iter_to_bytes(values)).await?;
@@ -189,7 +225,6 @@ pub(crate) async fn send_vec_next<N: NetworkTrait, R: RingImpl>(
     Ok(network.send_next_id(ring_slice_to_bytes(value)).await?)
 }
 
-#[allow(dead_code)]
 pub(crate) async fn send_vec_prev<N: NetworkTrait, R: RingImpl>(
     network: &mut N,
     value: &[R],
@@ -214,7 +249,6 @@ pub(crate) async fn receive_vec_prev<N: NetworkTrait, R: RingImpl>(
     ring_vec_from_bytes(response, len)
 }
 
-#[allow(dead_code)]
 pub(crate) async fn receive_vec_next<N: NetworkTrait, R: RingImpl>(
     network: &mut N,
     len: usize,
@@ -241,7 +275,7 @@ pub(crate) fn ring_iter_from_bytes<T>(bytes: BytesMut, n: usize) -> Result<RingB
 where
     T: RingImpl,
 {
-    if bytes.remaining() != n * ((T::K + 7) / 8) {
+    if bytes.remaining() != n * T::K.div_ceil(8) {
         return Err(Error::ConversionError);
     }
 
@@ -268,7 +302,7 @@ impl<T: RingImpl> Iterator for RingBytesIter<T> {
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        let len = self.bytes.remaining() / ((T::K + 7) / 8);
+        let len = self.bytes.remaining() / T::K.div_ceil(8);
         (len, Some(len))
     }
 }
@@ -287,11 +321,9 @@ where
     out.freeze()
 }
 
-pub(crate) fn ring_iter_to_bytes<'a, T: 'a>(
-    iter: impl Iterator<Item = &'a T> + ExactSizeIterator,
-) -> Bytes
+pub(crate) fn ring_iter_to_bytes<'a, T>(iter: impl ExactSizeIterator<Item = &'a T>) -> Bytes
 where
-    T: RingImpl,
+    T: RingImpl + 'a,
 {
     let size = T::K / 8 + ((T::K % 8) != 0) as usize;
     let mut out = BytesMut::with_capacity(size * iter.len());
@@ -301,7 +333,7 @@ where
     out.freeze()
 }
 
-pub(crate) async fn or_tree<T, Mpc, Share: ShareTrait>(
+pub(crate) async fn or_tree<T, Mpc, Share>(
     engine: &mut Mpc,
     mut inputs: Share::VecShare,
     chunk_size: usize,
@@ -309,7 +341,8 @@ pub(crate) async fn or_tree<T, Mpc, Share: ShareTrait>(
 where
     T: Sharable,
     Mpc: BinaryMpcTrait<T, Share>,
-    Share: Clone
+    Share: ShareTrait
+        + Clone
         + BitXorAssign
         + BitXor<Output = Share>
         + std::ops::ShlAssign<u32>
@@ -348,11 +381,9 @@ where
     Ok(output)
 }
 
-pub(crate) fn split<T: IntRing2k, U: IntRing2k>(
-    a: RingElement<T>,
-) -> (RingElement<U>, RingElement<U>)
+pub(crate) fn split<T, U: IntRing2k>(a: RingElement<T>) -> (RingElement<U>, RingElement<U>)
 where
-    T: AsPrimitive<U>,
+    T: IntRing2k + AsPrimitive<U>,
 {
     debug_assert_eq!(T::K, 2 * U::K);
     let shift = U::K;
@@ -384,7 +415,7 @@ pub(crate) fn transpose_shared_input<T: Sharable, U: Sharable>(
 }
 
 pub(crate) fn transpose_pack_u128<T: Sharable>(x: Vec<Aby3Share<T>>) -> Vec<Vec<Aby3Share<u128>>> {
-    let len = (x.len() + 127) / 128;
+    let len = x.len().div_ceil(128);
     let mut res = vec![Vec::with_capacity(len); T::Share::K];
 
     for x in x.chunks(128) {
@@ -396,37 +427,3 @@ pub(crate) fn transpose_pack_u128<T: Sharable>(x: Vec<Aby3Share<T>>) -> Vec<Vec<
     }
     res
 }
-
-pub(crate) fn transposed_pack_xor(
-    x1: &[Vec<Aby3Share<u128>>],
-    x2: &[Vec<Aby3Share<u128>>],
-) -> Vec<Vec<Aby3Share<u128>>> {
-    let len = x1.len();
-    debug_assert_eq!(len, x2.len());
-
-    let mut res = Vec::with_capacity(len);
-    for (x1, x2) in x1.iter().zip(x2.iter()) {
-        debug_assert_eq!(x1.len(), x2.len());
-        let mut v = Vec::with_capacity(x1.len());
-        for (x1, x2) in x1.iter().cloned().zip(x2.iter()) {
-            v.push(x1 ^ x2);
-        }
-        res.push(v);
-    }
-    res
-}
-
-pub(crate) fn transposed_pack_xor_assign(
-    x1: &mut [Vec<Aby3Share<u128>>],
-    x2: &[Vec<Aby3Share<u128>>],
-) {
-    let len = x1.len();
-    debug_assert_eq!(len, x2.len());
-
-    for (x1, x2) in x1.iter_mut().zip(x2.iter()) {
-        debug_assert_eq!(x1.len(), x2.len());
-        for (x1, x2) in x1.iter_mut().zip(x2.iter()) {
-            *x1 ^= x2;
-        }
-    }
-}
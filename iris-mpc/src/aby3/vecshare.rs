@@ -1,10 +1,12 @@
+use bytes::Bytes;
 use num_traits::Zero;
 
-use super::share::Share;
+use super::{share::Share, utils};
 use crate::{prelude::Error, traits::share_trait::VecShareTrait, types::sharable::Sharable};
 
 impl<T: Sharable> VecShareTrait for Vec<Share<T>> {
     type Share = Share<T>;
+    type Slice = [Share<T>];
 
     fn len(&self) -> usize {
         Vec::len(self)
@@ -12,7 +14,7 @@ impl<T: Sharable> VecShareTrait for Vec<Share<T>> {
 
     fn filter_reduce_add_twice(
         a: &Self,
-        b: &Self,
+        b: &Self::Slice,
         mask: &plain_reference::IrisCodeArray,
     ) -> Result<(Self::Share, Self::Share), Error> {
         if a.is_empty() || a.len() != b.len() {
@@ -97,4 +99,11 @@ impl<T: Sharable> VecShareTrait for Vec<Share<T>> {
     fn set_at(&mut self, index: usize, value: Self::Share) {
         self[index] = value;
     }
+
+    fn replicated_components(slice: &Self::Slice) -> Result<[Vec<Bytes>; 2], Error> {
+        Ok([
+            vec![utils::ring_iter_to_bytes(slice.iter().map(|s| &s.a))],
+            vec![utils::ring_iter_to_bytes(slice.iter().map(|s| &s.b))],
+        ])
+    }
 }
@@ -2,13 +2,19 @@ use crate::aby3::id::PartyID;
 use crate::aby3::random::prf::{Prf, PrfSeed};
 use crate::aby3::share::Share;
 use crate::aby3::utils;
+use crate::adder::{self, AdderStrategy};
+use crate::blame::{self, JmpQueue, JmpTranscript};
 use crate::commitment::{CommitOpening, Commitment};
+use crate::db::roots::{self, DbRoots};
+use crate::edabits::{self, EdaBits};
 use crate::error::Error;
+use crate::fss::msb::MsbVariant;
 use crate::traits::binary_trait::BinaryMpcTrait;
 use crate::traits::mpc_trait::MpcTrait;
 use crate::traits::network_trait::NetworkTrait;
 use crate::traits::security::MaliciousAbort;
 use crate::types::bit::Bit;
+use crate::types::packed_bits::PackedBits;
 use crate::types::ring_element::{RingElement, RingImpl};
 use crate::types::sharable::Sharable;
 use bytes::{Bytes, BytesMut};
@@ -17,15 +23,36 @@ use num_traits::Zero;
 use rand::distributions::{Distribution, Standard};
 use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha12Rng;
-use sha2::digest::Output;
 use sha2::{Digest, Sha512};
 use std::ops::{BitAnd, Mul, MulAssign};
 
+macro_rules! reduce_or {
+    ($([$typ_a:ident, $typ_b:ident,$name_a:ident,$name_b:ident]),*) => {
+        $(
+            async fn $name_a(&mut self, a: Share<$typ_a>) -> Result<Share<Bit>, Error> {
+                let (a, b) = a.get_ab();
+                let (a1, a2) = utils::split::<$typ_a, $typ_b>(a);
+                let (b1, b2) = utils::split::<$typ_a, $typ_b>(b);
+
+                let share_a = Share::new(a1, b1);
+                let share_b = Share::new(a2, b2);
+
+                let out = <Self as BinaryMpcTrait::<$typ_b, Share<$typ_b>>>::or(self, share_a, share_b).await?;
+                self.$name_b(out).await
+            }
+        )*
+    };
+}
+
 pub struct MalAby3<N: NetworkTrait> {
     network: N,
     prf: Prf,
-    send_queue_prev: BytesMut,
-    rcv_queue_next: BytesMut,
+    send_queue_next: JmpQueue,
+    send_queue_prev: JmpQueue,
+    rcv_queue_next: JmpQueue,
+    msb_variant: MsbVariant,
+    adder_strategy: AdderStrategy,
+    edabits: EdaBits,
 }
 
 impl<N: NetworkTrait> MaliciousAbort for MalAby3<N> {}
@@ -33,41 +60,100 @@ impl<N: NetworkTrait> MaliciousAbort for MalAby3<N> {}
 impl<N: NetworkTrait> MalAby3<N> {
     pub fn new(network: N) -> Self {
         let prf = Prf::default();
-        let send_queue_prev = BytesMut::new();
-        let rcv_queue_next = BytesMut::new();
+        let send_queue_next = JmpQueue::default();
+        let send_queue_prev = JmpQueue::default();
+        let rcv_queue_next = JmpQueue::default();
 
         Self {
             network,
             prf,
+            send_queue_next,
             send_queue_prev,
             rcv_queue_next,
+            msb_variant: MsbVariant::default(),
+            adder_strategy: AdderStrategy::default(),
+            edabits: EdaBits::default(),
         }
     }
 
+    /// Selects how the MSB is extracted in [`MpcTrait::get_msb_many`]. Has to be the same for all
+    /// parties. [`MsbVariant::Fss`] is not supported.
+    pub fn set_msb_variant(&mut self, variant: MsbVariant) -> Result<(), Error> {
+        if variant == MsbVariant::Fss {
+            return Err(Error::ConfigError);
+        }
+        self.msb_variant = variant;
+        Ok(())
+    }
+
+    pub fn get_msb_variant(&self) -> MsbVariant {
+        self.msb_variant
+    }
+
+    /// Selects the adder of [`MsbVariant::Adder`]. Has to be the same for all parties.
+    pub fn set_adder_strategy(&mut self, strategy: AdderStrategy) {
+        self.adder_strategy = strategy;
+    }
+
+    pub fn get_adder_strategy(&self) -> AdderStrategy {
+        self.adder_strategy
+    }
+
+    /// Preprocessing for [`MsbVariant::EdaBits`]: Generates `amount` edaBits for `T`.
+    pub async fn precompute_edabits<T: Sharable>(&mut self, amount: usize) -> Result<(), Error>
+    where
+        Standard: Distribution<T::Share>,
+        Share<T>: BitAnd<T::Share, Output = Share<T>>,
+        Share<T>: BitAnd<Share<T>, Output = Share<T>>,
+    {
+        let arithmetic = (0..amount).map(|_| self.prf.gen_rand::<T>()).collect_vec();
+        let binary = <Self as BinaryMpcTrait<T, Share<T>>>::arithmetic_to_binary_many(
+            self,
+            arithmetic.to_owned(),
+        )
+        .await?;
+        self.edabits.extend(arithmetic, binary);
+        Ok(())
+    }
+
+    // Starts the next round of JMPs (see [`JmpQueue`])
+    fn jmp_step(&mut self) {
+        self.send_queue_next.end_step();
+        self.send_queue_prev.end_step();
+        self.rcv_queue_next.end_step();
+    }
+
     #[inline(always)]
     async fn jmp_send<T: Sharable>(&mut self, send: T::Share) -> Result<(), Error> {
+        send.to_owned()
+            .add_to_bytes(self.send_queue_next.bytes_mut());
         utils::send_value_next(&mut self.network, send).await
     }
 
     #[inline(always)]
     fn jmp_buffer<T: Sharable>(&mut self, buffer: T::Share) {
-        buffer.add_to_bytes(&mut self.send_queue_prev);
+        buffer.add_to_bytes(self.send_queue_prev.bytes_mut());
     }
 
     #[inline(always)]
     async fn jmp_send_many<T: Sharable>(&mut self, send: &[T::Share]) -> Result<(), Error> {
+        for value in send {
+            value.add_to_bytes(self.send_queue_next.bytes_mut());
+        }
         utils::send_vec_next(&mut self.network, send).await
     }
 
     fn jmp_buffer_many<T: Sharable>(&mut self, buffer: &[T::Share]) {
         for value in buffer {
-            value.add_to_bytes(&mut self.send_queue_prev);
+            value.add_to_bytes(self.send_queue_prev.bytes_mut());
         }
     }
 
     async fn jmp_receive<T: Sharable>(&mut self) -> Result<T::Share, Error> {
         let value: T::Share = utils::receive_value_prev(&mut self.network).await?;
-        value.to_owned().add_to_bytes(&mut self.rcv_queue_next);
+        value
+            .to_owned()
+            .add_to_bytes(self.rcv_queue_next.bytes_mut());
         Ok(value)
     }
 
@@ -75,7 +161,7 @@ impl<N: NetworkTrait> MalAby3<N> {
         let values: Vec<T::Share> = utils::receive_vec_prev(&mut self.network, len).await?;
 
         for value in values.iter() {
-            value.add_to_bytes(&mut self.rcv_queue_next);
+            value.add_to_bytes(self.rcv_queue_next.bytes_mut());
         }
 
         Ok(values)
@@ -86,6 +172,7 @@ impl<N: NetworkTrait> MalAby3<N> {
         send: T::Share,
         buffer: T::Share,
     ) -> Result<T::Share, Error> {
+        self.jmp_step();
         self.jmp_buffer::<T>(buffer);
         self.jmp_send::<T>(send).await?;
         self.jmp_receive::<T>().await
@@ -97,26 +184,24 @@ impl<N: NetworkTrait> MalAby3<N> {
         buffer: &[T::Share],
     ) -> Result<Vec<T::Share>, Error> {
         let len = send.len();
+        self.jmp_step();
         self.jmp_buffer_many::<T>(buffer);
         self.jmp_send_many::<T>(send).await?;
         self.jmp_receive_many::<T>(len).await
     }
 
     async fn jmp_verify(&mut self) -> Result<(), Error> {
-        let send_prev = clear_and_hash(&mut self.send_queue_prev);
-        let hash_next = clear_and_hash(&mut self.rcv_queue_next);
+        let id = self.network.get_id();
+        let next_id = (id + 1) % 3;
+        let prev_id = (id + 2) % 3;
 
-        self.network
-            .send_prev_id(Bytes::from(send_prev.to_vec()))
-            .await?;
+        // The values are received from prev, the hash from next
+        let mut transcript = JmpTranscript::default();
+        transcript.sent_values[next_id] = Some(self.send_queue_next.finish());
+        transcript.sent_hashes[prev_id] = Some(self.send_queue_prev.finish());
+        transcript.received_values[next_id] = Some(self.rcv_queue_next.finish());
 
-        let rcv_next = self.network.receive_next_id().await?;
-
-        if rcv_next.as_ref() != hash_next.as_slice() {
-            return Err(Error::JmpVerifyError);
-        }
-
-        Ok(())
+        blame::jmp_verify(&mut self.network, &transcript).await
     }
 
     async fn coin<R: Rng + SeedableRng>(&mut self) -> Result<R::Seed, Error>
@@ -136,7 +221,7 @@ impl<N: NetworkTrait> MalAby3<N> {
         for (s1, (s2, s3)) in seed1
             .as_mut()
             .iter_mut()
-            .zip(seed2.as_ref().iter().zip(seed3.into_iter()))
+            .zip(seed2.as_ref().iter().zip(seed3))
         {
             *s1 ^= s3.0 ^ s2;
         }
@@ -168,7 +253,7 @@ impl<N: NetworkTrait> MalAby3<N> {
 
         let res: Vec<Share<T>> = shares_a
             .into_iter()
-            .zip(shares_b.into_iter())
+            .zip(shares_b)
             .map(|(a_, b_)| Share::new(a_, b_))
             .collect();
 
@@ -189,12 +274,12 @@ impl<N: NetworkTrait> MalAby3<N> {
 
         let mut shares_a = Vec::with_capacity(a.len());
 
-        for (a_, b_) in a.into_iter().zip(b.into_iter()) {
+        for (a_, b_) in a.into_iter().zip(b) {
             let mut rand = self.prf.gen_zero_share::<T>();
             if a_.len() != b_.len() {
                 return Err(Error::InvalidSizeError);
             }
-            for (a__, b__) in a_.into_iter().zip(b_.into_iter()) {
+            for (a__, b__) in a_.into_iter().zip(b_) {
                 rand += (a__ * b__).a;
             }
             shares_a.push(rand);
@@ -205,7 +290,7 @@ impl<N: NetworkTrait> MalAby3<N> {
 
         let res = shares_a
             .into_iter()
-            .zip(shares_b.into_iter())
+            .zip(shares_b)
             .map(|(a_, b_)| Share::new(a_, b_))
             .collect();
 
@@ -363,7 +448,7 @@ impl<N: NetworkTrait> MalAby3<N> {
                 for ((b_, v_), (c_0, c_1)) in b_mul
                     .into_iter()
                     .take(len)
-                    .zip(v.into_iter())
+                    .zip(v)
                     .zip(cs.iter().take(len).zip(cs.iter().skip(len)))
                 {
                     let w_ = b_ * v_.to_sharetype() - c_0.to_owned() * &r + c_1.to_owned();
@@ -380,7 +465,7 @@ impl<N: NetworkTrait> MalAby3<N> {
                 for ((b_, v_), (c_0, c_1)) in b_mul
                     .into_iter()
                     .take(len)
-                    .zip(v.into_iter())
+                    .zip(v)
                     .zip(cs.iter().take(len).zip(cs.iter().skip(len)))
                 {
                     let w_ = b_ * v_.to_sharetype() - c_0.to_owned() * &r + c_1.to_owned();
@@ -397,7 +482,7 @@ impl<N: NetworkTrait> MalAby3<N> {
                 for ((b_, v_), (c_0, c_1)) in b_mul
                     .into_iter()
                     .take(len)
-                    .zip(v.into_iter())
+                    .zip(v)
                     .zip(cs.iter().take(len).zip(cs.iter().skip(len)))
                 {
                     let w_ = b_ * v_.to_sharetype() - c_0.to_owned() * &r + c_1.to_owned();
@@ -482,7 +567,7 @@ impl<N: NetworkTrait> MalAby3<N> {
 
         let r = rng.gen::<UShare<T>>();
         let mut v_ = a;
-        for (des, a_) in v_.iter_mut().zip(a_.into_iter()) {
+        for (des, a_) in v_.iter_mut().zip(a_) {
             *des *= &r;
             *des -= a_;
         }
@@ -491,7 +576,7 @@ impl<N: NetworkTrait> MalAby3<N> {
         self.jmp_verify().await?;
 
         let mut w = -cs[0].to_owned() * r + &cs[1];
-        for (v, b) in v.into_iter().zip(b.into_iter()) {
+        for (v, b) in v.into_iter().zip(b) {
             w += b.to_owned() * v.to_sharetype();
         }
 
@@ -600,8 +685,8 @@ impl<N: NetworkTrait> MalAby3<N> {
         let mut rng = R::from_seed(seed);
         let r = rng.gen::<UShare<T>>();
 
-        for (des, a_) in v_.iter_mut().zip(a_.into_iter()) {
-            for (des_, a__) in des.iter_mut().zip(a_.into_iter()) {
+        for (des, a_) in v_.iter_mut().zip(a_) {
+            for (des_, a__) in des.iter_mut().zip(a_) {
                 *des_ *= &r;
                 *des_ -= a__;
             }
@@ -801,17 +886,11 @@ impl<N: NetworkTrait> MalAby3<N> {
         let mut seed1_ = [0u8; 32];
         let mut seed2_ = [0u8; 32];
 
-        for (r, (a, b)) in seed1_
-            .iter_mut()
-            .zip(seed1.iter().zip(rcv_open1.into_iter()))
-        {
+        for (r, (a, b)) in seed1_.iter_mut().zip(seed1.iter().zip(rcv_open1)) {
             *r = b ^ a;
         }
 
-        for (r, (a, b)) in seed2_
-            .iter_mut()
-            .zip(seed2.iter().zip(rcv_open2.into_iter()))
-        {
+        for (r, (a, b)) in seed2_.iter_mut().zip(seed2.iter().zip(rcv_open2)) {
             *r = b ^ a;
         }
 
@@ -845,6 +924,41 @@ impl<N: NetworkTrait> MalAby3<N> {
         (x1, x2, x3)
     }
 
+    reduce_or!(
+        [u128, u64, reduce_or_u128, reduce_or_u64],
+        [u64, u32, reduce_or_u64, reduce_or_u32],
+        [u32, u16, reduce_or_u32, reduce_or_u16],
+        [u16, u8, reduce_or_u16, reduce_or_u8]
+    );
+
+    async fn reduce_or_u8(&mut self, a: Share<u8>) -> Result<Share<Bit>, Error> {
+        const K: usize = 8;
+
+        let mut decomp: Vec<Share<Bit>> = Vec::with_capacity(K);
+        for i in 0..K as u32 {
+            let bit_a = ((a.a.to_owned() >> i) & RingElement(1)) == RingElement(1);
+            let bit_b = ((a.b.to_owned() >> i) & RingElement(1)) == RingElement(1);
+
+            decomp.push(Share::new(
+                <Bit as Sharable>::Share::from(bit_a),
+                <Bit as Sharable>::Share::from(bit_b),
+            ));
+        }
+
+        let mut k = K;
+        while k != 1 {
+            k >>= 1;
+            decomp = <Self as BinaryMpcTrait<Bit, Share<Bit>>>::or_many(
+                self,
+                decomp[..k].to_vec(),
+                decomp[k..].to_vec(),
+            )
+            .await?;
+        }
+
+        Ok(decomp[0].to_owned())
+    }
+
     fn pack_exact<T: Sharable>(&self, a: Vec<Share<Bit>>) -> Share<T> {
         debug_assert!(a.len() <= T::Share::K);
         let mut share_a = T::Share::zero();
@@ -858,7 +972,7 @@ impl<N: NetworkTrait> MalAby3<N> {
     }
 
     fn pack<T: Sharable>(&self, a: Vec<Share<Bit>>) -> Vec<Share<T>> {
-        let outlen = (a.len() + T::Share::K - 1) / T::Share::K;
+        let outlen = a.len().div_ceil(T::Share::K);
         let mut out = Vec::with_capacity(outlen);
 
         for a_ in a.chunks(T::Share::K) {
@@ -898,7 +1012,7 @@ impl<N: NetworkTrait> MalAby3<N> {
 
         let res = shares
             .iter()
-            .zip(shares_c.into_iter())
+            .zip(shares_c)
             .map(|(s, c)| T::from_sharetype(c + &s.a + &s.b))
             .collect();
         Ok(res)
@@ -925,7 +1039,7 @@ impl<N: NetworkTrait> MalAby3<N> {
 
         let res = shares
             .iter()
-            .zip(shares_c.into_iter())
+            .zip(shares_c)
             .map(|(s, c)| T::from_sharetype(c ^ &s.a ^ &s.b))
             .collect();
         Ok(res)
@@ -943,6 +1057,7 @@ impl<N: NetworkTrait> MalAby3<N> {
         let my_id = self.network.get_id();
         let sender = (id + 2) % 3;
 
+        self.jmp_step();
         if my_id == id {
             let c = self.jmp_receive::<T>().await?;
             Ok(Some(T::from_sharetype(share.a + share.b + c)))
@@ -954,6 +1069,53 @@ impl<N: NetworkTrait> MalAby3<N> {
             Ok(None)
         }
     }
+
+    // Computes the MSBs with the adder circuit selected by the adder strategy
+    async fn msb_adder_many<T: Sharable>(
+        &mut self,
+        a: Vec<Share<T>>,
+    ) -> Result<PackedBits<Share<Bit>>, Error>
+    where
+        Standard: Distribution<T::Share>,
+        Share<T>: BitAnd<T::Share, Output = Share<T>>,
+        Share<T>: BitAnd<Share<T>, Output = Share<T>>,
+    {
+        let len = a.len();
+        let mut x1 = Vec::with_capacity(len);
+        let mut x2 = Vec::with_capacity(len);
+        let mut x3 = Vec::with_capacity(len);
+
+        for x_ in a {
+            let (x1_, x2_, x3_) = self.a2b_pre(x_);
+            x1.push(x1_);
+            x2.push(x2_);
+            x3.push(x3_);
+        }
+
+        let res = match self.adder_strategy {
+            AdderStrategy::KoggeStone => {
+                let sum =
+                    <Self as BinaryMpcTrait<T, Share<T>>>::binary_add_3_many(self, x1, x2, x3)
+                        .await?;
+                utils::transpose_pack_u128::<T>(sum)
+                    .pop()
+                    .expect("Enough elements present")
+            }
+            strategy => {
+                adder::msb_many(
+                    self,
+                    strategy,
+                    utils::transpose_pack_u128::<T>(x1),
+                    utils::transpose_pack_u128::<T>(x2),
+                    utils::transpose_pack_u128::<T>(x3),
+                )
+                .await?
+            }
+        };
+
+        // The outputs are already bitsliced, the padding are shares of zero
+        Ok(PackedBits::from_words(res, len))
+    }
 }
 
 impl<N: NetworkTrait, T: Sharable> MpcTrait<T, Share<T>, Share<Bit>> for MalAby3<N>
@@ -997,6 +1159,10 @@ where
         Ok(self.network.print_connection_stats(out)?)
     }
 
+    async fn verify_db_roots(&mut self, roots: &DbRoots) -> Result<(), Error> {
+        roots::exchange_db_roots(&mut self.network, roots).await
+    }
+
     async fn input(&mut self, input: Option<T>, id: usize) -> Result<Share<T>, Error> {
         if id >= self.network.get_num_parties() {
             return Err(Error::IdError(id));
@@ -1022,8 +1188,9 @@ where
             let value = utils::receive_value_prev::<_, T::Share>(&mut self.network).await?;
             utils::send_value_next(&mut self.network, value.to_owned()).await?;
             let value_ = utils::receive_value_next(&mut self.network).await?;
+            // Either the input party or the forwarding party has cheated
             if value != value_ {
-                return Err(Error::VerifyError);
+                return Err(blame::conflict(id, (id + 2) % 3));
             }
             share.b += value;
             share
@@ -1032,7 +1199,7 @@ where
             utils::send_value_prev(&mut self.network, value.to_owned()).await?;
             let value_ = utils::receive_value_prev(&mut self.network).await?;
             if value != value_ {
-                return Err(Error::VerifyError);
+                return Err(blame::conflict(id, (id + 1) % 3));
             }
             share
         };
@@ -1096,7 +1263,7 @@ where
         self.jmp_verify().await?;
         let res = shares
             .iter()
-            .zip(shares_c.into_iter())
+            .zip(shares_c)
             .map(|(s, c)| T::from_sharetype(c + &s.a + &s.b))
             .collect();
         Ok(res)
@@ -1112,30 +1279,31 @@ where
         Ok((share.a ^ share.b ^ c).convert().convert())
     }
 
-    async fn open_bit_many(&mut self, shares: Vec<Share<Bit>>) -> Result<Vec<bool>, Error> {
+    async fn open_bit_many(&mut self, shares: PackedBits<Share<Bit>>) -> Result<Vec<bool>, Error> {
         self.jmp_verify().await?;
 
-        let len = shares.len();
-        let mut shares_a = Vec::with_capacity(len);
-        let mut shares_b = Vec::with_capacity(len);
+        // Open the packed words
+        let words = shares.words();
+        let mut shares_a = Vec::with_capacity(words.len());
+        let mut shares_b = Vec::with_capacity(words.len());
 
-        for share in shares.iter().cloned() {
+        for share in words.iter().cloned() {
             let (a, b) = share.get_ab();
             shares_a.push(a);
             shares_b.push(b);
         }
 
         let shares_c = self
-            .jmp_send_receive_many::<Bit>(&shares_b, &shares_a)
+            .jmp_send_receive_many::<u128>(&shares_b, &shares_a)
             .await?;
         self.jmp_verify().await?;
 
-        let res = shares
+        let res = words
             .iter()
-            .zip(shares_c.into_iter())
-            .map(|(s, c)| (c ^ &s.a ^ &s.b).convert().convert())
+            .zip(shares_c)
+            .map(|(s, c)| (c ^ &s.a ^ &s.b).convert())
             .collect();
-        Ok(res)
+        Ok(PackedBits::open_words(res, shares.len()))
     }
 
     fn add(&self, a: Share<T>, b: Share<T>) -> Share<T> {
@@ -1213,14 +1381,38 @@ where
     }
 
     async fn get_msb(&mut self, a: Share<T>) -> Result<Share<Bit>, Error> {
-        let bits = self.arithmetic_to_binary(a).await?;
-        Ok(bits.get_msb())
+        let res = self.get_msb_many(vec![a]).await?;
+        Ok(res.into_iter().next().expect("One output"))
     }
 
-    async fn get_msb_many(&mut self, a: Vec<Share<T>>) -> Result<Vec<Share<Bit>>, Error> {
-        let bits = self.arithmetic_to_binary_many(a).await?;
-        let res = bits.into_iter().map(|a| a.get_msb()).collect();
-        Ok(res)
+    async fn get_msb_many(&mut self, a: Vec<Share<T>>) -> Result<PackedBits<Share<Bit>>, Error> {
+        if self.msb_variant == MsbVariant::EdaBits {
+            let len = a.len();
+            let available = self.edabits.len::<T>();
+            if available < len {
+                tracing::warn!(
+                    "Party {}: Not enough edaBits, generating them inline",
+                    self.network.get_id()
+                );
+                self.precompute_edabits::<T>(len - available).await?;
+            }
+            let (r, r_bits) = self.edabits.take::<T>(len)?;
+            let masked = a.into_iter().zip(r).map(|(a_, r_)| a_ + r_).collect();
+            let c = self.open_many(masked).await?;
+            let id = PartyID::try_from(self.network.get_id())?;
+            let res =
+                edabits::msb_from_edabits::<T, _>(self, id, T::vec_to_sharetype(c), r_bits).await?;
+            return Ok(res.into_iter().collect());
+        }
+        self.msb_adder_many(a).await
+    }
+
+    fn msb_and_gates(&self, amount: usize) -> usize {
+        let per_value = match self.msb_variant {
+            MsbVariant::EdaBits => edabits::msb_and_gates(T::Share::K),
+            _ => self.adder_strategy.msb_and_gates(T::Share::K),
+        };
+        amount * per_value
     }
 
     async fn binary_or(&mut self, a: Share<Bit>, b: Share<Bit>) -> Result<Share<Bit>, Error> {
@@ -1229,11 +1421,12 @@ where
 
     async fn reduce_binary_or(
         &mut self,
-        a: Vec<Share<Bit>>,
+        a: PackedBits<Share<Bit>>,
         chunk_size: usize,
     ) -> Result<Share<Bit>, Error> {
-        let chunk_size = chunk_size * 128;
-        utils::or_tree::<Bit, _, _>(self, a, chunk_size).await
+        let packed = a.into_words();
+        let reduced = utils::or_tree::<u128, _, _>(self, packed, chunk_size).await?;
+        self.reduce_or_u128(reduced).await
     }
 
     async fn verify(&mut self) -> Result<(), Error> {
@@ -1323,11 +1516,6 @@ where
         Ok(res)
     }
 
-    async fn arithmetic_to_binary(&mut self, x: Share<T>) -> Result<Share<T>, Error> {
-        let (x1, x2, x3) = self.a2b_pre(x);
-        self.binary_add_3(x1, x2, x3).await
-    }
-
     async fn arithmetic_to_binary_many(
         &mut self,
         x: Vec<Share<T>>,
@@ -1346,12 +1534,3 @@ where
         self.binary_add_3_many(x1, x2, x3).await
     }
 }
-
-fn clear_and_hash(data: &mut BytesMut) -> Output<Sha512> {
-    let mut swap = BytesMut::new();
-    std::mem::swap(&mut swap, data);
-    let bytes = swap.freeze();
-    let mut hasher = Sha512::new();
-    hasher.update(bytes);
-    hasher.finalize()
-}
@@ -0,0 +1,391 @@
+use crate::{
+    aby3::utils::ceil_log2,
+    prelude::Error,
+    traits::{binary_trait::BinaryMpcTrait, share_trait::ShareTrait},
+};
+use std::ops::{BitXor, BitXorAssign, Shl, ShlAssign};
+
+/// Selects the adder circuit which adds the three binary shares of the arithmetic-to-binary
+/// conversion in [`MpcTrait::get_msb_many`](crate::prelude::MpcTrait::get_msb_many). The
+/// strategies trade communication rounds against AND gates, so the best choice depends on the
+/// network latency. Has to be the same for all parties.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AdderStrategy {
+    /// Sequential carry chain: K - 1 rounds with the fewest AND gates (2K - 3).
+    #[default]
+    RippleCarry,
+    /// Parallel prefix adder with log(K) + 2 rounds and the most AND gates. This is the packed
+    /// adder [`BinaryMpcTrait::binary_add_3_many`] of the arithmetic-to-binary conversion, which
+    /// computes all bits of the sum.
+    KoggeStone,
+    /// Parallel prefix adder with about 2 log(K) rounds and the fewest AND gates of the parallel
+    /// prefix adders.
+    BrentKung,
+    /// Parallel prefix adder with log(K) + 2 rounds and fewer AND gates than Kogge-Stone.
+    Sklansky,
+}
+
+impl AdderStrategy {
+    /// Returns the number of AND gates required to extract the MSB of one K-bit value.
+    pub fn msb_and_gates(&self, k: usize) -> usize {
+        debug_assert!(k >= 3);
+        match self {
+            // Full adder (K - 1) and one AND per bit of the carry chain (K - 2)
+            Self::RippleCarry => 2 * k - 3,
+            // Full adder, generate bits and two ANDs per bit and level, all on K bits
+            Self::KoggeStone => k * (2 + 2 * ceil_log2(k)),
+            _ => {
+                let n = k - 2;
+                let network = count_prefix_network(&self.schedule(n));
+                // Full adder, generate bits, prefix network
+                (k - 1) + n + network
+            }
+        }
+    }
+
+    /// Returns the number of communication rounds required to extract the MSB.
+    pub fn msb_rounds(&self, k: usize) -> usize {
+        debug_assert!(k >= 3);
+        match self {
+            Self::RippleCarry => k - 1,
+            Self::KoggeStone => 2 + ceil_log2(k),
+            // Full adder, generate bits, prefix network
+            _ => 2 + self.schedule(k - 2).len(),
+        }
+    }
+
+    // Returns the levels of the bitsliced prefix network on n positions. Each operation (j, i)
+    // combines the group of position j with the preceding group ending at position i. The
+    // operations of one level are evaluated in parallel.
+    fn schedule(&self, n: usize) -> Vec<Vec<(usize, usize)>> {
+        let mut levels = Vec::new();
+        match self {
+            Self::RippleCarry | Self::KoggeStone => {
+                unreachable!("{self:?} is not evaluated by a bitsliced prefix network")
+            }
+            Self::Sklansky => {
+                let mut d = 1;
+                while d < n {
+                    // The upper half of each block of size 2d combines with the end of the lower half
+                    levels.push(
+                        (0..n)
+                            .filter(|j| j & d != 0)
+                            .map(|j| (j, (j & !(2 * d - 1)) + d - 1))
+                            .collect(),
+                    );
+                    d <<= 1;
+                }
+            }
+            Self::BrentKung => {
+                // Up-sweep
+                let mut d = 1;
+                let mut top = 0;
+                while 2 * d - 1 < n {
+                    levels.push(
+                        ((2 * d - 1)..n)
+                            .step_by(2 * d)
+                            .map(|j| (j, j - d))
+                            .collect(),
+                    );
+                    top = d;
+                    d <<= 1;
+                }
+                // Down-sweep
+                let mut d = top;
+                while d > 0 {
+                    let level = ((3 * d - 1)..n)
+                        .step_by(2 * d)
+                        .map(|j| (j, j - d))
+                        .collect::<Vec<_>>();
+                    if !level.is_empty() {
+                        levels.push(level);
+                    }
+                    d >>= 1;
+                }
+            }
+        }
+        levels
+    }
+}
+
+// Counts the AND gates of the prefix network. The propagate bit of a group is only required as
+// long as the group does not start at position 0, since there is no carry-in.
+fn count_prefix_network(levels: &[Vec<(usize, usize)>]) -> usize {
+    let n = levels
+        .iter()
+        .flatten()
+        .map(|(j, _)| j + 1)
+        .max()
+        .unwrap_or_default();
+    let mut start = (0..n).collect::<Vec<_>>();
+    let mut ands = 0;
+    for level in levels {
+        let mut new_start = start.to_owned();
+        for (j, i) in level.iter().copied() {
+            new_start[j] = start[i];
+            ands += if start[i] == 0 { 1 } else { 2 };
+        }
+        start = new_start;
+    }
+    ands
+}
+
+/// Computes the MSB of x1 + x2 + x3, where the inputs are bitsliced, i.e., `x[i][j]` holds bit i
+/// of 128 values in register j. Returns the MSBs in the same packing. The inputs of
+/// [`AdderStrategy::KoggeStone`] are not bitsliced, see [`BinaryMpcTrait::binary_add_3_many`].
+pub(crate) async fn msb_many<Mpc, S>(
+    engine: &mut Mpc,
+    strategy: AdderStrategy,
+    x1: Vec<Vec<S>>,
+    x2: Vec<Vec<S>>,
+    mut x3: Vec<Vec<S>>,
+) -> Result<Vec<S>, Error>
+where
+    S: ShareTrait<VecShare = Vec<S>>
+        + Clone
+        + BitXorAssign
+        + BitXor<Output = S>
+        + ShlAssign<u32>
+        + Shl<u32, Output = S>
+        + Send
+        + Sync
+        + 'static,
+    Mpc: BinaryMpcTrait<u128, S>,
+{
+    if strategy == AdderStrategy::KoggeStone {
+        return Err(Error::ConfigError);
+    }
+    let k = x1.len();
+    if k < 3 || k != x2.len() || k != x3.len() {
+        return Err(Error::InvalidSizeError);
+    }
+
+    // Full adder to get 2 * c and s
+    let mut x2x3 = xor_layers(&x2, &x3);
+    let s = xor_layers(&x1, &x2x3);
+    let mut x1x3 = xor_layers(&x1, &x3);
+    // The carry of the MSB is not required
+    x1x3.pop().expect("Enough elements present");
+    x2x3.pop().expect("Enough elements present");
+    x3.pop().expect("Enough elements present");
+    let mut c = and_many_layers(engine, &x1x3, &x2x3).await?;
+    for (c_, x3_) in c.iter_mut().zip(x3) {
+        for (c__, x3__) in c_.iter_mut().zip(x3_) {
+            *c__ ^= x3__;
+        }
+    }
+
+    // Add 2c + s, where the LSB of 2c is 0. Thus, there is no carry from bit 0 and only bits
+    // 1..k-1 produce carries, bit i of 2c being c[i - 1].
+    let mut a = s;
+    let mut b = c;
+    let a_msb = a.pop().expect("Enough elements present");
+    let b_msb = b.pop().expect("Enough elements present");
+
+    let carry = match strategy {
+        AdderStrategy::RippleCarry => ripple_carry(engine, a, b).await?,
+        AdderStrategy::KoggeStone => unreachable!("Checked above"),
+        _ => prefix_carry(engine, strategy, a, b).await?,
+    };
+
+    let res = a_msb
+        .into_iter()
+        .zip(b_msb)
+        .zip(carry)
+        .map(|((a_, b_), c_)| a_ ^ b_ ^ c_)
+        .collect();
+    Ok(res)
+}
+
+// Returns the carry into bit k-1
+async fn ripple_carry<Mpc, S>(
+    engine: &mut Mpc,
+    a: Vec<Vec<S>>,
+    b: Vec<Vec<S>>,
+) -> Result<Vec<S>, Error>
+where
+    S: ShareTrait<VecShare = Vec<S>>
+        + Clone
+        + BitXorAssign
+        + BitXor<Output = S>
+        + ShlAssign<u32>
+        + Shl<u32, Output = S>
+        + Send
+        + Sync
+        + 'static,
+    Mpc: BinaryMpcTrait<u128, S>,
+{
+    // First full adder, the carry-in is 0
+    let mut c = engine.and_many(&a[1], &b[0]).await?;
+
+    // 2 -> k-2: c = c ^ ((a ^ c) & (b ^ c))
+    for (a_, b_) in a.into_iter().skip(2).zip(b.into_iter().skip(1)) {
+        let tmp_a = Mpc::xor_many(a_, c.to_owned())?;
+        let tmp_b = Mpc::xor_many(b_, c.to_owned())?;
+        let tmp_c = engine.and_many(&tmp_a, &tmp_b).await?;
+        c = Mpc::xor_many(tmp_c, c)?;
+    }
+    Ok(c)
+}
+
+// Returns the carry into bit k-1, i.e., the generate bit of the group of bits 1..k-2
+async fn prefix_carry<Mpc, S>(
+    engine: &mut Mpc,
+    strategy: AdderStrategy,
+    a: Vec<Vec<S>>,
+    b: Vec<Vec<S>>,
+) -> Result<Vec<S>, Error>
+where
+    S: ShareTrait<VecShare = Vec<S>>
+        + Clone
+        + BitXorAssign
+        + BitXor<Output = S>
+        + ShlAssign<u32>
+        + Shl<u32, Output = S>
+        + Send
+        + Sync
+        + 'static,
+    Mpc: BinaryMpcTrait<u128, S>,
+{
+    // Position j of the network is bit j + 1
+    let a = a.into_iter().skip(1).collect::<Vec<_>>();
+    let n = a.len();
+    debug_assert_eq!(n, b.len());
+
+    let mut g = and_many_layers(engine, &a, &b).await?;
+    let mut p = xor_layers(&a, &b);
+    let mut start = (0..n).collect::<Vec<_>>();
+
+    for level in strategy.schedule(n) {
+        // (G_j, P_j) = (G_j ^ P_j & G_i, P_j & P_i), all reading the values of the previous level
+        let mut lhs = Vec::with_capacity(2 * level.len());
+        let mut rhs = Vec::with_capacity(2 * level.len());
+        let mut with_p = Vec::with_capacity(level.len());
+        for (j, i) in level.iter().copied() {
+            lhs.push(p[j].to_owned());
+            rhs.push(g[i].to_owned());
+            // The propagate bit is not required anymore if the group starts at position 0
+            let needs_p = start[i] != 0;
+            if needs_p {
+                lhs.push(p[j].to_owned());
+                rhs.push(p[i].to_owned());
+            }
+            with_p.push(needs_p);
+        }
+        let mut res = and_many_layers(engine, &lhs, &rhs).await?.into_iter();
+        let mut new_start = start.to_owned();
+        for ((j, i), needs_p) in level.into_iter().zip(with_p) {
+            let pg = res.next().expect("Enough elements present");
+            for (g_, pg_) in g[j].iter_mut().zip(pg) {
+                *g_ ^= pg_;
+            }
+            if needs_p {
+                p[j] = res.next().expect("Enough elements present");
+            }
+            new_start[j] = start[i];
+        }
+        start = new_start;
+    }
+    Ok(g.pop().expect("Enough elements present"))
+}
+
+fn xor_layers<S: Clone + BitXor<Output = S>>(x1: &[Vec<S>], x2: &[Vec<S>]) -> Vec<Vec<S>> {
+    debug_assert_eq!(x1.len(), x2.len());
+    x1.iter()
+        .zip(x2.iter())
+        .map(|(x1, x2)| {
+            debug_assert_eq!(x1.len(), x2.len());
+            x1.iter()
+                .cloned()
+                .zip(x2.iter().cloned())
+                .map(|(x1, x2)| x1 ^ x2)
+                .collect()
+        })
+        .collect()
+}
+
+// ANDs all layers in one communication round
+async fn and_many_layers<Mpc, S>(
+    engine: &mut Mpc,
+    x1: &[Vec<S>],
+    x2: &[Vec<S>],
+) -> Result<Vec<Vec<S>>, Error>
+where
+    S: ShareTrait<VecShare = Vec<S>>
+        + Clone
+        + BitXorAssign
+        + BitXor<Output = S>
+        + ShlAssign<u32>
+        + Shl<u32, Output = S>
+        + Send
+        + Sync
+        + 'static,
+    Mpc: BinaryMpcTrait<u128, S>,
+{
+    let len = x1.len();
+    debug_assert_eq!(len, x2.len());
+    if len == 0 {
+        return Ok(Vec::new());
+    }
+    let inner_len = x1[0].len();
+    let x1 = x1.iter().flatten().cloned().collect::<Vec<_>>();
+    let x2 = x2.iter().flatten().cloned().collect::<Vec<_>>();
+    let x3 = engine.and_many(&x1, &x2).await?;
+    if inner_len == 0 {
+        return Ok(vec![Vec::new(); len]);
+    }
+    Ok(x3.chunks(inner_len).map(|x| x.to_owned()).collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const STRATEGIES: [AdderStrategy; 2] = [AdderStrategy::BrentKung, AdderStrategy::Sklansky];
+
+    // Checks that each position ends up with the group starting at position 0 and that no
+    // operation reads a value which is written in the same level
+    #[test]
+    fn prefix_schedule_test() {
+        for strategy in STRATEGIES {
+            for n in 1..=126 {
+                let mut start = (0..n).collect::<Vec<_>>();
+                for level in strategy.schedule(n) {
+                    let targets = level.iter().map(|(j, _)| *j).collect::<Vec<_>>();
+                    let mut new_start = start.to_owned();
+                    for (j, i) in level {
+                        assert!(i < j);
+                        // Groups have to be adjacent
+                        assert_eq!(start[j], i + 1, "{strategy:?}, n = {n}");
+                        assert!(!targets.contains(&i));
+                        new_start[j] = start[i];
+                    }
+                    start = new_start;
+                }
+                assert!(start.iter().all(|s| *s == 0), "{strategy:?}, n = {n}");
+            }
+        }
+    }
+
+    #[test]
+    fn msb_cost_test() {
+        for k in [8, 16, 32, 64, 128] {
+            let ripple = AdderStrategy::RippleCarry;
+            let ks = AdderStrategy::KoggeStone;
+            let bk = AdderStrategy::BrentKung;
+            let sk = AdderStrategy::Sklansky;
+            let logk = (k as f64).log2() as usize;
+
+            assert_eq!(ripple.msb_and_gates(k), 2 * k - 3);
+            assert_eq!(ripple.msb_rounds(k), k - 1);
+            assert_eq!(ks.msb_rounds(k), logk + 2);
+            assert_eq!(sk.msb_rounds(k), logk + 2);
+            assert!(bk.msb_rounds(k) > sk.msb_rounds(k));
+            assert!(bk.msb_rounds(k) < ripple.msb_rounds(k));
+
+            assert!(ripple.msb_and_gates(k) < bk.msb_and_gates(k));
+            assert!(bk.msb_and_gates(k) <= sk.msb_and_gates(k));
+            assert!(sk.msb_and_gates(k) < ks.msb_and_gates(k));
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use color_eyre::eyre::{Report, Result};
+use iris_mpc::prelude::{aby3_share_file_from_sqlite, swift3_share_file_from_sqlite};
+use rusqlite::Connection;
+
+#[derive(Parser)]
+#[command(author, version, about, long_about = None)]
+struct Opts {
+    /// path to the database file created by create_sample_data
+    #[arg(short, long, value_name = "FILE", required = true)]
+    database: PathBuf,
+
+    /// MPC protocol for which the database was generated
+    #[arg(short, long, value_name = "MPC", required = true)]
+    mpc: String,
+
+    /// prefix of the share files, the file of party i is written to <PREFIX><i>.shares
+    #[arg(short, long, value_name = "PREFIX", required = true)]
+    output: String,
+}
+
+fn main() -> Result<()> {
+    let opts: Opts = Opts::parse();
+    let prot = opts.mpc.to_lowercase();
+    let conn = Connection::open(&opts.database)?;
+
+    for party in 0..3 {
+        let path = format!("{}{}.shares", opts.output, party);
+        let header = if prot == "aby3" {
+            aby3_share_file_from_sqlite::<u16, _>(&conn, party, &path)?
+        } else if prot == "swift3" {
+            swift3_share_file_from_sqlite::<u16, _>(&conn, party, &path)?
+        } else {
+            return Err(Report::msg("Invalid MPC protocol specified"));
+        };
+        println!("Wrote {} records to {}", header.num_records, path);
+    }
+
+    Ok(())
+}
@@ -1,9 +1,20 @@
-use std::path::PathBuf;
+use std::{fs::File, path::PathBuf};
 
 use clap::Parser;
-use color_eyre::eyre::{Report, Result};
-use iris_mpc::prelude::{Aby3, Aby3Network, MpcTrait, SpdzWise, Swift3, Swift3Network};
-use plain_reference::IrisCode;
+use color_eyre::eyre::{Context, Report, Result};
+use iris_mpc::prelude::{
+    generate_random_codes, has_spdzwise_party_mac_key, input_codes, party_db_path,
+    party_seeds_path, read_spdzwise_party_mac_key, read_truth_db, truth_db_path,
+    write_aby3_party_db, write_compressed_db, write_party_seeds, write_spdzwise_party_db,
+    write_spdzwise_party_mac_key, write_swift3_party_db, Aby3, Aby3Network, Aby3Share, Bit,
+    BitShareTrait, DbSeeds, DbShares, Error, MpcTrait, Sharable, ShareTrait, SpdzWise,
+    SpdzWiseShare, Swift3, Swift3Network, Swift3Share, VecShareTrait,
+};
+use mpc_net::config::{NetworkConfig, NetworkParty};
+use plain_reference::{
+    template::{read_template_file, write_template_file, BitOrder, TemplateFormat},
+    IrisCode,
+};
 use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha12Rng;
 use rusqlite::Connection;
@@ -26,6 +37,55 @@ struct Opts {
     /// a seed for the random number generation, for reproducible test data
     #[arg(short, long, value_name = "SEED")]
     seed: Option<u64>,
+
+    /// store the aby3 shares compressed, i.e., derive two of the three share components from PRF seeds.
+    /// Always writes one database per party and a ground truth database
+    #[arg(long, default_value = "false")]
+    compressed: bool,
+
+    /// write one database per party containing only its shares, and a ground truth database
+    /// containing the plain codes, next to the given database path
+    #[arg(long, default_value = "false")]
+    per_party: bool,
+
+    /// generate only the database of this party together with the other parties, without a
+    /// trusted dealer. The database is written to the per party path of the given database path
+    #[arg(long, value_name = "ID")]
+    party: Option<usize>,
+
+    /// the network config file for the generation without a dealer
+    #[arg(long, value_name = "FILE")]
+    config_file: Option<PathBuf>,
+
+    /// the path to the .der key file of this party for the generation without a dealer
+    #[arg(long, value_name = "FILE")]
+    key_file: Option<PathBuf>,
+
+    /// the party which inputs its irises in the generation without a dealer. If not given, the
+    /// parties jointly generate random irises, whose masks are derived from --seed
+    #[arg(long, value_name = "ID")]
+    owner: Option<usize>,
+
+    /// ground truth database with the irises of the owner, only required at the owner
+    #[arg(long, value_name = "FILE")]
+    input: Option<PathBuf>,
+
+    /// import the irises from this template file instead of generating random ones. In the
+    /// generation without a dealer, the owner can provide it instead of --input
+    #[arg(long, value_name = "FILE")]
+    templates: Option<PathBuf>,
+
+    /// write the plain irises of the database to this template file
+    #[arg(long, value_name = "FILE")]
+    export_templates: Option<PathBuf>,
+
+    /// the format of the template files
+    #[arg(long, value_enum, default_value_t = TemplateFormat::OpenIris)]
+    template_format: TemplateFormat,
+
+    /// the order of the bits within a byte of packed template files
+    #[arg(long, value_enum, default_value_t = BitOrder::Lsb0)]
+    bit_order: BitOrder,
 }
 
 fn open_database(database_file: &PathBuf) -> Result<Connection> {
@@ -34,9 +94,28 @@ fn open_database(database_file: &PathBuf) -> Result<Connection> {
     Ok(conn)
 }
 
+// The first --items irises of the template file, or random ones
+fn sample_codes<R: Rng>(opts: &Opts, rng: &mut R) -> Result<Vec<IrisCode>> {
+    let num_items = opts.items as usize;
+    let codes = match &opts.templates {
+        Some(templates) => {
+            let mut codes = read_template_file(templates, opts.template_format, opts.bit_order)?;
+            if codes.len() < num_items {
+                return Err(Report::msg("The template file contains not enough irises"));
+            }
+            codes.truncate(num_items);
+            codes
+        }
+        None => (0..num_items).map(|_| IrisCode::random_rng(rng)).collect(),
+    };
+    if let Some(export) = &opts.export_templates {
+        write_template_file(export, &codes, opts.template_format, opts.bit_order)?;
+    }
+    Ok(codes)
+}
+
 fn create_aby3_db(opts: Opts) -> Result<()> {
-    let database_file = opts.database;
-    let num_items = opts.items;
+    let database_file = opts.database.to_owned();
 
     let mut conn = open_database(&database_file)?;
 
@@ -58,11 +137,7 @@ fn create_aby3_db(opts: Opts) -> Result<()> {
         None => ChaCha12Rng::from_entropy(),
     };
 
-    let mut codes = Vec::with_capacity(num_items as usize);
-    for _ in 0..num_items {
-        let code = IrisCode::random_rng(&mut rng);
-        codes.push(code);
-    }
+    let codes = sample_codes(&opts, &mut rng)?;
 
     // Insert the codes into the database
     let transaction = conn.transaction()?;
@@ -98,9 +173,34 @@ fn create_aby3_db(opts: Opts) -> Result<()> {
     Ok(())
 }
 
+fn create_compressed_aby3_db(opts: Opts) -> Result<()> {
+    let database_file = opts.database.to_owned();
+
+    let mut rng = match opts.seed {
+        Some(seed) => ChaCha12Rng::seed_from_u64(seed),
+        None => ChaCha12Rng::from_entropy(),
+    };
+    let seeds = DbSeeds::random(&mut rng);
+
+    let codes = sample_codes(&opts, &mut rng)?;
+
+    // Each party only gets a file with the seeds it is allowed to know and its own database, since
+    // party 1 would learn the codes from the stored share components
+    for party in 0..3 {
+        let conn = open_database(&party_seeds_path(&database_file, party))?;
+        write_party_seeds(&conn, &seeds.party_seeds(party)?)?;
+        let mut conn = open_database(&party_db_path(&database_file, party))?;
+        write_compressed_db::<u16>(&mut conn, &seeds, party, &codes)?;
+    }
+
+    // The plain codes are only stored in the ground truth database
+    create_truth_db(&opts, &codes, None)?;
+
+    Ok(())
+}
+
 fn create_swift3_db(opts: Opts) -> Result<()> {
-    let database_file = opts.database;
-    let num_items = opts.items;
+    let database_file = opts.database.to_owned();
 
     let mut conn = open_database(&database_file)?;
 
@@ -123,11 +223,7 @@ fn create_swift3_db(opts: Opts) -> Result<()> {
         None => ChaCha12Rng::from_entropy(),
     };
 
-    let mut codes = Vec::with_capacity(num_items as usize);
-    for _ in 0..num_items {
-        let code = IrisCode::random_rng(&mut rng);
-        codes.push(code);
-    }
+    let codes = sample_codes(&opts, &mut rng)?;
 
     // Insert the codes into the database
     let transaction = conn.transaction()?;
@@ -169,8 +265,7 @@ fn create_swift3_db(opts: Opts) -> Result<()> {
 }
 
 fn create_spdzwise_db(opts: Opts) -> Result<()> {
-    let database_file = opts.database;
-    let num_items = opts.items;
+    let database_file = opts.database.to_owned();
 
     let mut conn = open_database(&database_file)?;
 
@@ -208,11 +303,7 @@ fn create_spdzwise_db(opts: Opts) -> Result<()> {
     };
     let mac_key = rng.gen::<u64>();
 
-    let mut codes = Vec::with_capacity(num_items as usize);
-    for _ in 0..num_items {
-        let code = IrisCode::random_rng(&mut rng);
-        codes.push(code);
-    }
+    let codes = sample_codes(&opts, &mut rng)?;
     // Insert the mac key into the database
     let transaction = conn.transaction()?;
     let mut stmt = transaction
@@ -280,11 +371,285 @@ fn create_spdzwise_db(opts: Opts) -> Result<()> {
     Ok(())
 }
 
+fn create_truth_db(opts: &Opts, codes: &[IrisCode], mac_key: Option<u64>) -> Result<()> {
+    let mut conn = open_database(&truth_db_path(&opts.database))?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS iris_codes (
+            id INTEGER PRIMARY KEY,
+            code BLOB NOT NULL,
+            mask BLOB NOT NULL
+        )",
+        rusqlite::params![],
+    )?;
+
+    let transaction = conn.transaction()?;
+    if let Some(mac_key) = mac_key {
+        transaction.execute(
+            "CREATE TABLE IF NOT EXISTS mac_key (
+                id INTEGER PRIMARY KEY,
+                key BLOB NOT NULL
+            )",
+            rusqlite::params![],
+        )?;
+        transaction.execute(
+            "INSERT INTO mac_key (key) VALUES (?1)",
+            [bincode::serialize(&mac_key)?],
+        )?;
+    }
+
+    let mut stmt = transaction.prepare("INSERT INTO iris_codes (code, mask) VALUES (?1, ?2)")?;
+    for code in codes {
+        stmt.execute([code.code.as_raw_slice(), code.mask.as_raw_slice()])?;
+    }
+    drop(stmt);
+    transaction.commit()?;
+
+    Ok(())
+}
+
+// Number of records which are shared before they are written to the party databases
+const PARTY_DB_BATCH: usize = 1000;
+
+type PartyDbWriter<Ashare> =
+    fn(&mut Connection, usize, &DbShares<<Ashare as ShareTrait>::VecShare>) -> Result<(), Error>;
+
+// Shares the codes as a trusted dealer and appends the shares of each party to its database
+fn create_party_dbs<T, Ashare, Bshare, Mpc>(
+    opts: &Opts,
+    codes: &[IrisCode],
+    mac_key: T::VerificationShare,
+    rng: &mut ChaCha12Rng,
+    write: PartyDbWriter<Ashare>,
+) -> Result<()>
+where
+    T: Sharable,
+    Ashare: ShareTrait,
+    Bshare: BitShareTrait,
+    Mpc: MpcTrait<T, Ashare, Bshare>,
+{
+    let mut conns = (0..3)
+        .map(|party| open_database(&party_db_path(&opts.database, party)))
+        .collect::<Result<Vec<_>>>()?;
+
+    for batch in codes.chunks(PARTY_DB_BATCH) {
+        let mut dbs = [(); 3].map(|_| DbShares::<Ashare::VecShare>::default());
+        for code in batch {
+            let mut shared_code =
+                [(); 3].map(|_| Ashare::VecShare::with_capacity(IrisCode::IRIS_CODE_SIZE));
+            for bit in code.code.bits() {
+                // We simulate the parties already knowing the shares of the code.
+                let shares = Mpc::share(T::from(bit), mac_key.to_owned(), rng);
+                for (shared_code, share) in shared_code.iter_mut().zip(shares) {
+                    shared_code.push(share);
+                }
+            }
+            for (db, shared_code) in dbs.iter_mut().zip(shared_code) {
+                db.shares.push(shared_code);
+                db.masks.push(code.mask);
+            }
+        }
+        for (party, (conn, db)) in conns.iter_mut().zip(dbs.iter()).enumerate() {
+            write(conn, party, db)?;
+        }
+    }
+    Ok(())
+}
+
+fn create_aby3_party_dbs(opts: Opts) -> Result<()> {
+    let mut rng = match opts.seed {
+        Some(seed) => ChaCha12Rng::seed_from_u64(seed),
+        None => ChaCha12Rng::from_entropy(),
+    };
+
+    let codes = sample_codes(&opts, &mut rng)?;
+    create_truth_db(&opts, &codes, None)?;
+
+    create_party_dbs::<u16, _, Aby3Share<Bit>, Aby3<Aby3Network>>(
+        &opts,
+        &codes,
+        0,
+        &mut rng,
+        write_aby3_party_db::<u16>,
+    )
+}
+
+fn create_swift3_party_dbs(opts: Opts) -> Result<()> {
+    let mut rng = match opts.seed {
+        Some(seed) => ChaCha12Rng::seed_from_u64(seed),
+        None => ChaCha12Rng::from_entropy(),
+    };
+
+    let codes = sample_codes(&opts, &mut rng)?;
+    create_truth_db(&opts, &codes, None)?;
+
+    create_party_dbs::<u16, _, Swift3Share<Bit>, Swift3<Swift3Network, u16>>(
+        &opts,
+        &codes,
+        0,
+        &mut rng,
+        write_swift3_party_db::<u16>,
+    )
+}
+
+fn create_spdzwise_party_dbs(opts: Opts) -> Result<()> {
+    let mut rng = match opts.seed {
+        Some(seed) => ChaCha12Rng::seed_from_u64(seed),
+        None => ChaCha12Rng::from_entropy(),
+    };
+    let mac_key = rng.gen::<u64>();
+
+    let codes = sample_codes(&opts, &mut rng)?;
+    create_truth_db(&opts, &codes, Some(mac_key))?;
+
+    // Insert the shares of the mac key into the databases
+    let shares = Aby3::<Aby3Network>::share(mac_key, 0, &mut rng);
+    for (party, share) in shares.iter().enumerate() {
+        let conn = open_database(&party_db_path(&opts.database, party))?;
+        write_spdzwise_party_mac_key::<u16>(&conn, party, share)?;
+    }
+
+    create_party_dbs::<u16, _, Aby3Share<Bit>, SpdzWise<Aby3Network, u64>>(
+        &opts,
+        &codes,
+        mac_key,
+        &mut rng,
+        write_spdzwise_party_db::<u16>,
+    )
+}
+
+fn network_config(opts: &Opts, party: usize) -> Result<NetworkConfig> {
+    let config_file = opts
+        .config_file
+        .as_ref()
+        .ok_or(Report::msg("Missing --config-file"))?;
+    let key_path = opts
+        .key_file
+        .to_owned()
+        .ok_or(Report::msg("Missing --key-file"))?;
+    let parties: Vec<NetworkParty> =
+        serde_yaml::from_reader(File::open(config_file).context("opening config file")?)
+            .context("parsing config file")?;
+
+    Ok(NetworkConfig {
+        parties,
+        my_id: party,
+        key_path,
+    })
+}
+
+// Either the owner inputs its irises, or all parties jointly generate random ones
+async fn generate_party_shares<T, Ashare, Bshare, Mpc>(
+    opts: &Opts,
+    party: usize,
+    mpc: &mut Mpc,
+) -> Result<DbShares<Ashare::VecShare>>
+where
+    T: Sharable,
+    Ashare: ShareTrait,
+    Bshare: BitShareTrait,
+    Mpc: MpcTrait<T, Ashare, Bshare>,
+{
+    let num_items = opts.items as usize;
+    let shares = match opts.owner {
+        Some(owner) => {
+            let codes = if party == owner && opts.templates.is_some() {
+                Some(sample_codes(opts, &mut ChaCha12Rng::from_entropy())?)
+            } else if party == owner {
+                let input = opts.input.as_ref().ok_or(Report::msg(
+                    "The owner has to provide --input or --templates",
+                ))?;
+                let mut codes = read_truth_db(&open_database(input)?)?;
+                if codes.len() < num_items {
+                    return Err(Report::msg("The input contains not enough irises"));
+                }
+                codes.truncate(num_items);
+                Some(codes)
+            } else {
+                None
+            };
+            input_codes(mpc, codes.as_deref(), num_items, owner).await?
+        }
+        None => {
+            let mask_seed = opts.seed.ok_or(Report::msg(
+                "Generating random irises requires the same --seed at all parties",
+            ))?;
+            let mut rng = ChaCha12Rng::from_entropy();
+            generate_random_codes(mpc, num_items, mask_seed, &mut rng).await?
+        }
+    };
+    Ok(shares)
+}
+
+async fn create_distributed_party_db(opts: Opts, party: usize) -> Result<()> {
+    let prot = opts.mpc.to_lowercase();
+    let config = network_config(&opts, party)?;
+    let mut conn = open_database(&party_db_path(&opts.database, party))?;
+
+    if prot == "aby3" {
+        let network = Aby3Network::new(config).await?;
+        let mut mpc = Aby3::new(network);
+        MpcTrait::<u16, Aby3Share<u16>, Aby3Share<Bit>>::preprocess(&mut mpc).await?;
+        let shares = generate_party_shares::<u16, _, _, _>(&opts, party, &mut mpc).await?;
+        write_aby3_party_db(&mut conn, party, &shares)?;
+        MpcTrait::<u16, Aby3Share<u16>, Aby3Share<Bit>>::finish(mpc).await?;
+    } else if prot == "swift3" {
+        let network = Swift3Network::new(config).await?;
+        let mut mpc = Swift3::<_, u16>::new(network);
+        MpcTrait::<u16, Swift3Share<u16>, Swift3Share<Bit>>::preprocess(&mut mpc).await?;
+        let shares = generate_party_shares::<u16, _, _, _>(&opts, party, &mut mpc).await?;
+        write_swift3_party_db(&mut conn, party, &shares)?;
+        MpcTrait::<u16, Swift3Share<u16>, Swift3Share<Bit>>::finish(mpc).await?;
+    } else if prot == "spdzwise" {
+        type Share = SpdzWiseShare<u64>;
+        let network = Aby3Network::new(config).await?;
+        let mut mpc = SpdzWise::<_, u64>::new(network);
+        MpcTrait::<u16, Share, Aby3Share<Bit>>::preprocess(&mut mpc).await?;
+        // The MAC key is generated inside MPC, records appended later reuse the stored share
+        if has_spdzwise_party_mac_key(&conn)? {
+            let mac_key = read_spdzwise_party_mac_key::<u16>(&conn, party)?;
+            MpcTrait::<u16, Share, Aby3Share<Bit>>::set_mac_key(&mut mpc, mac_key);
+        } else {
+            MpcTrait::<u16, Share, Aby3Share<Bit>>::set_new_mac_key(&mut mpc);
+            write_spdzwise_party_mac_key::<u16>(&conn, party, &mpc.get_r())?;
+        }
+        let shares = generate_party_shares::<u16, _, _, _>(&opts, party, &mut mpc).await?;
+        MpcTrait::<u16, Share, Aby3Share<Bit>>::verify(&mut mpc).await?;
+        write_spdzwise_party_db::<u16>(&mut conn, party, &shares)?;
+        MpcTrait::<u16, Share, Aby3Share<Bit>>::finish(mpc).await?;
+    } else {
+        return Err(Report::msg("Invalid MPC protocol specified"));
+    }
+
+    Ok(())
+}
+
 fn main() -> Result<()> {
     let opts: Opts = Opts::parse();
     let prot = opts.mpc.to_lowercase();
 
-    if prot == "aby3" {
+    if let Some(party) = opts.party {
+        if opts.per_party || opts.compressed {
+            return Err(Report::msg(
+                "The generation without a dealer always writes uncompressed per party databases",
+            ));
+        }
+        let runtime = tokio::runtime::Runtime::new()?;
+        return runtime.block_on(create_distributed_party_db(opts, party));
+    }
+
+    if prot == "aby3" && opts.compressed {
+        create_compressed_aby3_db(opts)
+    } else if opts.per_party && prot == "aby3" {
+        create_aby3_party_dbs(opts)
+    } else if opts.per_party && prot == "swift3" {
+        create_swift3_party_dbs(opts)
+    } else if opts.per_party && prot == "spdzwise" {
+        create_spdzwise_party_dbs(opts)
+    } else if opts.compressed {
+        Err(Report::msg(
+            "Compressed databases are only supported for the aby3 protocol",
+        ))
+    } else if prot == "aby3" {
         create_aby3_db(opts)
     } else if prot == "swift3" {
         create_swift3_db(opts)
@@ -0,0 +1,141 @@
+use std::{fs::File, path::PathBuf};
+
+use clap::Parser;
+use color_eyre::eyre::{Context, Report, Result};
+use iris_mpc::prelude::{
+    check_party_db_consistency, delete_party_db_records, party_db_len, party_db_path,
+    read_aby3_party_db, read_party_info, read_spdzwise_party_db, read_spdzwise_party_mac_key,
+    read_swift3_party_db, update_aby3_party_db_records, update_spdzwise_party_db_records,
+    update_swift3_party_db_records, Aby3Network, DbShares, NetworkTrait,
+};
+use mpc_net::config::{NetworkConfig, NetworkParty};
+use rusqlite::Connection;
+
+#[derive(Parser)]
+#[command(author, version, about, long_about = None)]
+struct Opts {
+    /// path to the database file, the database of this party is derived from it as in
+    /// create_sample_data --per-party
+    #[arg(short, long, value_name = "FILE", required = true)]
+    database: PathBuf,
+
+    /// the id of this party
+    #[arg(short, long, value_name = "ID", required = true)]
+    party: usize,
+
+    /// the network config file
+    #[arg(short, long, value_name = "FILE", required = true)]
+    config_file: PathBuf,
+
+    /// the path to the .der key file of this party
+    #[arg(short, long, value_name = "FILE", required = true)]
+    key_file: PathBuf,
+
+    /// the record ids to delete, i.e., to revoke their enrollment
+    #[arg(
+        long,
+        value_name = "ID",
+        value_delimiter = ',',
+        conflicts_with = "update"
+    )]
+    delete: Vec<u64>,
+
+    /// the record ids to replace by the records of --source
+    #[arg(long, value_name = "ID", value_delimiter = ',', requires = "source")]
+    update: Vec<u64>,
+
+    /// path to the database file with the new records for --update, e.g., created with
+    /// create_sample_data --party. Its records replace the given ids in order
+    #[arg(long, value_name = "FILE")]
+    source: Option<PathBuf>,
+}
+
+fn network_config(opts: &Opts) -> Result<NetworkConfig> {
+    let parties: Vec<NetworkParty> =
+        serde_yaml::from_reader(File::open(&opts.config_file).context("opening config file")?)
+            .context("parsing config file")?;
+
+    Ok(NetworkConfig {
+        parties,
+        my_id: opts.party,
+        key_path: opts.key_file.to_owned(),
+    })
+}
+
+// Only the first records of the source replace the given ids
+fn truncate<V>(db: &mut DbShares<V>, len: usize) {
+    db.shares.truncate(len);
+    db.masks.truncate(len);
+}
+
+async fn update(
+    opts: &Opts,
+    network: &mut Aby3Network,
+    conn: &mut Connection,
+    protocol: &str,
+) -> Result<()> {
+    let source_path = opts
+        .source
+        .as_ref()
+        .ok_or(Report::msg("Missing --source"))?;
+    let source = Connection::open(party_db_path(source_path, opts.party))?;
+    let party = opts.party;
+    let ids = &opts.update;
+
+    if protocol == "aby3" {
+        let mut db = read_aby3_party_db::<u16>(&source, party)?;
+        truncate(&mut db, ids.len());
+        update_aby3_party_db_records(network, conn, party, ids, &db).await?;
+    } else if protocol == "swift3" {
+        let mut db = read_swift3_party_db::<u16>(&source, party)?;
+        truncate(&mut db, ids.len());
+        update_swift3_party_db_records(network, conn, party, ids, &db).await?;
+    } else if protocol == "spdzwise" {
+        if read_spdzwise_party_mac_key::<u16>(&source, party)?
+            != read_spdzwise_party_mac_key::<u16>(conn, party)?
+        {
+            return Err(Report::msg(
+                "The source records are authenticated with a different MAC key",
+            ));
+        }
+        let mut db = read_spdzwise_party_db::<u16>(&source, party)?;
+        truncate(&mut db, ids.len());
+        update_spdzwise_party_db_records::<_, u16>(network, conn, party, ids, &db).await?;
+    } else {
+        return Err(Report::msg("Invalid MPC protocol in database"));
+    }
+    Ok(())
+}
+
+async fn manage_db(opts: Opts) -> Result<()> {
+    let mut conn = Connection::open(party_db_path(&opts.database, opts.party))?;
+    let (party, protocol) = read_party_info(&conn)?;
+    if party != opts.party {
+        return Err(Report::msg(format!("Database belongs to party {party}")));
+    }
+
+    let mut network = Aby3Network::new(network_config(&opts)?).await?;
+
+    if !opts.delete.is_empty() {
+        delete_party_db_records(&mut network, &mut conn, &opts.delete).await?;
+        println!("Deleted {} records", opts.delete.len());
+    } else if !opts.update.is_empty() {
+        update(&opts, &mut network, &mut conn, &protocol).await?;
+        println!("Updated {} records", opts.update.len());
+    } else {
+        check_party_db_consistency(&mut network, &mut conn).await?;
+    }
+    println!(
+        "The databases of all parties are consistent and contain {} records",
+        party_db_len(&conn)?
+    );
+
+    network.shutdown().await?;
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    let opts: Opts = Opts::parse();
+    let runtime = tokio::runtime::Runtime::new()?;
+    runtime.block_on(manage_db(opts))
+}
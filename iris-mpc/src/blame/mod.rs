@@ -0,0 +1,367 @@
+use crate::{error::Error, traits::network_trait::NetworkTrait};
+use bytes::{Bytes, BytesMut};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha512};
+use std::collections::BTreeSet;
+
+// The blame phases of the malicious protocols, which turn a failed check into an identifiable
+// abort naming the cheating party (`Error::CheatingParty`) or a pair of conflicting parties, one
+// of which has cheated (`Error::ConflictingParties`). The following failures are attributed:
+// - the JMP verification of MalAby3, Swift3 and SpdzWise (see `jmp_verify`), which also covers
+//   all openings which are verified with it, e.g., the opened triples of the cut-and-choose. The
+//   messages are hashed per round (see `JmpQueue`), and only the first inconsistent round is
+//   judged,
+// - the DZKP verification of Swift3 (see `proof_blame`),
+// - the cross-checks of the input components and of the database roots (see
+//   `cross_check_components`),
+// - the forwarded input values of MalAby3.
+//
+// The checks of the multiplications of MalAby3 and SpdzWise, i.e., the MAC check, the sacrifice
+// of triples and the check of the opened triples of the cut-and-choose, still fail with the
+// anonymous `Error::VerifyError`. They detect an additive error which a cheater introduced in a
+// multiplication, and the (verified) messages do not tell who did. For the zero checks, each
+// party broadcasts the hash of its view of a value which should be zero. If the value is zero, the
+// two honest hashes match and the differing one names the cheater. But an additive error makes
+// the honest hashes differ as well, and the cheater can then copy either of them. Thus, no party
+// or pair can be named soundly without opening the multiplications themselves, which is out of
+// scope.
+
+/// The hashes of the non-empty steps of a [`JmpQueue`], i.e., the index of each step and the hash
+/// over its messages.
+pub(crate) type StepHashes = Vec<(u64, Vec<u8>)>;
+
+/// The messages of one JMP queue since the last verification, hashed per step. A step is one
+/// round of JMPs, so no message of a step depends on a message received in the same step. All
+/// parties end the steps at the same points of the protocol, such that the i-th step of the
+/// sender, the hasher and the receiver of a JMP cover the same messages.
+#[derive(Debug, Default)]
+pub(crate) struct JmpQueue {
+    bytes: BytesMut,
+    step: u64,
+    hashes: StepHashes,
+}
+
+impl JmpQueue {
+    /// The buffer of the current step.
+    pub(crate) fn bytes_mut(&mut self) -> &mut BytesMut {
+        &mut self.bytes
+    }
+
+    /// Ends the current step. Empty steps are not recorded.
+    pub(crate) fn end_step(&mut self) {
+        if !self.bytes.is_empty() {
+            let bytes = std::mem::take(&mut self.bytes).freeze();
+            self.hashes
+                .push((self.step, Sha512::digest(bytes).to_vec()));
+        }
+        self.step += 1;
+    }
+
+    /// Ends the current step and returns the hashes of all steps since the last call.
+    pub(crate) fn finish(&mut self) -> StepHashes {
+        self.end_step();
+        self.step = 0;
+        std::mem::take(&mut self.hashes)
+    }
+}
+
+/// The hashes of a JMP which consists of the single message `bytes`.
+pub(crate) fn single_step(bytes: &[u8]) -> StepHashes {
+    let mut queue = JmpQueue::default();
+    queue.bytes_mut().extend_from_slice(bytes);
+    queue.finish()
+}
+
+/// The hashes of the messages a party has sent and received since the last JMP verification, per
+/// step (see [`JmpQueue`]). In a JMP, the sender sends the values directly to the receiver, and
+/// the hasher sends the hash of the same values.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub(crate) struct JmpTranscript {
+    /// `sent_values[i]` are the hashes over the values sent directly to party `i`.
+    pub sent_values: [Option<StepHashes>; 3],
+    /// `sent_hashes[i]` are the hashes sent to party `i` for verification.
+    pub sent_hashes: [Option<StepHashes>; 3],
+    /// `received_values[i]` are the hashes over the values received directly, which are vouched
+    /// for by party `i`.
+    pub received_values: [Option<StepHashes>; 3],
+}
+
+impl JmpTranscript {
+    fn step_hashes(&self) -> impl Iterator<Item = &StepHashes> {
+        self.sent_values
+            .iter()
+            .chain(&self.sent_hashes)
+            .chain(&self.received_values)
+            .flatten()
+    }
+
+    // The steps of an honest party are ascending, and each of them has a hash
+    fn is_well_formed(&self) -> bool {
+        self.step_hashes().all(|hashes| {
+            hashes.windows(2).all(|w| w[0].0 < w[1].0)
+                && hashes
+                    .iter()
+                    .all(|(_, hash)| hash.len() == Sha512::output_size())
+        })
+    }
+}
+
+// The hash of `step`, which is empty if no messages were sent in this step
+fn step_hash(hashes: &StepHashes, step: u64) -> &[u8] {
+    match hashes.binary_search_by_key(&step, |(step, _)| *step) {
+        Ok(index) => &hashes[index].1,
+        Err(_) => &[],
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Verdict {
+    Party(usize),
+    Pair(usize, usize),
+}
+
+impl Verdict {
+    fn pair(a: usize, b: usize) -> Self {
+        Self::Pair(a.min(b), a.max(b))
+    }
+
+    fn contains(&self, id: usize) -> bool {
+        match *self {
+            Self::Party(p) => p == id,
+            Self::Pair(a, b) => a == id || b == id,
+        }
+    }
+}
+
+impl From<Verdict> for Error {
+    fn from(verdict: Verdict) -> Self {
+        match verdict {
+            Verdict::Party(p) => Error::CheatingParty(p),
+            Verdict::Pair(a, b) => Error::ConflictingParties(a, b),
+        }
+    }
+}
+
+fn serialize<S: Serialize>(value: &S) -> Result<Bytes, Error> {
+    Ok(Bytes::from(
+        bincode::serialize(value).map_err(|_| Error::SerializationError)?,
+    ))
+}
+
+fn deserialize_all<D: for<'de> Deserialize<'de>>(msgs: Vec<BytesMut>) -> Result<Vec<D>, Error> {
+    msgs.into_iter()
+        .enumerate()
+        .map(|(id, msg)| bincode::deserialize(&msg).map_err(|_| Error::CheatingParty(id)))
+        .collect()
+}
+
+// Decides on a failed check of `receiver`, given the hash over the values it received, the hash
+// published by `hasher`, and the hash over the values `sender` claims to have sent. With an honest
+// majority, at least two of the three parties are honest. The verdict contains the cheater if an
+// honest sender and an honest hasher have computed the same values. This holds in the first
+// inconsistent step of a JMP verification, but not in the later ones, since the values of the
+// honest parties may then depend on inconsistent messages of the earlier steps.
+fn judge(
+    (receiver, sender, hasher): (usize, usize, usize),
+    values_hash: &[u8],
+    received_hash: Option<&[u8]>,
+    sent_hash: Option<&[u8]>,
+) -> Option<Verdict> {
+    if received_hash == Some(values_hash) {
+        return None;
+    }
+    let verdict = if sent_hash == Some(values_hash) {
+        // The receiver and the sender agree, so one of them is in conflict with the hasher
+        Verdict::pair(sender, hasher)
+    } else if sent_hash != received_hash {
+        // The sender disagrees with both the receiver and the hasher
+        Verdict::Party(sender)
+    } else {
+        Verdict::pair(receiver, sender)
+    };
+    Some(verdict)
+}
+
+/// The error naming the pair of conflicting parties `a` and `b`, one of which has cheated.
+pub(crate) fn conflict(a: usize, b: usize) -> Error {
+    Verdict::pair(a, b).into()
+}
+
+// With at most one corrupted party, the cheater is contained in each verdict, so the verdicts have
+// to be sound on their own
+fn combine(verdicts: &[Verdict]) -> Verdict {
+    if let Some(party) = verdicts.iter().find(|v| matches!(v, Verdict::Party(_))) {
+        return *party;
+    }
+    let candidates = (0..3)
+        .filter(|id| verdicts.iter().all(|v| v.contains(*id)))
+        .collect::<Vec<_>>();
+    match candidates.as_slice() {
+        [p] => Verdict::Party(*p),
+        [a, b] => Verdict::pair(*a, *b),
+        _ => verdicts[0],
+    }
+}
+
+// The verdicts of the checks of all JMPs in `step`
+fn judge_step(transcripts: &[JmpTranscript], step: u64) -> Vec<Verdict> {
+    let mut verdicts = Vec::new();
+    for receiver in 0..3 {
+        for hasher in (0..3).filter(|hasher| *hasher != receiver) {
+            let sender = 3 - receiver - hasher;
+            // The receiver did not get any values vouched for by the hasher
+            let Some(values_hashes) = &transcripts[receiver].received_values[hasher] else {
+                continue;
+            };
+            let verdict = judge(
+                (receiver, sender, hasher),
+                step_hash(values_hashes, step),
+                transcripts[hasher].sent_hashes[receiver]
+                    .as_ref()
+                    .map(|hashes| step_hash(hashes, step)),
+                transcripts[sender].sent_values[receiver]
+                    .as_ref()
+                    .map(|hashes| step_hash(hashes, step)),
+            );
+            verdicts.extend(verdict);
+        }
+    }
+    verdicts
+}
+
+/// The JMP verification with identifiable abort. Every party broadcasts the hashes of its
+/// transcript, so a single round suffices to check all JMPs since the last verification, and no
+/// further messages are required if the checks succeed. Otherwise, each party names either the cheating party ([`Error::CheatingParty`]) or a pair of
+/// conflicting parties, one of which has cheated ([`Error::ConflictingParties`]).
+///
+/// The JMPs are batched, so an inconsistent message can make the values of two honest parties
+/// differ in all later steps. Thus, only the first inconsistent step is judged.
+///
+/// The honest parties only reach the same verdict if they receive the same transcripts.
+/// [`NetworkTrait::broadcast`] consists of point-to-point messages, so a cheater can send
+/// different transcripts to the two honest parties. With three parties and one corruption, such
+/// an equivocation cannot be detected reliably without a broadcast channel or signatures, so the
+/// honest parties may elect different TTPs (see [`elect_ttp`]) and the recovery of the robust mode
+/// fails.
+pub(crate) async fn jmp_verify<N: NetworkTrait>(
+    network: &mut N,
+    transcript: &JmpTranscript,
+) -> Result<(), Error> {
+    let my_id = network.get_id();
+
+    let msgs = network.broadcast(serialize(transcript)?).await?;
+    let transcripts: Vec<JmpTranscript> = deserialize_all(msgs)?;
+
+    if let Some(cheater) = transcripts.iter().position(|t| !t.is_well_formed()) {
+        tracing::warn!("Party {}: Malformed JMP transcript of {}", my_id, cheater);
+        return Err(Error::CheatingParty(cheater));
+    }
+
+    let steps = transcripts
+        .iter()
+        .flat_map(|transcript| transcript.step_hashes())
+        .flat_map(|hashes| hashes.iter().map(|(step, _)| *step))
+        .collect::<BTreeSet<_>>();
+
+    for step in steps {
+        let verdicts = judge_step(&transcripts, step);
+        if !verdicts.is_empty() {
+            let verdict = combine(&verdicts);
+            tracing::warn!(
+                "Party {}: JMP verification failed in step {}: {:?}",
+                my_id,
+                step,
+                verdict
+            );
+            return Err(verdict.into());
+        }
+    }
+    Ok(())
+}
+
+/// The blame phase after the verification of the distributed zero-knowledge proofs. Every party
+/// broadcasts the ids of the provers whose proofs it rejected. Since the verification messages are
+/// vouched for by the provers (see [`jmp_verify`]), a rejection puts the verifier and the prover in
+/// conflict. Like in [`jmp_verify`], an equivocating cheater can make
+/// the honest parties reach different verdicts.
+pub(crate) async fn proof_blame<N: NetworkTrait>(
+    network: &mut N,
+    rejected: Vec<usize>,
+) -> Result<(), Error> {
+    let my_id = network.get_id();
+
+    let msgs = network.broadcast(serialize(&rejected)?).await?;
+    let all_rejected: Vec<Vec<usize>> = deserialize_all(msgs)?;
+
+    let mut verdicts = Vec::new();
+    for (verifier, rejected) in all_rejected.iter().enumerate() {
+        for prover in rejected {
+            if *prover >= 3 || *prover == verifier {
+                return Err(Error::CheatingParty(verifier));
+            }
+            verdicts.push(Verdict::pair(verifier, *prover));
+        }
+    }
+
+    if verdicts.is_empty() {
+        return Ok(());
+    }
+
+    let verdict = combine(&verdicts);
+    tracing::warn!("Party {}: DZKP verification failed: {:?}", my_id, verdict);
+    Err(verdict.into())
+}
+
+/// Cross-checks hashes of the share components, which are each held by two parties: the first
+/// component of party i is the second component of party i + 1. Every party broadcasts the hashes
+/// of its two components, and the two hashes of each component have to match. Returns the hashes
+/// of all parties, or fails with an identifiable abort like [`jmp_verify`]. `check` names the
+/// check in the log.
+pub(crate) async fn cross_check_components<N: NetworkTrait>(
+    network: &mut N,
+    hashes: [Vec<u8>; 2],
+    check: &str,
+) -> Result<Vec<[Vec<u8>; 2]>, Error> {
+    let my_id = network.get_id();
+
+    let msgs = network.broadcast(serialize(&hashes)?).await?;
+    let all_hashes: Vec<[Vec<u8>; 2]> = deserialize_all(msgs)?;
+
+    let verdicts = (0..3)
+        .filter(|holder| all_hashes[*holder][0] != all_hashes[(holder + 1) % 3][1])
+        .map(|holder| Verdict::pair(holder, (holder + 1) % 3))
+        .collect::<Vec<_>>();
+
+    if !verdicts.is_empty() {
+        let verdict = combine(&verdicts);
+        tracing::warn!("Party {}: {} failed: {:?}", my_id, check, verdict);
+        return Err(verdict.into());
+    }
+    Ok(all_hashes)
+}
+
+/// Cross-checks the share components before a computation in the robust mode (see
+/// [`cross_check_components`]). Returns the hash of each component, indexed by the party which
+/// does not hold it, such that this party can check the component if it is handed over by either
+/// holder later.
+pub(crate) async fn commit_components<N: NetworkTrait>(
+    network: &mut N,
+    hashes: [Vec<u8>; 2],
+) -> Result<[Vec<u8>; 3], Error> {
+    let all_hashes = cross_check_components(network, hashes, "Input commitment").await?;
+
+    // The component held by parties i + 1 and i + 2 is missing at party i
+    Ok(std::array::from_fn(|missing| {
+        all_hashes[(missing + 1) % 3][0].to_owned()
+    }))
+}
+
+/// Elects the trusted third party after an identifiable abort, i.e., a party which is known to be
+/// honest. Returns `None` if the error does not identify the cheater.
+pub(crate) fn elect_ttp(error: &Error) -> Option<usize> {
+    match *error {
+        Error::CheatingParty(p) if p < 3 => Some((p + 1) % 3),
+        Error::ConflictingParties(a, b) if a < 3 && b < 3 && a != b => Some(3 - a - b),
+        _ => None,
+    }
+}
@@ -0,0 +1,343 @@
+use crate::{
+    prelude::{Error, Sharable},
+    traits::{binary_trait::BinaryMpcTrait, share_trait::ShareTrait},
+};
+use std::{
+    ops::{BitXor, BitXorAssign, Not, Shl, ShlAssign},
+    path::Path,
+    str::FromStr,
+};
+
+// Gates which do not require communication
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum LinearGate {
+    Xor(usize, usize, usize),
+    Inv(usize, usize),
+    Eqw(usize, usize),
+    Eq(bool, usize),
+}
+
+// All ANDs of one layer are independent of each other and are evaluated in one batch. The linear
+// gates are evaluated afterwards in the order of the circuit file.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct Layer {
+    ands: Vec<(usize, usize, usize)>,
+    linear: Vec<LinearGate>,
+}
+
+/// A boolean circuit in the Bristol Fashion format
+/// (<https://nigelsmart.github.io/MPC-Circuits/>). Supported gates are XOR, AND, INV, EQ, EQW and
+/// MAND. The wires of each input and output value are given in the order of the circuit file,
+/// i.e., for the circuits of the above website, least significant bit first.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Circuit {
+    num_wires: usize,
+    input_sizes: Vec<usize>,
+    output_sizes: Vec<usize>,
+    num_ands: usize,
+    layers: Vec<Layer>,
+}
+
+fn parse_usize(token: Option<&str>, line: usize) -> Result<usize, Error> {
+    token
+        .ok_or_else(|| Error::ValueError(format!("Line {line}: unexpected end of line")))?
+        .parse()
+        .map_err(|_| Error::ValueError(format!("Line {line}: expected a number")))
+}
+
+// Parses a line of the form "n a_1 ... a_n"
+fn parse_sizes(line: Option<(usize, &str)>) -> Result<Vec<usize>, Error> {
+    let (i, line) = line.ok_or_else(|| Error::ValueError("Incomplete header".to_owned()))?;
+    let mut tokens = line.split_whitespace();
+    let n = parse_usize(tokens.next(), i)?;
+    let sizes = (0..n)
+        .map(|_| parse_usize(tokens.next(), i))
+        .collect::<Result<Vec<_>, _>>()?;
+    if tokens.next().is_some() {
+        return Err(Error::ValueError(format!("Line {i}: too many values")));
+    }
+    Ok(sizes)
+}
+
+impl FromStr for Circuit {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Line numbers start at 1 in the error messages
+        let mut lines = s
+            .lines()
+            .enumerate()
+            .map(|(i, l)| (i + 1, l.trim()))
+            .filter(|(_, l)| !l.is_empty());
+
+        let (i, header) = lines
+            .next()
+            .ok_or_else(|| Error::ValueError("Empty circuit".to_owned()))?;
+        let mut tokens = header.split_whitespace();
+        let num_gates = parse_usize(tokens.next(), i)?;
+        let num_wires = parse_usize(tokens.next(), i)?;
+        let input_sizes = parse_sizes(lines.next())?;
+        let output_sizes = parse_sizes(lines.next())?;
+
+        let num_inputs = input_sizes.iter().sum::<usize>();
+        let num_outputs = output_sizes.iter().sum::<usize>();
+        if num_inputs > num_wires || num_outputs > num_wires {
+            return Err(Error::ValueError(
+                "More inputs or outputs than wires".to_owned(),
+            ));
+        }
+
+        // The AND depth of each wire, None if the wire is not assigned yet
+        let mut depth = vec![None; num_wires];
+        depth[..num_inputs].fill(Some(0));
+        let mut layers = vec![Layer::default()];
+        let mut num_ands = 0;
+
+        let mut gates = 0;
+        for (i, line) in lines {
+            gates += 1;
+            let tokens = line.split_whitespace().collect::<Vec<_>>();
+            let mut tokens = tokens.iter().copied();
+            let num_in = parse_usize(tokens.next(), i)?;
+            let num_out = parse_usize(tokens.next(), i)?;
+            let wires_in = (0..num_in)
+                .map(|_| parse_usize(tokens.next(), i))
+                .collect::<Result<Vec<_>, _>>()?;
+            let wires_out = (0..num_out)
+                .map(|_| parse_usize(tokens.next(), i))
+                .collect::<Result<Vec<_>, _>>()?;
+            let gate = tokens
+                .next()
+                .ok_or_else(|| Error::ValueError(format!("Line {i}: missing gate type")))?;
+            if tokens.next().is_some() {
+                return Err(Error::ValueError(format!("Line {i}: too many values")));
+            }
+
+            for w in wires_out.iter() {
+                if *w >= num_wires || depth[*w].is_some() {
+                    return Err(Error::ValueError(format!(
+                        "Line {i}: wire {w} is invalid or already assigned"
+                    )));
+                }
+            }
+            // EQ takes a constant instead of an input wire
+            let input_depth = if gate == "EQ" {
+                Vec::new()
+            } else {
+                wires_in
+                    .iter()
+                    .map(|w| {
+                        depth.get(*w).copied().flatten().ok_or_else(|| {
+                            Error::ValueError(format!("Line {i}: wire {w} is not assigned"))
+                        })
+                    })
+                    .collect::<Result<Vec<_>, _>>()?
+            };
+
+            let arity_error = || Error::ValueError(format!("Line {i}: invalid arity for {gate}"));
+            match gate {
+                "AND" | "MAND" => {
+                    let n = num_out;
+                    if num_in != 2 * n || (gate == "AND" && n != 1) {
+                        return Err(arity_error());
+                    }
+                    for j in 0..n {
+                        let d = input_depth[j].max(input_depth[n + j]) + 1;
+                        if layers.len() <= d {
+                            layers.push(Layer::default());
+                        }
+                        layers[d]
+                            .ands
+                            .push((wires_in[j], wires_in[n + j], wires_out[j]));
+                        depth[wires_out[j]] = Some(d);
+                    }
+                    num_ands += n;
+                }
+                _ => {
+                    let (lin, d) = match (gate, num_in, num_out) {
+                        ("XOR", 2, 1) => (
+                            LinearGate::Xor(wires_in[0], wires_in[1], wires_out[0]),
+                            input_depth[0].max(input_depth[1]),
+                        ),
+                        ("INV", 1, 1) => {
+                            (LinearGate::Inv(wires_in[0], wires_out[0]), input_depth[0])
+                        }
+                        ("EQW", 1, 1) => {
+                            (LinearGate::Eqw(wires_in[0], wires_out[0]), input_depth[0])
+                        }
+                        ("EQ", 1, 1) => {
+                            let value = match wires_in[0] {
+                                0 => false,
+                                1 => true,
+                                _ => return Err(arity_error()),
+                            };
+                            (LinearGate::Eq(value, wires_out[0]), 0)
+                        }
+                        ("XOR" | "INV" | "EQW" | "EQ", _, _) => return Err(arity_error()),
+                        _ => {
+                            return Err(Error::ValueError(format!(
+                                "Line {i}: unsupported gate {gate}"
+                            )))
+                        }
+                    };
+                    layers[d].linear.push(lin);
+                    depth[wires_out[0]] = Some(d);
+                }
+            }
+        }
+
+        if gates != num_gates {
+            return Err(Error::ValueError(format!(
+                "Expected {num_gates} gates, found {gates}"
+            )));
+        }
+        if depth[num_wires - num_outputs..].iter().any(|d| d.is_none()) {
+            return Err(Error::ValueError("Unassigned output wire".to_owned()));
+        }
+
+        Ok(Self {
+            num_wires,
+            input_sizes,
+            output_sizes,
+            num_ands,
+            layers,
+        })
+    }
+}
+
+impl Circuit {
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, Error> {
+        std::fs::read_to_string(path)?.parse()
+    }
+
+    /// Returns the number of wires of each input value.
+    pub fn input_sizes(&self) -> &[usize] {
+        &self.input_sizes
+    }
+
+    /// Returns the number of wires of each output value.
+    pub fn output_sizes(&self) -> &[usize] {
+        &self.output_sizes
+    }
+
+    pub fn and_gates(&self) -> usize {
+        self.num_ands
+    }
+
+    /// Returns the number of communication rounds of [`Self::evaluate`].
+    pub fn and_depth(&self) -> usize {
+        self.layers.len() - 1
+    }
+
+    /// Evaluates the circuit on binary shares. Each share is one wire, so packed shares evaluate
+    /// the circuit on all of their bits in parallel. `inputs[i][j]` is wire j of input value i.
+    pub async fn evaluate<T, Mpc, S>(
+        &self,
+        engine: &mut Mpc,
+        inputs: Vec<Vec<S>>,
+    ) -> Result<Vec<Vec<S>>, Error>
+    where
+        T: Sharable,
+        Mpc: BinaryMpcTrait<T, S>,
+        S: ShareTrait<VecShare = Vec<S>>
+            + Clone
+            + BitXorAssign
+            + BitXor<Output = S>
+            + ShlAssign<u32>
+            + Shl<u32, Output = S>
+            + Not<Output = S>
+            + Send
+            + Sync
+            + 'static,
+    {
+        if inputs.len() != self.input_sizes.len()
+            || inputs
+                .iter()
+                .zip(self.input_sizes.iter())
+                .any(|(i, s)| i.len() != *s)
+        {
+            return Err(Error::InvalidSizeError);
+        }
+
+        let mut wires = inputs.into_iter().flatten().collect::<Vec<_>>();
+        wires.resize(self.num_wires, S::zero());
+
+        for layer in self.layers.iter() {
+            if !layer.ands.is_empty() {
+                let (a, b): (Vec<_>, Vec<_>) = layer
+                    .ands
+                    .iter()
+                    .map(|(a, b, _)| (wires[*a].to_owned(), wires[*b].to_owned()))
+                    .unzip();
+                let res = engine.and_many(&a, &b).await?;
+                for ((_, _, out), r) in layer.ands.iter().zip(res) {
+                    wires[*out] = r;
+                }
+            }
+
+            for gate in layer.linear.iter() {
+                match gate {
+                    LinearGate::Xor(a, b, out) => {
+                        wires[*out] = Mpc::xor(wires[*a].to_owned(), wires[*b].to_owned())
+                    }
+                    LinearGate::Inv(a, out) => wires[*out] = !wires[*a].to_owned(),
+                    LinearGate::Eqw(a, out) => wires[*out] = wires[*a].to_owned(),
+                    LinearGate::Eq(false, out) => wires[*out] = S::zero(),
+                    LinearGate::Eq(true, out) => wires[*out] = !S::zero(),
+                }
+            }
+        }
+
+        // The outputs are the last wires
+        let mut outputs = wires.split_off(self.num_wires - self.output_sizes.iter().sum::<usize>());
+        let mut res = Vec::with_capacity(self.output_sizes.len());
+        for size in self.output_sizes.iter() {
+            let rest = outputs.split_off(*size);
+            res.push(outputs);
+            outputs = rest;
+        }
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // Computes (a ^ b, a & b) on 2 bits
+    const SIMPLE: &str = "3 8
+        2 2 2
+        2 2 2
+
+        2 1 0 2 4 XOR
+        2 1 1 3 5 XOR
+        4 2 0 1 2 3 6 7 MAND
+    ";
+
+    #[test]
+    fn parse_test() {
+        let circuit = Circuit::from_str(SIMPLE).unwrap();
+        assert_eq!(circuit.input_sizes(), &[2, 2]);
+        assert_eq!(circuit.output_sizes(), &[2, 2]);
+        assert_eq!(circuit.and_gates(), 2);
+        assert_eq!(circuit.and_depth(), 1);
+        assert!(Circuit::from_str(&SIMPLE.replace("2 1 1 3 5 XOR", "1 1 1 5 EQ")).is_ok());
+    }
+
+    #[test]
+    fn parse_error_test() {
+        // Wrong gate count
+        assert!(Circuit::from_str(&SIMPLE.replacen("3 8", "4 8", 1)).is_err());
+        // Unassigned input wire
+        assert!(Circuit::from_str(&SIMPLE.replace("0 2 4 XOR", "0 9 4 XOR")).is_err());
+        // Wire assigned twice
+        assert!(Circuit::from_str(&SIMPLE.replace("1 3 5 XOR", "1 3 4 XOR")).is_err());
+        // Unsupported gate
+        assert!(Circuit::from_str(&SIMPLE.replace("XOR", "OR")).is_err());
+        // Wrong arity
+        assert!(Circuit::from_str(&SIMPLE.replace("2 1 0 2 4 XOR", "1 1 0 4 XOR")).is_err());
+        assert!(Circuit::from_str(&SIMPLE.replace("MAND", "AND")).is_err());
+        // Invalid constant
+        assert!(Circuit::from_str(&SIMPLE.replace("2 1 1 3 5 XOR", "1 1 2 5 EQ")).is_err());
+    }
+}
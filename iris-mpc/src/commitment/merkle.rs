@@ -0,0 +1,46 @@
+use super::hash;
+
+// Leaves and inner nodes are hashed with different prefixes, such that an inner node can not be
+// passed off as a leaf
+const LEAF_PREFIX: u8 = 0;
+const NODE_PREFIX: u8 = 1;
+
+/// A Merkle tree over a sequence of leaves, using the hash function of the commitments. Each leaf
+/// is the concatenation of its parts. A node without a sibling is moved up to the next level
+/// unchanged.
+#[derive(Clone, Debug, Default)]
+pub struct MerkleTree {
+    leaves: Vec<Vec<u8>>,
+}
+
+impl MerkleTree {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, leaf: &[&[u8]]) {
+        let mut parts = Vec::with_capacity(leaf.len() + 1);
+        parts.push([LEAF_PREFIX].as_slice());
+        parts.extend_from_slice(leaf);
+        self.leaves.push(hash(&parts));
+    }
+
+    /// The root of the tree. The root of the empty tree is the hash of the node prefix.
+    pub fn root(&self) -> Vec<u8> {
+        if self.leaves.is_empty() {
+            return hash(&[&[NODE_PREFIX]]);
+        }
+        let mut level = self.leaves.to_owned();
+        while level.len() > 1 {
+            level = level
+                .chunks(2)
+                .map(|nodes| match nodes {
+                    [left, right] => hash(&[&[NODE_PREFIX], left, right]),
+                    [node] => node.to_owned(),
+                    _ => unreachable!(),
+                })
+                .collect();
+        }
+        level.pop().expect("level is not empty")
+    }
+}
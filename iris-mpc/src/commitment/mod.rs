@@ -1,8 +1,19 @@
+pub(crate) mod merkle;
+
 use crate::{aby3::utils::ring_slice_to_bytes, types::ring_element::RingImpl};
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha512};
 
+/// The hash function of the commitments, applied to the concatenation of `parts`.
+pub(crate) fn hash(parts: &[&[u8]]) -> Vec<u8> {
+    let mut hasher = Sha512::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize().to_vec()
+}
+
 #[derive(Clone, Debug)]
 pub struct Commitment<R: RingImpl> {
     /// The values which were committed to.
@@ -38,12 +49,8 @@ impl<R: RingImpl> Commitment<R> {
     }
 
     pub fn commit_with_rand(values: Vec<R>, rand: [u8; 32]) -> Self {
-        let mut hasher = Sha512::new();
         let bytes = ring_slice_to_bytes(&values);
-        hasher.update(bytes);
-        hasher.update(rand);
-
-        let comm = hasher.finalize().to_vec();
+        let comm = hash(&[&bytes, &rand]);
         Self { values, rand, comm }
     }
 
@@ -57,12 +64,8 @@ impl<R: RingImpl> Commitment<R> {
 
 impl<R: RingImpl> CommitOpening<R> {
     pub fn verify(self, comm: Vec<u8>) -> bool {
-        let mut hasher = Sha512::new();
         let bytes = ring_slice_to_bytes(&self.values);
-        hasher.update(bytes);
-        hasher.update(self.rand);
-
-        let comm2 = hasher.finalize().to_vec();
+        let comm2 = hash(&[&bytes, &self.rand]);
         comm == comm2
     }
 }
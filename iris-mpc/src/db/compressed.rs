@@ -0,0 +1,325 @@
+use crate::{
+    aby3::{id::PartyID, random::prf::PrfSeed, share::Share},
+    db::party_db::{check_party_info, read_code, with_infix, write_party_info},
+    error::Error,
+    types::{ring_element::RingImpl, sharable::Sharable},
+};
+use plain_reference::{IrisCode, IrisCodeArray};
+use rand::{
+    distributions::{Distribution, Standard},
+    Rng, SeedableRng,
+};
+use rand_chacha::ChaCha12Rng;
+use rusqlite::Connection;
+use std::path::{Path, PathBuf};
+
+// A DB entry x is shared as x = x0 + x1 + x2, where party i holds (xi, x{i-1}). The components x0
+// and x1 are derived from the seeds s0 and s1, where s0 is known to parties 0 and 1, and s1 is
+// known to parties 1 and 2. Only x2 = x - x0 - x1 has to be stored, i.e., party 0 and party 2 store
+// one component instead of two and party 1 only stores its seeds. Party 1 must never see x2, since
+// it would learn x from it, thus each party gets its own database.
+
+/// The PRF seeds from which the first two share components of a compressed database are derived.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DbSeeds {
+    seeds: [PrfSeed; 2],
+}
+
+/// The seeds of a single party, i.e., the seeds of its own and its previous share component, if
+/// these components are derived from a seed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PartySeeds {
+    id: PartyID,
+    own: Option<PrfSeed>,
+    prev: Option<PrfSeed>,
+}
+
+impl DbSeeds {
+    pub fn new(seed0: PrfSeed, seed1: PrfSeed) -> Self {
+        Self {
+            seeds: [seed0, seed1],
+        }
+    }
+
+    pub fn random<R: Rng>(rng: &mut R) -> Self {
+        Self::new(rng.gen(), rng.gen())
+    }
+
+    /// Returns the seeds a party is allowed to know.
+    pub fn party_seeds(&self, id: usize) -> Result<PartySeeds, Error> {
+        let id = PartyID::try_from(id)?;
+        let res = match id {
+            PartyID::ID0 => PartySeeds::new(id, Some(self.seeds[0]), None),
+            PartyID::ID1 => PartySeeds::new(id, Some(self.seeds[1]), Some(self.seeds[0])),
+            PartyID::ID2 => PartySeeds::new(id, None, Some(self.seeds[1])),
+        };
+        Ok(res)
+    }
+
+    /// Computes the share component x2 of `code` which has to be stored in the database.
+    pub fn compress_code<T: Sharable>(&self, code: &IrisCodeArray, record: u64) -> Vec<T::Share>
+    where
+        Standard: Distribution<T::Share>,
+    {
+        let mut rng0 = record_rng(&self.seeds[0], record);
+        let mut rng1 = record_rng(&self.seeds[1], record);
+        code.bits()
+            .map(|bit| {
+                let x0 = rng0.gen::<T::Share>();
+                let x1 = rng1.gen::<T::Share>();
+                T::from(bit).to_sharetype() - x0 - x1
+            })
+            .collect()
+    }
+}
+
+impl PartySeeds {
+    fn new(id: PartyID, own: Option<PrfSeed>, prev: Option<PrfSeed>) -> Self {
+        Self { id, own, prev }
+    }
+
+    pub fn get_id(&self) -> usize {
+        self.id as usize
+    }
+
+    /// Returns whether this party has to store a share component for each database entry.
+    pub fn needs_stored_component(&self) -> bool {
+        self.own.is_none() || self.prev.is_none()
+    }
+
+    /// Expands a compressed database entry into the shares of this party. `stored` is the stored
+    /// share component x2, which is ignored by party 1.
+    pub fn expand_shares<T: Sharable>(
+        &self,
+        record: u64,
+        stored: Option<Vec<T::Share>>,
+    ) -> Result<Vec<Share<T>>, Error>
+    where
+        Standard: Distribution<T::Share>,
+    {
+        let stored = if self.needs_stored_component() {
+            let stored = stored.ok_or(Error::InvalidCodeSizeError)?;
+            if stored.len() != IrisCode::IRIS_CODE_SIZE {
+                return Err(Error::InvalidCodeSizeError);
+            }
+            stored
+        } else {
+            Vec::new()
+        };
+
+        let mut own = self.own.as_ref().map(|seed| record_rng(seed, record));
+        let mut prev = self.prev.as_ref().map(|seed| record_rng(seed, record));
+        let mut stored = stored.into_iter();
+
+        let mut res = Vec::with_capacity(IrisCode::IRIS_CODE_SIZE);
+        for _ in 0..IrisCode::IRIS_CODE_SIZE {
+            let a = match own.as_mut() {
+                Some(rng) => rng.gen(),
+                None => stored.next().expect("Length checked above"),
+            };
+            let b = match prev.as_mut() {
+                Some(rng) => rng.gen(),
+                None => stored.next().expect("Length checked above"),
+            };
+            res.push(Share::new(a, b));
+        }
+        Ok(res)
+    }
+}
+
+// The stored components are only valid for the ring they were computed in, so the share width is
+// recorded in the party info of the database
+fn compressed_protocol<T: Sharable>() -> String {
+    format!("compressed-u{}", T::Share::K)
+}
+
+// Each record uses its own stream of the PRF, such that records can be expanded independently
+fn record_rng(seed: &PrfSeed, record: u64) -> ChaCha12Rng {
+    let mut rng = ChaCha12Rng::from_seed(*seed);
+    rng.set_stream(record);
+    rng
+}
+
+/// Creates the table of the compressed database of the party with `seeds`. It only contains the
+/// masks and, for parties 0 and 2, the stored share components. The seeds are kept in a separate
+/// file per party (see [`write_party_seeds`]).
+pub fn create_compressed_tables(conn: &Connection, seeds: &PartySeeds) -> Result<(), Error> {
+    let query = if seeds.needs_stored_component() {
+        "CREATE TABLE IF NOT EXISTS iris_codes_compressed (
+            id INTEGER PRIMARY KEY,
+            mask BLOB NOT NULL,
+            share_c BLOB NOT NULL
+        )"
+    } else {
+        "CREATE TABLE IF NOT EXISTS iris_codes_compressed (
+            id INTEGER PRIMARY KEY,
+            mask BLOB NOT NULL
+        )"
+    };
+    conn.execute(query, rusqlite::params![])?;
+    Ok(())
+}
+
+/// Writes the compressed database of `party` for `codes`, shared in the ring of `T`. The record ids
+/// start at 1, as for the uncompressed tables.
+pub fn write_compressed_db<T: Sharable>(
+    conn: &mut Connection,
+    seeds: &DbSeeds,
+    party: usize,
+    codes: &[IrisCode],
+) -> Result<(), Error>
+where
+    Standard: Distribution<T::Share>,
+{
+    let party_seeds = seeds.party_seeds(party)?;
+    write_party_info(conn, party, &compressed_protocol::<T>())?;
+    create_compressed_tables(conn, &party_seeds)?;
+
+    let transaction = conn.transaction()?;
+    if party_seeds.needs_stored_component() {
+        let mut stmt = transaction
+            .prepare("INSERT INTO iris_codes_compressed (id, mask, share_c) VALUES (?1, ?2, ?3)")?;
+        for (i, code) in codes.iter().enumerate() {
+            let record = i as u64 + 1;
+            let data = bincode::serialize(&seeds.compress_code::<T>(&code.code, record))
+                .map_err(|_| Error::SerializationError)?;
+            stmt.execute(rusqlite::params![record, code.mask.as_raw_slice(), &data])?;
+        }
+    } else {
+        let mut stmt =
+            transaction.prepare("INSERT INTO iris_codes_compressed (id, mask) VALUES (?1, ?2)")?;
+        for (i, code) in codes.iter().enumerate() {
+            let record = i as u64 + 1;
+            stmt.execute(rusqlite::params![record, code.mask.as_raw_slice()])?;
+        }
+    }
+    transaction.commit()?;
+    Ok(())
+}
+
+/// Returns whether the compressed database contains the stored share components.
+pub fn has_stored_component(conn: &Connection) -> Result<bool, Error> {
+    let count = conn.query_row(
+        "SELECT COUNT(*) FROM pragma_table_info('iris_codes_compressed') WHERE name = 'share_c';",
+        [],
+        |row| row.get::<_, usize>(0),
+    )?;
+    Ok(count != 0)
+}
+
+/// The path of the seed file of `party`, derived from the path of the compressed database, i.e.,
+/// `db.sqlite` becomes `db.party0.seeds.sqlite`.
+pub fn party_seeds_path(database: &Path, party: usize) -> PathBuf {
+    with_infix(database, &format!("party{party}.seeds"))
+}
+
+/// Stores the seeds of a single party, such that each party only gets to read its own seeds.
+pub fn write_party_seeds(conn: &Connection, seeds: &PartySeeds) -> Result<(), Error> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS prf_seeds (
+            id INTEGER PRIMARY KEY,
+            party INTEGER NOT NULL,
+            seed_own BLOB,
+            seed_prev BLOB
+        )",
+        rusqlite::params![],
+    )?;
+    conn.execute(
+        "INSERT INTO prf_seeds (party, seed_own, seed_prev) VALUES (?1, ?2, ?3)",
+        rusqlite::params![seeds.get_id(), seeds.own, seeds.prev],
+    )?;
+    Ok(())
+}
+
+/// Reads the seeds of `party` from its seed file.
+pub fn read_party_seeds(conn: &Connection, party: usize) -> Result<PartySeeds, Error> {
+    let (party_, own, prev) = conn.query_row(
+        "SELECT party, seed_own, seed_prev from prf_seeds;",
+        [],
+        |row| {
+            Ok((
+                row.get::<_, usize>(0)?,
+                row.get::<_, Option<Vec<u8>>>(1)?,
+                row.get::<_, Option<Vec<u8>>>(2)?,
+            ))
+        },
+    )?;
+    if party_ != party {
+        return Err(Error::IdError(party_));
+    }
+    let to_seed = |seed: Option<Vec<u8>>| {
+        seed.map(|seed| seed.try_into().map_err(|_| Error::SerializationError))
+            .transpose()
+    };
+    let seeds = PartySeeds::new(PartyID::try_from(party)?, to_seed(own)?, to_seed(prev)?);
+    // The seeds a party knows are determined by its id
+    if seeds.own.is_none() != (seeds.id == PartyID::ID2)
+        || seeds.prev.is_none() != (seeds.id == PartyID::ID0)
+    {
+        return Err(Error::ValueError(format!(
+            "Invalid seeds for party {party}"
+        )));
+    }
+    Ok(seeds)
+}
+
+/// The shares of a party for the whole database, in the order of the record ids.
+pub struct DbShares<V> {
+    pub shares: Vec<V>,
+    pub masks: Vec<IrisCodeArray>,
+}
+
+impl<V> Default for DbShares<V> {
+    fn default() -> Self {
+        Self {
+            shares: Vec::new(),
+            masks: Vec::new(),
+        }
+    }
+}
+
+/// Reads a compressed database and expands the entries into the shares of the party with the
+/// given seeds. Fails if the database was written for another party or another ring, or if the
+/// database of party 1 contains the stored share components, since party 1 could reconstruct the
+/// codes from them.
+pub fn read_compressed_db<T: Sharable>(
+    conn: &Connection,
+    seeds: &PartySeeds,
+) -> Result<DbShares<Vec<Share<T>>>, Error>
+where
+    Standard: Distribution<T::Share>,
+{
+    check_party_info(conn, seeds.get_id(), &compressed_protocol::<T>())?;
+    if has_stored_component(conn)? != seeds.needs_stored_component() {
+        return Err(Error::ValueError(format!(
+            "The compressed database does not belong to party {}",
+            seeds.get_id()
+        )));
+    }
+    let query = if seeds.needs_stored_component() {
+        "SELECT id, mask, share_c from iris_codes_compressed ORDER BY id;"
+    } else {
+        "SELECT id, mask from iris_codes_compressed ORDER BY id;"
+    };
+    let mut stmt = conn.prepare(query)?;
+    let mut rows = stmt.query([])?;
+
+    let mut shares = Vec::new();
+    let mut masks = Vec::new();
+    while let Some(row) = rows.next()? {
+        let record = row.get::<_, u64>(0)?;
+        let mask = read_code(row, 1)?;
+
+        let stored = if seeds.needs_stored_component() {
+            let stored: Vec<T::Share> = bincode::deserialize(&row.get::<_, Vec<u8>>(2)?)
+                .map_err(|_| Error::SerializationError)?;
+            Some(stored)
+        } else {
+            None
+        };
+
+        shares.push(seeds.expand_shares::<T>(record, stored)?);
+        masks.push(mask);
+    }
+    Ok(DbShares { shares, masks })
+}
@@ -0,0 +1,178 @@
+use crate::{
+    db::compressed::DbShares,
+    error::Error,
+    prelude::{MpcTrait, Sharable},
+    traits::share_trait::{BitShareTrait, ShareTrait, VecShareTrait},
+};
+use plain_reference::{IrisCode, IrisCodeArray};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+
+// Generates the shares of a database inside the MPC protocol, such that no party acts as a dealer
+// which sees all plain codes. Each party only learns its own shares and the masks, which are
+// public in the matching protocol anyway. For SpdzWise, the MAC key has to be set before, e.g., with
+// `set_new_mac_key`, such that it never appears in plain.
+
+fn bits_to_ring<T: Sharable>(code: &IrisCodeArray) -> Vec<T> {
+    code.bits().map(T::from).collect()
+}
+
+/// Secret shares the iris codes of party `owner` by inputting them into the MPC protocol. Only
+/// the owner has to provide the `amount` codes, while the masks are opened to all parties. Codes
+/// and masks which are not binary are rejected.
+pub async fn input_codes<T, Ashare, Bshare, Mpc>(
+    mpc: &mut Mpc,
+    codes: Option<&[IrisCode]>,
+    amount: usize,
+    owner: usize,
+) -> Result<DbShares<Ashare::VecShare>, Error>
+where
+    T: Sharable,
+    Ashare: ShareTrait,
+    Bshare: BitShareTrait,
+    Mpc: MpcTrait<T, Ashare, Bshare>,
+{
+    if codes.is_some_and(|codes| codes.len() != amount) {
+        return Err(Error::InvalidSizeError);
+    }
+
+    let mut res = DbShares::default();
+    for i in 0..amount {
+        // Code and mask are input at once and the mask is opened afterwards
+        let input = codes.map(|codes| {
+            codes[i]
+                .code
+                .bits()
+                .chain(codes[i].mask.bits())
+                .map(T::from)
+                .collect()
+        });
+        let shares = mpc
+            .input_many(input, 2 * IrisCode::IRIS_CODE_SIZE, owner)
+            .await?;
+        let (code, mask) = shares.split_at(IrisCode::IRIS_CODE_SIZE);
+
+        // The bit check of the code is opened together with the mask
+        let mut to_open = bit_checks(mpc, &code).await?;
+        to_open.extend(mask);
+        let mut opened = mpc.open_many(to_open).await?;
+        let mask = opened.split_off(IrisCode::IRIS_CODE_SIZE);
+        if opened.into_iter().any(|check| !check.is_zero()) {
+            return Err(Error::ValueError("Code is not binary".to_string()));
+        }
+
+        let mut plain_mask = IrisCodeArray::ZERO;
+        for (index, bit) in mask.into_iter().enumerate() {
+            if bit > T::one() {
+                return Err(Error::ValueError("Mask is not binary".to_string()));
+            }
+            plain_mask.set_bit(index, bit == T::one());
+        }
+
+        res.shares.push(code);
+        res.masks.push(plain_mask);
+    }
+    Ok(res)
+}
+
+/// Jointly generates `amount` random iris codes inside the MPC protocol: Each party inputs random
+/// bits and the code is their XOR, such that the code stays random as long as one party is honest.
+/// The inputs are checked to be bits before they are combined.
+/// The masks are public and derived from `mask_seed`, which has to be the same for all parties.
+pub async fn generate_random_codes<T, Ashare, Bshare, Mpc, R>(
+    mpc: &mut Mpc,
+    amount: usize,
+    mask_seed: u64,
+    rng: &mut R,
+) -> Result<DbShares<Ashare::VecShare>, Error>
+where
+    T: Sharable,
+    Ashare: ShareTrait,
+    Bshare: BitShareTrait,
+    Mpc: MpcTrait<T, Ashare, Bshare>,
+    R: Rng,
+{
+    let id = mpc.get_id();
+    let mut mask_rng = ChaCha12Rng::seed_from_u64(mask_seed);
+
+    let mut res = DbShares::default();
+    for _ in 0..amount {
+        let mut inputs = Vec::with_capacity(3);
+        for party in 0..3 {
+            let input = if party == id {
+                let bits = IrisCodeArray::random_rng(rng);
+                Some(bits_to_ring::<T>(&bits))
+            } else {
+                None
+            };
+            let bits = mpc
+                .input_many(input, IrisCode::IRIS_CODE_SIZE, party)
+                .await?;
+            inputs.push(bits);
+        }
+
+        // A party could input values other than bits, which would break the XOR
+        let mut all = Ashare::VecShare::with_capacity(3 * IrisCode::IRIS_CODE_SIZE);
+        for bits in inputs.iter() {
+            all.extend(bits.to_owned());
+        }
+        let checks = bit_checks(mpc, &all).await?;
+        let checks = mpc.open_many(checks).await?;
+        if checks.into_iter().any(|check| !check.is_zero()) {
+            return Err(Error::ValueError("Input is not binary".to_string()));
+        }
+
+        let mut inputs = inputs.into_iter();
+        let mut code = inputs.next().expect("Three inputs");
+        for bits in inputs {
+            code = xor_many(mpc, code, bits).await?;
+        }
+
+        res.shares.push(code);
+        res.masks.push(IrisCode::random_rng(&mut mask_rng).mask);
+    }
+    Ok(res)
+}
+
+// Computes x * (x - 1) for all shared values, which is zero if and only if x is a bit. Opening
+// the result therefore reveals nothing about valid bits.
+async fn bit_checks<T, Ashare, Bshare, Mpc>(
+    mpc: &mut Mpc,
+    x: &Ashare::VecShare,
+) -> Result<Ashare::VecShare, Error>
+where
+    T: Sharable,
+    Ashare: ShareTrait,
+    Bshare: BitShareTrait,
+    Mpc: MpcTrait<T, Ashare, Bshare>,
+{
+    let mut x_minus_one = Ashare::VecShare::with_capacity(x.len());
+    for i in 0..x.len() {
+        x_minus_one.push(mpc.sub_const(x.get_at(i), T::one()));
+    }
+    mpc.mul_many(x.to_owned(), x_minus_one).await
+}
+
+// Computes the XOR of shared bits as a + b - 2ab
+async fn xor_many<T, Ashare, Bshare, Mpc>(
+    mpc: &mut Mpc,
+    a: Ashare::VecShare,
+    b: Ashare::VecShare,
+) -> Result<Ashare::VecShare, Error>
+where
+    T: Sharable,
+    Ashare: ShareTrait,
+    Bshare: BitShareTrait,
+    Mpc: MpcTrait<T, Ashare, Bshare>,
+{
+    let products = mpc.mul_many(a.to_owned(), b.to_owned()).await?;
+
+    let mut res = Ashare::VecShare::with_capacity(a.len());
+    for i in 0..a.len() {
+        let sum = mpc.add(a.get_at(i), b.get_at(i));
+        let ab = products.get_at(i);
+        let ab2 = mpc.add(ab.to_owned(), ab);
+        res.push(mpc.sub(sum, ab2));
+    }
+    Ok(res)
+}
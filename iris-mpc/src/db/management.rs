@@ -0,0 +1,189 @@
+use crate::{
+    aby3::share::Share as Aby3Share,
+    db::{
+        compressed::DbShares,
+        party_db::{
+            aby3_blobs, check_party_info, read_party_info, spdzwise_blobs, swift3_blobs,
+            update_records, ABY3_COLUMNS, SPDZWISE_COLUMNS, SWIFT3_COLUMNS,
+        },
+    },
+    error::Error,
+    spdzwise::vecshare::VecShare as SpdzWiseVecShare,
+    swift3::share::Share as Swift3Share,
+    traits::network_trait::NetworkTrait,
+    types::sharable::Sharable,
+};
+use bytes::Bytes;
+use rusqlite::Connection;
+use sha2::{Digest, Sha512};
+use std::collections::HashSet;
+
+// Deleting and updating records of the party databases. The databases of all parties have to
+// contain the same records in the same order, since the records are matched by their position.
+// Each change is therefore applied in a local transaction, after which the parties exchange a
+// digest of their record ids (and the public masks of the changed records). The transaction is
+// only committed if all digests are equal, otherwise all parties roll back.
+
+/// Returns the ids of all records in a party database in ascending order.
+pub fn party_db_ids(conn: &Connection) -> Result<Vec<u64>, Error> {
+    let mut stmt = conn.prepare("SELECT id from iris_codes ORDER BY id;")?;
+    let ids = stmt
+        .query_map([], |row| row.get::<_, u64>(0))?
+        .collect::<Result<_, _>>()?;
+    Ok(ids)
+}
+
+/// Hashes the record ids of a party database, which determine the order of the records, together
+/// with the masks of the records in `changed` that still exist. The masks are public, thus the
+/// digest is equal at all parties if their databases are consistent.
+pub fn party_db_digest(conn: &Connection, changed: &[u64]) -> Result<Vec<u8>, Error> {
+    let mut hasher = Sha512::new();
+    for id in party_db_ids(conn)? {
+        hasher.update(id.to_le_bytes());
+    }
+    let mut stmt = conn.prepare("SELECT mask from iris_codes WHERE id = ?1;")?;
+    for id in changed {
+        let mut rows = stmt.query([id])?;
+        if let Some(row) = rows.next()? {
+            hasher.update(id.to_le_bytes());
+            hasher.update(row.get::<_, Vec<u8>>(0)?);
+        }
+    }
+    Ok(hasher.finalize().to_vec())
+}
+
+// Sends our digest, or an empty message if we failed, and returns whether all parties agree
+async fn agree_on_digest<N: NetworkTrait>(
+    network: &mut N,
+    digest: Option<&[u8]>,
+) -> Result<(), Error> {
+    let data = Bytes::copy_from_slice(digest.unwrap_or_default());
+    let digests = network.broadcast(data).await?;
+    for (party, other) in digests.iter().enumerate() {
+        if other.is_empty() {
+            return Err(Error::ValueError(format!(
+                "Party {party} failed to apply the change"
+            )));
+        }
+        if digest.is_some_and(|digest| digest != other.as_ref()) {
+            return Err(Error::InconsistentDatabase(party));
+        }
+    }
+    Ok(())
+}
+
+/// Checks that the databases of all parties contain the same records, without changing them. Like
+/// the changes, it takes the connection mutably, since a shared one can not be sent to another
+/// thread while the parties exchange their digests.
+pub async fn check_party_db_consistency<N: NetworkTrait>(
+    network: &mut N,
+    conn: &mut Connection,
+) -> Result<(), Error> {
+    let digest = party_db_digest(conn, &[]);
+    agree_on_digest(network, digest.as_deref().ok()).await?;
+    digest.map(|_| ())
+}
+
+async fn apply_consistently<N, F>(
+    network: &mut N,
+    conn: &mut Connection,
+    changed: &[u64],
+    apply: F,
+) -> Result<(), Error>
+where
+    N: NetworkTrait,
+    F: FnOnce(&Connection) -> Result<(), Error>,
+{
+    if changed.iter().collect::<HashSet<_>>().len() != changed.len() {
+        return Err(Error::ValueError("Duplicate record ids".to_string()));
+    }
+
+    // A manual transaction, since a rusqlite Transaction can not be held across the exchange
+    conn.execute_batch("BEGIN;")?;
+    let local = apply(conn).and_then(|_| party_db_digest(conn, changed));
+    let agreed = agree_on_digest(network, local.as_deref().ok()).await;
+
+    match (local, agreed) {
+        (Ok(_), Ok(())) => {
+            conn.execute_batch("COMMIT;")?;
+            Ok(())
+        }
+        (Err(err), _) | (Ok(_), Err(err)) => {
+            conn.execute_batch("ROLLBACK;")?;
+            Err(err)
+        }
+    }
+}
+
+/// Deletes the records with the given ids from the database of each party. The deletion is only
+/// committed if it succeeds at all parties and their databases are consistent afterwards.
+pub async fn delete_party_db_records<N: NetworkTrait>(
+    network: &mut N,
+    conn: &mut Connection,
+    ids: &[u64],
+) -> Result<(), Error> {
+    read_party_info(conn)?;
+    apply_consistently(network, conn, ids, |conn| {
+        let mut stmt = conn.prepare("DELETE FROM iris_codes WHERE id = ?1;")?;
+        for id in ids {
+            if stmt.execute([id])? != 1 {
+                return Err(Error::ValueError(format!("No record with id {id}")));
+            }
+        }
+        Ok(())
+    })
+    .await
+}
+
+/// Replaces the records with the given ids in the Aby3 database of each party by the new shares,
+/// e.g., of a re-enrollment. The update is only committed if it succeeds at all parties.
+pub async fn update_aby3_party_db_records<N: NetworkTrait, T: Sharable>(
+    network: &mut N,
+    conn: &mut Connection,
+    party: usize,
+    ids: &[u64],
+    db: &DbShares<Vec<Aby3Share<T>>>,
+) -> Result<(), Error> {
+    check_party_info(conn, party, "aby3")?;
+    apply_consistently(network, conn, ids, |conn| {
+        update_records(conn, ABY3_COLUMNS, ids, db, |shares| {
+            aby3_blobs::<T>(shares)
+        })
+    })
+    .await
+}
+
+/// Replaces the records with the given ids in the Swift3 database of each party by the new shares.
+/// The update is only committed if it succeeds at all parties.
+pub async fn update_swift3_party_db_records<N: NetworkTrait, T: Sharable>(
+    network: &mut N,
+    conn: &mut Connection,
+    party: usize,
+    ids: &[u64],
+    db: &DbShares<Vec<Swift3Share<T>>>,
+) -> Result<(), Error> {
+    check_party_info(conn, party, "swift3")?;
+    apply_consistently(network, conn, ids, |conn| {
+        update_records(conn, SWIFT3_COLUMNS, ids, db, |shares| {
+            swift3_blobs::<T>(shares)
+        })
+    })
+    .await
+}
+
+/// Replaces the records with the given ids in the SpdzWise database of each party by the new
+/// shares, which have to be authenticated with the MAC key of the database. The update is only
+/// committed if it succeeds at all parties.
+pub async fn update_spdzwise_party_db_records<N: NetworkTrait, T: Sharable>(
+    network: &mut N,
+    conn: &mut Connection,
+    party: usize,
+    ids: &[u64],
+    db: &DbShares<SpdzWiseVecShare<T::VerificationShare>>,
+) -> Result<(), Error> {
+    check_party_info(conn, party, "spdzwise")?;
+    apply_consistently(network, conn, ids, |conn| {
+        update_records(conn, SPDZWISE_COLUMNS, ids, db, spdzwise_blobs::<T>)
+    })
+    .await
+}
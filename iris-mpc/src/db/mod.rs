@@ -0,0 +1,6 @@
+pub(crate) mod compressed;
+pub(crate) mod generation;
+pub(crate) mod management;
+pub(crate) mod party_db;
+pub(crate) mod roots;
+pub(crate) mod share_file;
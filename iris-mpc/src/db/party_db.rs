@@ -0,0 +1,645 @@
+use crate::{
+    aby3::{id::PartyID, share::Share as Aby3Share},
+    db::compressed::DbShares,
+    error::Error,
+    spdzwise::{share::Share as SpdzWiseShare, vecshare::VecShare as SpdzWiseVecShare},
+    swift3::share::Share as Swift3Share,
+    types::sharable::Sharable,
+};
+use plain_reference::{IrisCode, IrisCodeArray};
+use rusqlite::{Connection, Row};
+use std::{
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+// A party database only contains the share components of its party, i.e., (xi, x{i-1}) for the
+// replicated sharings, and the plain masks:
+//   party:      (id, party, protocol)
+//   iris_codes: (id, mask, share_a, share_b)                      for aby3
+//               (id, mask, share_a, share_b, share_c)             for swift3
+//               (id, mask, share_a, mac_a, share_b, mac_b)        for spdzwise
+//   mac_key:    (id, share_a, share_b)                            for spdzwise
+// The ground truth database contains the plain codes and the plain MAC key:
+//   iris_codes: (id, code, mask)
+//   mac_key:    (id, key)                                         for spdzwise
+
+type UShare<T> = <<T as Sharable>::VerificationShare as Sharable>::Share;
+
+/// The path of the database of `party`, derived from the path of the combined database, i.e.,
+/// `db.sqlite` becomes `db.party0.sqlite`.
+pub fn party_db_path(database: &Path, party: usize) -> PathBuf {
+    with_infix(database, &format!("party{party}"))
+}
+
+/// The path of the ground truth database, i.e., `db.sqlite` becomes `db.truth.sqlite`.
+pub fn truth_db_path(database: &Path) -> PathBuf {
+    with_infix(database, "truth")
+}
+
+pub(crate) fn with_infix(database: &Path, infix: &str) -> PathBuf {
+    match database.extension() {
+        Some(ext) => database.with_extension(format!("{infix}.{}", ext.to_string_lossy())),
+        None => database.with_extension(infix),
+    }
+}
+
+/// Stores which party and protocol a party database belongs to.
+pub fn write_party_info(conn: &Connection, party: usize, protocol: &str) -> Result<(), Error> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS party (
+            id INTEGER PRIMARY KEY,
+            party INTEGER NOT NULL,
+            protocol TEXT NOT NULL
+        )",
+        rusqlite::params![],
+    )?;
+    conn.execute(
+        "INSERT INTO party (party, protocol) VALUES (?1, ?2)",
+        rusqlite::params![PartyID::try_from(party)? as usize, protocol],
+    )?;
+    Ok(())
+}
+
+/// Returns the party and the protocol a party database belongs to.
+pub fn read_party_info(conn: &Connection) -> Result<(usize, String), Error> {
+    let info = conn.query_row("SELECT party, protocol from party;", [], |row| {
+        Ok((row.get::<_, usize>(0)?, row.get::<_, String>(1)?))
+    })?;
+    Ok(info)
+}
+
+pub(crate) fn check_party_info(
+    conn: &Connection,
+    party: usize,
+    protocol: &str,
+) -> Result<(), Error> {
+    let (party_, protocol_) = read_party_info(conn)?;
+    if party_ != party {
+        return Err(Error::IdError(party_));
+    }
+    if protocol_ != protocol {
+        return Err(Error::ValueError(format!(
+            "Database was created for {protocol_}, not for {protocol}"
+        )));
+    }
+    Ok(())
+}
+
+fn table_exists(conn: &Connection, table: &str) -> Result<bool, Error> {
+    let count = conn.query_row(
+        "SELECT count(*) FROM sqlite_master WHERE type = 'table' AND name = ?1;",
+        [table],
+        |row| row.get::<_, usize>(0),
+    )?;
+    Ok(count > 0)
+}
+
+// Creates the tables of a new party database, or checks that an existing one belongs to the same
+// party and protocol, such that records can be appended
+fn init_party_db(
+    conn: &Connection,
+    party: usize,
+    protocol: &str,
+    iris_codes: &str,
+) -> Result<(), Error> {
+    if table_exists(conn, "party")? {
+        check_party_info(conn, party, protocol)?;
+    } else {
+        write_party_info(conn, party, protocol)?;
+    }
+    conn.execute(iris_codes, rusqlite::params![])?;
+    Ok(())
+}
+
+fn write_blob<S: serde::Serialize>(share: &[S]) -> Result<Vec<u8>, Error> {
+    bincode::serialize(share).map_err(|_| Error::SerializationError)
+}
+
+// The blobs of the share columns of a record, in the order of the `*_COLUMNS` constants
+pub(crate) type RecordBlobs<V> = fn(&V) -> Result<Vec<Vec<u8>>, Error>;
+
+pub(crate) fn aby3_blobs<T: Sharable>(shares: &[Aby3Share<T>]) -> Result<Vec<Vec<u8>>, Error> {
+    let (share_a, share_b): (Vec<_>, Vec<_>) = shares.iter().map(|s| s.to_owned().get_ab()).unzip();
+    Ok(vec![write_blob(&share_a)?, write_blob(&share_b)?])
+}
+
+pub(crate) fn swift3_blobs<T: Sharable>(shares: &[Swift3Share<T>]) -> Result<Vec<Vec<u8>>, Error> {
+    let mut share_a = Vec::with_capacity(shares.len());
+    let mut share_b = Vec::with_capacity(shares.len());
+    let mut share_c = Vec::with_capacity(shares.len());
+    for share in shares {
+        let (a, b, c) = share.to_owned().get_abc();
+        share_a.push(a);
+        share_b.push(b);
+        share_c.push(c);
+    }
+    Ok(vec![
+        write_blob(&share_a)?,
+        write_blob(&share_b)?,
+        write_blob(&share_c)?,
+    ])
+}
+
+pub(crate) fn spdzwise_blobs<T: Sharable>(
+    shares: &SpdzWiseVecShare<T::VerificationShare>,
+) -> Result<Vec<Vec<u8>>, Error> {
+    let (share_a, share_b): (Vec<_>, Vec<_>) =
+        shares.values.iter().map(|s| s.to_owned().get_ab()).unzip();
+    let (mac_a, mac_b): (Vec<_>, Vec<_>) =
+        shares.macs.iter().map(|s| s.to_owned().get_ab()).unzip();
+    Ok(vec![
+        write_blob(&share_a)?,
+        write_blob(&mac_a)?,
+        write_blob(&share_b)?,
+        write_blob(&mac_b)?,
+    ])
+}
+
+fn record_params<V>(
+    shares: &V,
+    mask: &IrisCodeArray,
+    blobs: RecordBlobs<V>,
+) -> Result<Vec<Vec<u8>>, Error> {
+    let mut params = vec![mask.as_raw_slice().to_vec()];
+    params.extend(blobs(shares)?);
+    Ok(params)
+}
+
+fn insert_records<V>(
+    conn: &mut Connection,
+    columns: &str,
+    db: &DbShares<V>,
+    blobs: RecordBlobs<V>,
+) -> Result<(), Error> {
+    let placeholders = (1..=columns.split(", ").count())
+        .map(|i| format!("?{i}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let transaction = conn.transaction()?;
+    let mut stmt = transaction.prepare(&format!(
+        "INSERT INTO iris_codes ({columns}) VALUES ({placeholders})"
+    ))?;
+    for (shares, mask) in db.shares.iter().zip(db.masks.iter()) {
+        stmt.execute(rusqlite::params_from_iter(record_params(
+            shares, mask, blobs,
+        )?))?;
+    }
+    drop(stmt);
+    transaction.commit()?;
+    Ok(())
+}
+
+/// Replaces the records with the given ids by the shares in `db`. Fails if a record does not
+/// exist, the changes are not committed by this function.
+pub(crate) fn update_records<V>(
+    conn: &Connection,
+    columns: &str,
+    ids: &[u64],
+    db: &DbShares<V>,
+    blobs: RecordBlobs<V>,
+) -> Result<(), Error> {
+    if ids.len() != db.shares.len() || ids.len() != db.masks.len() {
+        return Err(Error::InvalidSizeError);
+    }
+    let assignments = columns
+        .split(", ")
+        .enumerate()
+        .map(|(i, column)| format!("{column} = ?{}", i + 1))
+        .collect::<Vec<_>>();
+
+    let mut stmt = conn.prepare(&format!(
+        "UPDATE iris_codes SET {} WHERE id = ?{};",
+        assignments.join(", "),
+        assignments.len() + 1
+    ))?;
+    for ((id, shares), mask) in ids.iter().zip(db.shares.iter()).zip(db.masks.iter()) {
+        let mut params = record_params(shares, mask, blobs)?
+            .into_iter()
+            .map(rusqlite::types::Value::Blob)
+            .collect::<Vec<_>>();
+        params.push(rusqlite::types::Value::Integer(
+            i64::try_from(*id).map_err(|_| Error::ConversionError)?,
+        ));
+        if stmt.execute(rusqlite::params_from_iter(params))? != 1 {
+            return Err(Error::ValueError(format!("No record with id {id}")));
+        }
+    }
+    Ok(())
+}
+
+/// Appends the Aby3 shares of `party` to its database, which is created if it does not exist.
+pub fn write_aby3_party_db<T: Sharable>(
+    conn: &mut Connection,
+    party: usize,
+    db: &DbShares<Vec<Aby3Share<T>>>,
+) -> Result<(), Error> {
+    init_party_db(
+        conn,
+        party,
+        "aby3",
+        "CREATE TABLE IF NOT EXISTS iris_codes (
+            id INTEGER PRIMARY KEY,
+            mask BLOB NOT NULL,
+            share_a BLOB NOT NULL,
+            share_b BLOB NOT NULL
+        )",
+    )?;
+    insert_records(conn, ABY3_COLUMNS, db, |shares| aby3_blobs::<T>(shares))
+}
+
+/// Appends the Swift3 shares of `party` to its database, which is created if it does not exist.
+pub fn write_swift3_party_db<T: Sharable>(
+    conn: &mut Connection,
+    party: usize,
+    db: &DbShares<Vec<Swift3Share<T>>>,
+) -> Result<(), Error> {
+    init_party_db(
+        conn,
+        party,
+        "swift3",
+        "CREATE TABLE IF NOT EXISTS iris_codes (
+            id INTEGER PRIMARY KEY,
+            mask BLOB NOT NULL,
+            share_a BLOB NOT NULL,
+            share_b BLOB NOT NULL,
+            share_c BLOB NOT NULL
+        )",
+    )?;
+    insert_records(conn, SWIFT3_COLUMNS, db, |shares| swift3_blobs::<T>(shares))
+}
+
+/// Appends the SpdzWise shares of `party` to its database, which is created if it does not exist.
+/// The shares are over the verification type of `T`.
+pub fn write_spdzwise_party_db<T: Sharable>(
+    conn: &mut Connection,
+    party: usize,
+    db: &DbShares<SpdzWiseVecShare<T::VerificationShare>>,
+) -> Result<(), Error> {
+    init_party_db(
+        conn,
+        party,
+        "spdzwise",
+        "CREATE TABLE IF NOT EXISTS iris_codes (
+            id INTEGER PRIMARY KEY,
+            mask BLOB NOT NULL,
+            share_a BLOB NOT NULL,
+            mac_a BLOB NOT NULL,
+            share_b BLOB NOT NULL,
+            mac_b BLOB NOT NULL
+        )",
+    )?;
+    insert_records(conn, SPDZWISE_COLUMNS, db, spdzwise_blobs::<T>)
+}
+
+/// Stores the share of the MAC key in the SpdzWise database of `party`. Fails if the database
+/// already contains a MAC key, since its records would no longer verify.
+pub fn write_spdzwise_party_mac_key<T: Sharable>(
+    conn: &Connection,
+    party: usize,
+    mac_key: &Aby3Share<T::VerificationShare>,
+) -> Result<(), Error> {
+    if table_exists(conn, "party")? {
+        check_party_info(conn, party, "spdzwise")?;
+    } else {
+        write_party_info(conn, party, "spdzwise")?;
+    }
+    if has_spdzwise_party_mac_key(conn)? {
+        return Err(Error::ValueError(
+            "Database already contains a MAC key".to_string(),
+        ));
+    }
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS mac_key (
+            id INTEGER PRIMARY KEY,
+            share_a BLOB NOT NULL,
+            share_b BLOB NOT NULL
+        )",
+        rusqlite::params![],
+    )?;
+    let (share_a, share_b) = mac_key.to_owned().get_ab();
+    conn.execute(
+        "INSERT INTO mac_key (share_a, share_b) VALUES (?1, ?2)",
+        [
+            bincode::serialize(&share_a).map_err(|_| Error::SerializationError)?,
+            bincode::serialize(&share_b).map_err(|_| Error::SerializationError)?,
+        ],
+    )?;
+    Ok(())
+}
+
+/// Returns whether a SpdzWise party database already contains a share of the MAC key.
+pub fn has_spdzwise_party_mac_key(conn: &Connection) -> Result<bool, Error> {
+    if !table_exists(conn, "mac_key")? {
+        return Ok(false);
+    }
+    let count = conn.query_row("SELECT count(*) FROM mac_key;", [], |row| {
+        row.get::<_, usize>(0)
+    })?;
+    Ok(count > 0)
+}
+
+/// Reads a column of serialized shares of a full code.
+pub(crate) fn read_blob<T: Sharable>(row: &Row, index: usize) -> Result<Vec<T::Share>, Error> {
+    let share: Vec<T::Share> = bincode::deserialize(&row.get::<_, Vec<u8>>(index)?)
+        .map_err(|_| Error::SerializationError)?;
+    if share.len() != IrisCode::IRIS_CODE_SIZE {
+        return Err(Error::InvalidCodeSizeError);
+    }
+    Ok(share)
+}
+
+/// Reads a column of a packed code or mask.
+pub(crate) fn read_code(row: &Row, index: usize) -> Result<IrisCodeArray, Error> {
+    let bytes = row.get::<_, Vec<u8>>(index)?;
+    if bytes.len() != IrisCodeArray::IRIS_CODE_SIZE_BYTES {
+        return Err(Error::InvalidCodeSizeError);
+    }
+    let mut res = IrisCodeArray::default();
+    res.as_raw_mut_slice().copy_from_slice(&bytes);
+    Ok(res)
+}
+
+/// Returns the number of records in a party database.
+pub fn party_db_len(conn: &Connection) -> Result<usize, Error> {
+    let len = conn.query_row("SELECT count(*) FROM iris_codes;", [], |row| {
+        row.get::<_, usize>(0)
+    })?;
+    Ok(len)
+}
+
+fn read_aby3_row<T: Sharable>(row: &Row) -> Result<Vec<Aby3Share<T>>, Error> {
+    let share_a = read_blob::<T>(row, 1)?;
+    let share_b = read_blob::<T>(row, 2)?;
+    Ok(share_a
+        .into_iter()
+        .zip(share_b)
+        .map(|(a, b)| Aby3Share::new(a, b))
+        .collect())
+}
+
+fn read_swift3_row<T: Sharable>(row: &Row) -> Result<Vec<Swift3Share<T>>, Error> {
+    let share_a = read_blob::<T>(row, 1)?;
+    let share_b = read_blob::<T>(row, 2)?;
+    let share_c = read_blob::<T>(row, 3)?;
+    Ok(share_a
+        .into_iter()
+        .zip(share_b)
+        .zip(share_c)
+        .map(|((a, b), c)| Swift3Share::new(a, b, c))
+        .collect())
+}
+
+fn read_spdzwise_row<T: Sharable>(
+    row: &Row,
+) -> Result<SpdzWiseVecShare<T::VerificationShare>, Error> {
+    let share_a = read_blob::<T::VerificationShare>(row, 1)?;
+    let mac_a = read_blob::<T::VerificationShare>(row, 2)?;
+    let share_b = read_blob::<T::VerificationShare>(row, 3)?;
+    let mac_b = read_blob::<T::VerificationShare>(row, 4)?;
+    let values = share_a
+        .into_iter()
+        .zip(share_b)
+        .map(|(a, b)| Aby3Share::new(a, b))
+        .collect();
+    let macs = mac_a
+        .into_iter()
+        .zip(mac_b)
+        .map(|(a, b)| Aby3Share::new(a, b))
+        .collect();
+    Ok(SpdzWiseVecShare::new(values, macs))
+}
+
+fn read_rows<V, P: rusqlite::Params>(
+    conn: &Connection,
+    query: &str,
+    params: P,
+    read_row: fn(&Row) -> Result<V, Error>,
+) -> Result<DbShares<V>, Error> {
+    let mut stmt = conn.prepare(query)?;
+    let mut rows = stmt.query(params)?;
+
+    let mut res = DbShares::default();
+    while let Some(row) = rows.next()? {
+        res.shares.push(read_row(row)?);
+        res.masks.push(read_code(row, 0)?);
+    }
+    Ok(res)
+}
+
+pub(crate) const ABY3_COLUMNS: &str = "mask, share_a, share_b";
+pub(crate) const SWIFT3_COLUMNS: &str = "mask, share_a, share_b, share_c";
+pub(crate) const SPDZWISE_COLUMNS: &str = "mask, share_a, mac_a, share_b, mac_b";
+
+/// Reads the Aby3 database of `party`.
+pub fn read_aby3_party_db<T: Sharable>(
+    conn: &Connection,
+    party: usize,
+) -> Result<DbShares<Vec<Aby3Share<T>>>, Error> {
+    read_aby3_party_db_range(conn, party, 0..usize::MAX)
+}
+
+/// Reads the records with the indices in `range` from the Aby3 database of `party`, e.g., the
+/// shard of a worker (see [`shard_ranges`](crate::prelude::shard_ranges)).
+pub fn read_aby3_party_db_range<T: Sharable>(
+    conn: &Connection,
+    party: usize,
+    range: Range<usize>,
+) -> Result<DbShares<Vec<Aby3Share<T>>>, Error> {
+    check_party_info(conn, party, "aby3")?;
+    let limit = i64::try_from(range.len()).unwrap_or(i64::MAX);
+    let offset = i64::try_from(range.start).map_err(|_| Error::InvalidSizeError)?;
+    read_rows(
+        conn,
+        &format!("SELECT {ABY3_COLUMNS} from iris_codes ORDER BY id LIMIT ?1 OFFSET ?2;"),
+        [limit, offset],
+        read_aby3_row::<T>,
+    )
+}
+
+/// Reads the Swift3 database of `party`.
+pub fn read_swift3_party_db<T: Sharable>(
+    conn: &Connection,
+    party: usize,
+) -> Result<DbShares<Vec<Swift3Share<T>>>, Error> {
+    check_party_info(conn, party, "swift3")?;
+    read_rows(
+        conn,
+        &format!("SELECT {SWIFT3_COLUMNS} from iris_codes ORDER BY id;"),
+        [],
+        read_swift3_row::<T>,
+    )
+}
+
+/// Reads the SpdzWise database of `party`. The shares are over the verification type of `T`.
+pub fn read_spdzwise_party_db<T: Sharable>(
+    conn: &Connection,
+    party: usize,
+) -> Result<DbShares<SpdzWiseVecShare<T::VerificationShare>>, Error> {
+    check_party_info(conn, party, "spdzwise")?;
+    read_rows(
+        conn,
+        &format!("SELECT {SPDZWISE_COLUMNS} from iris_codes ORDER BY id;"),
+        [],
+        read_spdzwise_row::<T>,
+    )
+}
+
+/// Reads a party database chunk by chunk in the order of the record ids, such that only one chunk
+/// is held in memory at once. Created by [`aby3_party_db_chunks`], [`swift3_party_db_chunks`] and
+/// [`spdzwise_party_db_chunks`].
+pub struct PartyDbChunks<'a, V> {
+    conn: &'a Connection,
+    query: String,
+    read_row: fn(&Row) -> Result<V, Error>,
+    chunk_size: usize,
+    last_id: i64,
+    done: bool,
+}
+
+impl<'a, V> PartyDbChunks<'a, V> {
+    fn new(
+        conn: &'a Connection,
+        columns: &str,
+        read_row: fn(&Row) -> Result<V, Error>,
+        chunk_size: usize,
+    ) -> Result<Self, Error> {
+        if chunk_size == 0 {
+            return Err(Error::InvalidSizeError);
+        }
+        // The id is the last column, the records are paginated by their id instead of an offset
+        Ok(Self {
+            conn,
+            query: format!(
+                "SELECT {columns}, id from iris_codes WHERE id > ?1 ORDER BY id LIMIT ?2;"
+            ),
+            read_row,
+            chunk_size,
+            last_id: i64::MIN,
+            done: false,
+        })
+    }
+
+    fn read_chunk(&mut self) -> Result<DbShares<V>, Error> {
+        let mut stmt = self.conn.prepare_cached(&self.query)?;
+        let limit = i64::try_from(self.chunk_size).unwrap_or(i64::MAX);
+        let mut rows = stmt.query([self.last_id, limit])?;
+
+        let mut res = DbShares::default();
+        while let Some(row) = rows.next()? {
+            res.shares.push((self.read_row)(row)?);
+            res.masks.push(read_code(row, 0)?);
+            self.last_id = row.get(row.as_ref().column_count() - 1)?;
+        }
+        Ok(res)
+    }
+}
+
+impl<V> Iterator for PartyDbChunks<'_, V> {
+    type Item = Result<DbShares<V>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match self.read_chunk() {
+            Ok(chunk) if chunk.shares.is_empty() => {
+                self.done = true;
+                None
+            }
+            Ok(chunk) => Some(Ok(chunk)),
+            Err(err) => {
+                self.done = true;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+/// Reads the Aby3 database of `party` in chunks of `chunk_size` records.
+pub fn aby3_party_db_chunks<T: Sharable>(
+    conn: &Connection,
+    party: usize,
+    chunk_size: usize,
+) -> Result<PartyDbChunks<'_, Vec<Aby3Share<T>>>, Error> {
+    check_party_info(conn, party, "aby3")?;
+    PartyDbChunks::new(conn, ABY3_COLUMNS, read_aby3_row::<T>, chunk_size)
+}
+
+/// Reads the Swift3 database of `party` in chunks of `chunk_size` records.
+pub fn swift3_party_db_chunks<T: Sharable>(
+    conn: &Connection,
+    party: usize,
+    chunk_size: usize,
+) -> Result<PartyDbChunks<'_, Vec<Swift3Share<T>>>, Error> {
+    check_party_info(conn, party, "swift3")?;
+    PartyDbChunks::new(conn, SWIFT3_COLUMNS, read_swift3_row::<T>, chunk_size)
+}
+
+/// Reads the SpdzWise database of `party` in chunks of `chunk_size` records. The shares are over
+/// the verification type of `T`.
+pub fn spdzwise_party_db_chunks<T: Sharable>(
+    conn: &Connection,
+    party: usize,
+    chunk_size: usize,
+) -> Result<PartyDbChunks<'_, SpdzWiseVecShare<T::VerificationShare>>, Error> {
+    check_party_info(conn, party, "spdzwise")?;
+    PartyDbChunks::new(conn, SPDZWISE_COLUMNS, read_spdzwise_row::<T>, chunk_size)
+}
+
+/// Reads the share of the MAC key from the SpdzWise database of `party`.
+pub fn read_spdzwise_party_mac_key<T: Sharable>(
+    conn: &Connection,
+    party: usize,
+) -> Result<SpdzWiseShare<T::VerificationShare>, Error> {
+    check_party_info(conn, party, "spdzwise")?;
+    let (share_a, share_b) =
+        conn.query_row("SELECT share_a, share_b from mac_key;", [], |row| {
+            Ok((row.get::<_, Vec<u8>>(0)?, row.get::<_, Vec<u8>>(1)?))
+        })?;
+    let share_a: UShare<T> =
+        bincode::deserialize(&share_a).map_err(|_| Error::SerializationError)?;
+    let share_b: UShare<T> =
+        bincode::deserialize(&share_b).map_err(|_| Error::SerializationError)?;
+    Ok(SpdzWiseShare::new(
+        Aby3Share::default(),
+        Aby3Share::new(share_a, share_b),
+    ))
+}
+
+/// Reads the plain code with the given record id from the ground truth database.
+pub fn read_truth_code(conn: &Connection, id: u64) -> Result<IrisCode, Error> {
+    let mut stmt = conn.prepare("SELECT code, mask from iris_codes WHERE id = ?1;")?;
+    let mut rows = stmt.query([id])?;
+    let row = rows
+        .next()?
+        .ok_or_else(|| Error::ValueError(format!("No code with id {id}")))?;
+    Ok(IrisCode {
+        code: read_code(row, 0)?,
+        mask: read_code(row, 1)?,
+    })
+}
+
+/// Reads all plain codes from the ground truth database.
+pub fn read_truth_db(conn: &Connection) -> Result<Vec<IrisCode>, Error> {
+    let mut stmt = conn.prepare("SELECT code, mask from iris_codes ORDER BY id;")?;
+    let mut rows = stmt.query([])?;
+
+    let mut res = Vec::new();
+    while let Some(row) = rows.next()? {
+        res.push(IrisCode {
+            code: read_code(row, 0)?,
+            mask: read_code(row, 1)?,
+        });
+    }
+    Ok(res)
+}
+
+/// Reads the plain MAC key from the ground truth database of SpdzWise.
+pub fn read_truth_mac_key<T: Sharable>(conn: &Connection) -> Result<T::VerificationShare, Error> {
+    let key = conn.query_row("SELECT key from mac_key;", [], |row| {
+        row.get::<_, Vec<u8>>(0)
+    })?;
+    bincode::deserialize(&key).map_err(|_| Error::SerializationError)
+}
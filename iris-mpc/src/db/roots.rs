@@ -0,0 +1,149 @@
+use crate::{
+    blame,
+    commitment::merkle::MerkleTree,
+    db::compressed::DbShares,
+    error::Error,
+    traits::{
+        network_trait::NetworkTrait,
+        share_trait::{ShareTrait, VecShareTrait, VecSlice},
+    },
+};
+use plain_reference::IrisCodeArray;
+use std::borrow::Borrow;
+
+// Commitments to the state of the party databases. In the replicated sharings, party i stores the
+// components (xi, x{i-1}), so each component is stored by two neighbouring parties. Each party
+// builds one Merkle tree over the components it shares with the next party and one over those it
+// shares with the previous party. The roots are computed from the shares which are handed to the
+// protocol and cross-checked with the neighbours before matching, such that a party with a stale or
+// inconsistent database is detected even without MACs. Afterwards, the roots of the entries which
+// were actually matched are cross-checked again before the result is verified (see
+// `IrisProtocol::iris_in_shard`), which binds the matched shares to the committed roots.
+
+/// The Merkle roots over the records of a party database. The leaves of `next` contain the share
+/// components which the next party stores as well, the leaves of `prev` those which the previous
+/// party stores as well. Each leaf also contains the index and the public mask of its record.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DbRoots {
+    pub next: Vec<u8>,
+    pub prev: Vec<u8>,
+}
+
+impl DbRoots {
+    /// Computes the roots over the shares of a database, in the order of the records.
+    pub fn from_shares<S, D>(shares: &[D], masks: &[IrisCodeArray]) -> Result<Self, Error>
+    where
+        S: ShareTrait,
+        D: Borrow<VecSlice<S>>,
+    {
+        let mut builder = DbRootsBuilder::new();
+        builder.extend::<S, D>(shares, masks)?;
+        Ok(builder.roots())
+    }
+
+    /// Computes the roots over a database which is read in chunks, e.g., with
+    /// [`aby3_party_db_chunks`](crate::prelude::aby3_party_db_chunks).
+    pub fn from_chunks<S, D, I>(chunks: I) -> Result<Self, Error>
+    where
+        S: ShareTrait,
+        D: Borrow<VecSlice<S>>,
+        I: IntoIterator<Item = Result<DbShares<D>, Error>>,
+    {
+        let mut builder = DbRootsBuilder::new();
+        for chunk in chunks {
+            let chunk = chunk?;
+            builder.extend::<S, D>(&chunk.shares, &chunk.masks)?;
+        }
+        Ok(builder.roots())
+    }
+}
+
+/// Computes the [`DbRoots`] record by record.
+#[derive(Clone, Debug)]
+pub struct DbRootsBuilder {
+    next: MerkleTree,
+    prev: MerkleTree,
+    len: u64,
+}
+
+impl Default for DbRootsBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DbRootsBuilder {
+    pub fn new() -> Self {
+        Self {
+            next: MerkleTree::new(),
+            prev: MerkleTree::new(),
+            len: 0,
+        }
+    }
+
+    /// Appends a record. Fails if the shares are not replicated.
+    pub fn push<S: ShareTrait>(
+        &mut self,
+        shares: &VecSlice<S>,
+        mask: &IrisCodeArray,
+    ) -> Result<(), Error> {
+        let [next, prev] = S::VecShare::replicated_components(shares)?;
+        let index = self.len.to_le_bytes();
+
+        let mut leaf = vec![index.as_slice(), mask.as_raw_slice()];
+        leaf.extend(next.iter().map(|c| c.as_ref()));
+        self.next.push(&leaf);
+        leaf.truncate(2);
+        leaf.extend(prev.iter().map(|c| c.as_ref()));
+        self.prev.push(&leaf);
+
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Appends the records of a chunk of the database.
+    pub fn extend<S, D>(&mut self, shares: &[D], masks: &[IrisCodeArray]) -> Result<(), Error>
+    where
+        S: ShareTrait,
+        D: Borrow<VecSlice<S>>,
+    {
+        if shares.len() != masks.len() {
+            return Err(Error::InvalidSizeError);
+        }
+        for (shares, mask) in shares.iter().zip(masks.iter()) {
+            self.push::<S>(shares.borrow(), mask)?;
+        }
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn roots(&self) -> DbRoots {
+        DbRoots {
+            next: self.next.root(),
+            prev: self.prev.root(),
+        }
+    }
+}
+
+/// Broadcasts the roots and checks those of all parties: The `next` root of each party has to
+/// match the `prev` root of the next party. Like the other checks of the blame module, all parties
+/// abort with the pair of parties whose roots conflict.
+pub(crate) async fn exchange_db_roots<N: NetworkTrait>(
+    network: &mut N,
+    roots: &DbRoots,
+) -> Result<(), Error> {
+    blame::cross_check_components(
+        network,
+        [roots.next.to_owned(), roots.prev.to_owned()],
+        "Database root check",
+    )
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,404 @@
+use crate::{
+    aby3::{id::PartyID, share::Share as Aby3Share},
+    db::{
+        compressed::DbShares,
+        party_db::{read_blob, read_code},
+    },
+    error::Error,
+    swift3::share::Share as Swift3Share,
+    types::sharable::Sharable,
+};
+use memmap2::Mmap;
+use plain_reference::{IrisCode, IrisCodeArray};
+use rusqlite::Connection;
+use sha2::{Digest, Sha256};
+use std::{
+    fs::File,
+    io::{BufWriter, Seek, SeekFrom, Write},
+    marker::PhantomData,
+    mem::size_of,
+    path::Path,
+};
+
+// Layout of a share file (all integers in little endian):
+//   0..8:   magic
+//   8..10:  version
+//   10:     protocol
+//   11:     ring type, i.e., the size of a share component in bytes
+//   12:     party id
+//   13..16: reserved, zero
+//   16..24: number of records
+//   24..32: code length, i.e., the number of shares per record
+//   32..64: SHA-256 of the records
+// followed by the records, each consisting of the mask and the shares of the party. Header, masks
+// and shares are multiples of 16 bytes, such that the shares are aligned in the mapped file. The
+// shares are stored in native byte order, which is therefore required to be little endian.
+
+const MAGIC: [u8; 8] = *b"IRISSHRS";
+const VERSION: u16 = 1;
+const HEADER_SIZE: usize = 64;
+const MASK_SIZE: usize = IrisCodeArray::IRIS_CODE_SIZE_BYTES;
+
+/// The MPC protocol the shares in a share file belong to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShareProtocol {
+    Aby3 = 0,
+    Swift3 = 1,
+}
+
+impl TryFrom<u8> for ShareProtocol {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(ShareProtocol::Aby3),
+            1 => Ok(ShareProtocol::Swift3),
+            _ => Err(Error::ValueError(format!("Unknown protocol {value}"))),
+        }
+    }
+}
+
+/// Share types which can be stored in a share file and mapped from it without copying.
+///
+/// # Safety
+///
+/// The type has to consist of [`Self::COMPONENTS`] consecutive share components of type
+/// `<Self::Ring as Sharable>::Share`, followed only by zero-sized fields, such that a slice of
+/// shares can be reinterpreted as a slice of bytes and vice versa.
+pub unsafe trait FileShare: Sized {
+    type Ring: Sharable;
+    const PROTOCOL: ShareProtocol;
+    const COMPONENTS: usize;
+
+    /// The size of a share component in bytes, which identifies the ring.
+    fn ring_size() -> Result<u8, Error> {
+        if cfg!(target_endian = "big") {
+            return Err(Error::ValueError(
+                "Share files require a little endian platform".to_owned(),
+            ));
+        }
+        let size = size_of::<<Self::Ring as Sharable>::Share>();
+        debug_assert_eq!(size * Self::COMPONENTS, size_of::<Self>());
+        // Only integer rings, for which every bit pattern is valid
+        match size {
+            2 | 4 | 8 | 16 => Ok(size as u8),
+            _ => Err(Error::ValueError(format!("Unsupported ring size {size}"))),
+        }
+    }
+}
+
+// SAFETY: Aby3Share is repr(C) with two components and a PhantomData
+unsafe impl<T: Sharable> FileShare for Aby3Share<T> {
+    type Ring = T;
+    const PROTOCOL: ShareProtocol = ShareProtocol::Aby3;
+    const COMPONENTS: usize = 2;
+}
+
+// SAFETY: Swift3Share is repr(C) with three components and a PhantomData
+unsafe impl<T: Sharable> FileShare for Swift3Share<T> {
+    type Ring = T;
+    const PROTOCOL: ShareProtocol = ShareProtocol::Swift3;
+    const COMPONENTS: usize = 3;
+}
+
+fn shares_as_bytes<S: FileShare>(shares: &[S]) -> &[u8] {
+    // SAFETY: Guaranteed by the FileShare trait
+    unsafe {
+        std::slice::from_raw_parts(shares.as_ptr() as *const u8, std::mem::size_of_val(shares))
+    }
+}
+
+/// The header of a share file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShareFileHeader {
+    pub version: u16,
+    pub protocol: ShareProtocol,
+    pub ring_size: u8,
+    pub party: usize,
+    pub num_records: u64,
+    pub code_len: u64,
+    pub checksum: [u8; 32],
+}
+
+impl ShareFileHeader {
+    fn to_bytes(&self) -> [u8; HEADER_SIZE] {
+        let mut res = [0u8; HEADER_SIZE];
+        res[0..8].copy_from_slice(&MAGIC);
+        res[8..10].copy_from_slice(&self.version.to_le_bytes());
+        res[10] = self.protocol as u8;
+        res[11] = self.ring_size;
+        res[12] = self.party as u8;
+        res[16..24].copy_from_slice(&self.num_records.to_le_bytes());
+        res[24..32].copy_from_slice(&self.code_len.to_le_bytes());
+        res[32..64].copy_from_slice(&self.checksum);
+        res
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() < HEADER_SIZE || bytes[0..8] != MAGIC {
+            return Err(Error::ValueError("Not a share file".to_owned()));
+        }
+        let version = u16::from_le_bytes(bytes[8..10].try_into().expect("Size checked"));
+        if version != VERSION {
+            return Err(Error::ValueError(format!(
+                "Unsupported share file version {version}"
+            )));
+        }
+        Ok(Self {
+            version,
+            protocol: ShareProtocol::try_from(bytes[10])?,
+            ring_size: bytes[11],
+            party: PartyID::try_from(bytes[12] as usize)? as usize,
+            num_records: u64::from_le_bytes(bytes[16..24].try_into().expect("Size checked")),
+            code_len: u64::from_le_bytes(bytes[24..32].try_into().expect("Size checked")),
+            checksum: bytes[32..64].try_into().expect("Size checked"),
+        })
+    }
+
+    fn record_size(&self) -> usize {
+        MASK_SIZE + self.code_len as usize * self.share_size()
+    }
+
+    fn share_size(&self) -> usize {
+        let components = match self.protocol {
+            ShareProtocol::Aby3 => 2,
+            ShareProtocol::Swift3 => 3,
+        };
+        components * self.ring_size as usize
+    }
+}
+
+/// Writes the shares of a single party into a share file.
+pub struct ShareFileWriter<S: FileShare> {
+    writer: BufWriter<File>,
+    header: ShareFileHeader,
+    hasher: Sha256,
+    phantom: PhantomData<S>,
+}
+
+impl<S: FileShare> ShareFileWriter<S> {
+    pub fn create<P: AsRef<Path>>(path: P, party: usize) -> Result<Self, Error> {
+        let header = ShareFileHeader {
+            version: VERSION,
+            protocol: S::PROTOCOL,
+            ring_size: S::ring_size()?,
+            party: PartyID::try_from(party)? as usize,
+            num_records: 0,
+            code_len: IrisCode::IRIS_CODE_SIZE as u64,
+            checksum: [0; 32],
+        };
+        let mut writer = BufWriter::new(File::create(path)?);
+        // The header is finalized in finish
+        writer.write_all(&header.to_bytes())?;
+        Ok(Self {
+            writer,
+            header,
+            hasher: Sha256::new(),
+            phantom: PhantomData,
+        })
+    }
+
+    pub fn push(&mut self, mask: &IrisCodeArray, shares: &[S]) -> Result<(), Error> {
+        if shares.len() != self.header.code_len as usize {
+            return Err(Error::InvalidCodeSizeError);
+        }
+        let bytes = shares_as_bytes(shares);
+        self.hasher.update(mask.as_raw_slice());
+        self.hasher.update(bytes);
+        self.writer.write_all(mask.as_raw_slice())?;
+        self.writer.write_all(bytes)?;
+        self.header.num_records += 1;
+        Ok(())
+    }
+
+    /// Writes the final header and returns it.
+    pub fn finish(mut self) -> Result<ShareFileHeader, Error> {
+        self.header.checksum = self.hasher.finalize().into();
+        self.writer.seek(SeekFrom::Start(0))?;
+        self.writer.write_all(&self.header.to_bytes())?;
+        self.writer.flush()?;
+        Ok(self.header)
+    }
+}
+
+/// A memory mapped share file. The shares are accessed without copying them.
+pub struct ShareFile<S: FileShare> {
+    mmap: Mmap,
+    header: ShareFileHeader,
+    phantom: PhantomData<S>,
+}
+
+impl<S: FileShare> ShareFile<S> {
+    /// Maps a share file and checks the header, the size and the checksum. Verifying the checksum
+    /// reads the whole file once.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let file = File::open(path)?;
+        // SAFETY: The file must not be modified while it is mapped
+        let mmap = unsafe { Mmap::map(&file)? };
+        let header = ShareFileHeader::from_bytes(&mmap)?;
+
+        if header.protocol != S::PROTOCOL || header.ring_size != S::ring_size()? {
+            return Err(Error::ValueError(
+                "Share file does not match the share type".to_owned(),
+            ));
+        }
+        if header.code_len != IrisCode::IRIS_CODE_SIZE as u64 {
+            return Err(Error::InvalidCodeSizeError);
+        }
+        let expected = (header.num_records as usize)
+            .checked_mul(header.record_size())
+            .and_then(|size| size.checked_add(HEADER_SIZE));
+        if expected != Some(mmap.len()) {
+            return Err(Error::InvalidSizeError);
+        }
+        if mmap.as_ptr() as usize % std::mem::align_of::<S>() != 0 {
+            return Err(Error::ValueError("Share file is not aligned".to_owned()));
+        }
+
+        let file = Self {
+            mmap,
+            header,
+            phantom: PhantomData,
+        };
+        file.verify_checksum()?;
+        Ok(file)
+    }
+
+    pub fn header(&self) -> &ShareFileHeader {
+        &self.header
+    }
+
+    pub fn get_party(&self) -> usize {
+        self.header.party
+    }
+
+    pub fn len(&self) -> usize {
+        self.header.num_records as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.header.num_records == 0
+    }
+
+    fn record(&self, index: usize) -> &[u8] {
+        assert!(index < self.len(), "Index out of bounds");
+        let size = self.header.record_size();
+        let start = HEADER_SIZE + index * size;
+        &self.mmap[start..start + size]
+    }
+
+    pub fn get_mask(&self, index: usize) -> IrisCodeArray {
+        let mut mask = IrisCodeArray::default();
+        mask.as_raw_mut_slice()
+            .copy_from_slice(&self.record(index)[..MASK_SIZE]);
+        mask
+    }
+
+    /// Returns the shares of a record, directly from the mapped file.
+    pub fn get_shares(&self, index: usize) -> &[S] {
+        let bytes = &self.record(index)[MASK_SIZE..];
+        // SAFETY: Guaranteed by the FileShare trait, the alignment and the size are checked in
+        // open, and all offsets are multiples of 16 bytes
+        unsafe {
+            std::slice::from_raw_parts(bytes.as_ptr() as *const S, self.header.code_len as usize)
+        }
+    }
+
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (IrisCodeArray, &[S])> + '_ {
+        (0..self.len()).map(|i| (self.get_mask(i), self.get_shares(i)))
+    }
+
+    /// Recomputes the checksum of the records and compares it to the one in the header, which
+    /// [`Self::open`] already does.
+    pub fn verify_checksum(&self) -> Result<(), Error> {
+        let checksum: [u8; 32] = Sha256::digest(&self.mmap[HEADER_SIZE..]).into();
+        if checksum != self.header.checksum {
+            return Err(Error::ValueError("Share file checksum mismatch".to_owned()));
+        }
+        Ok(())
+    }
+
+    /// Returns the shares of all records, directly from the mapped file, and copies of the masks,
+    /// which can be passed to [`crate::prelude::IrisProtocol::iris_in_db`].
+    pub fn records(&self) -> DbShares<&[S]> {
+        let mut res = DbShares::default();
+        for (mask, shares) in self.iter() {
+            res.shares.push(shares);
+            res.masks.push(mask);
+        }
+        res
+    }
+
+    /// Returns the records in chunks of `chunk_size` records as in [`Self::records`], e.g., for
+    /// [`crate::prelude::IrisProtocol::iris_in_db_stream`].
+    pub fn chunks(&self, chunk_size: usize) -> impl Iterator<Item = DbShares<&[S]>> + '_ {
+        assert!(chunk_size > 0, "Chunk size must be positive");
+        (0..self.len()).step_by(chunk_size).map(move |start| {
+            let end = (start + chunk_size).min(self.len());
+            let mut res = DbShares::default();
+            for i in start..end {
+                res.shares.push(self.get_shares(i));
+                res.masks.push(self.get_mask(i));
+            }
+            res
+        })
+    }
+}
+
+/// Converts the Aby3 layout of `create_sample_data` into the share file of a party.
+pub fn aby3_share_file_from_sqlite<T: Sharable, P: AsRef<Path>>(
+    conn: &Connection,
+    party: usize,
+    path: P,
+) -> Result<ShareFileHeader, Error> {
+    let query = match PartyID::try_from(party)? {
+        PartyID::ID0 => "SELECT share_a, share_c, mask from iris_codes ORDER BY id;",
+        PartyID::ID1 => "SELECT share_b, share_a, mask from iris_codes ORDER BY id;",
+        PartyID::ID2 => "SELECT share_c, share_b, mask from iris_codes ORDER BY id;",
+    };
+    let mut stmt = conn.prepare(query)?;
+    let mut rows = stmt.query([])?;
+
+    let mut writer = ShareFileWriter::<Aby3Share<T>>::create(path, party)?;
+    while let Some(row) = rows.next()? {
+        let share_a = read_blob::<T>(row, 0)?;
+        let share_b = read_blob::<T>(row, 1)?;
+        let shares = share_a
+            .into_iter()
+            .zip(share_b)
+            .map(|(a, b)| Aby3Share::new(a, b))
+            .collect::<Vec<_>>();
+        writer.push(&read_code(row, 2)?, &shares)?;
+    }
+    writer.finish()
+}
+
+/// Converts the Swift3 layout of `create_sample_data` into the share file of a party.
+pub fn swift3_share_file_from_sqlite<T: Sharable, P: AsRef<Path>>(
+    conn: &Connection,
+    party: usize,
+    path: P,
+) -> Result<ShareFileHeader, Error> {
+    let query = match PartyID::try_from(party)? {
+        PartyID::ID0 => "SELECT share_a, share_c, share_d, mask from iris_codes ORDER BY id;",
+        PartyID::ID1 => "SELECT share_b, share_a, share_d, mask from iris_codes ORDER BY id;",
+        PartyID::ID2 => "SELECT share_c, share_b, share_d, mask from iris_codes ORDER BY id;",
+    };
+    let mut stmt = conn.prepare(query)?;
+    let mut rows = stmt.query([])?;
+
+    let mut writer = ShareFileWriter::<Swift3Share<T>>::create(path, party)?;
+    while let Some(row) = rows.next()? {
+        let share_a = read_blob::<T>(row, 0)?;
+        let share_b = read_blob::<T>(row, 1)?;
+        let share_c = read_blob::<T>(row, 2)?;
+        let shares = share_a
+            .into_iter()
+            .zip(share_b)
+            .zip(share_c)
+            .map(|((a, b), c)| Swift3Share::new(a, b, c))
+            .collect::<Vec<_>>();
+        writer.push(&read_code(row, 3)?, &shares)?;
+    }
+    writer.finish()
+}
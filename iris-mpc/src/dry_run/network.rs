@@ -0,0 +1,150 @@
+use crate::aby3::id::PartyID;
+use crate::error::Error;
+use crate::traits::network_trait::NetworkTrait;
+use bytes::{Bytes, BytesMut};
+use std::collections::VecDeque;
+use std::io::Error as IOError;
+
+/// A network of a single party which does not communicate, but records the communication
+/// pattern. The protocols of this crate are symmetric in the parties, so a message received from
+/// the previous (next) party has the same size as the last message sent to the next (previous)
+/// party. Receiving hence returns zeroes of this size.
+pub struct DryRunNetwork {
+    id: PartyID,
+    from_prev: VecDeque<usize>,
+    from_next: VecDeque<usize>,
+    stats: [usize; 4], // [sent_prev, sent_next, recv_prev, recv_next]
+    rounds: usize,
+    sent: bool,
+}
+
+impl DryRunNetwork {
+    pub fn new(id: usize) -> Result<Self, Error> {
+        Ok(Self {
+            id: PartyID::try_from(id)?,
+            from_prev: VecDeque::new(),
+            from_next: VecDeque::new(),
+            stats: [0; 4],
+            rounds: 0,
+            sent: false,
+        })
+    }
+
+    /// Returns the sent and received bytes as [sent_prev, sent_next, recv_prev, recv_next].
+    pub fn get_stats(&self) -> [usize; 4] {
+        self.stats
+    }
+
+    /// Returns the number of communication rounds, i.e., the number of times a party had to wait
+    /// for a message after sending.
+    pub fn get_rounds(&self) -> usize {
+        self.rounds
+    }
+
+    fn receive_len(&mut self, from_next: bool) -> Result<BytesMut, IOError> {
+        let (queue, index) = if from_next {
+            (&mut self.from_next, 3)
+        } else {
+            (&mut self.from_prev, 2)
+        };
+        let len = queue
+            .pop_front()
+            .ok_or_else(|| IOError::other("Receive without a matching send"))?;
+        self.stats[index] += len;
+
+        if self.sent {
+            self.sent = false;
+            self.rounds += 1;
+        }
+        Ok(BytesMut::zeroed(len))
+    }
+}
+
+impl NetworkTrait for DryRunNetwork {
+    async fn shutdown(self) -> Result<(), IOError> {
+        Ok(())
+    }
+
+    fn print_connection_stats(&self, out: &mut impl std::io::Write) -> std::io::Result<()> {
+        writeln!(
+            out,
+            "Connection \"prev\" stats:\n\tSENT: {} bytes\n\tRECV: {} bytes",
+            self.stats[0], self.stats[2]
+        )?;
+        writeln!(
+            out,
+            "Connection \"next\" stats:\n\tSENT: {} bytes\n\tRECV: {} bytes",
+            self.stats[1], self.stats[3]
+        )?;
+        Ok(())
+    }
+
+    async fn send(&mut self, id: usize, data: Bytes) -> std::io::Result<()> {
+        if id == usize::from(self.id.next_id()) {
+            self.send_next_id(data).await
+        } else if id == usize::from(self.id.prev_id()) {
+            self.send_prev_id(data).await
+        } else {
+            Err(IOError::other("Invalid ID"))
+        }
+    }
+
+    async fn receive(&mut self, id: usize) -> std::io::Result<BytesMut> {
+        if id == usize::from(self.id.prev_id()) {
+            self.receive_prev_id().await
+        } else if id == usize::from(self.id.next_id()) {
+            self.receive_next_id().await
+        } else {
+            Err(IOError::other("Invalid ID"))
+        }
+    }
+
+    async fn broadcast(&mut self, data: Bytes) -> Result<Vec<BytesMut>, IOError> {
+        let mut result = Vec::with_capacity(3);
+        for id in 0..3 {
+            if id != usize::from(self.id) {
+                self.send(id, data.clone()).await?;
+            }
+        }
+        for id in 0..3 {
+            if id == usize::from(self.id) {
+                result.push(BytesMut::from(data.as_ref()));
+            } else {
+                result.push(self.receive(id).await?);
+            }
+        }
+        Ok(result)
+    }
+
+    fn get_id(&self) -> usize {
+        self.id.into()
+    }
+
+    fn get_num_parties(&self) -> usize {
+        3
+    }
+
+    async fn send_next_id(&mut self, data: Bytes) -> Result<(), IOError> {
+        // The previous party sends us the same message
+        self.stats[1] += data.len();
+        self.from_prev.push_back(data.len());
+        self.sent = true;
+        Ok(())
+    }
+
+    async fn send_prev_id(&mut self, data: Bytes) -> Result<(), IOError> {
+        // The next party sends us the same message
+        self.stats[0] += data.len();
+        self.from_next.push_back(data.len());
+        self.sent = true;
+        Ok(())
+    }
+
+    async fn receive_prev_id(&mut self) -> Result<BytesMut, IOError> {
+        self.receive_len(false)
+    }
+
+    async fn receive_next_id(&mut self) -> Result<BytesMut, IOError> {
+        self.receive_len(true)
+    }
+}
@@ -0,0 +1,370 @@
+use super::network::DryRunNetwork;
+use crate::aby3::share::Share as Aby3Share;
+use crate::aby3::utils;
+use crate::adder::{self, AdderStrategy};
+use crate::error::Error;
+use crate::traits::binary_trait::BinaryMpcTrait;
+use crate::traits::mpc_trait::MpcTrait;
+use crate::traits::network_trait::NetworkTrait;
+use crate::types::bit::Bit;
+use crate::types::packed_bits::PackedBits;
+use crate::types::ring_element::RingImpl;
+use crate::types::sharable::Sharable;
+use bytes::Bytes;
+use num_traits::Zero;
+use rand::Rng;
+use std::fmt;
+
+/// The costs recorded by [`DryRun`] for one party.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CostReport {
+    /// Communication rounds
+    pub rounds: usize,
+    pub bytes_sent_prev: usize,
+    pub bytes_sent_next: usize,
+    pub bytes_recv_prev: usize,
+    pub bytes_recv_next: usize,
+    /// Arithmetic multiplications, each product of a dot product counts as one
+    pub mul_gates: usize,
+    /// Binary AND gates on single bits
+    pub and_gates: usize,
+}
+
+impl fmt::Display for CostReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Same format as NetworkTrait::print_connection_stats, such that the reports can be
+        // compared with measured runs
+        writeln!(
+            f,
+            "Connection \"prev\" stats:\n\tSENT: {} bytes\n\tRECV: {} bytes",
+            self.bytes_sent_prev, self.bytes_recv_prev
+        )?;
+        writeln!(
+            f,
+            "Connection \"next\" stats:\n\tSENT: {} bytes\n\tRECV: {} bytes",
+            self.bytes_sent_next, self.bytes_recv_next
+        )?;
+        writeln!(f, "Rounds: {}", self.rounds)?;
+        writeln!(f, "Multiplications: {}", self.mul_gates)?;
+        writeln!(f, "AND gates: {}", self.and_gates)
+    }
+}
+
+/// Cost model of [`Aby3`](crate::prelude::Aby3) with [`MsbVariant::Adder`](crate::prelude::MsbVariant::Adder).
+/// Computes on plain values like [`Plain`](crate::prelude::Plain), but sends messages of the
+/// same size and in the same rounds as one Aby3 party over a [`DryRunNetwork`]. Inputs of other
+/// parties are not known and replaced by zero.
+///
+/// Only this configuration is modelled. The other MSB variants need preprocessed material from a
+/// dealer, and the malicious backends (MalAby3, Swift3, SpdzWise) send additional messages for
+/// the verification, so their costs are not covered by the report.
+pub struct DryRun {
+    network: DryRunNetwork,
+    adder_strategy: AdderStrategy,
+    mul_gates: usize,
+    and_gates: usize,
+}
+
+impl DryRun {
+    pub fn new(id: usize) -> Result<Self, Error> {
+        Ok(Self {
+            network: DryRunNetwork::new(id)?,
+            adder_strategy: AdderStrategy::default(),
+            mul_gates: 0,
+            and_gates: 0,
+        })
+    }
+
+    /// Selects the adder of the MSB extraction, see [`Aby3::set_adder_strategy`](crate::prelude::Aby3::set_adder_strategy).
+    pub fn set_adder_strategy(&mut self, strategy: AdderStrategy) {
+        self.adder_strategy = strategy;
+    }
+
+    pub fn get_adder_strategy(&self) -> AdderStrategy {
+        self.adder_strategy
+    }
+
+    /// Returns the costs recorded since the creation of the protocol.
+    pub fn report(&self) -> CostReport {
+        let stats = self.network.get_stats();
+        CostReport {
+            rounds: self.network.get_rounds(),
+            bytes_sent_prev: stats[0],
+            bytes_sent_next: stats[1],
+            bytes_recv_prev: stats[2],
+            bytes_recv_next: stats[3],
+            mul_gates: self.mul_gates,
+            and_gates: self.and_gates,
+        }
+    }
+
+    // Aby3 reshares by sending to the next party and receiving from the previous one
+    async fn reshare(&mut self, bytes: usize) -> Result<(), Error> {
+        utils::send_and_receive(&mut self.network, Bytes::from(vec![0; bytes])).await?;
+        Ok(())
+    }
+
+    async fn reshare_many<T: Sharable>(&mut self, len: usize) -> Result<(), Error> {
+        self.reshare(len * T::Share::K.div_ceil(8)).await
+    }
+
+    // Bitslices the inputs, see utils::transpose_pack_u128
+    fn transpose_pack_u128<T: Sharable>(x: Vec<T>) -> Vec<Vec<u128>> {
+        let x = x
+            .into_iter()
+            .map(|x| Aby3Share::new(x.to_sharetype(), T::Share::zero()))
+            .collect();
+        utils::transpose_pack_u128::<T>(x)
+            .into_iter()
+            .map(|x| {
+                x.into_iter()
+                    .map(|x| u128::from_sharetype(x.get_ab().0))
+                    .collect()
+            })
+            .collect()
+    }
+
+    // Same messages as BinaryMpcTrait::binary_add_3_many on packed shares of T, i.e., a full adder,
+    // the generate bits and two ANDs per level of the Kogge-Stone adder
+    async fn msb_kogge_stone_many<T: Sharable>(
+        &mut self,
+        a: Vec<T>,
+    ) -> Result<PackedBits<Bit>, Error> {
+        let len = a.len();
+        let k = T::Share::K;
+        for ands in [len, len]
+            .into_iter()
+            .chain(std::iter::repeat(2 * len).take(utils::ceil_log2(k)))
+        {
+            self.and_gates += k * ands;
+            self.reshare_many::<T>(ands).await?;
+        }
+        Ok(a.into_iter()
+            .map(|a_| a_.to_sharetype().get_msb().convert())
+            .collect())
+    }
+
+    async fn msb_adder_many<T: Sharable>(&mut self, a: Vec<T>) -> Result<PackedBits<Bit>, Error> {
+        if self.adder_strategy == AdderStrategy::KoggeStone {
+            return self.msb_kogge_stone_many(a).await;
+        }
+        let len = a.len();
+        let x1 = Self::transpose_pack_u128(a);
+        // The other parties' summands of the arithmetic-to-binary conversion are zero in the clear
+        let x2 = x1.iter().map(|x| vec![0; x.len()]).collect::<Vec<_>>();
+        let x3 = x2.to_owned();
+
+        let res = adder::msb_many(self, self.adder_strategy, x1, x2, x3).await?;
+        Ok(PackedBits::from_words(res, len))
+    }
+
+    // Same messages as Aby3::reduce_or_u128, which splits the value into halves down to u8 and
+    // then reduces the bits in a tree
+    async fn reduce_or_u128(&mut self, mut a: u128) -> Result<Bit, Error> {
+        let mut k = 128;
+        while k != 8 {
+            k >>= 1;
+            self.and_gates += k;
+            self.reshare(k / 8).await?;
+            a = (a | (a >> k)) & ((1 << k) - 1);
+        }
+        while k != 1 {
+            k >>= 1;
+            self.and_gates += k;
+            self.reshare_many::<Bit>(k).await?;
+        }
+        Ok(Bit::new(a != 0))
+    }
+}
+
+impl<T: Sharable> MpcTrait<T, T, Bit> for DryRun {
+    fn get_id(&self) -> usize {
+        self.network.get_id()
+    }
+
+    async fn finish(self) -> Result<(), Error> {
+        self.network.shutdown().await?;
+        Ok(())
+    }
+
+    async fn preprocess(&mut self) -> Result<(), Error> {
+        // Exchange of the PRF seeds
+        self.reshare(32).await
+    }
+
+    fn set_mac_key(&mut self, _key: T) {}
+    fn set_new_mac_key(&mut self) {}
+    #[cfg(test)]
+    async fn open_mac_key(&mut self) -> Result<T::VerificationShare, Error> {
+        Ok(T::VerificationShare::default())
+    }
+
+    fn print_connection_stats(&self, out: &mut impl std::io::Write) -> Result<(), Error> {
+        Ok(self.network.print_connection_stats(out)?)
+    }
+
+    async fn input(&mut self, input: Option<T>, id: usize) -> Result<T, Error> {
+        if id >= self.network.get_num_parties() {
+            return Err(Error::IdError(id));
+        }
+        let value = if id == self.network.get_id() {
+            input.ok_or(Error::ValueError("Cannot share None".to_string()))?
+        } else {
+            T::zero()
+        };
+        self.reshare_many::<T>(1).await?;
+        Ok(value)
+    }
+
+    #[cfg(test)]
+    async fn input_all(&mut self, input: T) -> Result<Vec<T>, Error> {
+        self.reshare_many::<T>(3).await?;
+        Ok(vec![input])
+    }
+
+    fn share<R: Rng>(input: T, _mac_key: T::VerificationShare, _rng: &mut R) -> Vec<T> {
+        vec![input]
+    }
+
+    async fn open(&mut self, share: T) -> Result<T, Error> {
+        self.reshare_many::<T>(1).await?;
+        Ok(share)
+    }
+
+    async fn open_many(&mut self, shares: Vec<T>) -> Result<Vec<T>, Error> {
+        self.reshare_many::<T>(shares.len()).await?;
+        Ok(shares)
+    }
+
+    async fn open_bit(&mut self, share: Bit) -> Result<bool, Error> {
+        self.reshare_many::<Bit>(1).await?;
+        Ok(share.convert())
+    }
+
+    async fn open_bit_many(&mut self, shares: PackedBits<Bit>) -> Result<Vec<bool>, Error> {
+        // Aby3 opens the packed words
+        self.reshare_many::<u128>(shares.words().len()).await?;
+        Ok(shares.iter().map(Bit::convert).collect())
+    }
+
+    fn add(&self, a: T, b: T) -> T {
+        a.wrapping_add(&b)
+    }
+
+    fn sub(&self, a: T, b: T) -> T {
+        a.wrapping_sub(&b)
+    }
+
+    fn add_const(&self, a: T, b: T) -> T {
+        a.wrapping_add(&b)
+    }
+
+    fn sub_const(&self, a: T, b: T) -> T {
+        a.wrapping_sub(&b)
+    }
+
+    async fn mul(&mut self, a: T, b: T) -> Result<T, Error> {
+        self.mul_gates += 1;
+        self.reshare_many::<T>(1).await?;
+        Ok(a.wrapping_mul(&b))
+    }
+
+    fn mul_const(&self, a: T, b: T) -> T {
+        a.wrapping_mul(&b)
+    }
+
+    async fn dot(&mut self, a: Vec<T>, b: Vec<T>) -> Result<T, Error> {
+        let mut res = <Self as MpcTrait<T, T, Bit>>::dot_many(self, &[a], &[b]).await?;
+        Ok(res.pop().expect("One output"))
+    }
+
+    async fn dot_many(&mut self, a: &[Vec<T>], b: &[Vec<T>]) -> Result<Vec<T>, Error> {
+        if a.len() != b.len() {
+            return Err(Error::InvalidSizeError);
+        }
+
+        let mut res = Vec::with_capacity(a.len());
+        for (a_, b_) in a.iter().zip(b) {
+            if a_.len() != b_.len() {
+                return Err(Error::InvalidSizeError);
+            }
+            self.mul_gates += a_.len();
+            let mut r = T::zero();
+            for (a__, b__) in a_.iter().zip(b_) {
+                r = r.wrapping_add(&a__.wrapping_mul(b__));
+            }
+            res.push(r);
+        }
+        self.reshare_many::<T>(res.len()).await?;
+
+        Ok(res)
+    }
+
+    async fn mul_many(&mut self, a: Vec<T>, b: Vec<T>) -> Result<Vec<T>, Error> {
+        if a.len() != b.len() {
+            return Err(Error::InvalidSizeError);
+        }
+        self.mul_gates += a.len();
+        self.reshare_many::<T>(a.len()).await?;
+
+        Ok(a.into_iter()
+            .zip(b)
+            .map(|(a, b)| a.wrapping_mul(&b))
+            .collect())
+    }
+
+    async fn get_msb(&mut self, a: T) -> Result<Bit, Error> {
+        let res = self.msb_adder_many(vec![a]).await?;
+        Ok(res.into_iter().next().expect("One output"))
+    }
+
+    async fn get_msb_many(&mut self, a: Vec<T>) -> Result<PackedBits<Bit>, Error> {
+        self.msb_adder_many(a).await
+    }
+
+    fn msb_and_gates(&self, amount: usize) -> usize {
+        amount * self.adder_strategy.msb_and_gates(T::Share::K)
+    }
+
+    async fn binary_or(&mut self, a: Bit, b: Bit) -> Result<Bit, Error> {
+        self.and_gates += 1;
+        self.reshare_many::<Bit>(1).await?;
+        Ok(a | b)
+    }
+
+    async fn reduce_binary_or(
+        &mut self,
+        a: PackedBits<Bit>,
+        chunk_size: usize,
+    ) -> Result<Bit, Error> {
+        let packed = a.into_words();
+        let reduced = utils::or_tree::<u128, _, _>(self, packed, chunk_size).await?;
+        self.reduce_or_u128(reduced).await
+    }
+
+    async fn verify(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl BinaryMpcTrait<u128, u128> for DryRun {
+    async fn and(&mut self, a: u128, b: u128) -> Result<u128, Error> {
+        self.and_gates += 128;
+        self.reshare_many::<u128>(1).await?;
+        Ok(a & b)
+    }
+
+    async fn and_many(&mut self, a: &Vec<u128>, b: &Vec<u128>) -> Result<Vec<u128>, Error> {
+        if a.len() != b.len() {
+            return Err(Error::InvalidSizeError);
+        }
+        self.and_gates += 128 * a.len();
+        self.reshare_many::<u128>(a.len()).await?;
+        Ok(a.iter().zip(b).map(|(a_, b_)| a_ & b_).collect())
+    }
+
+    async fn arithmetic_to_binary_many(&mut self, x: Vec<u128>) -> Result<Vec<u128>, Error> {
+        let zeros = vec![0; x.len()];
+        self.binary_add_3_many(x, zeros.to_owned(), zeros).await
+    }
+}
@@ -209,7 +209,7 @@ impl AndProof {
             .map(|chunk| chunk.collect())
             .collect();
 
-        for (theta, f_) in thetas.iter().zip(f.into_iter()) {
+        for (theta, f_) in thetas.iter().zip(f) {
             let poly = Self::c(f_);
             res += poly * theta;
         }
@@ -237,15 +237,23 @@ impl AndProof {
             .map(|_| GF2p64::random(rng))
             .collect::<Vec<_>>();
 
+        // All but the first point are bits, so the interpolation only adds lagrange polynomials
         let mut f = Vec::with_capacity(circuit_size);
         for j in 0..self.l {
             for i in 0..6 {
-                let mut vec = Vec::with_capacity(self.m + 1);
-                vec.push(w[i * self.l + j]);
-                for l in 0..self.m {
-                    vec.push(GF2p64::lift(self.proof[j * self.m + l][i]));
+                let mut poly = lagrange_polys[0].to_owned() * w[i * self.l + j];
+                for (inp, lagrange) in self
+                    .proof
+                    .iter()
+                    .skip(j * self.m)
+                    .take(self.m)
+                    .zip(lagrange_polys.iter().skip(1))
+                {
+                    if inp[i] {
+                        poly += lagrange;
+                    }
                 }
-                f.push(Poly::interpolate(&vec, lagrange_polys));
+                f.push(poly);
             }
         }
 
@@ -292,15 +300,29 @@ impl AndProof {
         }
         let circuit_size = 6 * self.l;
 
+        // The f's are only required at r, so we evaluate the lagrange polynomials once instead of
+        // interpolating each f
+        let lagrange_r = lagrange_polys
+            .iter()
+            .cloned()
+            .map(|poly| poly.evaluate(r))
+            .collect::<Vec<_>>();
+
         let mut f = Vec::with_capacity(circuit_size);
         for j in 0..self.l {
             for i in 0..6 {
-                let mut vec = Vec::with_capacity(self.m + 1);
-                vec.push(proof.w[i * self.l + j]);
-                for l in 0..self.m {
-                    vec.push(GF2p64::lift(verify[j * self.m + l][i]));
+                let mut f_r = proof.w[i * self.l + j] * lagrange_r[0];
+                for (v, l_r) in verify
+                    .iter()
+                    .skip(j * self.m)
+                    .take(self.m)
+                    .zip(lagrange_r.iter().skip(1))
+                {
+                    if v[i] {
+                        f_r += l_r;
+                    }
                 }
-                f.push(Poly::interpolate(&vec, lagrange_polys).evaluate(r));
+                f.push(f_r);
             }
         }
 
@@ -349,6 +371,33 @@ impl AndProof {
         self.verify_pi(betas, r, lagrange_polys, coords, &self.verify_next, proof)
     }
 
+    /// Recomputes the verification message which the next party computes for our own proof.
+    /// Next verifies with the explicit proof share and its shares of our inputs, all of which we
+    /// know as well. We vouch for this message by sending its hash to the receiving party.
+    pub fn verify_own(
+        &self,
+        betas: &[GF2p64],
+        r: &GF2p64,
+        lagrange_polys: &[Poly<GF2p64>],
+        coords: &[GF2p64],
+        proof: Proof,
+    ) -> Result<SharedVerify, Error> {
+        let verify = self
+            .proof
+            .iter()
+            .zip(self.verify_next.iter())
+            .map(|(p, n)| Input {
+                a0: p.a0,
+                a1: false,
+                b0: p.b0,
+                b1: false,
+                r: n.r,
+                s: p.s,
+            })
+            .collect::<Vec<_>>();
+        self.verify_pi(betas, r, lagrange_polys, coords, &verify, proof)
+    }
+
     pub fn combine_verifications(
         &self,
         thetas: &[GF2p64],
@@ -192,7 +192,7 @@ where
             .map(|chunk| chunk.collect())
             .collect();
 
-        for (theta, f_) in thetas.iter().zip(f.into_iter()) {
+        for (theta, f_) in thetas.iter().zip(f) {
             let poly = Self::c(f_);
             res += poly * theta;
         }
@@ -282,8 +282,8 @@ where
             for i in 0..6 {
                 let mut vec = Vec::with_capacity(self.m + 1);
                 vec.push(proof.w[i * self.l + j].to_owned());
-                for l in 0..self.m {
-                    vec.push(Poly::lift(verify[j * self.m + l][i].to_owned()));
+                for v in verify.iter().skip(j * self.m).take(self.m) {
+                    vec.push(Poly::lift(v[i].to_owned()));
                 }
                 f.push(Poly::interpolate(&vec, lagrange_polys).evaluate(r) % &self.modulus);
             }
@@ -337,6 +337,33 @@ where
         self.verify_pi(betas, r, lagrange_polys, coords, &self.verify_next, proof)
     }
 
+    /// Recomputes the verification message which the next party computes for our own proof.
+    /// Next verifies with the explicit proof share and its shares of our inputs, all of which we
+    /// know as well. We vouch for this message by sending its hash to the receiving party.
+    pub fn verify_own(
+        &self,
+        betas: &[Poly<T>],
+        r: &Poly<T>,
+        lagrange_polys: &[Poly<Poly<T>>],
+        coords: &[Poly<T>],
+        proof: Proof<T>,
+    ) -> Result<SharedVerify<T>, Error> {
+        let verify = self
+            .proof
+            .iter()
+            .zip(self.verify_next.iter())
+            .map(|(p, n)| Input {
+                a0: p.a0.to_owned(),
+                a1: T::zero(),
+                b0: p.b0.to_owned(),
+                b1: T::zero(),
+                r: -n.r.to_owned(),
+                s: p.s.to_owned(),
+            })
+            .collect::<Vec<_>>();
+        self.verify_pi(betas, r, lagrange_polys, coords, &verify, proof)
+    }
+
     pub fn combine_verifications(
         &self,
         thetas: &[Poly<T>],
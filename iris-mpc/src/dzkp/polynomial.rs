@@ -766,7 +766,7 @@ mod test {
 
         let interpolated = Poly::interpolate(&ys, &lagrange_polys);
 
-        for (point, y) in points.into_iter().zip(ys.into_iter()) {
+        for (point, y) in points.into_iter().zip(ys) {
             assert_eq!(interpolated.to_owned().evaluate(&point) % &modulus, y);
         }
     }
@@ -792,7 +792,7 @@ mod test {
         let mut interpolated = Poly::interpolate(&ys, &lagrange_polys);
         interpolated.reduce_coeffs(&modulus);
 
-        for (point, y) in points.into_iter().zip(ys.into_iter()) {
+        for (point, y) in points.into_iter().zip(ys) {
             assert_eq!(interpolated.to_owned().evaluate(&point) % &modulus, y);
         }
     }
@@ -812,7 +812,7 @@ mod test {
 
         let interpolated = Poly::interpolate(&ys, &lagrange_polys);
 
-        for (point, y) in points.into_iter().zip(ys.into_iter()) {
+        for (point, y) in points.into_iter().zip(ys) {
             assert_eq!(interpolated.to_owned().evaluate(&point), y);
         }
     }
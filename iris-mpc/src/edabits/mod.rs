@@ -0,0 +1,138 @@
+use crate::{
+    aby3::{id::PartyID, utils},
+    adder::AdderStrategy,
+    prelude::{Aby3Share, Bit, Error, Sharable},
+    traits::binary_trait::BinaryMpcTrait,
+    types::ring_element::RingImpl,
+};
+use num_traits::Zero;
+use std::collections::{HashMap, VecDeque};
+
+// Preprocessed edaBits (Escudero et al., https://eprint.iacr.org/2020/338): random values r which
+// are shared both arithmetically and bitwise. Since all backends use replicated sharing, [r] is
+// sampled for free with the PRF and its bits are obtained with the (verified) A2B of the backend in
+// a preprocessing step.
+
+// Arithmetic and binary shares of the same values
+type EdaBitQueue = (VecDeque<Aby3Share<u128>>, VecDeque<Aby3Share<u128>>);
+
+#[derive(Default)]
+pub(crate) struct EdaBits {
+    // Stored as u128 and indexed by the bitsize, such that one store serves all share types
+    store: HashMap<usize, EdaBitQueue>,
+}
+
+impl EdaBits {
+    pub(crate) fn len<T: Sharable>(&self) -> usize {
+        self.store
+            .get(&T::Share::K)
+            .map_or(0, |(arithmetic, _)| arithmetic.len())
+    }
+
+    pub(crate) fn extend<T: Sharable>(
+        &mut self,
+        arithmetic: Vec<Aby3Share<T>>,
+        binary: Vec<Aby3Share<T>>,
+    ) {
+        debug_assert_eq!(arithmetic.len(), binary.len());
+        let (a, b) = self.store.entry(T::Share::K).or_default();
+        a.extend(arithmetic.into_iter().map(Self::upgrade));
+        b.extend(binary.into_iter().map(Self::upgrade));
+    }
+
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn take<T: Sharable>(
+        &mut self,
+        amount: usize,
+    ) -> Result<(Vec<Aby3Share<T>>, Vec<Aby3Share<T>>), Error> {
+        if amount > self.len::<T>() {
+            return Err(Error::ValueError("Not enough edaBits".to_string()));
+        }
+        let (a, b) = self.store.get_mut(&T::Share::K).expect("Is present");
+        let arithmetic = a
+            .drain(..amount)
+            .map(Self::downgrade::<T>)
+            .collect::<Result<_, _>>()?;
+        let binary = b
+            .drain(..amount)
+            .map(Self::downgrade::<T>)
+            .collect::<Result<_, _>>()?;
+        Ok((arithmetic, binary))
+    }
+
+    fn upgrade<T: Sharable>(share: Aby3Share<T>) -> Aby3Share<u128> {
+        let (a, b) = share.get_ab();
+        Aby3Share::new(a.upgrade_to_128(), b.upgrade_to_128())
+    }
+
+    fn downgrade<T: Sharable>(share: Aby3Share<u128>) -> Result<Aby3Share<T>, Error> {
+        let (a, b) = share.get_ab();
+        let a = T::Share::from_bits(&a.to_bits()[..T::Share::K])?;
+        let b = T::Share::from_bits(&b.to_bits()[..T::Share::K])?;
+        Ok(Aby3Share::new(a, b))
+    }
+}
+
+/// Returns the number of AND gates required to extract the MSB of one K-bit value with an edaBit,
+/// including the arithmetic-to-binary conversion (Kogge-Stone) which generates the edaBit.
+pub(crate) fn msb_and_gates(k: usize) -> usize {
+    AdderStrategy::KoggeStone.msb_and_gates(k) + k - 2
+}
+
+/// Computes the MSB of x given the opened c = x + r and the bits of the edaBit r:
+/// MSB(x) = MSB(c) ^ MSB(r) ^ [c' < r'], where c' and r' are the lower K-1 bits. The comparison
+/// with the public c' requires K-2 AND gates, instead of the ~2K of the adder.
+pub(crate) async fn msb_from_edabits<T, Mpc>(
+    engine: &mut Mpc,
+    id: PartyID,
+    c: Vec<T::Share>,
+    r: Vec<Aby3Share<T>>,
+) -> Result<Vec<Aby3Share<Bit>>, Error>
+where
+    T: Sharable,
+    Mpc: BinaryMpcTrait<u128, Aby3Share<u128>>,
+{
+    let len = c.len();
+    if len != r.len() {
+        return Err(Error::InvalidSizeError);
+    }
+    let k = T::Share::K;
+
+    let r = utils::transpose_pack_u128::<T>(r);
+    // Transpose the public values the same way
+    let c = utils::transpose_pack_u128::<T>(
+        c.into_iter()
+            .map(|c| Aby3Share::new(c, T::Share::zero()))
+            .collect(),
+    )
+    .into_iter()
+    .map(|c| c.into_iter().map(|c| c.get_a()).collect::<Vec<_>>())
+    .collect::<Vec<_>>();
+
+    // LSB: c_0 < r_0 iff !c_0 & r_0
+    let mut lt = r[0]
+        .iter()
+        .zip(c[0].iter())
+        .map(|(r_, c_)| r_.to_owned() & !c_.to_owned())
+        .collect::<Vec<_>>();
+
+    // lt = (r_i & lt) ^ (!c_i & (r_i ^ lt))
+    for (r_i, c_i) in r.iter().zip(c.iter()).take(k - 1).skip(1) {
+        let and = engine.and_many(r_i, &lt).await?;
+        lt = and
+            .into_iter()
+            .zip(r_i.iter().zip(c_i.iter()).zip(lt))
+            .map(|(and, ((r_, c_), lt_))| and ^ ((r_.to_owned() ^ lt_) & !c_.to_owned()))
+            .collect();
+    }
+
+    // MSB(c) ^ MSB(r) ^ lt
+    let mut res = Vec::with_capacity(len);
+    for ((r_, c_), lt_) in r[k - 1].iter().zip(c[k - 1].iter()).zip(lt) {
+        let mut msb = lt_ ^ r_;
+        msb.xor_assign_const(c_, id);
+        res.extend(msb.to_bits());
+    }
+    res.truncate(len);
+    Ok(res)
+}
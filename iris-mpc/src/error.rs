@@ -27,6 +27,15 @@ pub enum Error {
     /// Verify failed Error
     #[error("Verify failed")]
     VerifyError,
+    /// A party was identified as cheating
+    #[error("Party {0} cheated")]
+    CheatingParty(usize),
+    /// Two parties are in conflict, at least one of them cheated
+    #[error("Parties {0} and {1} are in conflict, one of them cheated")]
+    ConflictingParties(usize, usize),
+    /// A party did not send an expected message in time
+    #[error("Party {0} timed out")]
+    TimeoutError(usize),
     /// DZKP verify failed Error
     #[error("DZKP verify failed")]
     DZKPVerifyError,
@@ -54,6 +63,12 @@ pub enum Error {
     /// Invalid value provided
     #[error("Invalid value: {0}")]
     ValueError(String),
+    /// A database error has occurred
+    #[error("Database error")]
+    DatabaseError(#[from] rusqlite::Error),
+    /// The database of a party differs from ours
+    #[error("Database of party {0} is inconsistent")]
+    InconsistentDatabase(usize),
     /// Error from the color_eyre crate
     #[error("ColorEyre error")]
     ColorEyreError(#[from] color_eyre::Report),
@@ -0,0 +1,198 @@
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+use serde::{Deserialize, Serialize};
+
+// Distributed comparison function (DCF) following Boyle et al.
+// (https://eprint.iacr.org/2020/1392), specialized to outputs in GF(2): The two keys evaluate to
+// XOR shares of [x < alpha], where inputs are bit vectors in MSB-first order.
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct CorrectionWord {
+    s: u128,
+    v: bool,
+    t_l: bool,
+    t_r: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct DcfKey {
+    party: bool,
+    seed: u128,
+    cws: Vec<CorrectionWord>,
+    cw_last: bool,
+}
+
+struct Expanded {
+    s_l: u128,
+    v_l: bool,
+    t_l: bool,
+    s_r: u128,
+    v_r: bool,
+    t_r: bool,
+}
+
+impl Expanded {
+    fn correct(&mut self, cw: &CorrectionWord) {
+        self.s_l ^= cw.s;
+        self.t_l ^= cw.t_l;
+        self.s_r ^= cw.s;
+        self.t_r ^= cw.t_r;
+    }
+
+    // Returns (s, v, t) of the chosen child
+    fn child(&self, right: bool) -> (u128, bool, bool) {
+        if right {
+            (self.s_r, self.v_r, self.t_r)
+        } else {
+            (self.s_l, self.v_l, self.t_l)
+        }
+    }
+}
+
+// ChaCha based length-doubling PRG
+fn prg(seed: u128) -> Expanded {
+    let mut key = [0u8; 32];
+    key[..16].copy_from_slice(&seed.to_le_bytes());
+    let mut rng = ChaCha12Rng::from_seed(key);
+
+    let s_l = rng.gen::<u128>();
+    let s_r = rng.gen::<u128>();
+    let bits = rng.gen::<u8>();
+    Expanded {
+        s_l,
+        v_l: bits & 1 == 1,
+        t_l: bits & 2 == 2,
+        s_r,
+        v_r: bits & 4 == 4,
+        t_r: bits & 8 == 8,
+    }
+}
+
+fn convert(seed: u128) -> bool {
+    seed & 1 == 1
+}
+
+impl DcfKey {
+    /// Generates the keys of both evaluators for the function x -> [x < alpha].
+    pub(crate) fn gen<R: Rng>(alpha: &[bool], rng: &mut R) -> (Self, Self) {
+        let seed0 = rng.gen::<u128>();
+        let seed1 = rng.gen::<u128>();
+
+        let mut s0 = seed0;
+        let mut s1 = seed1;
+        let mut t0 = false;
+        let mut t1 = true;
+        let mut v_alpha = false;
+        let mut cws = Vec::with_capacity(alpha.len());
+
+        for a in alpha.iter().copied() {
+            let e0 = prg(s0);
+            let e1 = prg(s1);
+
+            // Keep the path of alpha, the other child is lost
+            let (s_lose0, v_lose0, _) = e0.child(!a);
+            let (s_lose1, v_lose1, _) = e1.child(!a);
+            let (s_keep0, v_keep0, t_keep0) = e0.child(a);
+            let (s_keep1, v_keep1, t_keep1) = e1.child(a);
+
+            let s_cw = s_lose0 ^ s_lose1;
+            // If alpha_i = 1, every x going left is smaller than alpha
+            let v_cw = v_lose0 ^ v_lose1 ^ v_alpha ^ a;
+            v_alpha ^= v_keep0 ^ v_keep1 ^ v_cw;
+            let t_l = e0.t_l ^ e1.t_l ^ a ^ true;
+            let t_r = e0.t_r ^ e1.t_r ^ a;
+            let t_keep = if a { t_r } else { t_l };
+
+            if t0 {
+                s0 = s_keep0 ^ s_cw;
+                t0 = t_keep0 ^ t_keep;
+            } else {
+                s0 = s_keep0;
+                t0 = t_keep0;
+            }
+            if t1 {
+                s1 = s_keep1 ^ s_cw;
+                t1 = t_keep1 ^ t_keep;
+            } else {
+                s1 = s_keep1;
+                t1 = t_keep1;
+            }
+
+            cws.push(CorrectionWord {
+                s: s_cw,
+                v: v_cw,
+                t_l,
+                t_r,
+            });
+        }
+        let cw_last = convert(s0) ^ convert(s1) ^ v_alpha;
+
+        let key0 = Self {
+            party: false,
+            seed: seed0,
+            cws: cws.to_owned(),
+            cw_last,
+        };
+        let key1 = Self {
+            party: true,
+            seed: seed1,
+            cws,
+            cw_last,
+        };
+        (key0, key1)
+    }
+
+    pub(crate) fn input_len(&self) -> usize {
+        self.cws.len()
+    }
+
+    /// Evaluates the key on x, returning a XOR share of [x < alpha].
+    pub(crate) fn eval(&self, x: &[bool]) -> bool {
+        debug_assert_eq!(x.len(), self.cws.len());
+        let mut s = self.seed;
+        let mut t = self.party;
+        let mut v = false;
+
+        for (cw, x_) in self.cws.iter().zip(x.iter().copied()) {
+            let mut e = prg(s);
+            if t {
+                e.correct(cw);
+            }
+            let (s_, v_, t_) = e.child(x_);
+            v ^= v_ ^ (t & cw.v);
+            s = s_;
+            t = t_;
+        }
+        v ^ convert(s) ^ (t & self.cw_last)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TESTRUNS: usize = 100;
+    const BITS: usize = 15;
+
+    fn to_bits(x: u16) -> Vec<bool> {
+        (0..BITS).rev().map(|i| (x >> i) & 1 == 1).collect()
+    }
+
+    #[test]
+    fn dcf_test() {
+        let mut rng = ChaCha12Rng::from_entropy();
+        for _ in 0..TESTRUNS {
+            let alpha = rng.gen::<u16>() >> 1;
+            let (key0, key1) = DcfKey::gen(&to_bits(alpha), &mut rng);
+            assert_eq!(key0.input_len(), BITS);
+
+            let mut inputs = vec![0, alpha, alpha.saturating_sub(1), (1 << BITS) - 1];
+            inputs.extend((0..10).map(|_| rng.gen::<u16>() >> 1));
+            for x in inputs {
+                let x_bits = to_bits(x);
+                let res = key0.eval(&x_bits) ^ key1.eval(&x_bits);
+                assert_eq!(res, x < alpha);
+            }
+        }
+    }
+}
@@ -0,0 +1,2 @@
+pub(crate) mod dcf;
+pub(crate) mod msb;
@@ -0,0 +1,65 @@
+use super::dcf::DcfKey;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Selects how the MSB of arithmetic shares is extracted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MsbVariant {
+    /// Convert to a binary sharing and evaluate a ripple carry adder (K rounds).
+    #[default]
+    Adder,
+    /// Open the masked value to P0 and P1, which evaluate a distributed comparison function
+    /// (DCF) key generated by P2 in a preprocessing step (2 rounds). Only supported by
+    /// [`Aby3`](crate::prelude::Aby3).
+    Fss,
+    /// Open the value masked with a preprocessed edaBit and compare the public result with the
+    /// bits of the edaBit (K-1 rounds, but half the AND gates of the adder).
+    EdaBits,
+}
+
+// MSB(x) = MSB(y) ^ MSB(r) ^ [y' < r'], where y = x + r and y', r' are the lower K-1 bits.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct EvaluatorMaterial {
+    pub(crate) key: DcfKey,
+    pub(crate) r_msb: bool,
+}
+
+pub(crate) enum MsbMaterial {
+    /// P0 and P1 hold a DCF key for [y' < r'] and a XOR share of MSB(r).
+    Evaluator(EvaluatorMaterial),
+    /// P2 holds the mask r in MSB-first order.
+    Dealer(Vec<bool>),
+}
+
+/// The dealer samples a mask of `k` bits and the material of both evaluators.
+pub(crate) fn deal<R: Rng>(
+    k: usize,
+    rng: &mut R,
+) -> (Vec<bool>, EvaluatorMaterial, EvaluatorMaterial) {
+    let r = (0..k).map(|_| rng.gen::<bool>()).collect::<Vec<_>>();
+    let (key0, key1) = DcfKey::gen(&r[1..], rng);
+    let r_msb0 = rng.gen::<bool>();
+    let r_msb1 = r_msb0 ^ r[0];
+
+    (
+        r,
+        EvaluatorMaterial {
+            key: key0,
+            r_msb: r_msb0,
+        },
+        EvaluatorMaterial {
+            key: key1,
+            r_msb: r_msb1,
+        },
+    )
+}
+
+impl EvaluatorMaterial {
+    /// Returns a XOR share of MSB(x) given y = x + r in MSB-first order. Only one evaluator adds
+    /// MSB(y).
+    pub(crate) fn eval(&self, y: &[bool], add_y_msb: bool) -> bool {
+        debug_assert_eq!(y.len(), self.key.input_len() + 1);
+        let lt = self.key.eval(&y[1..]);
+        lt ^ self.r_msb ^ (add_y_msb & y[0])
+    }
+}
@@ -1,15 +1,23 @@
 use crate::aby3::utils::ceil_log2;
-use crate::prelude::{Aby3Share, Error, MpcTrait, Sharable, SpdzWiseShare, Swift3Share};
-use crate::traits::share_trait::{ShareTrait, VecShareTrait};
+use crate::blame;
+use crate::prelude::{
+    Aby3Share, DbRoots, DbRootsBuilder, DbShares, Error, MpcTrait, NetworkTrait, PackedBits, Plain,
+    Sharable, SpdzWiseShare, Swift3, Swift3Share,
+};
+use crate::traits::share_trait::{BitShareTrait, ShareTrait, VecShareTrait, VecSlice};
 use crate::types::bit::Bit;
 use crate::types::ring_element::RingImpl;
+use futures::{future, Stream, StreamExt};
 use num_traits::Zero;
 use plain_reference::IrisCodeArray;
-use std::{marker::PhantomData, usize};
+use rand::distributions::{Distribution, Standard};
+use std::{borrow::Borrow, marker::PhantomData, ops::Mul};
 
 const IRIS_CODE_SIZE: usize = plain_reference::IrisCode::IRIS_CODE_SIZE;
 const MASK_THRESHOLD: usize = plain_reference::MASK_THRESHOLD;
 const MATCH_THRESHOLD_RATIO: f64 = plain_reference::MATCH_THRESHOLD_RATIO;
+// Sequential by default, since only some protocols support sessions (see `set_sessions`)
+const DEFAULT_SESSIONS: usize = 1;
 
 pub type IrisAby3<T, Mpc> = IrisProtocol<T, Aby3Share<T>, Aby3Share<Bit>, Mpc>;
 pub type IrisSwift3<T, Mpc> = IrisProtocol<T, Swift3Share<T>, Swift3Share<Bit>, Mpc>;
@@ -20,16 +28,18 @@ pub type IrisSpdzWise<T: Sharable, Mpc> =
 pub struct IrisProtocol<
     T: Sharable,
     Ashare: ShareTrait,
-    Bshare: ShareTrait,
+    Bshare: BitShareTrait,
     Mpc: MpcTrait<T, Ashare, Bshare>,
 > {
     mpc: Mpc,
+    sessions: usize,
+    db_roots: Option<DbRoots>,
     phantom_t: PhantomData<T>,
     phantom_a: PhantomData<Ashare>,
     phantom_b: PhantomData<Bshare>,
 }
 
-impl<T: Sharable, Ashare: ShareTrait, Bshare: ShareTrait, Mpc: MpcTrait<T, Ashare, Bshare>>
+impl<T: Sharable, Ashare: ShareTrait, Bshare: BitShareTrait, Mpc: MpcTrait<T, Ashare, Bshare>>
     IrisProtocol<T, Ashare, Bshare, Mpc>
 where
     Ashare: Zero,
@@ -46,6 +56,8 @@ where
 
         Ok(IrisProtocol {
             mpc,
+            sessions: DEFAULT_SESSIONS,
+            db_roots: None,
             phantom_t: PhantomData,
             phantom_a: PhantomData,
             phantom_b: PhantomData,
@@ -68,10 +80,60 @@ where
         self.mpc.print_connection_stats(out)
     }
 
+    /// Sets the number of concurrent sessions [`Self::iris_in_db`] distributes the chunks of the
+    /// database over. Has to be the same for all parties. By default, there is a single session,
+    /// i.e., the chunks are processed sequentially without pipelining. Fails with
+    /// [`Error::ConfigError`] if more than one session is requested from a protocol which does not
+    /// support sessions (see [`MpcTrait::supports_sessions`]), which currently holds for all
+    /// protocols except Aby3. If the network cannot fork a session, [`Self::iris_in_db`] falls back
+    /// to processing the chunks sequentially, while [`Self::iris_in_db_stream`] returns an error.
+    pub fn set_sessions(&mut self, sessions: usize) -> Result<(), Error> {
+        if sessions > 1 && !self.mpc.supports_sessions() {
+            return Err(Error::ConfigError);
+        }
+        self.sessions = sessions;
+        Ok(())
+    }
+
+    pub fn get_sessions(&self) -> usize {
+        self.sessions
+    }
+
+    /// Sets the roots of the database of this party (see [`DbRoots`]), which
+    /// [`Self::preprocessing`] cross-checks with the other parties. Afterwards, each matching call
+    /// computes the roots of the database handed to it, cross-checks them with the other parties
+    /// and compares them to these roots before the result is verified. Thus, the roots have to be
+    /// computed from the same shares which are matched, e.g., with [`DbRoots::from_shares`].
+    pub fn set_db_roots(&mut self, roots: DbRoots) {
+        self.db_roots = Some(roots);
+    }
+
+    pub fn get_db_roots(&self) -> Option<&DbRoots> {
+        self.db_roots.as_ref()
+    }
+
     pub async fn preprocessing(&mut self) -> Result<(), Error> {
+        // Abort before any matching if the parties hold different versions of the database
+        if let Some(roots) = &self.db_roots {
+            self.mpc.verify_db_roots(roots).await?;
+        }
         self.mpc.preprocess().await
     }
 
+    // Binds the matched database to the committed roots: the roots of the matched shares are
+    // cross-checked with the neighbours holding the same components, such that all parties abort
+    // if one of them matched other shares, and compared to the committed roots. Does nothing if no
+    // roots were committed.
+    async fn verify_matched_db(&mut self, matched: Option<DbRoots>) -> Result<(), Error> {
+        if let Some(matched) = matched {
+            self.mpc.verify_db_roots(&matched).await?;
+            if self.db_roots.as_ref() != Some(&matched) {
+                return Err(Error::InconsistentDatabase(self.mpc.get_id()));
+            }
+        }
+        Ok(())
+    }
+
     pub fn set_mac_key(&mut self, key: Ashare) {
         self.mpc.set_mac_key(key);
     }
@@ -116,14 +178,11 @@ where
     fn masked_hamming_distance_post(
         &self,
         a: &Ashare::VecShare,
-        b: &Ashare::VecShare,
+        b: &VecSlice<Ashare>,
         mask: &IrisCodeArray,
         dot: Ashare,
     ) -> Result<Ashare, Error> {
-        if a.is_empty() || a.len() != b.len() {
-            return Err(Error::InvalidCodeSizeError);
-        }
-
+        // The sizes are checked in filter_reduce_add_twice
         let (sum_a, sum_b) = Ashare::VecShare::filter_reduce_add_twice(a, b, mask)?;
 
         let dot = self.mpc.mul_const(dot, T::try_from(2).unwrap());
@@ -146,17 +205,20 @@ where
         Ok(res[0].to_owned())
     }
 
-    pub(crate) async fn masked_hamming_distance_many(
+    pub(crate) async fn masked_hamming_distance_many<D>(
         &mut self,
         a: &Ashare::VecShare,
-        b: &[Ashare::VecShare],
+        b: &[D],
         masks: Vec<IrisCodeArray>,
-    ) -> Result<Vec<Ashare>, Error> {
+    ) -> Result<Vec<Ashare>, Error>
+    where
+        D: Borrow<VecSlice<Ashare>> + Sync,
+    {
         let dots = self.mpc.masked_dot_many(a, b, &masks).await?;
 
         let mut res = Vec::with_capacity(dots.len());
-        for ((b_, dot), mask) in b.iter().zip(dots.into_iter()).zip(masks.into_iter()) {
-            let r = self.masked_hamming_distance_post(a, b_, &mask, dot)?;
+        for ((b_, dot), mask) in b.iter().zip(dots).zip(masks) {
+            let r = self.masked_hamming_distance_post(a, b_.borrow(), &mask, dot)?;
             res.push(r);
         }
 
@@ -190,7 +252,7 @@ where
         &mut self,
         hwds: Vec<Ashare>,
         mask_lens: Vec<usize>,
-    ) -> Result<Bshare::VecShare, Error> {
+    ) -> Result<PackedBits<Bshare>, Error> {
         if hwds.len() != mask_lens.len() {
             return Err(Error::InvalidSizeError);
         }
@@ -219,13 +281,16 @@ where
         Ok(tmp.get_at(0))
     }
 
-    pub(crate) async fn compare_iris_many(
+    pub(crate) async fn compare_iris_many<D>(
         &mut self,
         a: &Ashare::VecShare,
-        b: &[Ashare::VecShare],
+        b: &[D],
         mask_a: &IrisCodeArray,
         mask_b: &[IrisCodeArray],
-    ) -> Result<Bshare::VecShare, Error> {
+    ) -> Result<PackedBits<Bshare>, Error>
+    where
+        D: Borrow<VecSlice<Ashare>> + Sync,
+    {
         let amount = b.len();
         if (amount != mask_b.len()) || (amount == 0) {
             return Err(Error::InvalidSizeError);
@@ -242,27 +307,164 @@ where
     }
 
     pub(crate) fn msb_and_gates(&self, mut amount: usize, chunk_size: usize) -> usize {
-        // packed kogge stone, i.e. full a2b: K (2 + 2 * (log_2(K)))
-        // let logk = ceil_log2(T::Share::K);
-        // amount * T::Share::K * (2 + 2 * logk)
+        // Padded to the chunk size
+        amount = amount.div_ceil(chunk_size);
+        self.mpc.msb_and_gates(amount * chunk_size)
+    }
+
+    // Distributes the chunks round-robin over concurrent sessions, such that one session computes
+    // its local dot products while the others wait for the network. Returns None if the session
+    // cannot be forked.
+    async fn compare_chunks_pipelined<D>(
+        &mut self,
+        iris: &Ashare::VecShare,
+        db: &[D],
+        mask_iris: &IrisCodeArray,
+        mask_db: &[IrisCodeArray],
+        chunk_size: usize,
+    ) -> Result<Option<PackedBits<Bshare>>, Error>
+    where
+        D: Borrow<VecSlice<Ashare>> + Sync,
+    {
+        let num_sessions = self.sessions.min(db.len().div_ceil(chunk_size));
+        if num_sessions < 2 {
+            return Ok(None);
+        }
+
+        let Some(mut forks) = self.fork_sessions(num_sessions).await? else {
+            return Ok(None);
+        };
+
+        let mut sessions = vec![&mut *self];
+        sessions.extend(forks.iter_mut());
+        let bool_shares =
+            Self::compare_chunks_in_sessions(sessions, iris, db, mask_iris, mask_db, chunk_size)
+                .await;
+
+        let finished = Self::finish_sessions(forks).await;
+        let bool_shares = bool_shares?;
+        finished?;
+        Ok(Some(bool_shares))
+    }
+
+    // Forks the additional sessions for `num_sessions` concurrent sessions. Returns None if the
+    // protocol or the network does not support sessions. The sessions which are already forked are
+    // finished if forking fails.
+    async fn fork_sessions(&mut self, num_sessions: usize) -> Result<Option<Vec<Self>>, Error> {
+        let mut forks = Vec::with_capacity(num_sessions.saturating_sub(1));
+        for _ in 1..num_sessions {
+            let mpc = match self.mpc.fork().await {
+                Ok(Some(mpc)) => mpc,
+                Ok(None) => {
+                    Self::finish_sessions(forks).await?;
+                    return Ok(None);
+                }
+                Err(err) => {
+                    // Report the error of the fork rather than the one of the cleanup
+                    let _ = Self::finish_sessions(forks).await;
+                    return Err(err);
+                }
+            };
+            // Cannot fail, since this session passed the same checks
+            forks.push(Self::new(mpc)?);
+        }
+        Ok(Some(forks))
+    }
 
-        // ripple carry adder: 2 * K - 2
-        amount = (amount + chunk_size - 1) / chunk_size;
-        amount * chunk_size * (T::Share::K * 2 - 3)
+    // Finishes all sessions, even if finishing one of them fails
+    async fn finish_sessions(sessions: Vec<Self>) -> Result<(), Error> {
+        let results = future::join_all(sessions.into_iter().map(|s| s.finish())).await;
+        results.into_iter().collect()
     }
 
-    pub async fn iris_in_db(
+    async fn compare_chunks_in_sessions<D>(
+        sessions: Vec<&mut Self>,
+        iris: &Ashare::VecShare,
+        db: &[D],
+        mask_iris: &IrisCodeArray,
+        mask_db: &[IrisCodeArray],
+        chunk_size: usize,
+    ) -> Result<PackedBits<Bshare>, Error>
+    where
+        D: Borrow<VecSlice<Ashare>> + Sync,
+    {
+        let num_sessions = sessions.len();
+        let chunks = db
+            .chunks(chunk_size)
+            .zip(mask_db.chunks(chunk_size))
+            .collect::<Vec<_>>();
+        let results = future::try_join_all(sessions.into_iter().enumerate().map(|(i, session)| {
+            let chunks = chunks.iter().skip(i).step_by(num_sessions);
+            async move {
+                let mut res = Vec::with_capacity(chunks.len());
+                for (db_, mask_) in chunks {
+                    res.push(
+                        session
+                            .compare_iris_many(iris, db_, mask_iris, mask_)
+                            .await?,
+                    );
+                }
+                Ok::<_, Error>(res)
+            }
+        }))
+        .await?;
+
+        // Restore the order of the chunks
+        let mut bool_shares = PackedBits::with_capacity(db.len());
+        let mut results = results
+            .into_iter()
+            .map(|r| r.into_iter())
+            .collect::<Vec<_>>();
+        for i in 0..chunks.len() {
+            let res = results[i % num_sessions]
+                .next()
+                .expect("Each session returns its chunks");
+            bool_shares.extend(res);
+        }
+        Ok(bool_shares)
+    }
+
+    /// Matches `iris` against the database. The entries of `db` may be borrowed, e.g., from a
+    /// mapped [`ShareFile`](crate::prelude::ShareFile).
+    pub async fn iris_in_db<D>(
         &mut self,
         iris: &Ashare::VecShare,
-        db: &[Ashare::VecShare],
+        db: &[D],
         mask_iris: &IrisCodeArray,
         mask_db: &[IrisCodeArray],
         chunk_size: usize,
-    ) -> Result<bool, Error> {
+    ) -> Result<bool, Error>
+    where
+        D: Borrow<VecSlice<Ashare>> + Sync,
+    {
+        let res = self
+            .iris_in_shard(iris, db, mask_iris, mask_db, chunk_size)
+            .await?;
+        self.mpc.open_bit(res).await
+    }
+
+    /// Like [`Self::iris_in_db`], but returns the verified share of the result instead of opening
+    /// it, such that the results of several shards of the database can be combined with
+    /// [`Self::combine_shards`].
+    pub async fn iris_in_shard<D>(
+        &mut self,
+        iris: &Ashare::VecShare,
+        db: &[D],
+        mask_iris: &IrisCodeArray,
+        mask_db: &[IrisCodeArray],
+        chunk_size: usize,
+    ) -> Result<Bshare, Error>
+    where
+        D: Borrow<VecSlice<Ashare>> + Sync,
+    {
         let amount = db.len();
         if (amount != mask_db.len()) || (amount == 0) {
             return Err(Error::InvalidSizeError);
         }
+        let matched = match self.db_roots {
+            Some(_) => Some(DbRoots::from_shares::<Ashare, D>(db, mask_db)?),
+            None => None,
+        };
 
         // Get enough and triples. So far only relevant for SpdzWise
         // amount - 1 for or_reduce, but padded for chunk size + msb_extract
@@ -270,16 +472,270 @@ where
             + ((amount + chunk_size - 2) / chunk_size) * chunk_size;
         self.mpc.precompute_and_triples(num_and_triples).await?;
 
-        let mut bool_shares = Bshare::VecShare::with_capacity(amount);
+        let bool_shares = match self
+            .compare_chunks_pipelined(iris, db, mask_iris, mask_db, chunk_size)
+            .await?
+        {
+            Some(res) => res,
+            None => {
+                let mut bool_shares = PackedBits::with_capacity(amount);
+                for (db_, mask_) in db.chunks(chunk_size).zip(mask_db.chunks(chunk_size)) {
+                    let res = self.compare_iris_many(iris, db_, mask_iris, mask_).await?;
+                    bool_shares.extend(res);
+                }
+                bool_shares
+            }
+        };
+
+        let res = self.mpc.reduce_binary_or(bool_shares, chunk_size).await?;
 
-        for (db_, mask_) in db.chunks(chunk_size).zip(mask_db.chunks(chunk_size)) {
-            let res = self.compare_iris_many(iris, db_, mask_iris, mask_).await?;
-            bool_shares.extend(res);
+        self.verify_matched_db(matched).await?;
+        self.mpc.verify().await?;
+        Ok(res)
+    }
+
+    /// Like [`Self::iris_in_db`], but the database is streamed instead of being held in memory.
+    /// Each item of the stream is split into chunks of `chunk_size` codes, which are distributed
+    /// over the sessions as in [`Self::iris_in_db`]. Only one item is held in memory at once, so
+    /// items of `chunk_size` times the number of sessions codes keep all sessions busy.
+    pub async fn iris_in_db_stream<S, D>(
+        &mut self,
+        iris: &Ashare::VecShare,
+        mask_iris: &IrisCodeArray,
+        db: S,
+        chunk_size: usize,
+    ) -> Result<bool, Error>
+    where
+        S: Stream<Item = Result<DbShares<D>, Error>> + Unpin,
+        D: Borrow<VecSlice<Ashare>> + Sync,
+    {
+        let res = self
+            .iris_in_shard_stream(iris, mask_iris, db, chunk_size)
+            .await?;
+        self.mpc.open_bit(res).await
+    }
+
+    /// Like [`Self::iris_in_shard`], but the database is streamed as in
+    /// [`Self::iris_in_db_stream`].
+    pub async fn iris_in_shard_stream<S, D>(
+        &mut self,
+        iris: &Ashare::VecShare,
+        mask_iris: &IrisCodeArray,
+        mut db: S,
+        chunk_size: usize,
+    ) -> Result<Bshare, Error>
+    where
+        S: Stream<Item = Result<DbShares<D>, Error>> + Unpin,
+        D: Borrow<VecSlice<Ashare>> + Sync,
+    {
+        if chunk_size == 0 {
+            return Err(Error::InvalidSizeError);
+        }
+        // Other than in iris_in_db, a network without sessions is a configuration error here
+        let mut forks = if self.sessions > 1 {
+            self.fork_sessions(self.sessions)
+                .await?
+                .ok_or(Error::ConfigError)?
+        } else {
+            Vec::new()
+        };
+
+        let mut matched = self.db_roots.as_ref().map(|_| DbRootsBuilder::new());
+        let compared = self
+            .compare_stream_in_sessions(
+                &mut forks,
+                iris,
+                mask_iris,
+                &mut db,
+                &mut matched,
+                chunk_size,
+            )
+            .await;
+        let finished = Self::finish_sessions(forks).await;
+        let (amount, bool_shares) = compared?;
+        finished?;
+        if amount == 0 {
+            return Err(Error::InvalidSizeError);
         }
 
+        // amount - 1 for or_reduce, padded for chunk size
+        let num_and_triples = ((amount + chunk_size - 2) / chunk_size) * chunk_size;
+        self.mpc.precompute_and_triples(num_and_triples).await?;
         let res = self.mpc.reduce_binary_or(bool_shares, chunk_size).await?;
 
-        self.mpc.verify().await.unwrap();
+        self.verify_matched_db(matched.map(|builder| builder.roots()))
+            .await?;
+        self.mpc.verify().await?;
+        Ok(res)
+    }
+
+    // Compares all items of the stream, distributing the chunks of each item over this session
+    // and the forks. Returns the number of compared codes and the results. The compared items are
+    // appended to `matched`, if given.
+    async fn compare_stream_in_sessions<S, D>(
+        &mut self,
+        forks: &mut [Self],
+        iris: &Ashare::VecShare,
+        mask_iris: &IrisCodeArray,
+        db: &mut S,
+        matched: &mut Option<DbRootsBuilder>,
+        chunk_size: usize,
+    ) -> Result<(usize, PackedBits<Bshare>), Error>
+    where
+        S: Stream<Item = Result<DbShares<D>, Error>> + Unpin,
+        D: Borrow<VecSlice<Ashare>> + Sync,
+    {
+        let mut amount = 0;
+        let mut bool_shares = PackedBits::with_capacity(0);
+        while let Some(item) = db.next().await {
+            let item = item?;
+            if item.shares.len() != item.masks.len() {
+                return Err(Error::InvalidSizeError);
+            }
+            if item.shares.is_empty() {
+                continue;
+            }
+            amount += item.shares.len();
+            if let Some(matched) = matched {
+                matched.extend::<Ashare, D>(&item.shares, &item.masks)?;
+            }
+
+            // Get enough and triples for the msb_extract of this item. So far only relevant for
+            // SpdzWise, which does not support sessions
+            let num_and_triples = self.msb_and_gates(item.shares.len(), chunk_size);
+            self.mpc.precompute_and_triples(num_and_triples).await?;
+
+            let mut sessions = vec![&mut *self];
+            sessions.extend(forks.iter_mut());
+            let res = Self::compare_chunks_in_sessions(
+                sessions,
+                iris,
+                &item.shares,
+                mask_iris,
+                &item.masks,
+                chunk_size,
+            )
+            .await?;
+            bool_shares.extend(res);
+        }
+        Ok((amount, bool_shares))
+    }
+
+    /// OR-combines the results of [`Self::iris_in_shard`] of all shards of the database and opens
+    /// the combined result. The shares may stem from other instances of the same protocol.
+    pub async fn combine_shards(&mut self, shards: Vec<Bshare>) -> Result<bool, Error> {
+        let amount = shards.len();
+        if amount == 0 {
+            return Err(Error::InvalidSizeError);
+        }
+        self.mpc.precompute_and_triples(amount - 1).await?;
+
+        let bool_shares = shards.into_iter().collect();
+        let res = self.mpc.reduce_binary_or(bool_shares, amount).await?;
+
+        self.mpc.verify().await?;
         self.mpc.open_bit(res).await
     }
 }
+
+impl<N: NetworkTrait, T: Sharable> IrisSwift3<T, Swift3<N, T>>
+where
+    Standard: Distribution<T::Share>,
+    Swift3Share<T>: Mul<T::Share, Output = Swift3Share<T>>,
+    <T as std::convert::TryFrom<usize>>::Error: std::fmt::Debug,
+{
+    /// Like [`Self::iris_in_db`], but with identifiable abort and recovery by a trusted third
+    /// party if the underlying [`Swift3`] instance is in the robust mode. Before the computation,
+    /// the parties commit to the components of the input shares. If a verification fails, the
+    /// parties name a cheater (or a pair of conflicting parties) and elect the party which is
+    /// proven to be honest by the verdict as TTP. The TTP checks the components it is missing
+    /// against the commitments, reconstructs the inputs, computes the result in plain and sends it
+    /// to the other parties. Thus, the inputs are only revealed to an honest party, and the output
+    /// is correct.
+    ///
+    /// This does not guarantee output delivery. If the commitments already conflict without
+    /// naming a cheater, the inputs are inconsistent and no party can tell the correct components
+    /// apart, so the error is returned before any computation. An equivocating cheater may lead
+    /// the honest parties to different verdicts (see `blame::jmp_verify`), in which case the
+    /// recovery fails, and a cheater which stops sending during the computation blocks the other
+    /// parties. The messages of the recovery are bounded by a timeout (see
+    /// [`Swift3::set_ttp_timeout`]), after which a missing message fails the recovery.
+    pub async fn iris_in_db_robust(
+        &mut self,
+        iris: &[Swift3Share<T>],
+        db: &[Vec<Swift3Share<T>>],
+        mask_iris: &IrisCodeArray,
+        mask_db: &[IrisCodeArray],
+        chunk_size: usize,
+    ) -> Result<bool, Error> {
+        let iris = iris.to_vec();
+        if !self.mpc.is_robust() {
+            return self
+                .iris_in_db(&iris, db, mask_iris, mask_db, chunk_size)
+                .await;
+        }
+
+        let mut shares = iris.to_owned();
+        for code in db {
+            shares.extend_from_slice(code);
+        }
+
+        let (verdict, commitments) = match self.mpc.commit_inputs(&shares).await {
+            Ok(commitments) => match self
+                .iris_in_db(&iris, db, mask_iris, mask_db, chunk_size)
+                .await
+            {
+                Ok(res) => return Ok(res),
+                Err(err) => (err, Some(commitments)),
+            },
+            Err(err) => (err, None),
+        };
+
+        // The verdicts of the blame phases contain the cheater, so the elected TTP is honest
+        let ttp = match blame::elect_ttp(&verdict) {
+            // Without commitments, the TTP can only exclude a named cheater
+            Some(ttp) if commitments.is_some() || matches!(verdict, Error::CheatingParty(_)) => ttp,
+            _ => return Err(verdict),
+        };
+        tracing::warn!(
+            "Party {}: Completing the computation at the trusted third party {}",
+            self.get_id(),
+            ttp
+        );
+
+        let output = match self
+            .mpc
+            .ttp_reconstruct(&verdict, commitments.as_ref(), shares)
+            .await
+        {
+            Ok(Some(inputs)) => Some(
+                Self::iris_in_db_plain(inputs, iris.len(), db, mask_iris, mask_db, chunk_size)
+                    .await,
+            ),
+            Ok(None) => None,
+            Err(err) => Some(Err(err)),
+        };
+        self.mpc.ttp_output(ttp, output).await
+    }
+
+    async fn iris_in_db_plain(
+        mut inputs: Vec<T>,
+        iris_len: usize,
+        db: &[Vec<Swift3Share<T>>],
+        mask_iris: &IrisCodeArray,
+        mask_db: &[IrisCodeArray],
+        chunk_size: usize,
+    ) -> Result<bool, Error> {
+        let mut plain_db = Vec::with_capacity(db.len());
+        let mut rest = inputs.split_off(iris_len);
+        for code in db {
+            let tail = rest.split_off(code.len());
+            plain_db.push(rest);
+            rest = tail;
+        }
+
+        let mut iris: IrisProtocol<T, T, Bit, Plain> = IrisProtocol::new(Plain::default())?;
+        iris.iris_in_db(&inputs, &plain_db, mask_iris, mask_db, chunk_size)
+            .await
+    }
+}
@@ -1,10 +1,18 @@
 pub(crate) mod aby3;
 pub(crate) mod aby3_mal;
+pub(crate) mod adder;
+pub(crate) mod blame;
+pub(crate) mod bristol;
 pub(crate) mod commitment;
+pub(crate) mod db;
+pub(crate) mod dry_run;
 pub(crate) mod dzkp;
+pub(crate) mod edabits;
 pub(crate) mod error;
+pub(crate) mod fss;
 pub(crate) mod iris;
 pub mod prelude;
+pub(crate) mod sharding;
 pub(crate) mod spdzwise;
 pub(crate) mod swift3;
 #[cfg(test)]
@@ -2,22 +2,95 @@ pub use super::aby3::network::Aby3Network;
 pub use super::aby3::protocol::Aby3;
 pub use super::aby3::share::Share as Aby3Share;
 pub use super::aby3_mal::protocol::MalAby3;
+pub use super::adder::AdderStrategy;
+pub use super::bristol::Circuit;
+pub use super::db::compressed::create_compressed_tables;
+pub use super::db::compressed::has_stored_component;
+pub use super::db::compressed::party_seeds_path;
+pub use super::db::compressed::read_compressed_db;
+pub use super::db::compressed::read_party_seeds;
+pub use super::db::compressed::write_compressed_db;
+pub use super::db::compressed::write_party_seeds;
+pub use super::db::compressed::DbSeeds;
+pub use super::db::compressed::DbShares;
+pub use super::db::compressed::PartySeeds;
+pub use super::db::generation::generate_random_codes;
+pub use super::db::generation::input_codes;
+pub use super::db::management::check_party_db_consistency;
+pub use super::db::management::delete_party_db_records;
+pub use super::db::management::party_db_digest;
+pub use super::db::management::party_db_ids;
+pub use super::db::management::update_aby3_party_db_records;
+pub use super::db::management::update_spdzwise_party_db_records;
+pub use super::db::management::update_swift3_party_db_records;
+pub use super::db::party_db::aby3_party_db_chunks;
+pub use super::db::party_db::has_spdzwise_party_mac_key;
+pub use super::db::party_db::party_db_len;
+pub use super::db::party_db::party_db_path;
+pub use super::db::party_db::read_aby3_party_db;
+pub use super::db::party_db::read_aby3_party_db_range;
+pub use super::db::party_db::read_party_info;
+pub use super::db::party_db::read_spdzwise_party_db;
+pub use super::db::party_db::read_spdzwise_party_mac_key;
+pub use super::db::party_db::read_swift3_party_db;
+pub use super::db::party_db::read_truth_code;
+pub use super::db::party_db::read_truth_db;
+pub use super::db::party_db::read_truth_mac_key;
+pub use super::db::party_db::spdzwise_party_db_chunks;
+pub use super::db::party_db::swift3_party_db_chunks;
+pub use super::db::party_db::truth_db_path;
+pub use super::db::party_db::write_aby3_party_db;
+pub use super::db::party_db::write_party_info;
+pub use super::db::party_db::write_spdzwise_party_db;
+pub use super::db::party_db::write_spdzwise_party_mac_key;
+pub use super::db::party_db::write_swift3_party_db;
+pub use super::db::party_db::PartyDbChunks;
+pub use super::db::roots::DbRoots;
+pub use super::db::roots::DbRootsBuilder;
+pub use super::db::share_file::aby3_share_file_from_sqlite;
+pub use super::db::share_file::swift3_share_file_from_sqlite;
+pub use super::db::share_file::FileShare;
+pub use super::db::share_file::ShareFile;
+pub use super::db::share_file::ShareFileHeader;
+pub use super::db::share_file::ShareFileWriter;
+pub use super::db::share_file::ShareProtocol;
+pub use super::dry_run::network::DryRunNetwork;
+pub use super::dry_run::protocol::CostReport;
+pub use super::dry_run::protocol::DryRun;
 pub use super::error::Error;
+pub use super::fss::msb::MsbVariant;
 pub use super::iris::protocol::IrisAby3;
 pub use super::iris::protocol::IrisProtocol;
 pub use super::iris::protocol::IrisSpdzWise;
 pub use super::iris::protocol::IrisSwift3;
+pub use super::sharding::combine_shard_results;
+pub use super::sharding::match_shard;
+pub use super::sharding::network::PartyShardTestNetwork;
+pub use super::sharding::network::ShardNetwork;
+pub use super::sharding::network::ShardNetworkTrait;
+pub use super::sharding::network::ShardTestNetwork;
+pub use super::sharding::shard_ranges;
+pub use super::spdzwise::binary_mac::BinaryVerification;
+pub use super::spdzwise::cut_and_choose::CutAndChoose;
 pub use super::spdzwise::protocol::SpdzWise;
 pub use super::spdzwise::share::Share as SpdzWiseShare;
+pub use super::spdzwise::triple_store::TripleStore;
 pub use super::spdzwise::vecshare::VecShare as SpdzWiseVecShare;
 pub use super::swift3::network::Swift3Network;
 pub use super::swift3::protocol::Swift3;
 pub use super::swift3::share::Share as Swift3Share;
+pub use super::traits::binary_trait::BinaryMpcTrait;
 pub use super::traits::mpc_trait::MpcTrait;
+pub use super::traits::mpc_trait::Plain;
 pub use super::traits::network_trait::NetworkTrait;
+pub use super::traits::security::MaliciousAbort;
+pub use super::traits::security::SemiHonest;
+pub use super::traits::share_trait::BitShareTrait;
+pub use super::traits::share_trait::ShareTrait;
 pub use super::traits::share_trait::VecShareTrait;
 pub use super::traits::test_network::PartyTestNetwork;
 pub use super::traits::test_network::TestNetwork3p;
 pub use super::types::bit::Bit;
+pub use super::types::packed_bits::PackedBits;
 pub use super::types::ring_element::RingImpl;
 pub use super::types::sharable::Sharable;
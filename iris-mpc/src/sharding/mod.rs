@@ -0,0 +1,105 @@
+pub(crate) mod network;
+
+use crate::{
+    error::Error,
+    prelude::{IrisProtocol, MpcTrait, Sharable},
+    traits::share_trait::{BitShareTrait, ShareTrait},
+};
+use bytes::Bytes;
+use network::ShardNetworkTrait;
+use num_traits::Zero;
+use plain_reference::IrisCodeArray;
+use serde::{de::DeserializeOwned, Serialize};
+use std::ops::Range;
+
+// Horizontal sharding of the database: Each party splits its database into the same contiguous
+// shards and assigns each shard to one of its workers. The workers with the same shard form a
+// triple which runs the matching protocol on its shard, but does not open the result. Instead,
+// each worker sends its share of the result to the coordinator of its party. The coordinators form
+// a triple as well and OR-combine the results of all shards inside MPC before opening.
+
+/// Splits a database of `len` entries into `num_shards` contiguous shards, whose sizes differ by
+/// at most one. Shards are empty if the database has less than `num_shards` entries.
+pub fn shard_ranges(len: usize, num_shards: usize) -> Vec<Range<usize>> {
+    if num_shards == 0 {
+        return Vec::new();
+    }
+    let base = len / num_shards;
+    let rest = len % num_shards;
+    let mut start = 0;
+    (0..num_shards)
+        .map(|i| {
+            let end = start + base + usize::from(i < rest);
+            let range = start..end;
+            start = end;
+            range
+        })
+        .collect()
+}
+
+/// Runs the matching protocol on the shard of a worker and sends the share of the result to the
+/// coordinator. A worker with an empty shard sends a share of zero without running the protocol.
+pub async fn match_shard<T, Ashare, Bshare, Mpc, N>(
+    iris: &mut IrisProtocol<T, Ashare, Bshare, Mpc>,
+    network: &mut N,
+    iris_share: &Ashare::VecShare,
+    db: &[Ashare::VecShare],
+    mask_iris: &IrisCodeArray,
+    mask_db: &[IrisCodeArray],
+    chunk_size: usize,
+) -> Result<(), Error>
+where
+    T: Sharable,
+    Ashare: ShareTrait + Zero,
+    Bshare: BitShareTrait + Serialize,
+    Mpc: MpcTrait<T, Ashare, Bshare>,
+    N: ShardNetworkTrait,
+    <T as std::convert::TryFrom<usize>>::Error: std::fmt::Debug,
+{
+    if network.get_id() == N::COORDINATOR {
+        return Err(Error::IdError(network.get_id()));
+    }
+
+    let res = if db.is_empty() {
+        Bshare::zero()
+    } else {
+        iris.iris_in_shard(iris_share, db, mask_iris, mask_db, chunk_size)
+            .await?
+    };
+
+    let data = bincode::serialize(&res).map_err(|_| Error::SerializationError)?;
+    network.send(N::COORDINATOR, Bytes::from(data)).await?;
+    // Wait for the acknowledgement, such that the share is not lost when closing the connection
+    network.receive(N::COORDINATOR).await?;
+    Ok(())
+}
+
+/// Receives the shares of the results of all workers and OR-combines them with the coordinators of
+/// the other parties. Each received share is acknowledged to its worker.
+pub async fn combine_shard_results<T, Ashare, Bshare, Mpc, N>(
+    iris: &mut IrisProtocol<T, Ashare, Bshare, Mpc>,
+    network: &mut N,
+) -> Result<bool, Error>
+where
+    T: Sharable,
+    Ashare: ShareTrait + Zero,
+    Bshare: BitShareTrait + DeserializeOwned,
+    Mpc: MpcTrait<T, Ashare, Bshare>,
+    N: ShardNetworkTrait,
+    <T as std::convert::TryFrom<usize>>::Error: std::fmt::Debug,
+{
+    if network.get_id() != N::COORDINATOR {
+        return Err(Error::IdError(network.get_id()));
+    }
+
+    let num_workers = network.get_num_workers();
+    let mut shards = Vec::with_capacity(num_workers);
+    for worker in 1..=num_workers {
+        let data = network.receive(worker).await?;
+        let share = bincode::deserialize(&data).map_err(|_| Error::SerializationError)?;
+        shards.push(share);
+        network.send(worker, Bytes::new()).await?;
+    }
+
+    iris.combine_shards(shards).await
+}
@@ -0,0 +1,184 @@
+use bytes::{Bytes, BytesMut};
+use mpc_net::{channel::ChannelHandle, config::NetworkConfig, MpcNetworkHandler};
+use std::{collections::HashMap, io};
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+
+type Channel = ChannelHandle<Bytes, BytesMut>;
+
+/// The links between the coordinator of a party and the workers of the same party. The
+/// coordinator has id [`ShardNetworkTrait::COORDINATOR`], the worker of shard `i` has id `i + 1`.
+#[allow(async_fn_in_trait)]
+pub trait ShardNetworkTrait {
+    const COORDINATOR: usize = 0;
+
+    fn get_id(&self) -> usize;
+    fn get_num_workers(&self) -> usize;
+
+    async fn shutdown(self) -> Result<(), io::Error>;
+
+    /// Sends to the coordinator if called by a worker, or to the worker `id` if called by the
+    /// coordinator.
+    async fn send(&mut self, id: usize, data: Bytes) -> Result<(), io::Error>;
+    async fn receive(&mut self, id: usize) -> Result<BytesMut, io::Error>;
+}
+
+pub struct ShardNetwork {
+    handler: MpcNetworkHandler,
+    id: usize,
+    num_workers: usize,
+    channels: HashMap<usize, Channel>,
+}
+
+impl ShardNetwork {
+    /// Connects the coordinator and the workers of one party. The config contains the coordinator
+    /// with id 0 and the workers with ids `1..=num_workers`.
+    pub async fn new(mut config: NetworkConfig) -> Result<Self, io::Error> {
+        let id = config.my_id;
+        let num_workers = config.parties.len().saturating_sub(1);
+        if num_workers == 0 {
+            return Err(io::Error::other("At least one worker is required"));
+        }
+
+        // The workers do not talk to each other, so they only connect to the coordinator
+        if id != Self::COORDINATOR {
+            config
+                .parties
+                .retain(|party| party.id == id || party.id == Self::COORDINATOR);
+        }
+        let handler = MpcNetworkHandler::establish(config)
+            .await
+            .map_err(io::Error::other)?;
+        let channels = handler
+            .get_byte_channels()
+            .await?
+            .into_iter()
+            .map(|(other, channel)| (other, ChannelHandle::manage(channel)))
+            .collect();
+
+        Ok(Self {
+            handler,
+            id,
+            num_workers,
+            channels,
+        })
+    }
+
+    fn channel(&mut self, id: usize) -> io::Result<&mut Channel> {
+        self.channels
+            .get_mut(&id)
+            .ok_or_else(|| io::Error::other("Invalid ID"))
+    }
+}
+
+impl ShardNetworkTrait for ShardNetwork {
+    fn get_id(&self) -> usize {
+        self.id
+    }
+
+    fn get_num_workers(&self) -> usize {
+        self.num_workers
+    }
+
+    async fn shutdown(self) -> io::Result<()> {
+        drop(self.channels);
+        self.handler.shutdown().await;
+        Ok(())
+    }
+
+    async fn send(&mut self, id: usize, data: Bytes) -> io::Result<()> {
+        tracing::trace!("shard send {}->{}: {:?}", self.id, id, data);
+        let send_status = self.channel(id)?.send(data).await;
+        std::mem::drop(send_status);
+        Ok(())
+    }
+
+    async fn receive(&mut self, id: usize) -> io::Result<BytesMut> {
+        tracing::trace!("shard recv {}<-{}: ", self.id, id);
+        let buf = self.channel(id)?.recv().await.await;
+        tracing::trace!("shard recv {}<-{}: done", self.id, id);
+        if let Ok(maybe_packet) = buf {
+            maybe_packet
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::ConnectionAborted,
+                "Receive on closed Channel",
+            ))
+        }
+    }
+}
+
+/// An in-memory [`ShardNetworkTrait`] between the coordinator and the workers of one party.
+pub struct ShardTestNetwork {
+    num_workers: usize,
+}
+
+impl ShardTestNetwork {
+    pub fn new(num_workers: usize) -> Self {
+        Self { num_workers }
+    }
+
+    /// Returns the network of the coordinator followed by the networks of the workers.
+    pub fn get_party_networks(self) -> Vec<PartyShardTestNetwork> {
+        let mut coordinator = PartyShardTestNetwork {
+            id: PartyShardTestNetwork::COORDINATOR,
+            num_workers: self.num_workers,
+            senders: HashMap::with_capacity(self.num_workers),
+            receivers: HashMap::with_capacity(self.num_workers),
+        };
+        let mut res = Vec::with_capacity(self.num_workers + 1);
+        for id in 1..=self.num_workers {
+            let (send_up, recv_up) = mpsc::unbounded_channel();
+            let (send_down, recv_down) = mpsc::unbounded_channel();
+            coordinator.senders.insert(id, send_down);
+            coordinator.receivers.insert(id, recv_up);
+            res.push(PartyShardTestNetwork {
+                id,
+                num_workers: self.num_workers,
+                senders: HashMap::from([(PartyShardTestNetwork::COORDINATOR, send_up)]),
+                receivers: HashMap::from([(PartyShardTestNetwork::COORDINATOR, recv_down)]),
+            });
+        }
+        res.insert(0, coordinator);
+        res
+    }
+}
+
+pub struct PartyShardTestNetwork {
+    id: usize,
+    num_workers: usize,
+    senders: HashMap<usize, UnboundedSender<Bytes>>,
+    receivers: HashMap<usize, UnboundedReceiver<Bytes>>,
+}
+
+impl ShardNetworkTrait for PartyShardTestNetwork {
+    fn get_id(&self) -> usize {
+        self.id
+    }
+
+    fn get_num_workers(&self) -> usize {
+        self.num_workers
+    }
+
+    async fn shutdown(self) -> io::Result<()> {
+        Ok(())
+    }
+
+    async fn send(&mut self, id: usize, data: Bytes) -> io::Result<()> {
+        self.senders
+            .get(&id)
+            .ok_or_else(|| io::Error::other("Invalid ID"))?
+            .send(data)
+            .map_err(|_| io::Error::other("Send failed"))
+    }
+
+    async fn receive(&mut self, id: usize) -> io::Result<BytesMut> {
+        let data = self
+            .receivers
+            .get_mut(&id)
+            .ok_or_else(|| io::Error::other("Invalid ID"))?
+            .recv()
+            .await
+            .ok_or_else(|| io::Error::other("Receive failed"))?;
+        Ok(BytesMut::from(data.as_ref()))
+    }
+}
@@ -0,0 +1,95 @@
+use crate::{
+    aby3::random::prf::Prf, dzkp::gf2p64::GF2p64, prelude::Aby3Share,
+    types::ring_element::RingElement,
+};
+use num_traits::Zero;
+use std::ops::{Add, AddAssign, Mul};
+
+/// Selects how [`SpdzWise`](crate::prelude::SpdzWise) verifies the AND gates of binary circuits.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BinaryVerification {
+    /// Sacrifice AND triples which were generated and checked with cut-and-choose.
+    #[default]
+    CutAndChoose,
+    /// MAC the bits in GF(2^64) and check all AND gates with one batched random linear
+    /// combination. Requires no precomputed triples.
+    Gf2p64Mac,
+}
+
+/// A replicated share of an element of GF(2^64). Party i holds (x_i, x_{i-1}).
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct GfShare {
+    pub(crate) a: GF2p64,
+    pub(crate) b: GF2p64,
+}
+
+impl GfShare {
+    pub(crate) fn new(a: GF2p64, b: GF2p64) -> Self {
+        Self { a, b }
+    }
+
+    pub(crate) fn random(prf: &mut Prf) -> Self {
+        let (a, b) = prf.gen_rands::<u64>();
+        Self::new(GF2p64::new(a), GF2p64::new(b))
+    }
+
+    /// Lifts bit `i` of a binary share into GF(2^64).
+    pub(crate) fn lift_bit(share: &Aby3Share<u128>, i: usize) -> Self {
+        Self::new(
+            GF2p64::lift((share.a.0 >> i) & 1 == 1),
+            GF2p64::lift((share.b.0 >> i) & 1 == 1),
+        )
+    }
+
+    /// Returns the additive share of the product, which has to be reshared.
+    pub(crate) fn mul_local(&self, other: &Self) -> GF2p64 {
+        self.a * other.a + self.a * other.b + self.b * other.a
+    }
+
+    // GF(2^k) has characteristic 2, so the same zero share works as for binary shares
+    pub(crate) fn zero_share(prf: &mut Prf) -> GF2p64 {
+        let (a, b) = prf.gen_rands::<u64>();
+        GF2p64::new(a ^ b)
+    }
+
+    pub(crate) fn to_ring(x: GF2p64) -> RingElement<u64> {
+        RingElement(x.get())
+    }
+
+    pub(crate) fn from_ring(x: RingElement<u64>) -> GF2p64 {
+        GF2p64::new(x.0)
+    }
+}
+
+impl Add for GfShare {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(self.a + rhs.a, self.b + rhs.b)
+    }
+}
+
+impl AddAssign for GfShare {
+    fn add_assign(&mut self, rhs: Self) {
+        self.a += rhs.a;
+        self.b += rhs.b;
+    }
+}
+
+impl Mul<GF2p64> for GfShare {
+    type Output = Self;
+
+    fn mul(self, rhs: GF2p64) -> Self::Output {
+        Self::new(self.a * rhs, self.b * rhs)
+    }
+}
+
+impl Zero for GfShare {
+    fn zero() -> Self {
+        Self::default()
+    }
+
+    fn is_zero(&self) -> bool {
+        self.a.is_zero() && self.b.is_zero()
+    }
+}
@@ -0,0 +1,116 @@
+use crate::prelude::Error;
+
+/// Parameters of the cut-and-choose generation of AND triples in
+/// [`SpdzWise`](crate::prelude::SpdzWise), following Furukawa et al.
+/// (<https://eprint.iacr.org/2016/944>) and Araki et al.
+/// (<https://www.ieee-security.org/TC/SP2017/papers/96.pdf>).
+///
+/// Per generated triple, `bucket_size - 1` triples are sacrificed to verify it and `opened`
+/// triples are opened to check their correctness. For `opened >= bucket_size`, an adversary
+/// remains undetected with probability at most `1 / binom(n * bucket_size, bucket_size)`, where
+/// `n` is the number of triples generated in one batch.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CutAndChoose {
+    bucket_size: usize,
+    opened: usize,
+    security: usize,
+}
+
+impl Default for CutAndChoose {
+    // B=2 buckets and one opened 128 bit register for 40 bit statistical security
+    fn default() -> Self {
+        Self {
+            bucket_size: 2,
+            opened: 128,
+            security: 40,
+        }
+    }
+}
+
+impl CutAndChoose {
+    pub fn new(bucket_size: usize, opened: usize, security: usize) -> Result<Self, Error> {
+        if bucket_size < 2 {
+            return Err(Error::ValueError(format!(
+                "Bucket size {} is too small, at least 2 are required",
+                bucket_size
+            )));
+        }
+        if opened < bucket_size {
+            return Err(Error::ValueError(format!(
+                "At least {} triples have to be opened, got {}",
+                bucket_size, opened
+            )));
+        }
+        if security == 0 {
+            return Err(Error::ValueError(
+                "Statistical security has to be positive".to_string(),
+            ));
+        }
+        Ok(Self {
+            bucket_size,
+            opened,
+            security,
+        })
+    }
+
+    pub fn get_bucket_size(&self) -> usize {
+        self.bucket_size
+    }
+
+    pub fn get_opened(&self) -> usize {
+        self.opened
+    }
+
+    pub fn get_security(&self) -> usize {
+        self.security
+    }
+
+    /// Statistical security in bits when generating a batch of `n` triples, i.e.,
+    /// `log2(binom(n * bucket_size, bucket_size))`.
+    pub fn security_level(&self, n: usize) -> f64 {
+        Self::log2_binom(n.saturating_mul(self.bucket_size), self.bucket_size)
+    }
+
+    /// The minimum number of triples which have to be generated in one batch to reach the target
+    /// statistical security.
+    pub fn min_batch_size(&self) -> usize {
+        let security = self.security as f64;
+        if self.security_level(1) >= security {
+            return 1;
+        }
+
+        // Exponential search for an upper bound, followed by a binary search
+        let mut high = 2;
+        while self.security_level(high) < security {
+            high *= 2;
+        }
+        let mut low = high / 2;
+        while high - low > 1 {
+            let mid = low + (high - low) / 2;
+            if self.security_level(mid) >= security {
+                high = mid;
+            } else {
+                low = mid;
+            }
+        }
+        high
+    }
+
+    // The protocol generates triples as multiples of 128 bit registers
+    pub(crate) fn min_registers(&self) -> usize {
+        self.min_batch_size().div_ceil(128)
+    }
+
+    pub(crate) fn opened_registers(&self) -> usize {
+        self.opened.div_ceil(128)
+    }
+
+    fn log2_binom(n: usize, k: usize) -> f64 {
+        if k > n {
+            return f64::NEG_INFINITY;
+        }
+        (0..k)
+            .map(|i| ((n - i) as f64).log2() - ((i + 1) as f64).log2())
+            .sum()
+    }
+}
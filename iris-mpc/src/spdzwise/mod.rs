@@ -1,4 +1,7 @@
+pub(crate) mod binary_mac;
+pub(crate) mod cut_and_choose;
 pub(crate) mod protocol;
 pub(crate) mod share;
+pub(crate) mod triple_store;
 pub(crate) mod triples;
 pub(crate) mod vecshare;
@@ -1,11 +1,28 @@
-use super::{share::Share, triples::Triples, vecshare::VecShare};
+use super::{
+    binary_mac::{BinaryVerification, GfShare},
+    cut_and_choose::CutAndChoose,
+    share::Share,
+    triple_store::TripleStore,
+    triples::Triples,
+    vecshare::VecShare,
+};
 use crate::{
-    aby3::utils,
-    prelude::{Aby3, Aby3Share, Bit, Error, MpcTrait, NetworkTrait, Sharable},
-    traits::{binary_trait::BinaryMpcTrait, security::MaliciousAbort, share_trait::VecShareTrait},
+    aby3::{id::PartyID, utils},
+    adder::{self, AdderStrategy},
+    blame::{self, JmpQueue, JmpTranscript},
+    db::roots::{self, DbRoots},
+    dzkp::gf2p64::GF2p64,
+    edabits::{self, EdaBits},
+    fss::msb::MsbVariant,
+    prelude::{Aby3, Aby3Share, Bit, Error, MpcTrait, NetworkTrait, PackedBits, Sharable},
+    traits::{
+        binary_trait::BinaryMpcTrait,
+        security::MaliciousAbort,
+        share_trait::{VecShareTrait, VecSlice},
+    },
     types::ring_element::{RingElement, RingImpl},
 };
-use bytes::{Bytes, BytesMut};
+use bytes::Bytes;
 use itertools::Itertools;
 use num_traits::Zero;
 use plain_reference::IrisCodeArray;
@@ -14,8 +31,8 @@ use rand::{
     Rng, SeedableRng,
 };
 use rand_chacha::ChaCha12Rng;
-use sha2::{digest::Output, Digest, Sha512};
-use std::ops::Mul;
+use sha2::{Digest, Sha512};
+use std::{borrow::Borrow, ops::Mul};
 
 #[allow(type_alias_bounds)]
 pub(crate) type TShare<T: Sharable> = Share<T::VerificationShare>;
@@ -28,10 +45,17 @@ pub struct SpdzWise<N: NetworkTrait, U: Sharable> {
     aby3: Aby3<N>,
     mac_key: Aby3Share<U>,
     verifyqueue: VecShare<U>,  // For arithmetic
-    send_queue_prev: BytesMut, // For binary
-    rcv_queue_next: BytesMut,  // For binary
+    send_queue_next: JmpQueue, // For binary
+    send_queue_prev: JmpQueue, // For binary
+    rcv_queue_next: JmpQueue,  // For binary
     prec_triples: Triples,
     triple_buffer: Triples,
+    triple_store: Option<TripleStore>,
+    cut_and_choose: CutAndChoose,
+    binary_verification: BinaryVerification,
+    msb_variant: MsbVariant,
+    adder_strategy: AdderStrategy,
+    edabits: EdaBits,
 }
 
 impl<N: NetworkTrait, U: Sharable> MaliciousAbort for SpdzWise<N, U> {}
@@ -60,19 +84,235 @@ where
     Aby3Share<U>: Mul<U::Share, Output = Aby3Share<U>>,
 {
     pub fn new(network: N) -> Self {
+        Self::with_binary_verification(network, BinaryVerification::default())
+    }
+
+    /// Creates the protocol with the given method of verifying AND gates of binary circuits.
+    pub fn with_binary_verification(network: N, binary_verification: BinaryVerification) -> Self {
         let aby3 = Aby3::new(network);
-        let send_queue_prev = BytesMut::new();
-        let rcv_queue_next = BytesMut::new();
+        let send_queue_next = JmpQueue::default();
+        let send_queue_prev = JmpQueue::default();
+        let rcv_queue_next = JmpQueue::default();
 
         Self {
             aby3,
             mac_key: Aby3Share::default(),
             verifyqueue: VecShare::default(),
+            send_queue_next,
             send_queue_prev,
             rcv_queue_next,
             prec_triples: Triples::default(),
             triple_buffer: Triples::default(),
+            triple_store: None,
+            cut_and_choose: CutAndChoose::default(),
+            binary_verification,
+            msb_variant: MsbVariant::default(),
+            adder_strategy: AdderStrategy::default(),
+            edabits: EdaBits::default(),
+        }
+    }
+
+    /// Sets the parameters of the cut-and-choose triple generation. Has to be the same for all
+    /// parties.
+    pub fn set_cut_and_choose(&mut self, params: CutAndChoose) {
+        self.cut_and_choose = params;
+    }
+
+    pub fn get_cut_and_choose(&self) -> CutAndChoose {
+        self.cut_and_choose
+    }
+
+    pub fn get_binary_verification(&self) -> BinaryVerification {
+        self.binary_verification
+    }
+
+    /// Selects how the MSB is extracted in [`MpcTrait::get_msb_many`]. Has to be the same for all
+    /// parties. [`MsbVariant::Fss`] is not supported.
+    pub fn set_msb_variant(&mut self, variant: MsbVariant) -> Result<(), Error> {
+        if variant == MsbVariant::Fss {
+            return Err(Error::ConfigError);
+        }
+        self.msb_variant = variant;
+        Ok(())
+    }
+
+    pub fn get_msb_variant(&self) -> MsbVariant {
+        self.msb_variant
+    }
+
+    /// Selects the adder of [`MsbVariant::Adder`]. Has to be the same for all parties.
+    pub fn set_adder_strategy(&mut self, strategy: AdderStrategy) {
+        self.adder_strategy = strategy;
+    }
+
+    pub fn get_adder_strategy(&self) -> AdderStrategy {
+        self.adder_strategy
+    }
+
+    /// Sets the number of threads for the local computation of [`MpcTrait::masked_dot_many`].
+    /// With a single thread (the default), everything is computed on the calling thread.
+    pub fn set_threads(&mut self, threads: usize) -> Result<(), Error> {
+        self.aby3.set_threads(threads)
+    }
+
+    pub fn get_threads(&self) -> usize {
+        self.aby3.get_threads()
+    }
+
+    /// Preprocessing for [`MsbVariant::EdaBits`]: Generates `amount` edaBits for `T`. The AND
+    /// gates of the generation are verified right away.
+    pub async fn precompute_edabits<T: Sharable>(&mut self, amount: usize) -> Result<(), Error>
+    where
+        Standard: Distribution<T::Share>,
+        Aby3Share<T>: Mul<T::Share, Output = Aby3Share<T>>,
+    {
+        self.generate_edabits::<T>(amount).await?;
+        self.verify_and_gates().await
+    }
+
+    // Generates edaBits without verifying the AND gates, which stay in the buffer
+    async fn generate_edabits<T: Sharable>(&mut self, amount: usize) -> Result<(), Error>
+    where
+        Standard: Distribution<T::Share>,
+        Aby3Share<T>: Mul<T::Share, Output = Aby3Share<T>>,
+    {
+        let arithmetic = (0..amount)
+            .map(|_| self.aby3.prf.gen_rand::<T>())
+            .collect_vec();
+        let binary = <Self as BinaryMpcTrait<T, Aby3Share<T>>>::arithmetic_to_binary_many(
+            self,
+            arithmetic.to_owned(),
+        )
+        .await?;
+        self.edabits.extend(arithmetic, binary);
+        Ok(())
+    }
+
+    // Verifies the buffered AND gates right away. In cut-and-choose mode, fresh triples are
+    // generated, such that the precomputed triples of the online phase are left untouched.
+    async fn verify_and_gates(&mut self) -> Result<(), Error> {
+        match self.binary_verification {
+            BinaryVerification::Gf2p64Mac => self.verify_triple_queue_mac().await,
+            BinaryVerification::CutAndChoose => {
+                let online = std::mem::take(&mut self.prec_triples);
+                self.prec_triples = self
+                    .generate_triples::<ChaCha12Rng>(self.triple_buffer.len())
+                    .await?;
+                let res = self.verify_triple_queue::<ChaCha12Rng>().await;
+                self.prec_triples = online;
+                res
+            }
+        }
+    }
+
+    async fn msb_edabits_many<T: Sharable>(
+        &mut self,
+        values: Vec<Aby3Share<T>>,
+    ) -> Result<Vec<Aby3Share<Bit>>, Error>
+    where
+        Standard: Distribution<T::Share>,
+        Aby3Share<T>: Mul<T::Share, Output = Aby3Share<T>>,
+    {
+        let len = values.len();
+        let available = self.edabits.len::<T>();
+        if available < len {
+            tracing::warn!(
+                "Party {}: Not enough edaBits, generating them inline",
+                self.get_id()
+            );
+            // The AND gates of the generation are verified together with the online ones, using
+            // the precomputed triples (see `msb_and_gates`). Opening x + r does not depend on them.
+            self.generate_edabits::<T>(len - available).await?;
+        }
+        let (r, r_bits) = self.edabits.take::<T>(len)?;
+        let masked = values.into_iter().zip(r).map(|(v, r_)| v + r_).collect();
+        let c = self.aby3_jmp_open_many::<T>(masked).await?;
+        let id = PartyID::try_from(self.get_id())?;
+        edabits::msb_from_edabits::<T, _>(self, id, c, r_bits).await
+    }
+
+    /// Sets the store from which the AND triples of the offline phase (see
+    /// [`Self::store_triples`]) are loaded when precomputing triples.
+    pub fn set_triple_store(&mut self, store: TripleStore) -> Result<(), Error> {
+        if store.get_party_id() != self.get_id() {
+            return Err(Error::IdError(store.get_party_id()));
+        }
+        self.triple_store = Some(store);
+        Ok(())
+    }
+
+    /// Offline phase: Generates at least `amount` verified AND triples and persists them as a new
+    /// batch in `store`. Returns the id of the batch, which is the same for all parties.
+    pub async fn store_triples(
+        &mut self,
+        amount: usize,
+        store: &TripleStore,
+    ) -> Result<String, Error> {
+        if store.get_party_id() != self.get_id() {
+            return Err(Error::IdError(store.get_party_id()));
+        }
+        let batch_id = self.batch_id().await?;
+        let triples = self
+            .generate_triples::<ChaCha12Rng>(amount.div_ceil(128))
+            .await?;
+        store.write(&batch_id, triples)?;
+        Ok(batch_id)
+    }
+
+    // The id only has to be consistent between the parties, so no commitments are required
+    async fn batch_id(&mut self) -> Result<String, Error> {
+        let rand = ChaCha12Rng::from_entropy().gen::<[u8; 16]>();
+        let rands = self
+            .aby3
+            .network
+            .broadcast(Bytes::from(rand.to_vec()))
+            .await?;
+
+        let mut hasher = Sha512::new();
+        for rand in rands {
+            hasher.update(rand);
         }
+        let digest = hasher.finalize();
+        Ok(digest[..16].iter().map(|b| format!("{:02x}", b)).collect())
+    }
+
+    // Online phase: Loads stored batches until more than amount (u128) triples are present. The
+    // parties agree on each batch before consuming it.
+    async fn load_triples(&mut self, amount: usize) -> Result<(), Error> {
+        let store = match self.triple_store.to_owned() {
+            Some(store) => store,
+            None => return Ok(()),
+        };
+
+        while self.prec_triples.len() <= amount {
+            let batch_id = store.list()?.into_iter().next();
+            let msg = bincode::serialize(&batch_id).map_err(|_| Error::SerializationError)?;
+            let rcv = self.aby3.network.broadcast(Bytes::from(msg)).await?;
+            for msg in rcv {
+                let other: Option<String> =
+                    bincode::deserialize(&msg).map_err(|_| Error::SerializationError)?;
+                if other != batch_id {
+                    return Err(Error::ValueError(
+                        "Parties disagree on the stored triples".to_string(),
+                    ));
+                }
+            }
+
+            match batch_id {
+                Some(batch_id) => {
+                    let triples = store.take(&batch_id)?;
+                    self.prec_triples.extend(triples);
+                }
+                None => {
+                    tracing::warn!(
+                        "Party {}: Not enough stored triples, generating them inline",
+                        self.get_id()
+                    );
+                    break;
+                }
+            }
+        }
+        Ok(())
     }
 
     pub fn get_r(&self) -> Aby3Share<U> {
@@ -88,6 +328,12 @@ where
         self.aby3.network.get_id()
     }
 
+    #[cfg(test)]
+    pub(crate) fn tamper_and_triple(&mut self) {
+        let id = self.get_id();
+        self.triple_buffer.tamper_c(id);
+    }
+
     async fn hash_based_zero_verify(&mut self, w: Aby3Share<U>) -> Result<(), Error> {
         let (wa, wb) = w.get_ab();
         let w_neg = -wa.to_owned() - &wb;
@@ -160,30 +406,44 @@ where
         self.hash_based_zero_verify(zero).await
     }
 
+    // Starts the next round of JMPs (see [`JmpQueue`])
+    fn jmp_step(&mut self) {
+        self.send_queue_next.end_step();
+        self.send_queue_prev.end_step();
+        self.rcv_queue_next.end_step();
+    }
+
     #[inline(always)]
     async fn jmp_send<T: Sharable>(&mut self, send: T::Share) -> Result<(), Error> {
+        send.to_owned()
+            .add_to_bytes(self.send_queue_next.bytes_mut());
         utils::send_value_next(&mut self.aby3.network, send).await
     }
 
     #[inline(always)]
     fn jmp_buffer<T: Sharable>(&mut self, buffer: T::Share) {
-        buffer.add_to_bytes(&mut self.send_queue_prev);
+        buffer.add_to_bytes(self.send_queue_prev.bytes_mut());
     }
 
     #[inline(always)]
     async fn jmp_send_many<T: Sharable>(&mut self, send: &[T::Share]) -> Result<(), Error> {
+        for value in send {
+            value.add_to_bytes(self.send_queue_next.bytes_mut());
+        }
         utils::send_vec_next(&mut self.aby3.network, send).await
     }
 
     fn jmp_buffer_many<T: Sharable>(&mut self, buffer: &[T::Share]) {
         for value in buffer {
-            value.add_to_bytes(&mut self.send_queue_prev);
+            value.add_to_bytes(self.send_queue_prev.bytes_mut());
         }
     }
 
     async fn jmp_receive<T: Sharable>(&mut self) -> Result<T::Share, Error> {
         let value: T::Share = utils::receive_value_prev(&mut self.aby3.network).await?;
-        value.to_owned().add_to_bytes(&mut self.rcv_queue_next);
+        value
+            .to_owned()
+            .add_to_bytes(self.rcv_queue_next.bytes_mut());
         Ok(value)
     }
 
@@ -191,7 +451,7 @@ where
         let values: Vec<T::Share> = utils::receive_vec_prev(&mut self.aby3.network, len).await?;
 
         for value in values.iter() {
-            value.add_to_bytes(&mut self.rcv_queue_next);
+            value.add_to_bytes(self.rcv_queue_next.bytes_mut());
         }
 
         Ok(values)
@@ -202,6 +462,7 @@ where
         send: T::Share,
         buffer: T::Share,
     ) -> Result<T::Share, Error> {
+        self.jmp_step();
         self.jmp_buffer::<T>(buffer);
         self.jmp_send::<T>(send).await?;
         self.jmp_receive::<T>().await
@@ -213,36 +474,24 @@ where
         buffer: &[T::Share],
     ) -> Result<Vec<T::Share>, Error> {
         let len = send.len();
+        self.jmp_step();
         self.jmp_buffer_many::<T>(buffer);
         self.jmp_send_many::<T>(send).await?;
         self.jmp_receive_many::<T>(len).await
     }
 
-    fn clear_and_hash(data: &mut BytesMut) -> Output<Sha512> {
-        let mut swap = BytesMut::new();
-        std::mem::swap(&mut swap, data);
-        let bytes = swap.freeze();
-        let mut hasher = Sha512::new();
-        hasher.update(bytes);
-        hasher.finalize()
-    }
-
     async fn jmp_verify(&mut self) -> Result<(), Error> {
-        let send_prev = Self::clear_and_hash(&mut self.send_queue_prev);
-        let hash_next = Self::clear_and_hash(&mut self.rcv_queue_next);
-
-        self.aby3
-            .network
-            .send_prev_id(Bytes::from(send_prev.to_vec()))
-            .await?;
-
-        let rcv_next = self.aby3.network.receive_next_id().await?;
+        let id = self.get_id();
+        let next_id = (id + 1) % 3;
+        let prev_id = (id + 2) % 3;
 
-        if rcv_next.as_ref() != hash_next.as_slice() {
-            return Err(Error::JmpVerifyError);
-        }
+        // The values are received from prev, the hash from next
+        let mut transcript = JmpTranscript::default();
+        transcript.sent_values[next_id] = Some(self.send_queue_next.finish());
+        transcript.sent_hashes[prev_id] = Some(self.send_queue_prev.finish());
+        transcript.received_values[next_id] = Some(self.rcv_queue_next.finish());
 
-        Ok(())
+        blame::jmp_verify(&mut self.aby3.network, &transcript).await
     }
 
     async fn coin<R: Rng + SeedableRng>(&mut self) -> Result<R::Seed, Error>
@@ -262,7 +511,7 @@ where
         for (s1, (s2, s3)) in seed1
             .as_mut()
             .iter_mut()
-            .zip(seed2.as_ref().iter().zip(seed3.into_iter()))
+            .zip(seed2.as_ref().iter().zip(seed3))
         {
             *s1 ^= s3.0 ^ s2;
         }
@@ -329,52 +578,83 @@ where
         R::Seed: AsRef<[u8]>,
     {
         // https://www.ieee-security.org/TC/SP2017/papers/96.pdf
-        // Assumes B=2 buckets (Secure when generating 2^20 triples)
-        const N: usize = 1usize << (20 - 7); // # of 128 bit registers, corresponds to 2^20 AND GATES
-                                             // Here we have C=128 which is significantly more than required for security
-        let n = std::cmp::max(num, N);
-        let a = (0..2 * n + 1)
+        let params = self.cut_and_choose;
+        let buckets = params.get_bucket_size();
+        let n = std::cmp::max(num, params.min_registers()); // # of 128 bit registers
+        let num_open = params.opened_registers();
+        let sacrifice = (buckets - 1) * n;
+        let total = buckets * n + num_open;
+
+        let a = (0..total)
             .map(|_| self.aby3.prf.gen_rand::<u128>())
             .collect::<Vec<_>>();
-        let b = (0..2 * n + 1)
+        let b = (0..total)
             .map(|_| self.aby3.prf.gen_rand::<u128>())
             .collect::<Vec<_>>();
 
         let c = self.aby3_and_many::<u128>(&a, &b).await?;
 
         // Split to buckets
-        let (a_triple, mut a_sacrifice) = a.split_at(n);
-        let (b_triple, mut b_sacrifice) = b.split_at(n);
-        let (c_triple, mut c_sacrifice) = c.split_at(n);
-
-        // permute second bucket
+        let (a_triple, a_sacrifice) = a.split_at(n);
+        let (b_triple, b_sacrifice) = b.split_at(n);
+        let (c_triple, c_sacrifice) = c.split_at(n);
+        let mut a_sacrifice = a_sacrifice.to_vec();
+        let mut b_sacrifice = b_sacrifice.to_vec();
+        let mut c_sacrifice = c_sacrifice.to_vec();
+
+        // permute the sacrifice buckets, which randomly assigns them to the triples
         self.permute::<R>(&mut a_sacrifice, &mut b_sacrifice, &mut c_sacrifice)
             .await?;
 
-        // Open 128 bit triples
-        let a_open = a_sacrifice.pop().expect("Enough triples generated");
-        let b_open = b_sacrifice.pop().expect("Enough triples generated");
-        let c_open = c_sacrifice.pop().expect("Enough triples generated");
-
-        let opened = self
-            .aby3_open_bin_many::<u128>(vec![a_open, b_open, c_open])
-            .await?;
-        if opened[0].to_owned() & &opened[1] != opened[2] {
-            return Err(Error::VerifyError);
+        // Open C triples (as multiples of 128 bit)
+        let a_open = a_sacrifice.split_off(sacrifice);
+        let b_open = b_sacrifice.split_off(sacrifice);
+        let c_open = c_sacrifice.split_off(sacrifice);
+
+        // The opening is verified, such that an inconsistent share is attributed and all parties
+        // check the same triples
+        let open = [a_open, b_open, c_open].concat();
+        let opened = self.aby3_jmp_open_bin_many::<u128>(open).await?;
+        for i in 0..num_open {
+            if opened[i].to_owned() & &opened[num_open + i] != opened[2 * num_open + i] {
+                return Err(Error::VerifyError);
+            }
         }
 
-        // Check each element in first bucket using sacrifice bucket
+        // Check each element in first bucket using each of the B-1 sacrifice buckets
+        let a_check = a_triple
+            .iter()
+            .cycle()
+            .take(sacrifice)
+            .cloned()
+            .collect_vec();
+        let b_check = b_triple
+            .iter()
+            .cycle()
+            .take(sacrifice)
+            .cloned()
+            .collect_vec();
+        let c_check = c_triple
+            .iter()
+            .cycle()
+            .take(sacrifice)
+            .cloned()
+            .collect_vec();
         self.verify_triples(
-            &a_triple,
-            &b_triple,
-            &c_triple,
+            &a_check,
+            &b_check,
+            &c_check,
             a_sacrifice,
             b_sacrifice,
             c_sacrifice,
         )
         .await?;
 
-        Ok(Triples::new(a_triple, b_triple, c_triple))
+        Ok(Triples::new(
+            a_triple.to_vec(),
+            b_triple.to_vec(),
+            c_triple.to_vec(),
+        ))
     }
 
     async fn verify_triples(
@@ -490,7 +770,7 @@ where
             return Ok(());
         }
         let (a, b, c) = self.triple_buffer.get_all();
-        let len_ = std::cmp::max(len, 1usize << (20 - 7)); // Permute at least 2^(20) triples, as required for security
+        let len_ = std::cmp::max(len, self.cut_and_choose.min_registers()); // Permute enough triples, as required for security
         let (mut x, mut y, mut z) = self.prec_triples.get(len_)?;
 
         // Permute the precomputed triples again
@@ -508,6 +788,113 @@ where
         Ok(())
     }
 
+    // Checks the buffered AND triples (a, b, c) with MACs in GF(2^64), like SPDZ-wise checks
+    // multiplications: For a random key alpha, we MAC both inputs and compute the MAC of c as the
+    // product of the MAC of a with b. Only then, alpha and random coefficients rho are opened, and
+    // the sum of rho * (mac_x - alpha * x) over all a_k, b_k and c_k is zero if all c_k = a_k * b_k.
+    // An additive error on any c_k or on any reshared MAC is only undetected if the adversary
+    // guesses alpha or the coefficients, independently of the secret bits.
+    async fn verify_triple_queue_mac(&mut self) -> Result<(), Error> {
+        let len = self.triple_buffer.len();
+        if len == 0 {
+            return Ok(());
+        }
+        let (a, b, c) = self.triple_buffer.get_all();
+        let alpha = GfShare::random(&mut self.aby3.prf);
+
+        // MAC a and b
+        let mut mac_ab = Vec::with_capacity(2 * len * 128);
+        for x in a.iter().chain(b.iter()) {
+            for i in 0..128 {
+                let mac = GfShare::lift_bit(x, i).mul_local(&alpha)
+                    + GfShare::zero_share(&mut self.aby3.prf);
+                mac_ab.push(GfShare::to_ring(mac));
+            }
+        }
+        let mac_ab = self.gf_reshare(mac_ab).await?;
+        let (mac_a, mac_b) = mac_ab.split_at(len * 128);
+
+        // MAC c as the product of the MAC of a with b
+        let mut mac_c = Vec::with_capacity(len * 128);
+        let mut macs = mac_a.iter();
+        for b_ in b.iter() {
+            for i in 0..128 {
+                let mac = macs
+                    .next()
+                    .expect("Same size")
+                    .mul_local(&GfShare::lift_bit(b_, i))
+                    + GfShare::zero_share(&mut self.aby3.prf);
+                mac_c.push(GfShare::to_ring(mac));
+            }
+        }
+        let mac_c = self.gf_reshare(mac_c).await?;
+
+        // Alpha and the coefficients are opened after the MACs are fixed
+        let alpha_c = self
+            .jmp_send_receive_many::<u64>(
+                &[GfShare::to_ring(alpha.b)],
+                &[GfShare::to_ring(alpha.a)],
+            )
+            .await?;
+        let alpha = alpha.a + alpha.b + GfShare::from_ring(alpha_c[0].to_owned());
+        let seed = self.coin::<ChaCha12Rng>().await?;
+        self.jmp_verify().await?;
+        let mut rng = ChaCha12Rng::from_seed(seed);
+
+        // Characteristic 2, so subtracting is adding
+        let mut w = GfShare::zero();
+        let mut macs = mac_a.iter().chain(mac_b.iter()).chain(mac_c.iter());
+        for x in a.iter().chain(b.iter()).chain(c.iter()) {
+            for i in 0..128 {
+                let rho = GF2p64::random(&mut rng);
+                let mac = *macs.next().expect("Same size") + GfShare::lift_bit(x, i) * alpha;
+                w += mac * rho;
+            }
+        }
+        self.gf_zero_verify(w).await
+    }
+
+    // Reshares the additive shares of products in GF(2^64) to replicated shares
+    async fn gf_reshare(&mut self, values: Vec<RingElement<u64>>) -> Result<Vec<GfShare>, Error> {
+        let prev = utils::send_slice_and_receive_iter(&mut self.aby3.network, &values).await?;
+        Ok(values
+            .into_iter()
+            .zip(prev)
+            .map(|(own, prev)| GfShare::new(GfShare::from_ring(own), GfShare::from_ring(prev)))
+            .collect())
+    }
+
+    async fn gf_zero_verify(&mut self, w: GfShare) -> Result<(), Error> {
+        let wa = w.a.get();
+        let wb = w.b.get();
+        let w_neg = wa ^ wb;
+
+        let mut hasher = Sha512::new();
+        let order = match self.get_id() {
+            0 => [wa, w_neg, wb],
+            1 => [wb, wa, w_neg],
+            2 => [w_neg, wb, wa],
+            _ => unreachable!(),
+        };
+        for w in order {
+            hasher.update(w.to_le_bytes());
+        }
+        let digest = hasher.finalize();
+
+        let hashes = self
+            .aby3
+            .network
+            .broadcast(Bytes::from(digest.to_vec()))
+            .await?;
+        debug_assert_eq!(hashes.len(), 3);
+
+        if hashes[0] != hashes[1] || hashes[0] != hashes[2] {
+            Err(Error::VerifyError)
+        } else {
+            Ok(())
+        }
+    }
+
     async fn aby3_and<T: Sharable>(
         &mut self,
         a: Aby3Share<T>,
@@ -534,7 +921,7 @@ where
         self.aby3.and_many(a, b).await
     }
 
-    async fn aby3_open_bin_many<T: Sharable>(
+    async fn aby3_jmp_open_many<T: Sharable>(
         &mut self,
         shares: Vec<Aby3Share<T>>,
     ) -> Result<Vec<T::Share>, Error>
@@ -542,12 +929,25 @@ where
         Standard: Distribution<T::Share>,
         Aby3Share<T>: Mul<T::Share, Output = Aby3Share<T>>,
     {
-        let shares_b = shares.iter().map(|s| &s.b);
-        let shares_c = utils::send_iter_and_receive_iter(&mut self.aby3.network, shares_b).await?;
+        let len = shares.len();
+        let mut shares_a = Vec::with_capacity(len);
+        let mut shares_b = Vec::with_capacity(len);
+
+        for share in shares.iter().cloned() {
+            let (a, b) = share.get_ab();
+            shares_a.push(a);
+            shares_b.push(b);
+        }
+
+        let shares_c = self
+            .jmp_send_receive_many::<T>(&shares_b, &shares_a)
+            .await?;
+        self.jmp_verify().await?;
+
         let res = shares
             .iter()
             .zip(shares_c)
-            .map(|(s, c)| c ^ &s.a ^ &s.b)
+            .map(|(s, c)| c + &s.a + &s.b)
             .collect();
         Ok(res)
     }
@@ -579,31 +979,12 @@ where
 
         let res = shares
             .iter()
-            .zip(shares_c.into_iter())
-            .map(|(s, c)| (c ^ &s.a ^ &s.b))
+            .zip(shares_c)
+            .map(|(s, c)| c ^ &s.a ^ &s.b)
             .collect();
         Ok(res)
     }
 
-    fn pack<T: Sharable>(&self, a: Vec<Aby3Share<Bit>>) -> Vec<Aby3Share<T>> {
-        let outlen = (a.len() + T::Share::K - 1) / T::Share::K;
-        let mut out = Vec::with_capacity(outlen);
-
-        for a_ in a.chunks(T::Share::K) {
-            let mut share_a = T::Share::zero();
-            let mut share_b = T::Share::zero();
-            for (i, bit) in a_.iter().enumerate() {
-                let (bit_a, bit_b) = bit.to_owned().get_ab();
-                share_a |= T::Share::from(bit_a.convert().convert()) << (i as u32);
-                share_b |= T::Share::from(bit_b.convert().convert()) << (i as u32);
-            }
-            let share = Aby3Share::new(share_a, share_b);
-            out.push(share);
-        }
-
-        out
-    }
-
     reduce_or!(
         [u128, u64, reduce_or_u128, reduce_or_u64],
         [u64, u32, reduce_or_u64, reduce_or_u32],
@@ -639,89 +1020,50 @@ where
         Ok(decomp[0].to_owned())
     }
 
-    async fn transposed_pack_and(
-        &mut self,
-        x1: Vec<Vec<Aby3Share<u128>>>,
-        x2: Vec<Vec<Aby3Share<u128>>>,
-    ) -> Result<Vec<Vec<Aby3Share<u128>>>, Error> {
-        let len = x1.len();
-        debug_assert_eq!(len, x2.len());
-        let inner_len = x1[0].len();
-
-        let x3 = <Self as BinaryMpcTrait<u128, Aby3Share<u128>>>::and_many(
-            self,
-            &x1.into_iter().flatten().collect_vec(),
-            &x2.into_iter().flatten().collect_vec(),
-        )
-        .await?;
-
-        Ok(x3.chunks(inner_len))
-    }
-
+    // Computes the MSBs with the adder circuit selected by the adder strategy
     async fn msb_adder_many<T: Sharable>(
         &mut self,
-        x1: Vec<Aby3Share<T>>,
-        x2: Vec<Aby3Share<T>>,
-        x3: Vec<Aby3Share<T>>,
-    ) -> Result<Vec<Aby3Share<Bit>>, Error> {
-        let len = x1.len();
-        if len != x2.len() || len != x3.len() {
-            return Err(Error::InvalidSizeError);
-        }
+        a: Vec<Aby3Share<T>>,
+    ) -> Result<PackedBits<Aby3Share<Bit>>, Error>
+    where
+        Standard: Distribution<T::Share>,
+        Aby3Share<T>: Mul<T::Share, Output = Aby3Share<T>>,
+    {
+        let len = a.len();
+        let mut x1 = Vec::with_capacity(len);
+        let mut x2 = Vec::with_capacity(len);
+        let mut x3 = Vec::with_capacity(len);
 
-        let x1 = utils::transpose_pack_u128::<T>(x1);
-        let x2 = utils::transpose_pack_u128::<T>(x2);
-        let mut x3 = utils::transpose_pack_u128::<T>(x3);
-
-        // Full adder to get 2 * c and s
-        let mut x2x3 = utils::transposed_pack_xor(&x2, &x3);
-        let s = utils::transposed_pack_xor(&x1, &x2x3);
-        let mut x1x3 = utils::transposed_pack_xor(&x1, &x3);
-        // 2 * c
-        x1x3.pop().expect("Enough elements present");
-        x2x3.pop().expect("Enough elements present");
-        x3.pop().expect("Enough elements present");
-        let mut c = self.transposed_pack_and(x1x3, x2x3).await?;
-        utils::transposed_pack_xor_assign(&mut c, &x3);
-
-        // Add 2c + s via a ripple carry adder
-        // LSB of c is 0
-        // First round: half adder can be skipped due to LSB of c being 0
-        let mut a = s;
-        let mut b = c;
-
-        // First full adder
-        let mut c =
-            <Self as BinaryMpcTrait<u128, Aby3Share<u128>>>::and_many(self, &a[1], &b[0]).await?;
-
-        // For last round
-        let a_msb = a.pop().expect("Enough elements present");
-        let b_msb = b.pop().expect("Enough elements present");
-
-        // 2 -> k-1
-        for (a_, b_) in a.into_iter().skip(2).zip(b.into_iter().skip(1)) {
-            let tmp_a =
-                <Self as BinaryMpcTrait<u128, Aby3Share<u128>>>::xor_many(a_, c.to_owned())?;
-            let tmp_b =
-                <Self as BinaryMpcTrait<u128, Aby3Share<u128>>>::xor_many(b_, c.to_owned())?;
-            let tmp_c =
-                <Self as BinaryMpcTrait<u128, Aby3Share<u128>>>::and_many(self, &tmp_a, &tmp_b)
-                    .await?;
-            c = <Self as BinaryMpcTrait<u128, Aby3Share<u128>>>::xor_many(tmp_c, c)?;
+        for x_ in a {
+            let (x1_, x2_, x3_) = self.aby3.a2b_pre(x_);
+            x1.push(x1_);
+            x2.push(x2_);
+            x3.push(x3_);
         }
 
-        let res = <Self as BinaryMpcTrait<u128, Aby3Share<u128>>>::xor_many(a_msb, b_msb)?;
-        let res: Vec<Aby3Share<u128>> =
-            <Self as BinaryMpcTrait<u128, Aby3Share<u128>>>::xor_many(res, c)?;
-
-        // Extract bits for outputs
-        let mut res = res
-            .into_iter()
-            .flat_map(|t| t.to_bits())
-            .collect::<Vec<_>>();
-        res.resize(len, Aby3Share::default());
+        let res = match self.adder_strategy {
+            AdderStrategy::KoggeStone => {
+                let sum =
+                    <Self as BinaryMpcTrait<T, Aby3Share<T>>>::binary_add_3_many(self, x1, x2, x3)
+                        .await?;
+                utils::transpose_pack_u128::<T>(sum)
+                    .pop()
+                    .expect("Enough elements present")
+            }
+            strategy => {
+                adder::msb_many(
+                    self,
+                    strategy,
+                    utils::transpose_pack_u128::<T>(x1),
+                    utils::transpose_pack_u128::<T>(x2),
+                    utils::transpose_pack_u128::<T>(x3),
+                )
+                .await?
+            }
+        };
 
-        Ok(res)
+        // The outputs are already bitsliced, the padding are shares of zero
+        Ok(PackedBits::from_words(res, len))
     }
 }
 
@@ -750,8 +1092,16 @@ where
     }
 
     async fn precompute_and_triples(&mut self, amount: usize) -> Result<(), Error> {
+        if self.binary_verification == BinaryVerification::Gf2p64Mac {
+            // AND gates are checked with MACs instead of sacrificing triples
+            return Ok(());
+        }
+        let amount = amount.div_ceil(128); // We allow precomputing as multiples of 128 bit
+        if self.prec_triples.len() <= amount {
+            // Prefer the triples of the offline phase
+            self.load_triples(amount).await?;
+        }
         let ands = self.prec_triples.len();
-        let amount = (amount + 127) / 128; // We allow precomputing as multiples of 128 bit
         if ands <= amount {
             // We have to precompute AND gates
             let prec_triples = self.generate_triples::<ChaCha12Rng>(amount - ands).await?;
@@ -792,6 +1142,10 @@ where
         >>::print_connection_stats(&self.aby3, out)
     }
 
+    async fn verify_db_roots(&mut self, roots: &DbRoots) -> Result<(), Error> {
+        roots::exchange_db_roots(&mut self.aby3.network, roots).await
+    }
+
     async fn input(&mut self, input: Option<T>, id: usize) -> Result<TShare<T>, Error> {
         let input = input.map(|i| T::to_verificationshare(i));
 
@@ -817,6 +1171,31 @@ where
         Ok(share)
     }
 
+    async fn input_many(
+        &mut self,
+        input: Option<Vec<T>>,
+        len: usize,
+        id: usize,
+    ) -> Result<VecTShare<T>, Error> {
+        let input = input.map(|i| i.into_iter().map(T::to_verificationshare).collect());
+
+        let values = <_ as MpcTrait<
+            T::VerificationShare,
+            Aby3Share<T::VerificationShare>,
+            Aby3Share<Bit>,
+        >>::input_many(&mut self.aby3, input, len, id)
+        .await?;
+
+        let r = vec![self.get_r(); len];
+        let macs = self.aby3.mul_many(values.to_owned(), r).await?;
+
+        let shares = VecShare::new(values, macs);
+
+        self.verifyqueue.extend(shares.to_owned());
+
+        Ok(shares)
+    }
+
     #[cfg(test)]
     async fn input_all(&mut self, input: T) -> Result<Vec<TShare<T>>, Error> {
         // Since this is only for testing we perform a bad one
@@ -909,30 +1288,34 @@ where
         Ok((share.a ^ share.b ^ c).convert().convert())
     }
 
-    async fn open_bit_many(&mut self, shares: Vec<Aby3Share<Bit>>) -> Result<Vec<bool>, Error> {
+    async fn open_bit_many(
+        &mut self,
+        shares: PackedBits<Aby3Share<Bit>>,
+    ) -> Result<Vec<bool>, Error> {
         self.jmp_verify().await?;
 
-        let len = shares.len();
-        let mut shares_a = Vec::with_capacity(len);
-        let mut shares_b = Vec::with_capacity(len);
+        // Open the packed words
+        let words = shares.words();
+        let mut shares_a = Vec::with_capacity(words.len());
+        let mut shares_b = Vec::with_capacity(words.len());
 
-        for share in shares.iter().cloned() {
+        for share in words.iter().cloned() {
             let (a, b) = share.get_ab();
             shares_a.push(a);
             shares_b.push(b);
         }
 
         let shares_c = self
-            .jmp_send_receive_many::<Bit>(&shares_b, &shares_a)
+            .jmp_send_receive_many::<u128>(&shares_b, &shares_a)
             .await?;
         self.jmp_verify().await?;
 
-        let res = shares
+        let res = words
             .iter()
-            .zip(shares_c.into_iter())
-            .map(|(s, c)| (c ^ &s.a ^ &s.b).convert().convert())
+            .zip(shares_c)
+            .map(|(s, c)| (c ^ &s.a ^ &s.b).convert())
             .collect();
-        Ok(res)
+        Ok(PackedBits::open_words(res, shares.len()))
     }
 
     fn add(&self, a: TShare<T>, b: TShare<T>) -> TShare<T> {
@@ -1126,7 +1509,7 @@ where
 
         let res = shares_a
             .into_iter()
-            .zip(mac_shares_a.into_iter())
+            .zip(mac_shares_a)
             .zip(shares_b.zip(mac_shares_b))
             .map(|((a_val, a_mac), (b_val, b_mac))| {
                 let share = Aby3Share::new(a_val, b_val);
@@ -1144,38 +1527,74 @@ where
         Ok(res)
     }
 
-    async fn masked_dot_many(
+    async fn mul_many(&mut self, a: VecTShare<T>, b: VecTShare<T>) -> Result<VecTShare<T>, Error> {
+        let len = a.len();
+        if len != b.len() {
+            return Err(Error::InvalidSizeError);
+        }
+
+        // Values and MACs are multiplied with the value of b in the same round
+        let (a_v, a_m) = a.get();
+        let b_v = b.get_values();
+        let lhs = a_v.into_iter().chain(a_m).collect();
+        let rhs = b_v.iter().chain(b_v.iter()).cloned().collect();
+
+        let mut values = self.aby3.mul_many(lhs, rhs).await?;
+        let macs = values.split_off(len);
+        let res = VecTShare::<T>::new(values, macs);
+
+        // Add to verification queue
+        self.verifyqueue.extend(res.to_owned());
+
+        Ok(res)
+    }
+
+    async fn masked_dot_many<D>(
         &mut self,
         a: &VecTShare<T>,
-        b: &[VecTShare<T>],
+        b: &[D],
         masks: &[IrisCodeArray],
-    ) -> Result<Vec<TShare<T>>, Error> {
+    ) -> Result<Vec<TShare<T>>, Error>
+    where
+        D: Borrow<VecSlice<TShare<T>>> + Sync,
+    {
         let len = b.len();
         if a.len() != IrisCodeArray::IRIS_CODE_SIZE {
             return Err(Error::InvalidSizeError);
         }
 
-        let mut shares_a = Vec::with_capacity(len);
-        let mut mac_shares_a = Vec::with_capacity(len);
-
-        for (b, mask) in b.iter().zip(masks.iter()) {
-            let mut rand = self.aby3.prf.gen_zero_share::<T::VerificationShare>();
-            let mut rand2 = self.aby3.prf.gen_zero_share::<T::VerificationShare>();
+        if len != masks.len() || b.iter().any(|b_| b_.borrow().len() != a.len()) {
+            return Err(Error::InvalidSizeError);
+        }
 
-            for (bit, ((a_, b_), am)) in mask
-                .bits()
-                .zip(a.values.iter().zip(b.values.iter()).zip(a.macs.iter()))
-            {
-                // only aggregate if mask is set
-                if bit {
-                    rand += (a_.clone() * b_).a;
-                    rand2 += (am.clone() * b_).a;
-                    // TODO: check if we can allow ref * ref ops in RingImpl
+        // The randomness is drawn in order, the local dot products are computed in parallel
+        let rands = (0..len)
+            .map(|_| {
+                let rand = self.aby3.prf.gen_zero_share::<T::VerificationShare>();
+                let rand2 = self.aby3.prf.gen_zero_share::<T::VerificationShare>();
+                (rand, rand2)
+            })
+            .collect_vec();
+        let (shares_a, mac_shares_a): (Vec<_>, Vec<_>) =
+            utils::par_map(self.aby3.get_pool(), len, |i| {
+                let (mut rand, mut rand2) = rands[i].to_owned();
+                for (bit, ((a_, b_), am)) in masks[i].bits().zip(
+                    a.values
+                        .iter()
+                        .zip(b[i].borrow().values.iter())
+                        .zip(a.macs.iter()),
+                ) {
+                    // only aggregate if mask is set
+                    if bit {
+                        rand += (a_.clone() * b_).a;
+                        rand2 += (am.clone() * b_).a;
+                        // TODO: check if we can allow ref * ref ops in RingImpl
+                    }
                 }
-            }
-            shares_a.push(rand);
-            mac_shares_a.push(rand2);
-        }
+                (rand, rand2)
+            })
+            .into_iter()
+            .unzip();
 
         // Network: reshare
         let (shares_b, mac_shares_b) =
@@ -1184,7 +1603,7 @@ where
 
         let res = shares_a
             .into_iter()
-            .zip(mac_shares_a.into_iter())
+            .zip(mac_shares_a)
             .zip(shares_b.zip(mac_shares_b))
             .map(|((a_val, a_mac), (b_val, b_mac))| {
                 let share = Aby3Share::new(a_val, b_val);
@@ -1208,11 +1627,18 @@ where
 
         // protocol switch
         let value = Aby3Share::<T>::from_verificationtype(a.get_value());
-        let bits = self.arithmetic_to_binary(value).await?;
-        Ok(bits.get_msb())
+        if self.msb_variant == MsbVariant::EdaBits {
+            let mut res = self.msb_edabits_many(vec![value]).await?;
+            return Ok(res.pop().expect("One output"));
+        }
+        let res = self.msb_adder_many(vec![value]).await?;
+        Ok(res.get_at(0))
     }
 
-    async fn get_msb_many(&mut self, a: Vec<TShare<T>>) -> Result<Vec<Aby3Share<Bit>>, Error> {
+    async fn get_msb_many(
+        &mut self,
+        a: Vec<TShare<T>>,
+    ) -> Result<PackedBits<Aby3Share<Bit>>, Error> {
         self.verifyqueue.reserve(a.len());
         for a_ in a.iter().cloned() {
             self.verifyqueue.push(a_);
@@ -1225,25 +1651,19 @@ where
             .map(|a| Aby3Share::<T>::from_verificationtype(a.get_value()))
             .collect::<Vec<_>>();
 
-        // TODO one can switch adder here
-
-        // let bits = self.arithmetic_to_binary_many(values).await?;
-        // let res = bits.into_iter().map(|a| a.get_msb()).collect();
-        // Ok(res)
-
-        let len = values.len();
-        let mut x1 = Vec::with_capacity(len);
-        let mut x2 = Vec::with_capacity(len);
-        let mut x3 = Vec::with_capacity(len);
-
-        for x_ in values {
-            let (x1_, x2_, x3_) = self.aby3.a2b_pre(x_);
-            x1.push(x1_);
-            x2.push(x2_);
-            x3.push(x3_);
+        if self.msb_variant == MsbVariant::EdaBits {
+            return Ok(self.msb_edabits_many(values).await?.into_iter().collect());
         }
 
-        self.msb_adder_many(x1, x2, x3).await
+        self.msb_adder_many(values).await
+    }
+
+    fn msb_and_gates(&self, amount: usize) -> usize {
+        let per_value = match self.msb_variant {
+            MsbVariant::EdaBits => edabits::msb_and_gates(T::Share::K),
+            _ => self.adder_strategy.msb_and_gates(T::Share::K),
+        };
+        amount * per_value
     }
 
     async fn binary_or(
@@ -1256,17 +1676,20 @@ where
 
     async fn reduce_binary_or(
         &mut self,
-        a: Vec<Aby3Share<Bit>>,
+        a: PackedBits<Aby3Share<Bit>>,
         chunk_size: usize,
     ) -> Result<Aby3Share<Bit>, Error> {
-        let packed = self.pack(a);
+        let packed = a.into_words();
         let reduced = utils::or_tree::<u128, _, _>(self, packed, chunk_size).await?;
         self.reduce_or_u128(reduced).await
     }
 
     async fn verify(&mut self) -> Result<(), Error> {
         self.verify_macs().await?;
-        self.verify_triple_queue::<ChaCha12Rng>().await
+        match self.binary_verification {
+            BinaryVerification::CutAndChoose => self.verify_triple_queue::<ChaCha12Rng>().await,
+            BinaryVerification::Gf2p64Mac => self.verify_triple_queue_mac().await,
+        }
     }
 }
 
@@ -1297,11 +1720,6 @@ where
         Ok(c)
     }
 
-    async fn arithmetic_to_binary(&mut self, x: Aby3Share<T>) -> Result<Aby3Share<T>, Error> {
-        let (x1, x2, x3) = self.aby3.a2b_pre(x);
-        self.binary_add_3(x1, x2, x3).await
-    }
-
     async fn arithmetic_to_binary_many(
         &mut self,
         x: Vec<Aby3Share<T>>,
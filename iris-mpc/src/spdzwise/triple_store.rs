@@ -0,0 +1,99 @@
+use super::triples::Triples;
+use crate::prelude::Error;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File},
+    io::{BufReader, BufWriter},
+    path::PathBuf,
+};
+
+const FILE_PREFIX: &str = "triples_";
+const FILE_EXTENSION: &str = "bin";
+
+#[derive(Serialize, Deserialize)]
+struct StoredTriples {
+    party_id: usize,
+    batch_id: String,
+    triples: Triples,
+}
+
+/// Persists batches of verified AND triples of one party on disk. Each batch is identified by an
+/// id on which all three parties agree when generating it, such that the parties consume the same
+/// batch in the online phase. A batch is removed from disk when it is loaded, since triples must
+/// never be used twice.
+#[derive(Clone, Debug)]
+pub struct TripleStore {
+    dir: PathBuf,
+    party_id: usize,
+}
+
+impl TripleStore {
+    pub fn new(dir: impl Into<PathBuf>, party_id: usize) -> Result<Self, Error> {
+        if party_id >= 3 {
+            return Err(Error::IdError(party_id));
+        }
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir, party_id })
+    }
+
+    pub fn get_party_id(&self) -> usize {
+        self.party_id
+    }
+
+    fn path(&self, batch_id: &str) -> PathBuf {
+        self.dir.join(format!(
+            "{}p{}_{}.{}",
+            FILE_PREFIX, self.party_id, batch_id, FILE_EXTENSION
+        ))
+    }
+
+    pub(crate) fn write(&self, batch_id: &str, triples: Triples) -> Result<(), Error> {
+        let stored = StoredTriples {
+            party_id: self.party_id,
+            batch_id: batch_id.to_owned(),
+            triples,
+        };
+        let file = File::create(self.path(batch_id))?;
+        bincode::serialize_into(BufWriter::new(file), &stored)
+            .map_err(|_| Error::SerializationError)
+    }
+
+    /// Returns the ids of all stored batches of this party, sorted such that all parties consume
+    /// them in the same order.
+    pub fn list(&self) -> Result<Vec<String>, Error> {
+        let prefix = format!("{}p{}_", FILE_PREFIX, self.party_id);
+        let mut ids = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some(FILE_EXTENSION) {
+                continue;
+            }
+            let stem = match path.file_stem().and_then(|s| s.to_str()) {
+                Some(stem) => stem,
+                None => continue,
+            };
+            if let Some(id) = stem.strip_prefix(&prefix) {
+                ids.push(id.to_owned());
+            }
+        }
+        ids.sort();
+        Ok(ids)
+    }
+
+    /// Loads a batch and removes it from disk.
+    pub(crate) fn take(&self, batch_id: &str) -> Result<Triples, Error> {
+        let path = self.path(batch_id);
+        let file = File::open(&path)?;
+        let stored: StoredTriples = bincode::deserialize_from(BufReader::new(file))
+            .map_err(|_| Error::SerializationError)?;
+        if stored.party_id != self.party_id || stored.batch_id != batch_id {
+            return Err(Error::ValueError(format!(
+                "Triple batch {} does not belong to party {}",
+                batch_id, self.party_id
+            )));
+        }
+        fs::remove_file(path)?;
+        Ok(stored.triples)
+    }
+}
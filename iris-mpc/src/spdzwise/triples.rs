@@ -2,8 +2,9 @@ use crate::{
     prelude::{Aby3Share, Error, Sharable},
     types::ring_element::RingImpl,
 };
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Triples {
     a: Vec<Aby3Share<u128>>,
     b: Vec<Aby3Share<u128>>,
@@ -41,6 +42,21 @@ impl Triples {
         self.a.len()
     }
 
+    // Adds an error to the first bit of c by flipping x_0 consistently, i.e., P0 flips its a and
+    // P1 its b component
+    #[cfg(test)]
+    pub(crate) fn tamper_c(&mut self, id: usize) {
+        use crate::types::ring_element::RingElement;
+
+        if let Some(c) = self.c.first_mut() {
+            match id {
+                0 => c.a ^= RingElement(1),
+                1 => c.b ^= RingElement(1),
+                _ => {}
+            }
+        }
+    }
+
     #[allow(clippy::type_complexity)]
     pub fn get_all(
         &mut self,
@@ -158,9 +174,9 @@ impl Triples {
 
     pub fn add_many_t<T: Sharable>(
         &mut self,
-        a: &Vec<Aby3Share<T>>,
-        b: &Vec<Aby3Share<T>>,
-        c: &Vec<Aby3Share<T>>,
+        a: &[Aby3Share<T>],
+        b: &[Aby3Share<T>],
+        c: &[Aby3Share<T>],
     ) {
         debug_assert_eq!(a.len(), b.len());
         debug_assert_eq!(a.len(), c.len());
@@ -2,6 +2,7 @@ use crate::{
     prelude::{Aby3Share, Error, Sharable},
     traits::share_trait::VecShareTrait,
 };
+use bytes::Bytes;
 use num_traits::Zero;
 use serde::{Deserialize, Serialize};
 
@@ -40,6 +41,7 @@ impl<T: Sharable> VecShare<T> {
 
 impl<T: Sharable> VecShareTrait for VecShare<T> {
     type Share = Share<T>;
+    type Slice = Self;
 
     fn len(&self) -> usize {
         debug_assert!(self.values.len() == self.macs.len());
@@ -48,7 +50,7 @@ impl<T: Sharable> VecShareTrait for VecShare<T> {
 
     fn filter_reduce_add_twice(
         a: &Self,
-        b: &Self,
+        b: &Self::Slice,
         mask: &plain_reference::IrisCodeArray,
     ) -> Result<(Self::Share, Self::Share), Error> {
         if a.is_empty() || a.len() != b.len() {
@@ -149,4 +151,15 @@ impl<T: Sharable> VecShareTrait for VecShare<T> {
         self.values[index] = val;
         self.macs[index] = mac;
     }
+
+    fn replicated_components(slice: &Self::Slice) -> Result<[Vec<Bytes>; 2], Error> {
+        let [values_next, values_prev] =
+            <Vec<Aby3Share<T>> as VecShareTrait>::replicated_components(&slice.values)?;
+        let [macs_next, macs_prev] =
+            <Vec<Aby3Share<T>> as VecShareTrait>::replicated_components(&slice.macs)?;
+        Ok([
+            values_next.into_iter().chain(macs_next).collect(),
+            values_prev.into_iter().chain(macs_prev).collect(),
+        ])
+    }
 }
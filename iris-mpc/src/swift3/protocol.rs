@@ -5,14 +5,17 @@ use super::{
 use crate::dzkp::mul_proof::{MulProof, Proof as MulProofStruct};
 use crate::{
     aby3::utils,
+    adder::{self, AdderStrategy},
+    blame::{self, JmpQueue, JmpTranscript},
     commitment::{CommitOpening, Commitment},
+    db::roots::{self, DbRoots},
     dzkp::{
         and_proof::{AndProof, Proof as AndProofStruct},
         dot_proof::{DotProof, Proof as DotProofStruct},
         gf2p64::GF2p64,
         polynomial::Poly,
     },
-    prelude::{Aby3Share, Bit, Error, MpcTrait, Sharable},
+    prelude::{Aby3Share, Bit, Error, MpcTrait, PackedBits, Sharable},
     traits::{binary_trait::BinaryMpcTrait, network_trait::NetworkTrait, security::MaliciousAbort},
     types::ring_element::{RingElement, RingImpl},
 };
@@ -23,19 +26,31 @@ use rand::{
     Rng, SeedableRng,
 };
 use rand_chacha::ChaCha12Rng;
-use sha2::{digest::Output, Digest, Sha512};
-use std::{marker::PhantomData, ops::Mul};
+use serde::{de::DeserializeOwned, Serialize};
+use sha2::{Digest, Sha512};
+use std::{marker::PhantomData, ops::Mul, time::Duration};
+
+// The proofs received from prev and next, which are checked when verifying
+type ReceivedProofs<P> = (Result<P, Error>, Result<P, Error>);
+
+// How long the parties wait for a message of the recovery by the trusted third party
+const DEFAULT_TTP_TIMEOUT: Duration = Duration::from_secs(60);
 
 pub struct Swift3<N: NetworkTrait, U: Sharable> {
     network: N,
     prf: Prf,
-    send_queue_next: BytesMut,
-    send_queue_prev: BytesMut,
-    rcv_queue_next: BytesMut,
-    rcv_queue_prev: BytesMut,
+    direct_queue_next: JmpQueue,
+    direct_queue_prev: JmpQueue,
+    send_queue_next: JmpQueue,
+    send_queue_prev: JmpQueue,
+    rcv_queue_next: JmpQueue,
+    rcv_queue_prev: JmpQueue,
     and_proof: AndProof,
     mul_proof: MulProof<U::Share>,
     dot_proof: DotProof<U::Share>,
+    robust: bool,
+    ttp_timeout: Duration,
+    adder_strategy: AdderStrategy,
     _data: PhantomData<U>,
 }
 
@@ -66,14 +81,18 @@ where
 {
     pub fn new(network: N) -> Self {
         let prf = Prf::default();
-        let send_queue_next = BytesMut::new();
-        let send_queue_prev = BytesMut::new();
-        let rcv_queue_next = BytesMut::new();
-        let rcv_queue_prev = BytesMut::new();
+        let direct_queue_next = JmpQueue::default();
+        let direct_queue_prev = JmpQueue::default();
+        let send_queue_next = JmpQueue::default();
+        let send_queue_prev = JmpQueue::default();
+        let rcv_queue_next = JmpQueue::default();
+        let rcv_queue_prev = JmpQueue::default();
 
         Self {
             network,
             prf,
+            direct_queue_next,
+            direct_queue_prev,
             send_queue_next,
             send_queue_prev,
             rcv_queue_next,
@@ -81,10 +100,50 @@ where
             and_proof: AndProof::default(),
             mul_proof: MulProof::default(),
             dot_proof: DotProof::default(),
+            robust: false,
+            ttp_timeout: DEFAULT_TTP_TIMEOUT,
+            adder_strategy: AdderStrategy::default(),
             _data: PhantomData,
         }
     }
 
+    /// Creates a new instance in the robust mode, i.e., identifiable abort with recovery by a
+    /// trusted third party. Failed JMP and DZKP verifications are attributed to a cheating party
+    /// (or to a pair of conflicting parties) in both modes, and the verdict never names an honest
+    /// party. In the robust mode, the party which the verdict proves to be honest is elected as
+    /// TTP to complete the computation (see `IrisSwift3::iris_in_db_robust`).
+    ///
+    /// This is not guaranteed output delivery: The recovery only succeeds if the honest parties
+    /// reach the same verdict, which an equivocating cheater can prevent without a broadcast
+    /// channel, and a cheater which stops sending during the computation blocks the other parties.
+    /// The messages of the recovery itself are bounded by a timeout (see
+    /// [`Self::set_ttp_timeout`]).
+    pub fn new_robust(network: N) -> Self {
+        let mut swift3 = Self::new(network);
+        swift3.robust = true;
+        swift3
+    }
+
+    pub fn is_robust(&self) -> bool {
+        self.robust
+    }
+
+    /// Sets how long the parties wait for a message of the recovery in the robust mode before
+    /// giving up on its sender. The other parties wait for the output of the TTP for three times
+    /// as long, which has to cover the computation at the TTP.
+    pub fn set_ttp_timeout(&mut self, timeout: Duration) {
+        self.ttp_timeout = timeout;
+    }
+
+    /// Selects the adder used for extracting the MSB. Has to be the same for all parties.
+    pub fn set_adder_strategy(&mut self, strategy: AdderStrategy) {
+        self.adder_strategy = strategy;
+    }
+
+    pub fn get_adder_strategy(&self) -> AdderStrategy {
+        self.adder_strategy
+    }
+
     fn a2b_pre<T: Sharable>(&mut self, x: Share<T>) -> (Share<T>, Share<T>, Share<T>) {
         let (a, b, c) = x.get_abc();
 
@@ -134,8 +193,30 @@ where
         Ok(())
     }
 
+    // Starts the next round of JMPs (see [`JmpQueue`])
+    fn jmp_step(&mut self) {
+        self.direct_queue_next.end_step();
+        self.direct_queue_prev.end_step();
+        self.send_queue_next.end_step();
+        self.send_queue_prev.end_step();
+        self.rcv_queue_next.end_step();
+        self.rcv_queue_prev.end_step();
+    }
+
     #[inline(always)]
     async fn jmp_send<T: Sharable>(&mut self, value: T::Share, id: usize) -> Result<(), Error> {
+        let my_id = self.network.get_id();
+        if id == (my_id + 1) % 3 {
+            value
+                .to_owned()
+                .add_to_bytes(self.direct_queue_next.bytes_mut());
+        } else if id == (my_id + 2) % 3 {
+            value
+                .to_owned()
+                .add_to_bytes(self.direct_queue_prev.bytes_mut());
+        } else {
+            return Err(Error::IdError(id));
+        }
         utils::send_value(&mut self.network, value, id).await
     }
 
@@ -145,15 +226,27 @@ where
         values: &[T::Share],
         id: usize,
     ) -> Result<(), Error> {
+        let my_id = self.network.get_id();
+        if id == (my_id + 1) % 3 {
+            for value in values {
+                value.add_to_bytes(self.direct_queue_next.bytes_mut());
+            }
+        } else if id == (my_id + 2) % 3 {
+            for value in values {
+                value.add_to_bytes(self.direct_queue_prev.bytes_mut());
+            }
+        } else {
+            return Err(Error::IdError(id));
+        }
         utils::send_vec(&mut self.network, values, id).await
     }
 
     fn jmp_queue<T: Sharable>(&mut self, value: T::Share, id: usize) -> Result<(), Error> {
         let my_id = self.network.get_id();
         if id == (my_id + 1) % 3 {
-            value.add_to_bytes(&mut self.send_queue_next);
+            value.add_to_bytes(self.send_queue_next.bytes_mut());
         } else if id == (my_id + 2) % 3 {
-            value.add_to_bytes(&mut self.send_queue_prev);
+            value.add_to_bytes(self.send_queue_prev.bytes_mut());
         } else {
             return Err(Error::IdError(id));
         }
@@ -164,11 +257,11 @@ where
         let my_id = self.network.get_id();
         if id == (my_id + 1) % 3 {
             for value in values {
-                value.add_to_bytes(&mut self.send_queue_next);
+                value.add_to_bytes(self.send_queue_next.bytes_mut());
             }
         } else if id == (my_id + 2) % 3 {
             for value in values {
-                value.add_to_bytes(&mut self.send_queue_prev);
+                value.add_to_bytes(self.send_queue_prev.bytes_mut());
             }
         } else {
             return Err(Error::IdError(id));
@@ -200,6 +293,7 @@ where
     where
         Standard: Distribution<U::Share>,
     {
+        self.jmp_step();
         let id = self.network.get_id();
 
         let (x_a, x_b, x_c) = a.get_abc();
@@ -254,6 +348,7 @@ where
     where
         Standard: Distribution<T::Share>,
     {
+        self.jmp_step();
         let id = self.network.get_id();
 
         let (x_a, x_b, x_c) = a.get_abc();
@@ -303,13 +398,14 @@ where
 
     async fn and_post_many<T: Sharable>(
         &mut self,
-        a: &Vec<Share<T>>,
-        b: &Vec<Share<T>>,
+        a: &[Share<T>],
+        b: &[Share<T>],
         de: Vec<Aby3Share<T>>,
     ) -> Result<Vec<Share<T>>, Error>
     where
         Standard: Distribution<T::Share>,
     {
+        self.jmp_step();
         let len = a.len();
         debug_assert_eq!(len, b.len());
         debug_assert_eq!(len, de.len());
@@ -427,6 +523,7 @@ where
     where
         Standard: Distribution<U::Share>,
     {
+        self.jmp_step();
         debug_assert_eq!(a.len(), b.len());
         let id = self.network.get_id();
 
@@ -489,6 +586,7 @@ where
     where
         Standard: Distribution<U::Share>,
     {
+        self.jmp_step();
         let len = a.len();
         debug_assert_eq!(len, b.len());
         debug_assert_eq!(len, de.len());
@@ -671,40 +769,44 @@ where
             _ => unreachable!(),
         };
 
-        let shared_verify_prev = self.and_proof.verify_prev(
-            &betas[prev_id],
-            &r[prev_id],
-            &lagrange_polys,
-            &coords,
-            proof_prev,
-        )?;
-
-        let shared_verify_next = self.and_proof.verify_next(
-            &betas[next_id],
-            &r[next_id],
-            &lagrange_polys,
-            &coords,
-            proof_next,
-        )?;
+        let shared_verify_prev = proof_prev.and_then(|proof_prev| {
+            self.and_proof.verify_prev(
+                &betas[prev_id],
+                &r[prev_id],
+                &lagrange_polys,
+                &coords,
+                proof_prev,
+            )
+        });
+
+        let shared_verify_next = proof_next.and_then(|proof_next| {
+            self.and_proof.verify_next(
+                &betas[next_id],
+                &r[next_id],
+                &lagrange_polys,
+                &coords,
+                proof_next,
+            )
+        });
 
-        // send prev_verification to next
-        self.network
-            .send_next_id(Bytes::from(
-                bincode::serialize(&shared_verify_prev).map_err(|_| Error::SerializationError)?,
-            ))
+        // Vouch for the verification of our own proof by next, such that a rejected proof can be
+        // attributed
+        let id = self.network.get_id();
+        let shared_verify_own =
+            self.and_proof
+                .verify_own(&betas[id], &r[id], &lagrange_polys, &coords, proof)?;
+        let (shared_verify_rcv, prev_rejected) = self
+            .send_receive_verification(shared_verify_prev, &shared_verify_own)
             .await?;
-        let bytes = self.network.receive_prev_id().await?;
-        let shared_verify_rcv =
-            bincode::deserialize(&bytes).map_err(|_| Error::SerializationError)?;
 
-        // finally, combine the shared verifications to verify the proof of next_id
-        self.and_proof.combine_verifications(
-            &thetas[next_id],
-            shared_verify_rcv,
-            shared_verify_next,
-        )?;
-
-        Ok(())
+        let next_accepted = match (shared_verify_rcv, shared_verify_next) {
+            (Some(rcv), Ok(next)) => self
+                .and_proof
+                .combine_verifications(&thetas[next_id], rcv, next)
+                .is_ok(),
+            _ => false,
+        };
+        self.dzkp_blame(prev_rejected, !next_accepted).await
     }
 
     async fn mul_verify(&mut self) -> Result<(), Error> {
@@ -753,40 +855,44 @@ where
             _ => unreachable!(),
         };
 
-        let shared_verify_prev = self.mul_proof.verify_prev(
-            &betas[prev_id],
-            &r[prev_id],
-            &lagrange_polys,
-            &coords,
-            proof_prev,
-        )?;
-
-        let shared_verify_next = self.mul_proof.verify_next(
-            &betas[next_id],
-            &r[next_id],
-            &lagrange_polys,
-            &coords,
-            proof_next,
-        )?;
+        let shared_verify_prev = proof_prev.and_then(|proof_prev| {
+            self.mul_proof.verify_prev(
+                &betas[prev_id],
+                &r[prev_id],
+                &lagrange_polys,
+                &coords,
+                proof_prev,
+            )
+        });
+
+        let shared_verify_next = proof_next.and_then(|proof_next| {
+            self.mul_proof.verify_next(
+                &betas[next_id],
+                &r[next_id],
+                &lagrange_polys,
+                &coords,
+                proof_next,
+            )
+        });
 
-        // send prev_verification to next
-        self.network
-            .send_next_id(Bytes::from(
-                bincode::serialize(&shared_verify_prev).map_err(|_| Error::SerializationError)?,
-            ))
+        // Vouch for the verification of our own proof by next, such that a rejected proof can be
+        // attributed
+        let id = self.network.get_id();
+        let shared_verify_own =
+            self.mul_proof
+                .verify_own(&betas[id], &r[id], &lagrange_polys, &coords, proof)?;
+        let (shared_verify_rcv, prev_rejected) = self
+            .send_receive_verification(shared_verify_prev, &shared_verify_own)
             .await?;
-        let bytes = self.network.receive_prev_id().await?;
-        let shared_verify_rcv =
-            bincode::deserialize(&bytes).map_err(|_| Error::SerializationError)?;
 
-        // finally, combine the shared verifications to verify the proof of next_id
-        self.mul_proof.combine_verifications(
-            &thetas[next_id],
-            shared_verify_rcv,
-            shared_verify_next,
-        )?;
-
-        Ok(())
+        let next_accepted = match (shared_verify_rcv, shared_verify_next) {
+            (Some(rcv), Ok(next)) => self
+                .mul_proof
+                .combine_verifications(&thetas[next_id], rcv, next)
+                .is_ok(),
+            _ => false,
+        };
+        self.dzkp_blame(prev_rejected, !next_accepted).await
     }
 
     async fn dot_verify(&mut self) -> Result<(), Error> {
@@ -836,40 +942,239 @@ where
             _ => unreachable!(),
         };
 
-        let shared_verify_prev = self.dot_proof.verify_prev(
-            &betas[prev_id],
-            &r[prev_id],
-            &lagrange_polys,
-            &coords,
-            proof_prev,
-        )?;
-
-        let shared_verify_next = self.dot_proof.verify_next(
-            &betas[next_id],
-            &r[next_id],
-            &lagrange_polys,
-            &coords,
-            proof_next,
-        )?;
+        let shared_verify_prev = proof_prev.and_then(|proof_prev| {
+            self.dot_proof.verify_prev(
+                &betas[prev_id],
+                &r[prev_id],
+                &lagrange_polys,
+                &coords,
+                proof_prev,
+            )
+        });
+
+        let shared_verify_next = proof_next.and_then(|proof_next| {
+            self.dot_proof.verify_next(
+                &betas[next_id],
+                &r[next_id],
+                &lagrange_polys,
+                &coords,
+                proof_next,
+            )
+        });
 
-        // send prev_verification to next
-        self.network
-            .send_next_id(Bytes::from(
-                bincode::serialize(&shared_verify_prev).map_err(|_| Error::SerializationError)?,
-            ))
+        // Vouch for the verification of our own proof by next, such that a rejected proof can be
+        // attributed
+        let id = self.network.get_id();
+        let shared_verify_own =
+            self.dot_proof
+                .verify_own(&betas[id], &r[id], &lagrange_polys, &coords, proof)?;
+        let (shared_verify_rcv, prev_rejected) = self
+            .send_receive_verification(shared_verify_prev, &shared_verify_own)
             .await?;
+
+        let next_accepted = match (shared_verify_rcv, shared_verify_next) {
+            (Some(rcv), Ok(next)) => self
+                .dot_proof
+                .combine_verifications(&thetas[next_id], rcv, next)
+                .is_ok(),
+            _ => false,
+        };
+        self.dzkp_blame(prev_rejected, !next_accepted).await
+    }
+
+    // Sends the verification of the proof of prev to next, and publishes the hash of the
+    // verification of our own proof. Thus, the verification received from prev is checked against the
+    // hash of its prover (next) like a JMP message. Returns the received verification (if it is
+    // well-formed) and whether we rejected the proof of prev.
+    async fn send_receive_verification<V: Serialize + DeserializeOwned>(
+        &mut self,
+        shared_verify_prev: Result<V, Error>,
+        shared_verify_own: &V,
+    ) -> Result<(Option<V>, bool), Error> {
+        let id = self.network.get_id();
+        let next_id = (id + 1) % 3;
+        let prev_id = (id + 2) % 3;
+
+        // We send an empty verification if we rejected the proof of prev
+        let prev_rejected = shared_verify_prev.is_err();
+        let msg = match shared_verify_prev {
+            Ok(verify) => bincode::serialize(&verify).map_err(|_| Error::SerializationError)?,
+            Err(_) => Vec::new(),
+        };
+        let own = bincode::serialize(shared_verify_own).map_err(|_| Error::SerializationError)?;
+
+        let mut transcript = JmpTranscript::default();
+        transcript.sent_values[next_id] = Some(blame::single_step(&msg));
+        transcript.sent_hashes[prev_id] = Some(blame::single_step(&own));
+
+        self.network.send_next_id(Bytes::from(msg)).await?;
         let bytes = self.network.receive_prev_id().await?;
-        let shared_verify_rcv =
-            bincode::deserialize(&bytes).map_err(|_| Error::SerializationError)?;
 
-        // finally, combine the shared verifications to verify the proof of next_id
-        self.dot_proof.combine_verifications(
-            &thetas[next_id],
-            shared_verify_rcv,
-            shared_verify_next,
-        )?;
+        // The hash of the verification is published by its prover (next)
+        transcript.received_values[next_id] = Some(blame::single_step(&bytes));
+        blame::jmp_verify(&mut self.network, &transcript).await?;
 
-        Ok(())
+        Ok((bincode::deserialize(&bytes).ok(), prev_rejected))
+    }
+
+    async fn dzkp_blame(&mut self, prev_rejected: bool, next_rejected: bool) -> Result<(), Error> {
+        let id = self.network.get_id();
+
+        let mut rejected = Vec::with_capacity(2);
+        if prev_rejected {
+            rejected.push((id + 2) % 3);
+        }
+        if next_rejected {
+            rejected.push((id + 1) % 3);
+        }
+        blame::proof_blame(&mut self.network, rejected).await
+    }
+
+    /// Commits to the components of the inputs of a computation in the robust mode (see
+    /// [`blame::commit_components`]). Returns the hashes of the components, indexed by the party
+    /// which does not hold them.
+    pub(crate) async fn commit_inputs<T: Sharable>(
+        &mut self,
+        shares: &[Share<T>],
+    ) -> Result<[Vec<u8>; 3], Error> {
+        let a = utils::ring_iter_to_bytes(shares.iter().map(|share| &share.a));
+        let b = utils::ring_iter_to_bytes(shares.iter().map(|share| &share.b));
+        let hashes = [Sha512::digest(a).to_vec(), Sha512::digest(b).to_vec()];
+        blame::commit_components(&mut self.network, hashes).await
+    }
+
+    /// Hands the shares of the inputs to the trusted third party, which is elected based on the
+    /// identifiable abort `verdict`. The TTP is missing one of the `a` components, which is held
+    /// by both other parties. If the verdict names the cheater, only the other honest party sends
+    /// its components. Otherwise, both parties send them and the TTP takes the ones matching the
+    /// hash in `commitments` (see [`Self::commit_inputs`]), so the cheater cannot prevent the
+    /// reconstruction. Returns the reconstructed inputs at the TTP and `None` at the other parties.
+    /// The TTP waits at most for the timeout of the recovery for each message.
+    pub(crate) async fn ttp_reconstruct<T: Sharable>(
+        &mut self,
+        verdict: &Error,
+        commitments: Option<&[Vec<u8>; 3]>,
+        shares: Vec<Share<T>>,
+    ) -> Result<Option<Vec<T>>, Error> {
+        let ttp = match blame::elect_ttp(verdict) {
+            Some(ttp) => ttp,
+            None => return Err(Error::ValueError("No trusted third party".to_string())),
+        };
+        let cheater = match *verdict {
+            Error::CheatingParty(p) => Some(p),
+            _ => None,
+        };
+        let commitment = match (cheater, commitments) {
+            (Some(_), _) => None,
+            (None, Some(commitments)) => Some(&commitments[ttp]),
+            // Without a named cheater, the TTP cannot tell which of the components is correct
+            (None, None) => {
+                return Err(Error::ValueError(
+                    "No commitments to the inputs".to_string(),
+                ))
+            }
+        };
+
+        let id = self.network.get_id();
+        let ttp_next = (ttp + 1) % 3;
+        let ttp_prev = (ttp + 2) % 3;
+        let len = shares.len();
+
+        if id != ttp {
+            if Some(id) != cheater {
+                let values = shares
+                    .into_iter()
+                    .map(|share| if id == ttp_next { share.a } else { share.b })
+                    .collect::<Vec<_>>();
+                utils::send_vec(&mut self.network, &values, ttp).await?;
+            }
+            return Ok(None);
+        }
+
+        let missing: Vec<T::Share> = match commitment {
+            // A named cheater is always prev of the TTP
+            None => {
+                let msg = self.ttp_receive(ttp_next, self.ttp_timeout).await?;
+                utils::ring_vec_from_bytes(msg, len)?
+            }
+            Some(commitment) => {
+                let mut missing = None;
+                for party in [ttp_next, ttp_prev] {
+                    // The message of the cheater may be missing as well
+                    let Ok(msg) = self.ttp_receive(party, self.ttp_timeout).await else {
+                        continue;
+                    };
+                    if missing.is_none() && Sha512::digest(&msg).as_slice() == commitment {
+                        missing = Some(utils::ring_vec_from_bytes(msg, len)?);
+                    }
+                }
+                missing.ok_or(Error::ConflictingParties(
+                    ttp_next.min(ttp_prev),
+                    ttp_next.max(ttp_prev),
+                ))?
+            }
+        };
+
+        let res = shares
+            .into_iter()
+            .zip(missing)
+            .map(|(share, missing)| {
+                let (a, b, c) = share.get_abc();
+                T::from_sharetype(c - a - b - missing)
+            })
+            .collect();
+        Ok(Some(res))
+    }
+
+    /// Distributes the output computed by the trusted third party `ttp`. If the TTP failed to
+    /// compute the output, or its output does not arrive in time, the parties return an error.
+    pub(crate) async fn ttp_output(
+        &mut self,
+        ttp: usize,
+        output: Option<Result<bool, Error>>,
+    ) -> Result<bool, Error> {
+        let id = self.network.get_id();
+
+        if id == ttp {
+            let output = output.ok_or(Error::IdError(ttp))?;
+            let msg = match output {
+                Ok(false) => 0u8,
+                Ok(true) => 1,
+                Err(_) => 2,
+            };
+            for party in [(id + 1) % 3, (id + 2) % 3] {
+                // The cheater may have left, which must not keep the output from the TTP
+                if self
+                    .network
+                    .send(party, Bytes::from(vec![msg]))
+                    .await
+                    .is_err()
+                {
+                    tracing::warn!("Party {}: Could not send the output to {}", id, party);
+                }
+            }
+            return output;
+        }
+
+        // The TTP may wait for the components of both other parties before computing the output
+        let msg = self.ttp_receive(ttp, self.ttp_timeout * 3).await?;
+        match msg.as_ref() {
+            [0] => Ok(false),
+            [1] => Ok(true),
+            [2] => Err(Error::ValueError(
+                "Trusted third party failed to compute the output".to_string(),
+            )),
+            _ => Err(Error::InvalidMessageSize),
+        }
+    }
+
+    // Receives a message of the recovery from `id`, which fails if `id` stays silent for longer
+    // than `timeout`
+    async fn ttp_receive(&mut self, id: usize, timeout: Duration) -> Result<BytesMut, Error> {
+        match tokio::time::timeout(timeout, self.network.receive(id)).await {
+            Ok(msg) => Ok(msg?),
+            Err(_) => Err(Error::TimeoutError(id)),
+        }
     }
 
     async fn aby_mul(&mut self, a: Aby3Share<U>, b: Aby3Share<U>) -> Result<Aby3Share<U>, Error>
@@ -946,17 +1251,12 @@ where
 
         let res: Vec<Aby3Share<T>> = shares_a
             .into_iter()
-            .zip(shares_b.into_iter())
+            .zip(shares_b)
             .map(|(a_, b_)| Aby3Share::new(a_, b_))
             .collect();
 
         // Register for proof
-        for (((a, b), c), r) in a
-            .into_iter()
-            .zip(b.into_iter())
-            .zip(res.iter())
-            .zip(rs.into_iter())
-        {
+        for (((a, b), c), r) in a.into_iter().zip(b).zip(res.iter()).zip(rs) {
             let (a0, a1) = a.get_ab();
             let (b0, b1) = b.get_ab();
             let (r0, r1) = r.get_ab();
@@ -988,7 +1288,7 @@ where
         let mut b1 = Vec::with_capacity(len);
 
         let mut c = Aby3Share::new(rand, U::zero().to_sharetype());
-        for (a_, b_) in a.into_iter().zip(b.into_iter()) {
+        for (a_, b_) in a.into_iter().zip(b) {
             c += a_.to_owned() * &b_;
 
             // Register for proof
@@ -1030,7 +1330,7 @@ where
         let mut r0 = Vec::with_capacity(len);
         let mut r1 = Vec::with_capacity(len);
 
-        for (a_, b_) in a.into_iter().zip(b.into_iter()) {
+        for (a_, b_) in a.into_iter().zip(b) {
             let (r0_, r1_) = self.prf.gen_for_zero_share::<U>();
             let mut rand = r0_.to_owned() - &r1_;
             let len_ = a_.len();
@@ -1044,7 +1344,7 @@ where
             let mut b0_ = Vec::with_capacity(len_);
             let mut b1_ = Vec::with_capacity(len_);
 
-            for (a__, b__) in a_.into_iter().zip(b_.into_iter()) {
+            for (a__, b__) in a_.into_iter().zip(b_) {
                 rand += (a__.to_owned() * &b__).a;
 
                 // Register for proof
@@ -1071,10 +1371,8 @@ where
             a1.into_iter().zip(
                 b0.into_iter().zip(
                     b1.into_iter().zip(
-                        r0.into_iter().zip(
-                            r1.into_iter()
-                                .zip(shares_a.into_iter().zip(shares_b.into_iter())),
-                        ),
+                        r0.into_iter()
+                            .zip(r1.into_iter().zip(shares_a.into_iter().zip(shares_b))),
                     ),
                 ),
             ),
@@ -1094,9 +1392,13 @@ where
         let my_id = self.network.get_id();
         // if id==next_id, i should recv from prev and vice versa
         if id == (my_id + 1) % 3 {
-            value.to_owned().add_to_bytes(&mut self.rcv_queue_prev);
+            value
+                .to_owned()
+                .add_to_bytes(self.rcv_queue_prev.bytes_mut());
         } else if id == (my_id + 2) % 3 {
-            value.to_owned().add_to_bytes(&mut self.rcv_queue_next);
+            value
+                .to_owned()
+                .add_to_bytes(self.rcv_queue_next.bytes_mut());
         } else {
             return Err(Error::IdError(id));
         }
@@ -1116,11 +1418,11 @@ where
         // if id==next_id, i should recv from prev and vice versa
         if id == (my_id + 1) % 3 {
             for value in values.iter() {
-                value.add_to_bytes(&mut self.rcv_queue_prev);
+                value.add_to_bytes(self.rcv_queue_prev.bytes_mut());
             }
         } else if id == (my_id + 2) % 3 {
             for value in values.iter() {
-                value.add_to_bytes(&mut self.rcv_queue_next);
+                value.add_to_bytes(self.rcv_queue_next.bytes_mut());
             }
         } else {
             return Err(Error::IdError(id));
@@ -1129,40 +1431,21 @@ where
         Ok(values)
     }
 
-    fn clear_and_hash(data: &mut BytesMut) -> Output<Sha512> {
-        let mut swap = BytesMut::new();
-        std::mem::swap(&mut swap, data);
-        let bytes = swap.freeze();
-        let mut hasher = Sha512::new();
-        hasher.update(bytes);
-        hasher.finalize()
-    }
-
     async fn jmp_verify(&mut self) -> Result<(), Error> {
         let id = self.network.get_id();
         let next_id = (id + 1) % 3;
         let prev_id = (id + 2) % 3;
 
-        let send_next = Self::clear_and_hash(&mut self.send_queue_next);
-        let send_prev = Self::clear_and_hash(&mut self.send_queue_prev);
-        let hash_next = Self::clear_and_hash(&mut self.rcv_queue_next);
-        let hash_prev = Self::clear_and_hash(&mut self.rcv_queue_prev);
-
-        self.network
-            .send(next_id, Bytes::from(send_next.to_vec()))
-            .await?;
-        self.network
-            .send(prev_id, Bytes::from(send_prev.to_vec()))
-            .await?;
-
-        let rcv_prev = self.network.receive(prev_id).await?;
-        let rcv_next = self.network.receive(next_id).await?;
-
-        if rcv_prev.as_ref() != hash_prev.as_slice() || rcv_next.as_ref() != hash_next.as_slice() {
-            return Err(Error::JmpVerifyError);
-        }
+        // rcv_queue_prev holds the values received from next, which are verified by prev
+        let mut transcript = JmpTranscript::default();
+        transcript.sent_values[next_id] = Some(self.direct_queue_next.finish());
+        transcript.sent_values[prev_id] = Some(self.direct_queue_prev.finish());
+        transcript.sent_hashes[next_id] = Some(self.send_queue_next.finish());
+        transcript.sent_hashes[prev_id] = Some(self.send_queue_prev.finish());
+        transcript.received_values[next_id] = Some(self.rcv_queue_next.finish());
+        transcript.received_values[prev_id] = Some(self.rcv_queue_prev.finish());
 
-        Ok(())
+        blame::jmp_verify(&mut self.network, &transcript).await
     }
 
     async fn jshare<T: Sharable>(
@@ -1175,6 +1458,7 @@ where
     where
         Standard: Distribution<T::Share>,
     {
+        self.jmp_step();
         if sender2 != ((sender1 + 1) % 3) {
             std::mem::swap(&mut sender1, &mut sender2);
         }
@@ -1222,6 +1506,7 @@ where
     where
         Standard: Distribution<T::Share>,
     {
+        self.jmp_step();
         if sender2 != ((sender1 + 1) % 3) {
             std::mem::swap(&mut sender1, &mut sender2);
         }
@@ -1304,6 +1589,7 @@ where
     where
         Standard: Distribution<T::Share>,
     {
+        self.jmp_step();
         if sender2 != ((sender1 + 1) % 3) {
             std::mem::swap(&mut sender1, &mut sender2);
         }
@@ -1351,6 +1637,7 @@ where
     where
         Standard: Distribution<T::Share>,
     {
+        self.jmp_step();
         if sender2 != ((sender1 + 1) % 3) {
             std::mem::swap(&mut sender1, &mut sender2);
         }
@@ -1568,25 +1855,18 @@ where
         let mut seed2 = [0u8; 32];
         let mut seed3 = [0u8; 32];
 
-        for (r, (a, b)) in seed1
-            .iter_mut()
-            .zip(seeds[0].iter().zip(rcv_open1.into_iter()))
-        {
+        for (r, (a, b)) in seed1.iter_mut().zip(seeds[0].iter().zip(rcv_open1)) {
             *r = b ^ a;
         }
 
-        for (r, (a, b)) in seed2
-            .iter_mut()
-            .zip(seeds[1].iter().zip(rcv_open3.into_iter()))
-        {
+        for (r, (a, b)) in seed2.iter_mut().zip(seeds[1].iter().zip(rcv_open3)) {
             *r = b ^ a;
         }
 
-        for (r, (a, (b, c))) in seed3.iter_mut().zip(
-            seeds[2]
-                .iter()
-                .zip(rcv_open2.into_iter().zip(rcv_open4.into_iter())),
-        ) {
+        for (r, (a, (b, c))) in seed3
+            .iter_mut()
+            .zip(seeds[2].iter().zip(rcv_open2.into_iter().zip(rcv_open4)))
+        {
             *r = b ^ a ^ c;
         }
 
@@ -1599,25 +1879,76 @@ where
         Ok(())
     }
 
-    fn pack<T: Sharable>(&self, a: Vec<Share<Bit>>) -> Vec<Share<T>> {
-        let outlen = (a.len() + T::Share::K - 1) / T::Share::K;
-        let mut out = Vec::with_capacity(outlen);
+    // Bitslices the inputs, see utils::transpose_pack_u128. The transposition is linear, so it
+    // is applied to the components (a, b) and c separately.
+    fn transpose_pack_u128<T: Sharable>(x: Vec<Share<T>>) -> Vec<Vec<Share<u128>>> {
+        let (ab, c): (Vec<_>, Vec<_>) = x
+            .into_iter()
+            .map(|x| {
+                let (a, b, c) = x.get_abc();
+                (Aby3Share::new(a, b), Aby3Share::new(c, T::Share::zero()))
+            })
+            .unzip();
+        let ab = utils::transpose_pack_u128::<T>(ab);
+        let c = utils::transpose_pack_u128::<T>(c);
+
+        ab.into_iter()
+            .zip(c)
+            .map(|(ab, c)| {
+                ab.into_iter()
+                    .zip(c)
+                    .map(|(ab, c)| {
+                        let (a, b) = ab.get_ab();
+                        Share::new(a, b, c.get_a())
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    // Computes the MSBs with the adder circuit selected by the adder strategy
+    async fn msb_adder_many<T: Sharable>(
+        &mut self,
+        a: Vec<Share<T>>,
+    ) -> Result<PackedBits<Share<Bit>>, Error>
+    where
+        Standard: Distribution<T::Share>,
+    {
+        let len = a.len();
+        let mut x1 = Vec::with_capacity(len);
+        let mut x2 = Vec::with_capacity(len);
+        let mut x3 = Vec::with_capacity(len);
 
-        for a_ in a.chunks(T::Share::K) {
-            let mut share_a = T::Share::zero();
-            let mut share_b = T::Share::zero();
-            let mut share_c = T::Share::zero();
-            for (i, bit) in a_.iter().enumerate() {
-                let (bit_a, bit_b, bit_c) = bit.to_owned().get_abc();
-                share_a |= T::Share::from(bit_a.convert().convert()) << (i as u32);
-                share_b |= T::Share::from(bit_b.convert().convert()) << (i as u32);
-                share_c |= T::Share::from(bit_c.convert().convert()) << (i as u32);
-            }
-            let share = Share::new(share_a, share_b, share_c);
-            out.push(share);
+        for x_ in a {
+            let (x1_, x2_, x3_) = self.a2b_pre(x_);
+            x1.push(x1_);
+            x2.push(x2_);
+            x3.push(x3_);
         }
 
-        out
+        let res = match self.adder_strategy {
+            AdderStrategy::KoggeStone => {
+                let sum =
+                    <Self as BinaryMpcTrait<T, Share<T>>>::binary_add_3_many(self, x1, x2, x3)
+                        .await?;
+                Self::transpose_pack_u128::<T>(sum)
+                    .pop()
+                    .expect("Enough elements present")
+            }
+            strategy => {
+                adder::msb_many(
+                    self,
+                    strategy,
+                    Self::transpose_pack_u128::<T>(x1),
+                    Self::transpose_pack_u128::<T>(x2),
+                    Self::transpose_pack_u128::<T>(x3),
+                )
+                .await?
+            }
+        };
+
+        // The outputs are already bitsliced, the padding are shares of zero
+        Ok(PackedBits::from_words(res, len))
     }
 
     reduce_or!(
@@ -1722,7 +2053,7 @@ where
 
         seed.as_mut()
             .iter_mut()
-            .zip(rcv_open1.into_iter().zip(rcv_open2.into_iter()))
+            .zip(rcv_open1.into_iter().zip(rcv_open2))
             .for_each(|(r, (a, b))| *r ^= a ^ b);
 
         Ok(seed)
@@ -1734,7 +2065,7 @@ where
         seed: R::Seed,
         l: usize,
         m: usize,
-    ) -> Result<(AndProofStruct, AndProofStruct), Error>
+    ) -> Result<ReceivedProofs<AndProofStruct>, Error>
     where
         R::Seed: AsRef<[u8]>,
     {
@@ -1748,19 +2079,20 @@ where
             .await?;
 
         let proof_bytes = self.network.receive_prev_id().await?;
-        let proof_prev =
-            bincode::deserialize(&proof_bytes).map_err(|_| Error::SerializationError)?;
+        // A malformed proof is rejected later, such that it can be attributed
+        let proof_prev = bincode::deserialize(&proof_bytes).map_err(|_| Error::SerializationError);
 
         let seed_next = self.network.receive_next_id().await?.freeze();
-        if seed_next.len() != seed.as_ref().len() {
-            return Err(Error::InvalidMessageSize);
-        }
-        let mut seed_next_ = <ChaCha12Rng as SeedableRng>::Seed::default();
-        seed_next_
-            .iter_mut()
-            .zip(seed_next.into_iter())
-            .for_each(|(a, b)| *a = b);
-        let proof_next = AndProofStruct::from_seed::<ChaCha12Rng>(seed_next_, l, m);
+        let proof_next = if seed_next.len() != seed.as_ref().len() {
+            Err(Error::InvalidMessageSize)
+        } else {
+            let mut seed_next_ = <ChaCha12Rng as SeedableRng>::Seed::default();
+            seed_next_
+                .iter_mut()
+                .zip(seed_next)
+                .for_each(|(a, b)| *a = b);
+            Ok(AndProofStruct::from_seed::<ChaCha12Rng>(seed_next_, l, m))
+        };
 
         Ok((proof_prev, proof_next))
     }
@@ -1773,7 +2105,7 @@ where
         l: usize,
         m: usize,
         d: usize,
-    ) -> Result<(MulProofStruct<U::Share>, MulProofStruct<U::Share>), Error>
+    ) -> Result<ReceivedProofs<MulProofStruct<U::Share>>, Error>
     where
         R::Seed: AsRef<[u8]>,
     {
@@ -1787,19 +2119,22 @@ where
             .await?;
 
         let proof_bytes = self.network.receive_prev_id().await?;
-        let proof_prev =
-            bincode::deserialize(&proof_bytes).map_err(|_| Error::SerializationError)?;
+        // A malformed proof is rejected later, such that it can be attributed
+        let proof_prev = bincode::deserialize(&proof_bytes).map_err(|_| Error::SerializationError);
 
         let seed_next = self.network.receive_next_id().await?.freeze();
-        if seed_next.len() != seed.as_ref().len() {
-            return Err(Error::InvalidMessageSize);
-        }
-        let mut seed_next_ = <ChaCha12Rng as SeedableRng>::Seed::default();
-        seed_next_
-            .iter_mut()
-            .zip(seed_next.into_iter())
-            .for_each(|(a, b)| *a = b);
-        let proof_next = MulProofStruct::from_seed::<ChaCha12Rng>(seed_next_, l, m, d);
+        let proof_next = if seed_next.len() != seed.as_ref().len() {
+            Err(Error::InvalidMessageSize)
+        } else {
+            let mut seed_next_ = <ChaCha12Rng as SeedableRng>::Seed::default();
+            seed_next_
+                .iter_mut()
+                .zip(seed_next)
+                .for_each(|(a, b)| *a = b);
+            Ok(MulProofStruct::from_seed::<ChaCha12Rng>(
+                seed_next_, l, m, d,
+            ))
+        };
 
         Ok((proof_prev, proof_next))
     }
@@ -1828,7 +2163,7 @@ where
         m: usize,
         d: usize,
         dot: usize,
-    ) -> Result<(DotProofStruct<U::Share>, DotProofStruct<U::Share>), Error>
+    ) -> Result<ReceivedProofs<DotProofStruct<U::Share>>, Error>
     where
         R::Seed: AsRef<[u8]>,
     {
@@ -1842,19 +2177,22 @@ where
             .await?;
 
         let proof_bytes = self.network.receive_prev_id().await?;
-        let proof_prev =
-            bincode::deserialize(&proof_bytes).map_err(|_| Error::SerializationError)?;
+        // A malformed proof is rejected later, such that it can be attributed
+        let proof_prev = bincode::deserialize(&proof_bytes).map_err(|_| Error::SerializationError);
 
         let seed_next = self.network.receive_next_id().await?.freeze();
-        if seed_next.len() != seed.as_ref().len() {
-            return Err(Error::InvalidMessageSize);
-        }
-        let mut seed_next_ = <ChaCha12Rng as SeedableRng>::Seed::default();
-        seed_next_
-            .iter_mut()
-            .zip(seed_next.into_iter())
-            .for_each(|(a, b)| *a = b);
-        let proof_next = DotProofStruct::from_seed::<ChaCha12Rng>(seed_next_, l, m, d, dot);
+        let proof_next = if seed_next.len() != seed.as_ref().len() {
+            Err(Error::InvalidMessageSize)
+        } else {
+            let mut seed_next_ = <ChaCha12Rng as SeedableRng>::Seed::default();
+            seed_next_
+                .iter_mut()
+                .zip(seed_next)
+                .for_each(|(a, b)| *a = b);
+            Ok(DotProofStruct::from_seed::<ChaCha12Rng>(
+                seed_next_, l, m, d, dot,
+            ))
+        };
 
         Ok((proof_prev, proof_next))
     }
@@ -1937,7 +2275,12 @@ where
         Ok(self.network.print_connection_stats(out)?)
     }
 
+    async fn verify_db_roots(&mut self, roots: &DbRoots) -> Result<(), Error> {
+        roots::exchange_db_roots(&mut self.network, roots).await
+    }
+
     async fn input(&mut self, input: Option<T>, id: usize) -> Result<Share<T>, Error> {
+        self.jmp_step();
         let self_id = self.get_id();
         if id == self_id && input.is_none() {
             return Err(Error::ValueError("Cannot share None".to_string()));
@@ -2025,6 +2368,78 @@ where
         Ok(share)
     }
 
+    async fn input_many(
+        &mut self,
+        input: Option<Vec<T>>,
+        len: usize,
+        id: usize,
+    ) -> Result<Vec<Share<T>>, Error> {
+        self.jmp_step();
+        // Same sharing as in `input`: The owner sends beta directly to one party and via JMP to
+        // the other one, which gets the hash from the first receiver.
+        let (direct, jmp) = match id {
+            0 => (1, 2),
+            1 => (2, 0),
+            2 => (1, 0),
+            _ => return Err(Error::IdError(id)),
+        };
+        let self_id = self.get_id();
+
+        let shares = if self_id == id {
+            let input = input.ok_or(Error::ValueError("Cannot share None".to_string()))?;
+            if input.len() != len {
+                return Err(Error::InvalidSizeError);
+            }
+            let mut alphas = Vec::with_capacity(len);
+            let mut betas = Vec::with_capacity(len);
+            for value in input {
+                let alpha1 = self.prf.gen_1::<T::Share>();
+                let alpha2 = self.prf.gen_2::<T::Share>();
+                let alpha3 = self.prf.gen_p::<T::Share>();
+                let alpha = alpha3 + &alpha1 + &alpha2;
+                betas.push(value.to_sharetype() + alpha);
+                alphas.push((alpha1, alpha2));
+            }
+            utils::send_vec(&mut self.network, &betas, direct).await?;
+            self.jmp_send_many::<T>(&betas, jmp).await?;
+            alphas
+                .into_iter()
+                .zip(betas)
+                .map(|((alpha1, alpha2), beta)| Share::new(alpha1, alpha2, beta))
+                .collect()
+        } else {
+            // The next party of the owner shares (alpha_p, alpha_2), the previous one
+            // (alpha_1, alpha_p)
+            let is_next = self_id == (id + 1) % 3;
+            let mut alphas = Vec::with_capacity(len);
+            for _ in 0..len {
+                if is_next {
+                    let alpha3 = self.prf.gen_p::<T::Share>();
+                    let alpha2 = self.prf.gen_2::<T::Share>();
+                    alphas.push((alpha3, alpha2));
+                } else {
+                    let alpha1 = self.prf.gen_1::<T::Share>();
+                    let alpha3 = self.prf.gen_p::<T::Share>();
+                    alphas.push((alpha1, alpha3));
+                }
+            }
+            let betas = if self_id == direct {
+                let betas: Vec<T::Share> = utils::receive_vec(&mut self.network, id, len).await?;
+                self.jmp_queue_many::<T>(&betas, jmp)?;
+                betas
+            } else {
+                self.jmp_receive_many::<T>(id, len).await?
+            };
+            alphas
+                .into_iter()
+                .zip(betas)
+                .map(|((alpha_a, alpha_b), beta)| Share::new(alpha_a, alpha_b, beta))
+                .collect()
+        };
+
+        Ok(shares)
+    }
+
     #[cfg(test)]
     async fn input_all(&mut self, input: T) -> Result<Vec<Share<T>>, Error> {
         // Since this is only for testing we perform a bad one
@@ -2150,16 +2565,19 @@ where
         Ok((c ^ a ^ b ^ rcv).convert().convert())
     }
 
-    async fn open_bit_many(&mut self, shares: Vec<Share<Bit>>) -> Result<Vec<bool>, Error> {
+    async fn open_bit_many(&mut self, shares: PackedBits<Share<Bit>>) -> Result<Vec<bool>, Error> {
         self.jmp_verify().await?;
 
         let id = self.network.get_id();
+        // Open the packed words
         let len = shares.len();
-        let mut a = Vec::with_capacity(len);
-        let mut b = Vec::with_capacity(len);
-        let mut c = Vec::with_capacity(len);
+        let words = shares.into_words();
+        let num_words = words.len();
+        let mut a = Vec::with_capacity(num_words);
+        let mut b = Vec::with_capacity(num_words);
+        let mut c = Vec::with_capacity(num_words);
 
-        for share in shares {
+        for share in words {
             let (a_, b_, c_) = share.get_abc();
             a.push(a_);
             b.push(b_);
@@ -2167,30 +2585,30 @@ where
         }
 
         let rcv = if id == 0 {
-            self.jmp_send_many::<Bit>(&a, 2).await?;
-            self.jmp_send_many::<Bit>(&b, 1).await?;
-            self.jmp_receive_many::<Bit>(1, len).await?
+            self.jmp_send_many::<u128>(&a, 2).await?;
+            self.jmp_send_many::<u128>(&b, 1).await?;
+            self.jmp_receive_many::<u128>(1, num_words).await?
         } else if id == 1 {
-            self.jmp_send_many::<Bit>(&a, 0).await?;
-            self.jmp_queue_many::<Bit>(&b, 2)?;
-            self.jmp_receive_many::<Bit>(0, len).await?
+            self.jmp_send_many::<u128>(&a, 0).await?;
+            self.jmp_queue_many::<u128>(&b, 2)?;
+            self.jmp_receive_many::<u128>(0, num_words).await?
         } else if id == 2 {
-            self.jmp_queue_many::<Bit>(&b, 0)?;
-            self.jmp_queue_many::<Bit>(&a, 1)?;
-            self.jmp_receive_many::<Bit>(0, len).await?
+            self.jmp_queue_many::<u128>(&b, 0)?;
+            self.jmp_queue_many::<u128>(&a, 1)?;
+            self.jmp_receive_many::<u128>(0, num_words).await?
         } else {
             unreachable!()
         };
 
         self.jmp_verify().await?;
 
-        let mut output = Vec::with_capacity(len);
+        let mut output = Vec::with_capacity(num_words);
 
         for (rcv_, (a_, (b_, c_))) in rcv.into_iter().zip(a.into_iter().zip(b.into_iter().zip(c))) {
-            output.push((c_ ^ a_ ^ b_ ^ rcv_).convert().convert());
+            output.push((c_ ^ a_ ^ b_ ^ rcv_).convert());
         }
 
-        Ok(output)
+        Ok(PackedBits::open_words(output, len))
     }
 
     fn add(&self, a: Share<T>, b: Share<T>) -> Share<T> {
@@ -2255,7 +2673,7 @@ where
             let mut d = Vec::with_capacity(len);
             let mut e = Vec::with_capacity(len);
 
-            for (a__, b__) in a_.into_iter().zip(b_.into_iter()) {
+            for (a__, b__) in a_.into_iter().zip(b_) {
                 let (d_, e_) = self.mul_pre(a__, b__);
                 d.push(d_);
                 e.push(e_);
@@ -2270,14 +2688,16 @@ where
     }
 
     async fn get_msb(&mut self, a: Share<T>) -> Result<Share<Bit>, Error> {
-        let bits = self.arithmetic_to_binary(a).await?;
-        Ok(bits.get_msb())
+        let res = self.msb_adder_many(vec![a]).await?;
+        Ok(res.into_iter().next().expect("One output"))
     }
 
-    async fn get_msb_many(&mut self, a: Vec<Share<T>>) -> Result<Vec<Share<Bit>>, Error> {
-        let bits = self.arithmetic_to_binary_many(a).await?;
-        let res = bits.into_iter().map(|a| a.get_msb()).collect();
-        Ok(res)
+    async fn get_msb_many(&mut self, a: Vec<Share<T>>) -> Result<PackedBits<Share<Bit>>, Error> {
+        self.msb_adder_many(a).await
+    }
+
+    fn msb_and_gates(&self, amount: usize) -> usize {
+        amount * self.adder_strategy.msb_and_gates(T::Share::K)
     }
 
     async fn binary_or(&mut self, a: Share<Bit>, b: Share<Bit>) -> Result<Share<Bit>, Error> {
@@ -2286,15 +2706,19 @@ where
 
     async fn reduce_binary_or(
         &mut self,
-        a: Vec<Share<Bit>>,
+        a: PackedBits<Share<Bit>>,
         chunk_size: usize,
     ) -> Result<Share<Bit>, Error> {
-        let packed = self.pack(a);
+        let packed = a.into_words();
         let reduced = utils::or_tree::<u128, _, _>(self, packed, chunk_size).await?;
         self.reduce_or_u128(reduced).await
     }
 
     async fn verify(&mut self) -> Result<(), Error> {
+        // The verdicts of the DZKP blame phase are only sound if the views of the honest parties
+        // are consistent, so the JMPs are checked first
+        self.jmp_verify().await?;
+
         if self.mul_proof.get_muls() != 0 {
             self.mul_verify().await?;
         }
@@ -2345,11 +2769,6 @@ where
         self.and_post_many(a, b, de).await
     }
 
-    async fn arithmetic_to_binary(&mut self, x: Share<T>) -> Result<Share<T>, Error> {
-        let (x1, x2, x3) = self.a2b_pre(x);
-        self.binary_add_3(x1, x2, x3).await
-    }
-
     async fn arithmetic_to_binary_many(
         &mut self,
         x: Vec<Share<T>>,
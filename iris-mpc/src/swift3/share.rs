@@ -1,8 +1,9 @@
 use crate::{
     prelude::{Bit, Sharable},
-    traits::share_trait::ShareTrait,
+    traits::share_trait::{BitShareTrait, ShareTrait},
     types::{
         int_ring::IntRing2k,
+        packed_bits::{get_ring_bit, set_ring_bit},
         ring_element::{RingElement, RingImpl},
     },
 };
@@ -21,7 +22,9 @@ use std::{
 //   P_1: (a2, a1, b)
 //   P_2: (a3, a2, b)
 // where b = x + a1 + a2 + a3
+// The C layout is relied upon by `unsafe impl FileShare for Swift3Share` in db/share_file.rs
 #[derive(Clone, Debug, PartialEq, Default, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[repr(C)]
 pub struct Share<T: Sharable> {
     pub(crate) a: T::Share,
     pub(crate) b: T::Share,
@@ -33,6 +36,24 @@ impl<T: Sharable> ShareTrait for Share<T> {
     type VecShare = Vec<Self>;
 }
 
+impl BitShareTrait for Share<Bit> {
+    type Word = Share<u128>;
+
+    fn get_bit(word: &Share<u128>, index: usize) -> Self {
+        Share::new(
+            get_ring_bit(&word.a, index),
+            get_ring_bit(&word.b, index),
+            get_ring_bit(&word.c, index),
+        )
+    }
+
+    fn set_bit(word: &mut Share<u128>, index: usize, bit: Self) {
+        set_ring_bit(&mut word.a, index, bit.a);
+        set_ring_bit(&mut word.b, index, bit.b);
+        set_ring_bit(&mut word.c, index, bit.c);
+    }
+}
+
 impl<T: Sharable> Share<T> {
     pub fn new(a: T::Share, b: T::Share, c: T::Share) -> Self {
         Share {
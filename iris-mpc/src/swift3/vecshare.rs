@@ -1,13 +1,16 @@
+use bytes::Bytes;
 use num_traits::Zero;
 
 use super::share::Share;
 use crate::{
+    aby3::utils,
     prelude::{Error, Sharable},
     traits::share_trait::VecShareTrait,
 };
 
 impl<T: Sharable> VecShareTrait for Vec<Share<T>> {
     type Share = Share<T>;
+    type Slice = [Share<T>];
 
     fn len(&self) -> usize {
         Vec::len(self)
@@ -15,7 +18,7 @@ impl<T: Sharable> VecShareTrait for Vec<Share<T>> {
 
     fn filter_reduce_add_twice(
         a: &Self,
-        b: &Self,
+        b: &Self::Slice,
         mask: &plain_reference::IrisCodeArray,
     ) -> Result<(Self::Share, Self::Share), Error> {
         if a.is_empty() || a.len() != b.len() {
@@ -100,4 +103,16 @@ impl<T: Sharable> VecShareTrait for Vec<Share<T>> {
     fn set_at(&mut self, index: usize, value: Self::Share) {
         self[index] = value;
     }
+
+    fn replicated_components(slice: &Self::Slice) -> Result<[Vec<Bytes>; 2], Error> {
+        // c is held by all parties
+        let c = utils::ring_iter_to_bytes(slice.iter().map(|s| &s.c));
+        Ok([
+            vec![
+                utils::ring_iter_to_bytes(slice.iter().map(|s| &s.a)),
+                c.clone(),
+            ],
+            vec![utils::ring_iter_to_bytes(slice.iter().map(|s| &s.b)), c],
+        ])
+    }
 }
@@ -0,0 +1,655 @@
+mod blame_test {
+    use crate::{
+        aby3::share::Share as Aby3Share,
+        prelude::{
+            BinaryVerification, Bit, Error, IrisSwift3, MalAby3, NetworkTrait, PartyTestNetwork,
+            SpdzWise, Swift3, Swift3Share, TestNetwork3p,
+        },
+        spdzwise::protocol::TShare,
+        tests::iris_config::iris_config::create_database,
+        traits::{binary_trait::BinaryMpcTrait, mpc_trait::MpcTrait},
+        types::sharable::Sharable,
+    };
+    use bytes::{Bytes, BytesMut};
+    use num_traits::Zero;
+    use plain_reference::{IrisCode, IrisCodeArray};
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha12Rng;
+    use std::{
+        io,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    const NUM_PARTIES: usize = PartyTestNetwork::NUM_PARTIES;
+
+    /// Flips a bit in the first message sent to the next party after being armed, or in the first
+    /// broadcast message if `broadcast` is set
+    struct TamperNetwork {
+        net: PartyTestNetwork,
+        armed: Arc<AtomicBool>,
+        broadcast: bool,
+    }
+
+    impl TamperNetwork {
+        fn tamper(&self, data: Bytes) -> Bytes {
+            if !self.armed.swap(false, Ordering::SeqCst) {
+                return data;
+            }
+            let mut data = BytesMut::from(data.as_ref());
+            data[0] ^= 1;
+            data.freeze()
+        }
+    }
+
+    impl NetworkTrait for TamperNetwork {
+        fn get_id(&self) -> usize {
+            self.net.get_id()
+        }
+
+        fn get_num_parties(&self) -> usize {
+            self.net.get_num_parties()
+        }
+
+        fn print_connection_stats(&self, out: &mut impl std::io::Write) -> io::Result<()> {
+            self.net.print_connection_stats(out)
+        }
+
+        async fn shutdown(self) -> io::Result<()> {
+            self.net.shutdown().await
+        }
+
+        async fn send(&mut self, id: usize, data: Bytes) -> io::Result<()> {
+            let data = if id == (self.get_id() + 1) % 3 && !self.broadcast {
+                self.tamper(data)
+            } else {
+                data
+            };
+            self.net.send(id, data).await
+        }
+
+        async fn send_next_id(&mut self, data: Bytes) -> io::Result<()> {
+            let data = if self.broadcast {
+                data
+            } else {
+                self.tamper(data)
+            };
+            self.net.send_next_id(data).await
+        }
+
+        async fn send_prev_id(&mut self, data: Bytes) -> io::Result<()> {
+            self.net.send_prev_id(data).await
+        }
+
+        async fn receive(&mut self, id: usize) -> io::Result<BytesMut> {
+            self.net.receive(id).await
+        }
+
+        async fn receive_prev_id(&mut self) -> io::Result<BytesMut> {
+            self.net.receive_prev_id().await
+        }
+
+        async fn receive_next_id(&mut self) -> io::Result<BytesMut> {
+            self.net.receive_next_id().await
+        }
+
+        async fn broadcast(&mut self, data: Bytes) -> io::Result<Vec<BytesMut>> {
+            // All parties receive the same tampered message
+            let data = if self.broadcast {
+                self.tamper(data)
+            } else {
+                data
+            };
+            self.net.broadcast(data).await
+        }
+    }
+
+    // Only party 0 can be armed
+    fn tamper_networks(broadcast: bool) -> ([TamperNetwork; 3], Arc<AtomicBool>) {
+        let network = TestNetwork3p::new();
+        let armed = Arc::new(AtomicBool::new(false));
+        let [n0, n1, n2] = network.get_party_networks();
+        let nets = [
+            TamperNetwork {
+                net: n0,
+                armed: armed.to_owned(),
+                broadcast,
+            },
+            TamperNetwork {
+                net: n1,
+                armed: Arc::new(AtomicBool::new(false)),
+                broadcast,
+            },
+            TamperNetwork {
+                net: n2,
+                armed: Arc::new(AtomicBool::new(false)),
+                broadcast,
+            },
+        ];
+        (nets, armed)
+    }
+
+    fn get_tamper_networks() -> ([TamperNetwork; 3], Arc<AtomicBool>) {
+        tamper_networks(false)
+    }
+
+    async fn check_results(tasks: Vec<tokio::task::JoinHandle<Result<bool, Error>>>) {
+        for t in tasks {
+            let res = t.await.expect("Task exited normally");
+            assert!(matches!(res, Err(Error::ConflictingParties(0, 1))));
+        }
+    }
+
+    #[tokio::test]
+    async fn aby3_mal_blame_test() {
+        let mut tasks = Vec::with_capacity(NUM_PARTIES);
+        let (nets, armed) = get_tamper_networks();
+
+        for n in nets {
+            let armed = armed.to_owned();
+            let t = tokio::spawn(async move {
+                let mut protocol = MalAby3::new(n);
+                MpcTrait::<u16, Aby3Share<u16>, Aby3Share<Bit>>::preprocess(&mut protocol).await?;
+                let id = MpcTrait::<u16, Aby3Share<u16>, Aby3Share<Bit>>::get_id(&protocol);
+                if id == 0 {
+                    armed.store(true, Ordering::SeqCst);
+                }
+                MpcTrait::<u16, Aby3Share<u16>, Aby3Share<Bit>>::open_bit(
+                    &mut protocol,
+                    Aby3Share::zero(),
+                )
+                .await
+            });
+            tasks.push(t);
+        }
+
+        check_results(tasks).await;
+    }
+
+    #[tokio::test]
+    async fn aby3_mal_input_blame_test() {
+        let mut tasks = Vec::with_capacity(NUM_PARTIES);
+        let (nets, armed) = get_tamper_networks();
+
+        for n in nets {
+            let armed = armed.to_owned();
+            let t = tokio::spawn(async move {
+                let mut protocol = MalAby3::new(n);
+                MpcTrait::<u16, Aby3Share<u16>, Aby3Share<Bit>>::preprocess(&mut protocol).await?;
+                let id = MpcTrait::<u16, Aby3Share<u16>, Aby3Share<Bit>>::get_id(&protocol);
+                let input = if id == 0 {
+                    armed.store(true, Ordering::SeqCst);
+                    Some(5)
+                } else {
+                    None
+                };
+                MpcTrait::<u16, Aby3Share<u16>, Aby3Share<Bit>>::input(&mut protocol, input, 0)
+                    .await
+            });
+            tasks.push(t);
+        }
+
+        // Party 0 sends a different value to party 1, which both receivers detect
+        let mut res = Vec::with_capacity(NUM_PARTIES);
+        for t in tasks {
+            res.push(t.await.expect("Task exited normally"));
+        }
+        assert!(res[0].is_ok());
+        assert!(matches!(res[1], Err(Error::ConflictingParties(0, 2))));
+        assert!(matches!(res[2], Err(Error::ConflictingParties(0, 1))));
+    }
+
+    #[tokio::test]
+    async fn swift3_blame_test() {
+        let mut tasks = Vec::with_capacity(NUM_PARTIES);
+        let (nets, armed) = get_tamper_networks();
+
+        for n in nets {
+            let armed = armed.to_owned();
+            let t = tokio::spawn(async move {
+                let mut protocol = Swift3::<_, u16>::new(n);
+                MpcTrait::<u16, Swift3Share<u16>, Swift3Share<Bit>>::preprocess(&mut protocol)
+                    .await?;
+                let id = MpcTrait::<u16, Swift3Share<u16>, Swift3Share<Bit>>::get_id(&protocol);
+                if id == 0 {
+                    armed.store(true, Ordering::SeqCst);
+                }
+                MpcTrait::<u16, Swift3Share<u16>, Swift3Share<Bit>>::open_bit(
+                    &mut protocol,
+                    Swift3Share::zero(),
+                )
+                .await
+            });
+            tasks.push(t);
+        }
+
+        check_results(tasks).await;
+    }
+
+    #[tokio::test]
+    async fn swift3_chained_mul_blame_test() {
+        let mut tasks = Vec::with_capacity(NUM_PARTIES);
+        let (nets, armed) = get_tamper_networks();
+
+        for n in nets {
+            let armed = armed.to_owned();
+            let t = tokio::spawn(async move {
+                let mut protocol = Swift3::<_, u16>::new(n);
+                MpcTrait::<u16, Swift3Share<u16>, Swift3Share<Bit>>::preprocess(&mut protocol)
+                    .await?;
+                let id = MpcTrait::<u16, Swift3Share<u16>, Swift3Share<Bit>>::get_id(&protocol);
+                if id == 0 {
+                    armed.store(true, Ordering::SeqCst);
+                }
+                // The tampered message of the first multiplication makes the values of the two
+                // honest parties differ in the later ones, which are verified in the same batch
+                let mut share = Swift3Share::zero();
+                for _ in 0..3 {
+                    share = MpcTrait::<u16, Swift3Share<u16>, Swift3Share<Bit>>::mul(
+                        &mut protocol,
+                        share.to_owned(),
+                        share,
+                    )
+                    .await?;
+                }
+                MpcTrait::<u16, Swift3Share<u16>, Swift3Share<Bit>>::open(&mut protocol, share)
+                    .await
+                    .map(|res| res == 0)
+            });
+            tasks.push(t);
+        }
+
+        check_results(tasks).await;
+    }
+
+    #[tokio::test]
+    async fn spdzwise_blame_test() {
+        let mut tasks = Vec::with_capacity(NUM_PARTIES);
+        let (nets, armed) = get_tamper_networks();
+
+        for n in nets {
+            let armed = armed.to_owned();
+            let t = tokio::spawn(async move {
+                let mut protocol = SpdzWise::<_, <u16 as Sharable>::VerificationShare>::new(n);
+                MpcTrait::<u16, TShare<u16>, Aby3Share<Bit>>::preprocess(&mut protocol).await?;
+                let id = MpcTrait::<u16, TShare<u16>, Aby3Share<Bit>>::get_id(&protocol);
+                if id == 0 {
+                    armed.store(true, Ordering::SeqCst);
+                }
+                MpcTrait::<u16, TShare<u16>, Aby3Share<Bit>>::open_bit(
+                    &mut protocol,
+                    Aby3Share::zero(),
+                )
+                .await
+            });
+            tasks.push(t);
+        }
+
+        check_results(tasks).await;
+    }
+
+    #[tokio::test]
+    async fn spdzwise_mac_reshare_test() {
+        // Party 0 adds an error to its reshare of the first MAC of the GF(2^64) check. All inputs
+        // of the AND gates are zero, so the error is only detected if the detection does not
+        // depend on the secret bits.
+        let mut tasks = Vec::with_capacity(NUM_PARTIES);
+        let (nets, armed) = get_tamper_networks();
+
+        for n in nets {
+            let armed = armed.to_owned();
+            let t = tokio::spawn(async move {
+                let mut protocol =
+                    SpdzWise::<_, <u16 as Sharable>::VerificationShare>::with_binary_verification(
+                        n,
+                        BinaryVerification::Gf2p64Mac,
+                    );
+                MpcTrait::<u16, TShare<u16>, Aby3Share<Bit>>::preprocess(&mut protocol).await?;
+                let id = MpcTrait::<u16, TShare<u16>, Aby3Share<Bit>>::get_id(&protocol);
+                let zeros = vec![Aby3Share::<u128>::zero(); 2];
+                BinaryMpcTrait::<u128, _>::and_many(&mut protocol, &zeros, &zeros).await?;
+                if id == 0 {
+                    armed.store(true, Ordering::SeqCst);
+                }
+                MpcTrait::<u16, TShare<u16>, Aby3Share<Bit>>::verify(&mut protocol).await
+            });
+            tasks.push(t);
+        }
+
+        for t in tasks {
+            let res = t.await.expect("Task exited normally");
+            assert!(matches!(res, Err(Error::VerifyError)));
+        }
+    }
+
+    async fn swift3_verify(
+        n: TamperNetwork,
+        armed: Arc<AtomicBool>,
+        values: [u16; 4],
+        robust: bool,
+    ) -> Result<Vec<u16>, Error> {
+        let mut protocol = if robust {
+            Swift3::<_, u16>::new_robust(n)
+        } else {
+            Swift3::<_, u16>::new(n)
+        };
+        MpcTrait::<u16, Swift3Share<u16>, Swift3Share<Bit>>::preprocess(&mut protocol).await?;
+        let id = MpcTrait::<u16, Swift3Share<u16>, Swift3Share<Bit>>::get_id(&protocol);
+
+        let mut shares = Vec::with_capacity(values.len());
+        for value in values {
+            let input = if id == 0 { Some(value) } else { None };
+            shares.push(protocol.input(input, 0).await?);
+        }
+        if id == 0 {
+            armed.store(true, Ordering::SeqCst);
+        }
+
+        let mul = protocol
+            .mul(shares[0].to_owned(), shares[1].to_owned())
+            .await?;
+        let dot = protocol
+            .dot(shares[..2].to_vec(), shares[2..].to_vec())
+            .await?;
+        let msb = MpcTrait::<u16, Swift3Share<u16>, Swift3Share<Bit>>::get_msb(
+            &mut protocol,
+            shares[3].to_owned(),
+        )
+        .await?;
+        MpcTrait::<u16, Swift3Share<u16>, Swift3Share<Bit>>::verify(&mut protocol).await?;
+
+        let mut res = protocol.open_many(vec![mul, dot]).await?;
+        let msb = MpcTrait::<u16, Swift3Share<u16>, Swift3Share<Bit>>::open_bit(&mut protocol, msb)
+            .await?;
+        res.push(msb as u16);
+        Ok(res)
+    }
+
+    #[tokio::test]
+    async fn swift3_robust_honest_test() {
+        let mut tasks = Vec::with_capacity(NUM_PARTIES);
+        let (nets, _) = get_tamper_networks();
+        let values = [3, 5, 7, u16::MAX];
+
+        for n in nets {
+            // Never armed
+            let t = tokio::spawn(async move {
+                swift3_verify(n, Arc::new(AtomicBool::new(false)), values, true).await
+            });
+            tasks.push(t);
+        }
+
+        for t in tasks {
+            let res = t.await.expect("Task exited normally").unwrap();
+            assert_eq!(
+                res,
+                vec![15, 21u16.wrapping_add(5u16.wrapping_mul(u16::MAX)), 1]
+            );
+        }
+    }
+
+    async fn swift3_dzkp_blame_test_impl(robust: bool) {
+        let mut tasks = Vec::with_capacity(NUM_PARTIES);
+        let (nets, armed) = get_tamper_networks();
+        let values = [3, 5, 7, u16::MAX];
+
+        for n in nets {
+            let armed = armed.to_owned();
+            let t = tokio::spawn(async move { swift3_verify(n, armed, values, robust).await });
+            tasks.push(t);
+        }
+
+        for t in tasks {
+            let res = t.await.expect("Task exited normally");
+            assert!(matches!(res, Err(Error::ConflictingParties(0, 1))));
+        }
+    }
+
+    #[tokio::test]
+    async fn swift3_robust_dzkp_blame_test() {
+        swift3_dzkp_blame_test_impl(true).await;
+    }
+
+    #[tokio::test]
+    async fn swift3_dzkp_blame_test() {
+        swift3_dzkp_blame_test_impl(false).await;
+    }
+
+    const TTP_INPUTS: usize = 10;
+    const TTP_TIMEOUT_MS: u64 = 200;
+
+    async fn swift3_ttp(
+        n: TamperNetwork,
+        armed: Arc<AtomicBool>,
+        verdict: Error,
+        silent: bool,
+    ) -> Result<(usize, Option<Vec<u16>>, bool), Error> {
+        let mut protocol = Swift3::<_, u16>::new_robust(n);
+        protocol.set_ttp_timeout(Duration::from_millis(TTP_TIMEOUT_MS));
+        MpcTrait::<u16, Swift3Share<u16>, Swift3Share<Bit>>::preprocess(&mut protocol).await?;
+        let id = MpcTrait::<u16, Swift3Share<u16>, Swift3Share<Bit>>::get_id(&protocol);
+
+        // We simulate the parties already knowing the shares of the inputs
+        let mut rng = ChaCha12Rng::seed_from_u64(42);
+        let shares: Vec<_> = (0..TTP_INPUTS as u16)
+            .map(|i| {
+                Swift3::<PartyTestNetwork, u16>::share(
+                    i,
+                    <u16 as Sharable>::VerificationShare::default(),
+                    &mut rng,
+                )[id]
+                    .to_owned()
+            })
+            .collect();
+
+        let commitments = protocol.commit_inputs::<u16>(&shares).await?;
+
+        if id == 0 {
+            armed.store(true, Ordering::SeqCst);
+        }
+        let ttp = crate::blame::elect_ttp(&verdict).unwrap();
+        let inputs = if silent && id == 0 {
+            // The cheater does not send its components, but waits for the output
+            Ok(None)
+        } else {
+            protocol
+                .ttp_reconstruct::<u16>(&verdict, Some(&commitments), shares)
+                .await
+        };
+        let output = match &inputs {
+            Ok(Some(inputs)) => Some(Ok(inputs.iter().sum::<u16>() % 2 == 1)),
+            Ok(None) => None,
+            Err(_) => Some(Err(Error::ValueError("Inconsistent inputs".to_string()))),
+        };
+        let output = protocol.ttp_output(ttp, output).await?;
+        Ok((id, inputs.ok().flatten(), output))
+    }
+
+    async fn swift3_ttp_test_impl(verdict: fn() -> Error, ttp: usize, silent: bool) {
+        let mut tasks = Vec::with_capacity(NUM_PARTIES);
+        let (nets, armed) = get_tamper_networks();
+
+        for n in nets {
+            let armed = armed.to_owned();
+            let t = tokio::spawn(async move { swift3_ttp(n, armed, verdict(), silent).await });
+            tasks.push(t);
+        }
+
+        let expected = (0..TTP_INPUTS as u16).collect::<Vec<_>>();
+        for t in tasks {
+            let (id, inputs, output) = t.await.expect("Task exited normally").unwrap();
+            if id == ttp {
+                assert_eq!(inputs.as_ref(), Some(&expected));
+            } else {
+                assert!(inputs.is_none());
+            }
+            assert!(output);
+        }
+    }
+
+    #[tokio::test]
+    async fn swift3_ttp_conflict_test() {
+        // Party 0 tampers with a message to party 1, but the TTP is party 2
+        swift3_ttp_test_impl(|| Error::ConflictingParties(0, 1), 2, false).await
+    }
+
+    #[tokio::test]
+    async fn swift3_ttp_silent_test() {
+        // The TTP stops waiting for the components of party 0 and takes the ones of party 1
+        swift3_ttp_test_impl(|| Error::ConflictingParties(0, 1), 2, true).await
+    }
+
+    #[tokio::test]
+    async fn swift3_ttp_cheater_test() {
+        // Party 0 does not send to the TTP, since it is known to be the cheater
+        swift3_ttp_test_impl(|| Error::CheatingParty(0), 1, false).await
+    }
+
+    const ROBUST_DB_SIZE: usize = 2;
+    const ROBUST_CHUNK_SIZE: usize = 1;
+
+    // The shares of a query, which is close to an entry of the database, and of the database
+    struct RobustInputs {
+        query: Vec<Swift3Share<u16>>,
+        db: Vec<Vec<Swift3Share<u16>>>,
+        query_mask: IrisCodeArray,
+        db_masks: Vec<IrisCodeArray>,
+    }
+
+    fn share_code<R: Rng>(code: &IrisCode, id: usize, rng: &mut R) -> Vec<Swift3Share<u16>> {
+        // We simulate the parties already knowing the shares of the code
+        (0..IrisCode::IRIS_CODE_SIZE)
+            .map(|i| {
+                Swift3::<PartyTestNetwork, u16>::share(
+                    u16::from(code.code.get_bit(i)),
+                    <u16 as Sharable>::VerificationShare::default(),
+                    rng,
+                )[id]
+                    .to_owned()
+            })
+            .collect()
+    }
+
+    fn robust_inputs(id: usize) -> RobustInputs {
+        let mut rng = ChaCha12Rng::seed_from_u64(42);
+        let db = create_database(ROBUST_DB_SIZE, &mut rng);
+        let query = db[1].get_similar_iris(&mut rng);
+        assert!(db.iter().any(|code| query.is_close(code)));
+
+        RobustInputs {
+            query: share_code(&query, id, &mut rng),
+            db: db
+                .iter()
+                .map(|code| share_code(code, id, &mut rng))
+                .collect(),
+            query_mask: query.mask,
+            db_masks: db.iter().map(|code| code.mask).collect(),
+        }
+    }
+
+    async fn swift3_robust_iris(n: TamperNetwork, armed: Arc<AtomicBool>) -> Result<bool, Error> {
+        let mut iris = IrisSwift3::new(Swift3::<_, u16>::new_robust(n))?;
+        iris.preprocessing().await?;
+        let inputs = robust_inputs(iris.get_id());
+
+        if iris.get_id() == 0 {
+            armed.store(true, Ordering::SeqCst);
+        }
+        iris.iris_in_db_robust(
+            &inputs.query,
+            &inputs.db,
+            &inputs.query_mask,
+            &inputs.db_masks,
+            ROBUST_CHUNK_SIZE,
+        )
+        .await
+    }
+
+    // Follows IrisSwift3::iris_in_db_robust, but tampers with a JMP message and afterwards with the
+    // components handed to the TTP
+    async fn swift3_robust_iris_cheater(
+        n: TamperNetwork,
+        armed: Arc<AtomicBool>,
+    ) -> Result<bool, Error> {
+        let mut iris = IrisSwift3::new(Swift3::<_, u16>::new_robust(n))?;
+        iris.preprocessing().await?;
+        let inputs = robust_inputs(iris.get_id());
+
+        let mut shares = inputs.query.to_owned();
+        for code in inputs.db.iter() {
+            shares.extend_from_slice(code);
+        }
+        let commitments = iris.get_mpc_mut().commit_inputs(&shares).await?;
+
+        armed.store(true, Ordering::SeqCst);
+        let verdict = iris
+            .iris_in_db(
+                &inputs.query,
+                &inputs.db,
+                &inputs.query_mask,
+                &inputs.db_masks,
+                ROBUST_CHUNK_SIZE,
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(verdict, Error::ConflictingParties(0, 1)));
+
+        // The TTP is party 2, which is missing the a components of party 0
+        let shares = shares
+            .into_iter()
+            .map(|mut share| {
+                share.a = !share.a;
+                share
+            })
+            .collect();
+        let mpc = iris.get_mpc_mut();
+        let res = mpc
+            .ttp_reconstruct::<u16>(&verdict, Some(&commitments), shares)
+            .await?;
+        assert!(res.is_none());
+        mpc.ttp_output(2, None).await
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+    async fn swift3_robust_iris_cheater_test() {
+        // Party 0 sends a malformed commitment, so it is named as the cheater before matching
+        let mut tasks = Vec::with_capacity(NUM_PARTIES);
+        let (nets, armed) = tamper_networks(true);
+
+        for n in nets {
+            let armed = armed.to_owned();
+            let t = tokio::spawn(async move { swift3_robust_iris(n, armed).await });
+            tasks.push(t);
+        }
+
+        for t in tasks {
+            assert!(t.await.expect("Task exited normally").unwrap());
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+    async fn swift3_robust_iris_conflict_test() {
+        // Party 0 tampers with a message to party 1 and then hands wrong components to the TTP
+        let mut tasks = Vec::with_capacity(NUM_PARTIES);
+        let (nets, armed) = get_tamper_networks();
+
+        for n in nets {
+            let armed = armed.to_owned();
+            let t = tokio::spawn(async move {
+                if n.get_id() == 0 {
+                    swift3_robust_iris_cheater(n, armed).await
+                } else {
+                    swift3_robust_iris(n, armed).await
+                }
+            });
+            tasks.push(t);
+        }
+
+        for t in tasks {
+            assert!(t.await.expect("Task exited normally").unwrap());
+        }
+    }
+}
@@ -0,0 +1,347 @@
+mod bristol_test {
+    use crate::{
+        aby3::share::Share,
+        prelude::{Aby3, Circuit, MalAby3, NetworkTrait, PartyTestNetwork, TestNetwork3p},
+        traits::mpc_trait::{MpcTrait, Plain},
+        types::{bit::Bit, ring_element::RingElement},
+    };
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha12Rng;
+
+    const NUM_PARTIES: usize = PartyTestNetwork::NUM_PARTIES;
+    const BITS: usize = 16;
+    // A 64-bit ripple-carry adder with the interface of adder64.txt of the Bristol Fashion
+    // collection, i.e., two 64-bit inputs and the 64-bit sum as output
+    const ADDER64: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/src/tests/circuits/adder64.txt"
+    );
+
+    // Builds a circuit for two BITS-bit inputs a, b with the outputs a + b, !(a + b) and a & b.
+    // The addition uses a ripple-carry adder, the AND a single MAND gate.
+    fn adder_circuit() -> String {
+        let mut gates = Vec::new();
+        let mut next = 2 * BITS;
+        let mut new_wire = || {
+            next += 1;
+            next - 1
+        };
+
+        let mut carry = new_wire();
+        gates.push(format!("1 1 0 {carry} EQ"));
+        let mut sum = Vec::with_capacity(BITS);
+        for i in 0..BITS {
+            let (a, b) = (i, BITS + i);
+            let t = new_wire();
+            gates.push(format!("2 1 {a} {b} {t} XOR"));
+            let s = new_wire();
+            gates.push(format!("2 1 {t} {carry} {s} XOR"));
+            sum.push(s);
+
+            if i + 1 < BITS {
+                // carry = carry ^ ((a ^ carry) & (b ^ carry))
+                let (x, y, z, c) = (new_wire(), new_wire(), new_wire(), new_wire());
+                gates.push(format!("2 1 {a} {carry} {x} XOR"));
+                gates.push(format!("2 1 {b} {carry} {y} XOR"));
+                gates.push(format!("2 1 {x} {y} {z} AND"));
+                gates.push(format!("2 1 {carry} {z} {c} XOR"));
+                carry = c;
+            }
+        }
+
+        // The outputs have to be the last wires
+        for s in sum.iter() {
+            let o = new_wire();
+            gates.push(format!("1 1 {s} {o} EQW"));
+        }
+        for s in sum.iter() {
+            let o = new_wire();
+            gates.push(format!("1 1 {s} {o} INV"));
+        }
+        let ins = (0..2 * BITS).map(|w| w.to_string()).collect::<Vec<_>>();
+        let outs = (0..BITS)
+            .map(|_| new_wire().to_string())
+            .collect::<Vec<_>>();
+        gates.push(format!(
+            "{} {BITS} {} {} MAND",
+            2 * BITS,
+            ins.join(" "),
+            outs.join(" ")
+        ));
+
+        format!(
+            "{} {}\n2 {BITS} {BITS}\n3 {BITS} {BITS} {BITS}\n\n{}\n",
+            gates.len(),
+            next,
+            gates.join("\n")
+        )
+    }
+
+    // Bit j of value k is stored in bit k of wire j
+    fn pack(values: &[u16]) -> Vec<u128> {
+        let mut res = vec![0u128; BITS];
+        for (k, v) in values.iter().enumerate() {
+            for (j, r) in res.iter_mut().enumerate() {
+                *r |= u128::from((v >> j) & 1) << k;
+            }
+        }
+        res
+    }
+
+    fn unpack(wires: &[u128]) -> Vec<u16> {
+        (0..128)
+            .map(|k| {
+                wires
+                    .iter()
+                    .enumerate()
+                    .fold(0u16, |acc, (j, w)| acc | (((w >> k) & 1) as u16) << j)
+            })
+            .collect()
+    }
+
+    fn expected(a: &[u16], b: &[u16]) -> Vec<Vec<u16>> {
+        let sum = a
+            .iter()
+            .zip(b)
+            .map(|(a_, b_)| a_.wrapping_add(*b_))
+            .collect::<Vec<_>>();
+        let not_sum = sum.iter().map(|s| !s).collect();
+        let and = a.iter().zip(b).map(|(a_, b_)| a_ & b_).collect();
+        vec![sum, not_sum, and]
+    }
+
+    #[test]
+    fn circuit_stats_test() {
+        let circuit = adder_circuit().parse::<Circuit>().unwrap();
+        assert_eq!(circuit.input_sizes(), &[BITS, BITS]);
+        assert_eq!(circuit.output_sizes(), &[BITS, BITS, BITS]);
+        assert_eq!(circuit.and_gates(), 2 * BITS - 1);
+        // The MAND gate is evaluated in the first layer together with the first carry
+        assert_eq!(circuit.and_depth(), BITS - 1);
+    }
+
+    #[tokio::test]
+    async fn plain_test() {
+        let circuit = adder_circuit().parse::<Circuit>().unwrap();
+        let mut rng = ChaCha12Rng::from_entropy();
+        let a = (0..128).map(|_| rng.gen::<u16>()).collect::<Vec<_>>();
+        let b = (0..128).map(|_| rng.gen::<u16>()).collect::<Vec<_>>();
+
+        let mut plain = Plain::default();
+        let res = circuit
+            .evaluate::<u128, _, _>(&mut plain, vec![pack(&a), pack(&b)])
+            .await
+            .unwrap();
+
+        let res = res.iter().map(|r| unpack(r)).collect::<Vec<_>>();
+        assert_eq!(res, expected(&a, &b));
+
+        // Wrong input sizes
+        assert!(circuit
+            .evaluate::<u128, _, _>(&mut plain, vec![pack(&a)])
+            .await
+            .is_err());
+    }
+
+    // XOR-shares the wires in the replicated format of Aby3
+    fn share_wires<R: Rng>(wires: &[u128], id: usize, rng: &mut R) -> Vec<Share<u128>> {
+        wires
+            .iter()
+            .map(|w| {
+                let a = rng.gen::<u128>();
+                let b = rng.gen::<u128>();
+                let c = w ^ a ^ b;
+                let shares = [(a, c), (b, a), (c, b)];
+                let (x, y) = shares[id];
+                Share::new(RingElement(x), RingElement(y))
+            })
+            .collect()
+    }
+
+    async fn aby3_test_party(
+        net: PartyTestNetwork,
+        seed: <ChaCha12Rng as SeedableRng>::Seed,
+        a: Vec<u128>,
+        b: Vec<u128>,
+    ) -> Vec<Vec<u128>> {
+        let circuit = adder_circuit().parse::<Circuit>().unwrap();
+        let mut protocol = Aby3::<PartyTestNetwork>::new(net);
+        MpcTrait::<u128, Share<u128>, Share<Bit>>::preprocess(&mut protocol)
+            .await
+            .unwrap();
+        let id = MpcTrait::<u128, Share<u128>, Share<Bit>>::get_id(&protocol);
+
+        let mut rng = ChaCha12Rng::from_seed(seed);
+        let a = share_wires(&a, id, &mut rng);
+        let b = share_wires(&b, id, &mut rng);
+
+        let res = circuit
+            .evaluate::<u128, _, _>(&mut protocol, vec![a, b])
+            .await
+            .unwrap();
+
+        MpcTrait::<u128, Share<u128>, Share<Bit>>::finish(protocol)
+            .await
+            .unwrap();
+        res.into_iter()
+            .map(|r| r.into_iter().map(|s| s.get_a().convert()).collect())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn aby3_test() {
+        let mut rng = ChaCha12Rng::from_entropy();
+        let seed = rng.gen::<<ChaCha12Rng as SeedableRng>::Seed>();
+        let a = (0..128).map(|_| rng.gen::<u16>()).collect::<Vec<_>>();
+        let b = (0..128).map(|_| rng.gen::<u16>()).collect::<Vec<_>>();
+
+        let network = TestNetwork3p::new();
+        let net = network.get_party_networks();
+
+        let mut tasks = Vec::with_capacity(NUM_PARTIES);
+        for n in net {
+            let (a_, b_) = (pack(&a), pack(&b));
+            let t = tokio::spawn(async move { aby3_test_party(n, seed, a_, b_).await });
+            tasks.push(t);
+        }
+
+        let mut res: Option<Vec<Vec<u128>>> = None;
+        for t in tasks {
+            let r = t.await.expect("Task exited normally");
+            match res.as_mut() {
+                None => res = Some(r),
+                Some(res) => {
+                    for (res_, r_) in res.iter_mut().zip(r) {
+                        for (x, y) in res_.iter_mut().zip(r_) {
+                            *x ^= y;
+                        }
+                    }
+                }
+            }
+        }
+
+        let res = res.unwrap().iter().map(|r| unpack(r)).collect::<Vec<_>>();
+        assert_eq!(res, expected(&a, &b));
+    }
+
+    // Bit j of value k is stored in bit k of wire j
+    fn pack_u64(values: &[u64]) -> Vec<u128> {
+        let mut res = vec![0u128; 64];
+        for (k, v) in values.iter().enumerate() {
+            for (j, r) in res.iter_mut().enumerate() {
+                *r |= u128::from((v >> j) & 1) << k;
+            }
+        }
+        res
+    }
+
+    fn unpack_u64(wires: &[u128]) -> Vec<u64> {
+        (0..128)
+            .map(|k| {
+                wires
+                    .iter()
+                    .enumerate()
+                    .fold(0u64, |acc, (j, w)| acc | (((w >> k) & 1) as u64) << j)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn adder64_stats_test() {
+        let circuit = Circuit::from_file(ADDER64).unwrap();
+        assert_eq!(circuit.input_sizes(), &[64, 64]);
+        assert_eq!(circuit.output_sizes(), &[64]);
+        assert_eq!(circuit.and_gates(), 63);
+        assert_eq!(circuit.and_depth(), 63);
+    }
+
+    async fn adder64_party(
+        net: PartyTestNetwork,
+        seed: <ChaCha12Rng as SeedableRng>::Seed,
+        a: Vec<u128>,
+        b: Vec<u128>,
+        malicious: bool,
+    ) -> Vec<u128> {
+        let circuit = Circuit::from_file(ADDER64).unwrap();
+        let id = net.get_id();
+        let mut rng = ChaCha12Rng::from_seed(seed);
+        let inputs = vec![share_wires(&a, id, &mut rng), share_wires(&b, id, &mut rng)];
+
+        let res = if malicious {
+            let mut protocol = MalAby3::<PartyTestNetwork>::new(net);
+            MpcTrait::<u128, Share<u128>, Share<Bit>>::preprocess(&mut protocol)
+                .await
+                .unwrap();
+            let res = circuit
+                .evaluate::<u128, _, _>(&mut protocol, inputs)
+                .await
+                .unwrap();
+            MpcTrait::<u128, Share<u128>, Share<Bit>>::verify(&mut protocol)
+                .await
+                .unwrap();
+            MpcTrait::<u128, Share<u128>, Share<Bit>>::finish(protocol)
+                .await
+                .unwrap();
+            res
+        } else {
+            let mut protocol = Aby3::<PartyTestNetwork>::new(net);
+            MpcTrait::<u128, Share<u128>, Share<Bit>>::preprocess(&mut protocol)
+                .await
+                .unwrap();
+            let res = circuit
+                .evaluate::<u128, _, _>(&mut protocol, inputs)
+                .await
+                .unwrap();
+            MpcTrait::<u128, Share<u128>, Share<Bit>>::finish(protocol)
+                .await
+                .unwrap();
+            res
+        };
+        res[0]
+            .iter()
+            .map(|s| s.to_owned().get_a().convert())
+            .collect()
+    }
+
+    async fn adder64_test_impl(malicious: bool) {
+        let mut rng = ChaCha12Rng::from_entropy();
+        let seed = rng.gen::<<ChaCha12Rng as SeedableRng>::Seed>();
+        let a = (0..128).map(|_| rng.gen::<u64>()).collect::<Vec<_>>();
+        let b = (0..128).map(|_| rng.gen::<u64>()).collect::<Vec<_>>();
+
+        let network = TestNetwork3p::new();
+        let net = network.get_party_networks();
+
+        let mut tasks = Vec::with_capacity(NUM_PARTIES);
+        for n in net {
+            let (a_, b_) = (pack_u64(&a), pack_u64(&b));
+            let t = tokio::spawn(async move { adder64_party(n, seed, a_, b_, malicious).await });
+            tasks.push(t);
+        }
+
+        let mut res = vec![0u128; 64];
+        for t in tasks {
+            let r = t.await.expect("Task exited normally");
+            for (x, y) in res.iter_mut().zip(r) {
+                *x ^= y;
+            }
+        }
+
+        let expected = a
+            .iter()
+            .zip(b.iter())
+            .map(|(a_, b_)| a_.wrapping_add(*b_))
+            .collect::<Vec<_>>();
+        assert_eq!(unpack_u64(&res), expected);
+    }
+
+    #[tokio::test]
+    async fn adder64_aby3_test() {
+        adder64_test_impl(false).await
+    }
+
+    #[tokio::test]
+    async fn adder64_aby3_mal_test() {
+        adder64_test_impl(true).await
+    }
+}
@@ -0,0 +1,166 @@
+mod compressed_db_test {
+    use crate::{
+        aby3::share::Share,
+        prelude::{
+            has_stored_component, read_compressed_db, read_party_seeds, write_compressed_db,
+            write_party_seeds, DbSeeds, Error,
+        },
+        tests::iris_config::iris_config::create_database,
+        types::ring_element::RingElement,
+    };
+    use plain_reference::IrisCode;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
+    use rusqlite::Connection;
+
+    const NUM_PARTIES: usize = 3;
+    const DB_SIZE: usize = 10;
+
+    fn check_shares(code: &IrisCode, shares: &[Vec<Share<u16>>]) {
+        for (i, bit) in code.code.bits().enumerate() {
+            // Replicated: party i holds (xi, x{i-1})
+            for id in 0..NUM_PARTIES {
+                let next = (id + 1) % NUM_PARTIES;
+                assert_eq!(shares[id][i].a, shares[next][i].b);
+            }
+            let sum = shares.iter().fold(RingElement(0), |sum, s| sum + &s[i].a);
+            assert_eq!(sum, RingElement(u16::from(bit)));
+        }
+    }
+
+    #[test]
+    fn expand_test() {
+        let mut rng = ChaCha12Rng::from_entropy();
+        let seeds = DbSeeds::random(&mut rng);
+        let code = IrisCode::random_rng(&mut rng);
+        let record = 42;
+
+        let stored = seeds.compress_code::<u16>(&code.code, record);
+        assert_eq!(stored.len(), IrisCode::IRIS_CODE_SIZE);
+
+        let mut shares = Vec::with_capacity(NUM_PARTIES);
+        for id in 0..NUM_PARTIES {
+            let party_seeds = seeds.party_seeds(id).unwrap();
+            assert_eq!(party_seeds.needs_stored_component(), id != 1);
+            let share = party_seeds
+                .expand_shares::<u16>(record, Some(stored.to_owned()))
+                .unwrap();
+            shares.push(share);
+        }
+        check_shares(&code, &shares);
+
+        // Other records use other randomness
+        let other = seeds.compress_code::<u16>(&code.code, record + 1);
+        assert_ne!(stored, other);
+    }
+
+    #[test]
+    fn invalid_input_test() {
+        let mut rng = ChaCha12Rng::from_entropy();
+        let seeds = DbSeeds::random(&mut rng);
+        assert!(matches!(seeds.party_seeds(3), Err(Error::IdError(3))));
+
+        let party_seeds = seeds.party_seeds(0).unwrap();
+        assert!(party_seeds.expand_shares::<u16>(1, None).is_err());
+        assert!(party_seeds
+            .expand_shares::<u16>(1, Some(vec![RingElement(0); 10]))
+            .is_err());
+
+        // Party 1 does not need a stored component
+        let party_seeds = seeds.party_seeds(1).unwrap();
+        assert!(party_seeds.expand_shares::<u16>(1, None).is_ok());
+    }
+
+    #[test]
+    fn sqlite_roundtrip_test() {
+        let mut rng = ChaCha12Rng::from_entropy();
+        let seeds = DbSeeds::random(&mut rng);
+        let db = create_database(DB_SIZE, &mut rng);
+
+        // Each party only reads its own database and seed file
+        let conns = (0..NUM_PARTIES)
+            .map(|id| {
+                let mut conn = Connection::open_in_memory().unwrap();
+                write_compressed_db::<u16>(&mut conn, &seeds, id, &db).unwrap();
+                conn
+            })
+            .collect::<Vec<_>>();
+        let seed_conns = (0..NUM_PARTIES)
+            .map(|id| {
+                let conn = Connection::open_in_memory().unwrap();
+                write_party_seeds(&conn, &seeds.party_seeds(id).unwrap()).unwrap();
+                conn
+            })
+            .collect::<Vec<_>>();
+        assert!(matches!(
+            read_party_seeds(&seed_conns[0], 1),
+            Err(Error::IdError(0))
+        ));
+
+        let mut parties = Vec::with_capacity(NUM_PARTIES);
+        for (id, (conn, seed_conn)) in conns.iter().zip(seed_conns.iter()).enumerate() {
+            let party_seeds = read_party_seeds(seed_conn, id).unwrap();
+            assert_eq!(party_seeds, seeds.party_seeds(id).unwrap());
+            let read = read_compressed_db::<u16>(conn, &party_seeds).unwrap();
+            assert_eq!(read.shares.len(), DB_SIZE);
+            assert_eq!(read.masks.len(), DB_SIZE);
+            for (mask, code) in read.masks.iter().zip(db.iter()) {
+                assert_eq!(mask, &code.mask);
+            }
+            parties.push(read.shares);
+        }
+
+        for (i, code) in db.iter().enumerate() {
+            let shares = parties.iter().map(|p| p[i].to_owned()).collect::<Vec<_>>();
+            check_shares(code, &shares);
+        }
+    }
+
+    #[test]
+    fn party1_without_stored_component_test() {
+        let mut rng = ChaCha12Rng::from_entropy();
+        let seeds = DbSeeds::random(&mut rng);
+        let db = create_database(DB_SIZE, &mut rng);
+
+        let mut conns = Vec::with_capacity(NUM_PARTIES);
+        for id in 0..NUM_PARTIES {
+            let mut conn = Connection::open_in_memory().unwrap();
+            write_compressed_db::<u16>(&mut conn, &seeds, id, &db).unwrap();
+            // Party 1 could reconstruct the codes from x2 and its seeds
+            assert_eq!(has_stored_component(&conn).unwrap(), id != 1);
+            conns.push(conn);
+        }
+        let columns = conns[1]
+            .prepare("SELECT name FROM pragma_table_info('iris_codes_compressed');")
+            .unwrap()
+            .query_map([], |row| row.get::<_, String>(0))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(columns, ["id", "mask"]);
+
+        // Party 1 refuses to read a database with the stored components and vice versa
+        let party1 = seeds.party_seeds(1).unwrap();
+        assert!(read_compressed_db::<u16>(&conns[0], &party1).is_err());
+        let party0 = seeds.party_seeds(0).unwrap();
+        assert!(read_compressed_db::<u16>(&conns[1], &party0).is_err());
+    }
+
+    #[test]
+    fn share_width_test() {
+        let mut rng = ChaCha12Rng::from_entropy();
+        let seeds = DbSeeds::random(&mut rng);
+        let db = create_database(DB_SIZE, &mut rng);
+
+        // The components are only valid in the ring they were written for, also for party 1 which
+        // expands all of them from its seeds
+        for id in 0..NUM_PARTIES {
+            let mut conn = Connection::open_in_memory().unwrap();
+            write_compressed_db::<u32>(&mut conn, &seeds, id, &db).unwrap();
+            let party_seeds = seeds.party_seeds(id).unwrap();
+            assert!(read_compressed_db::<u16>(&conn, &party_seeds).is_err());
+            let read = read_compressed_db::<u32>(&conn, &party_seeds).unwrap();
+            assert_eq!(read.shares.len(), DB_SIZE);
+        }
+    }
+}
@@ -0,0 +1,160 @@
+mod db_management_test {
+    use crate::{
+        error::Error,
+        prelude::{
+            check_party_db_consistency, delete_party_db_records, party_db_ids, read_aby3_party_db,
+            update_aby3_party_db_records, write_aby3_party_db, Aby3Share, DbShares,
+            PartyTestNetwork, TestNetwork3p,
+        },
+    };
+    use plain_reference::{IrisCode, IrisCodeArray};
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha12Rng;
+    use rusqlite::Connection;
+    use std::future::Future;
+
+    const DB_SIZE: usize = 5;
+    const MASK_SEED: u64 = 42;
+
+    fn random_shares<R: Rng>(amount: usize, rng: &mut R) -> DbShares<Vec<Aby3Share<u16>>> {
+        // The masks are public and thus equal at all parties
+        let mut mask_rng = ChaCha12Rng::seed_from_u64(MASK_SEED + amount as u64);
+        let mut res = DbShares::default();
+        for _ in 0..amount {
+            res.shares.push(
+                (0..IrisCode::IRIS_CODE_SIZE)
+                    .map(|_| Aby3Share::new(rng.gen(), rng.gen()))
+                    .collect(),
+            );
+            res.masks.push(IrisCodeArray::random_rng(&mut mask_rng));
+        }
+        res
+    }
+
+    fn party_db(party: usize, size: usize) -> Connection {
+        let mut rng = ChaCha12Rng::from_entropy();
+        let mut conn = Connection::open_in_memory().unwrap();
+        write_aby3_party_db(&mut conn, party, &random_shares(size, &mut rng)).unwrap();
+        conn
+    }
+
+    async fn run_parties<F, Fut, R>(f: F) -> Vec<R>
+    where
+        F: Fn(PartyTestNetwork, usize) -> Fut,
+        Fut: Future<Output = R> + Send + 'static,
+        R: Send + 'static,
+    {
+        let network = TestNetwork3p::new();
+        let tasks = network
+            .get_party_networks()
+            .into_iter()
+            .enumerate()
+            .map(|(party, net)| tokio::spawn(f(net, party)))
+            .collect::<Vec<_>>();
+
+        let mut res = Vec::with_capacity(tasks.len());
+        for t in tasks {
+            res.push(t.await.expect("Task exited normally"));
+        }
+        res
+    }
+
+    #[tokio::test]
+    async fn delete_test() {
+        let res = run_parties(|mut net, party| async move {
+            let mut conn = party_db(party, DB_SIZE);
+            let res = delete_party_db_records(&mut net, &mut conn, &[2, 4]).await;
+            check_party_db_consistency(&mut net, &mut conn)
+                .await
+                .unwrap();
+            (res, party_db_ids(&conn).unwrap())
+        })
+        .await;
+
+        for (res, ids) in res {
+            assert!(res.is_ok());
+            assert_eq!(ids, vec![1, 3, 5]);
+        }
+    }
+
+    #[tokio::test]
+    async fn delete_missing_test() {
+        let res = run_parties(|mut net, party| async move {
+            let mut conn = party_db(party, DB_SIZE);
+            // Only party 2 is missing the record, thus all parties roll back
+            if party == 2 {
+                conn.execute("DELETE FROM iris_codes WHERE id = 1;", [])
+                    .unwrap();
+            }
+            let res = delete_party_db_records(&mut net, &mut conn, &[1, 3]).await;
+            (res, party_db_ids(&conn).unwrap())
+        })
+        .await;
+
+        for (party, (res, ids)) in res.into_iter().enumerate() {
+            assert!(res.is_err());
+            assert_eq!(ids.len(), DB_SIZE - usize::from(party == 2));
+        }
+    }
+
+    #[tokio::test]
+    async fn delete_diverging_test() {
+        let res = run_parties(|mut net, party| async move {
+            let mut conn = party_db(party, DB_SIZE);
+            let ids = if party == 1 { [1] } else { [2] };
+            let res = delete_party_db_records(&mut net, &mut conn, &ids).await;
+            (res, party_db_ids(&conn).unwrap())
+        })
+        .await;
+
+        for (res, ids) in res {
+            assert!(matches!(res, Err(Error::InconsistentDatabase(_))));
+            assert_eq!(ids, (1..=DB_SIZE as u64).collect::<Vec<_>>());
+        }
+    }
+
+    #[tokio::test]
+    async fn inconsistent_db_test() {
+        let res = run_parties(|mut net, party| async move {
+            let mut conn = party_db(party, DB_SIZE + usize::from(party == 0));
+            check_party_db_consistency(&mut net, &mut conn).await
+        })
+        .await;
+
+        for res in res {
+            assert!(matches!(res, Err(Error::InconsistentDatabase(_))));
+        }
+    }
+
+    #[tokio::test]
+    async fn update_test() {
+        let res = run_parties(|mut net, party| async move {
+            let mut rng = ChaCha12Rng::from_entropy();
+            let mut conn = party_db(party, DB_SIZE);
+            let update = random_shares(2, &mut rng);
+            update_aby3_party_db_records(&mut net, &mut conn, party, &[4, 2], &update)
+                .await
+                .unwrap();
+            // Updating a missing record changes nothing
+            assert!(update_aby3_party_db_records(
+                &mut net,
+                &mut conn,
+                party,
+                &[1, DB_SIZE as u64 + 1],
+                &random_shares(2, &mut rng)
+            )
+            .await
+            .is_err());
+            (update, read_aby3_party_db::<u16>(&conn, party).unwrap())
+        })
+        .await;
+
+        for (update, db) in res {
+            assert_eq!(db.shares.len(), DB_SIZE);
+            assert_eq!(db.shares[3], update.shares[0]);
+            assert_eq!(db.masks[3], update.masks[0]);
+            assert_eq!(db.shares[1], update.shares[1]);
+            assert_eq!(db.masks[1], update.masks[1]);
+        }
+    }
+}
@@ -0,0 +1,368 @@
+mod db_roots_test {
+    use crate::{
+        commitment::merkle::MerkleTree,
+        error::Error,
+        prelude::{
+            aby3_party_db_chunks, spdzwise_party_db_chunks, swift3_party_db_chunks,
+            write_aby3_party_db, write_spdzwise_party_db, write_swift3_party_db, Aby3, Aby3Share,
+            DbRoots, DbShares, IrisAby3, PartyTestNetwork, Sharable, SpdzWiseShare,
+            SpdzWiseVecShare, Swift3Share, TestNetwork3p,
+        },
+        tests::iris_config::iris_config::create_database,
+    };
+    use plain_reference::{IrisCode, IrisCodeArray};
+    use rand::{
+        distributions::{Distribution, Standard},
+        Rng, SeedableRng,
+    };
+    use rand_chacha::ChaCha12Rng;
+    use rusqlite::Connection;
+
+    const NUM_PARTIES: usize = PartyTestNetwork::NUM_PARTIES;
+    const DB_SIZE: usize = 5;
+    // Reads the party databases in chunks which do not divide the database
+    const READ_SIZE: usize = 2;
+
+    type Component<T> = Vec<<T as Sharable>::Share>;
+
+    // The components x0, x1, x2 of the replicated sharings and the component d of swift3
+    fn random_components<T: Sharable, R: Rng>(rng: &mut R) -> Vec<[Component<T>; 4]>
+    where
+        Standard: Distribution<T::Share>,
+    {
+        (0..DB_SIZE)
+            .map(|_| [(); 4].map(|_| (0..IrisCode::IRIS_CODE_SIZE).map(|_| rng.gen()).collect()))
+            .collect()
+    }
+
+    fn aby3_shares<T: Sharable>(
+        components: &[[Component<T>; 4]],
+        masks: &[IrisCodeArray],
+        party: usize,
+    ) -> DbShares<Vec<Aby3Share<T>>> {
+        let prev = (party + NUM_PARTIES - 1) % NUM_PARTIES;
+        DbShares {
+            shares: components
+                .iter()
+                .map(|c| {
+                    c[party]
+                        .iter()
+                        .zip(c[prev].iter())
+                        .map(|(a, b)| Aby3Share::new(a.to_owned(), b.to_owned()))
+                        .collect()
+                })
+                .collect(),
+            masks: masks.to_vec(),
+        }
+    }
+
+    fn aby3_roots(db: &DbShares<Vec<Aby3Share<u16>>>) -> DbRoots {
+        DbRoots::from_shares::<Aby3Share<u16>, _>(&db.shares, &db.masks).unwrap()
+    }
+
+    fn aby3_party_dbs(
+        components: &[[Component<u16>; 4]],
+        masks: &[IrisCodeArray],
+    ) -> Vec<Connection> {
+        (0..NUM_PARTIES)
+            .map(|party| {
+                let mut conn = Connection::open_in_memory().unwrap();
+                write_aby3_party_db(
+                    &mut conn,
+                    party,
+                    &aby3_shares::<u16>(components, masks, party),
+                )
+                .unwrap();
+                conn
+            })
+            .collect()
+    }
+
+    fn assert_replicated(roots: &[DbRoots]) {
+        for party in 0..NUM_PARTIES {
+            let next = (party + 1) % NUM_PARTIES;
+            assert_eq!(roots[party].next, roots[next].prev);
+            assert_ne!(roots[party].next, roots[party].prev);
+        }
+    }
+
+    #[test]
+    fn merkle_tree_test() {
+        let leaves = (0..5u8).map(|i| vec![i; 3]).collect::<Vec<_>>();
+        let root = |leaves: &[Vec<u8>]| {
+            let mut tree = MerkleTree::new();
+            for leaf in leaves {
+                tree.push(&[leaf]);
+            }
+            tree.root()
+        };
+
+        let expected = root(&leaves);
+        assert_eq!(root(&leaves), expected);
+        for len in 0..leaves.len() {
+            assert_ne!(root(&leaves[..len]), expected);
+        }
+
+        let mut swapped = leaves.to_owned();
+        swapped.swap(1, 2);
+        assert_ne!(root(&swapped), expected);
+
+        let mut modified = leaves.to_owned();
+        modified[4][0] ^= 1;
+        assert_ne!(root(&modified), expected);
+
+        // The parts of a leaf are concatenated
+        let mut tree = MerkleTree::new();
+        tree.push(&[&[0], &[0, 0]]);
+        assert_eq!(tree.root(), root(&leaves[..1]));
+    }
+
+    #[test]
+    fn aby3_roots_test() {
+        let mut rng = ChaCha12Rng::from_entropy();
+        let masks = create_database(DB_SIZE, &mut rng)
+            .into_iter()
+            .map(|code| code.mask)
+            .collect::<Vec<_>>();
+        let components = random_components::<u16, _>(&mut rng);
+        let dbs = (0..NUM_PARTIES)
+            .map(|party| aby3_shares::<u16>(&components, &masks, party))
+            .collect::<Vec<_>>();
+
+        let roots = dbs.iter().map(aby3_roots).collect::<Vec<_>>();
+        assert_replicated(&roots);
+
+        // The roots do not depend on how the database is read
+        let conns = aby3_party_dbs(&components, &masks);
+        for (party, conn) in conns.iter().enumerate() {
+            let chunks = aby3_party_db_chunks::<u16>(conn, party, READ_SIZE).unwrap();
+            let read = DbRoots::from_chunks::<Aby3Share<u16>, _, _>(chunks).unwrap();
+            assert_eq!(read, roots[party]);
+        }
+
+        // A modified share component changes the roots of both parties storing it
+        let mut modified = aby3_shares::<u16>(&components, &masks, 1);
+        modified.shares[2][0] = Aby3Share::new(rng.gen(), components[2][0][0].to_owned());
+        let modified = aby3_roots(&modified);
+        assert_ne!(modified.next, roots[1].next);
+        assert_eq!(modified.prev, roots[1].prev);
+
+        // As does a modified mask
+        let mut modified = aby3_shares::<u16>(&components, &masks, 2);
+        modified.masks[1] = masks[0];
+        let modified = aby3_roots(&modified);
+        assert_ne!(modified.next, roots[2].next);
+        assert_ne!(modified.prev, roots[2].prev);
+
+        // As does the order of the records
+        let mut modified = aby3_shares::<u16>(&components, &masks, 0);
+        modified.shares.swap(1, 2);
+        modified.masks.swap(1, 2);
+        assert_ne!(aby3_roots(&modified), roots[0]);
+
+        // Shares which are not replicated can not be committed to
+        let plain = vec![vec![0u16; IrisCode::IRIS_CODE_SIZE]];
+        assert!(DbRoots::from_shares::<u16, _>(&plain, &masks[..1]).is_err());
+        assert!(DbRoots::from_shares::<Aby3Share<u16>, _>(&dbs[0].shares, &masks[..1]).is_err());
+    }
+
+    #[test]
+    fn swift3_roots_test() {
+        let mut rng = ChaCha12Rng::from_entropy();
+        let masks = create_database(DB_SIZE, &mut rng)
+            .into_iter()
+            .map(|code| code.mask)
+            .collect::<Vec<_>>();
+        let components = random_components::<u16, _>(&mut rng);
+
+        let roots = (0..NUM_PARTIES)
+            .map(|party| {
+                let prev = (party + NUM_PARTIES - 1) % NUM_PARTIES;
+                let shares = components
+                    .iter()
+                    .map(|c| {
+                        (0..IrisCode::IRIS_CODE_SIZE)
+                            .map(|j| {
+                                Swift3Share::<u16>::new(
+                                    c[party][j].to_owned(),
+                                    c[prev][j].to_owned(),
+                                    c[3][j].to_owned(),
+                                )
+                            })
+                            .collect()
+                    })
+                    .collect();
+                let db = DbShares {
+                    shares,
+                    masks: masks.to_owned(),
+                };
+                let roots =
+                    DbRoots::from_shares::<Swift3Share<u16>, _>(&db.shares, &db.masks).unwrap();
+
+                let mut conn = Connection::open_in_memory().unwrap();
+                write_swift3_party_db(&mut conn, party, &db).unwrap();
+                let chunks = swift3_party_db_chunks::<u16>(&conn, party, READ_SIZE).unwrap();
+                let read = DbRoots::from_chunks::<Swift3Share<u16>, _, _>(chunks).unwrap();
+                assert_eq!(read, roots);
+                roots
+            })
+            .collect::<Vec<_>>();
+        assert_replicated(&roots);
+    }
+
+    #[test]
+    fn spdzwise_roots_test() {
+        type V = <u16 as Sharable>::VerificationShare;
+        let mut rng = ChaCha12Rng::from_entropy();
+        let masks = create_database(DB_SIZE, &mut rng)
+            .into_iter()
+            .map(|code| code.mask)
+            .collect::<Vec<_>>();
+        let values = random_components::<V, _>(&mut rng);
+        let macs = random_components::<V, _>(&mut rng);
+
+        let roots = (0..NUM_PARTIES)
+            .map(|party| {
+                let values = aby3_shares(&values, &masks, party);
+                let macs = aby3_shares(&macs, &masks, party);
+                let db = DbShares {
+                    shares: values
+                        .shares
+                        .into_iter()
+                        .zip(macs.shares)
+                        .map(|(values, macs)| SpdzWiseVecShare::new(values, macs))
+                        .collect(),
+                    masks: masks.to_owned(),
+                };
+                let roots =
+                    DbRoots::from_shares::<SpdzWiseShare<V>, _>(&db.shares, &db.masks).unwrap();
+
+                let mut conn = Connection::open_in_memory().unwrap();
+                write_spdzwise_party_db::<u16>(&mut conn, party, &db).unwrap();
+                let chunks = spdzwise_party_db_chunks::<u16>(&conn, party, READ_SIZE).unwrap();
+                let read = DbRoots::from_chunks::<SpdzWiseShare<V>, _, _>(chunks).unwrap();
+                assert_eq!(read, roots);
+                roots
+            })
+            .collect::<Vec<_>>();
+        assert_replicated(&roots);
+    }
+
+    async fn preprocessing_test_impl(modify: Option<usize>) -> Vec<Result<(), Error>> {
+        let mut rng = ChaCha12Rng::from_entropy();
+        let masks = create_database(DB_SIZE, &mut rng)
+            .into_iter()
+            .map(|code| code.mask)
+            .collect::<Vec<_>>();
+        let mut components = random_components::<u16, _>(&mut rng);
+        let mut roots = (0..NUM_PARTIES)
+            .map(|party| aby3_roots(&aby3_shares(&components, &masks, party)))
+            .collect::<Vec<_>>();
+        // The party works on a stale version of the database
+        if let Some(party) = modify {
+            components[0][party][0] = rng.gen();
+            roots[party] = aby3_roots(&aby3_shares(&components, &masks, party));
+        }
+
+        let network = TestNetwork3p::new();
+        let tasks = network
+            .get_party_networks()
+            .into_iter()
+            .zip(roots)
+            .map(|(net, roots)| {
+                tokio::spawn(async move {
+                    let protocol = Aby3::<PartyTestNetwork>::new(net);
+                    let mut iris = IrisAby3::<u16, _>::new(protocol).unwrap();
+                    iris.set_db_roots(roots);
+                    let res = iris.preprocessing().await;
+                    if res.is_ok() {
+                        iris.finish().await.unwrap();
+                    }
+                    res
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut res = Vec::with_capacity(NUM_PARTIES);
+        for t in tasks {
+            res.push(t.await.expect("Task exited normally"));
+        }
+        res
+    }
+
+    #[tokio::test]
+    async fn preprocessing_test() {
+        for res in preprocessing_test_impl(None).await {
+            res.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn stale_db_test() {
+        // The component x1 is stored by parties 1 and 2, which conflict. All parties abort
+        for res in preprocessing_test_impl(Some(1)).await {
+            assert!(matches!(res, Err(Error::ConflictingParties(1, 2))));
+        }
+    }
+
+    async fn matching_test_impl(swap: Option<usize>) -> Vec<Result<bool, Error>> {
+        let mut rng = ChaCha12Rng::from_entropy();
+        let masks = create_database(DB_SIZE, &mut rng)
+            .into_iter()
+            .map(|code| code.mask)
+            .collect::<Vec<_>>();
+        let components = random_components::<u16, _>(&mut rng);
+        let query = random_components::<u16, _>(&mut rng);
+
+        let network = TestNetwork3p::new();
+        let tasks = network
+            .get_party_networks()
+            .into_iter()
+            .enumerate()
+            .map(|(party, net)| {
+                let mut db = aby3_shares(&components, &masks, party);
+                let iris = aby3_shares(&query, &masks, party).shares.swap_remove(0);
+                let mask = masks[0];
+                tokio::spawn(async move {
+                    let protocol = Aby3::<PartyTestNetwork>::new(net);
+                    let mut iris_protocol = IrisAby3::<u16, _>::new(protocol).unwrap();
+                    iris_protocol.set_db_roots(aby3_roots(&db));
+                    iris_protocol.preprocessing().await?;
+
+                    // The party swaps two of its shares after committing to the database
+                    if swap == Some(party) {
+                        db.shares.swap(1, 2);
+                    }
+                    let res = iris_protocol
+                        .iris_in_db(&iris, &db.shares, &mask, &db.masks, DB_SIZE)
+                        .await;
+                    if res.is_ok() {
+                        iris_protocol.finish().await.unwrap();
+                    }
+                    res
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut res = Vec::with_capacity(NUM_PARTIES);
+        for t in tasks {
+            res.push(t.await.expect("Task exited normally"));
+        }
+        res
+    }
+
+    #[tokio::test]
+    async fn matching_test() {
+        for res in matching_test_impl(None).await {
+            res.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn swapped_share_test() {
+        // The swapped shares conflict with both neighbours, so all parties blame party 1
+        for res in matching_test_impl(Some(1)).await {
+            assert!(matches!(res, Err(Error::CheatingParty(1))));
+        }
+    }
+}
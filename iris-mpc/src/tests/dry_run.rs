@@ -0,0 +1,227 @@
+mod dry_run_test {
+    use crate::{
+        aby3::share::Share,
+        iris::protocol::IrisProtocol,
+        prelude::{
+            Aby3, AdderStrategy, DryRun, MpcTrait, PartyTestNetwork, Sharable, TestNetwork3p,
+        },
+        tests::iris_config::iris_config::create_database,
+        types::{bit::Bit, ring_element::RingImpl},
+    };
+    use plain_reference::IrisCode;
+    use rand::{
+        distributions::{Distribution, Standard},
+        Rng, SeedableRng,
+    };
+    use rand_chacha::ChaCha12Rng;
+    use std::ops::Mul;
+
+    const NUM_PARTIES: usize = PartyTestNetwork::NUM_PARTIES;
+    const DB_SIZE: usize = 64;
+    const CHUNK_SIZE: usize = 16;
+    const STRATEGIES: [AdderStrategy; 4] = [
+        AdderStrategy::RippleCarry,
+        AdderStrategy::KoggeStone,
+        AdderStrategy::BrentKung,
+        AdderStrategy::Sklansky,
+    ];
+
+    fn iris_code_plain_type<T: Sharable>(code: &IrisCode) -> Vec<T> {
+        let mut res = Vec::with_capacity(IrisCode::IRIS_CODE_SIZE);
+        for i in 0..IrisCode::IRIS_CODE_SIZE {
+            res.push(T::from(code.code.get_bit(i)));
+        }
+        res
+    }
+
+    fn share_iris_code<T: Sharable, R: Rng>(
+        code: &IrisCode,
+        id: usize,
+        rng: &mut R,
+    ) -> Vec<Share<T>>
+    where
+        Standard: Distribution<T>,
+        Standard: Distribution<T::Share>,
+        Share<T>: Mul<Output = Share<T>>,
+        Share<T>: Mul<T::Share, Output = Share<T>>,
+    {
+        let mut shared_code = Vec::with_capacity(IrisCode::IRIS_CODE_SIZE);
+        for i in 0..IrisCode::IRIS_CODE_SIZE {
+            let shares = Aby3::<PartyTestNetwork>::share(
+                T::from(code.code.get_bit(i)),
+                T::VerificationShare::default(),
+                rng,
+            );
+            shared_code.push(shares[id].to_owned());
+        }
+        shared_code
+    }
+
+    async fn msb_cost_test_impl<T: Sharable>(strategy: AdderStrategy)
+    where
+        Standard: Distribution<T>,
+    {
+        let mut rng = ChaCha12Rng::from_entropy();
+        let inputs = (0..128).map(|_| rng.gen::<T>()).collect::<Vec<_>>();
+
+        let mut protocol = DryRun::new(0).unwrap();
+        protocol.set_adder_strategy(strategy);
+        MpcTrait::<T, T, Bit>::preprocess(&mut protocol)
+            .await
+            .unwrap();
+        let before = protocol.report();
+        let msbs = MpcTrait::<T, T, Bit>::get_msb_many(&mut protocol, inputs.to_owned())
+            .await
+            .unwrap();
+        let after = protocol.report();
+
+        for (i, o) in inputs.into_iter().zip(msbs) {
+            assert_eq!(i.to_sharetype().get_msb().convert(), o);
+        }
+
+        // One full register of 128 values
+        let k = T::Share::K;
+        assert_eq!(after.rounds - before.rounds, strategy.msb_rounds(k));
+        assert_eq!(
+            after.and_gates - before.and_gates,
+            128 * strategy.msb_and_gates(k)
+        );
+        assert_eq!(
+            after.and_gates - before.and_gates,
+            MpcTrait::<T, T, Bit>::msb_and_gates(&protocol, 128)
+        );
+    }
+
+    #[tokio::test]
+    async fn msb_cost_test() {
+        for strategy in STRATEGIES {
+            msb_cost_test_impl::<u16>(strategy).await;
+            msb_cost_test_impl::<u32>(strategy).await;
+        }
+    }
+
+    async fn aby3_party<T: Sharable, R: Rng + SeedableRng>(
+        net: PartyTestNetwork,
+        seed: R::Seed,
+        iris_seed: R::Seed,
+        strategy: AdderStrategy,
+    ) -> (bool, String)
+    where
+        Standard: Distribution<T>,
+        Standard: Distribution<T::Share>,
+        Share<T>: Mul<Output = Share<T>>,
+        Share<T>: Mul<T::Share, Output = Share<T>>,
+        <T as std::convert::TryFrom<usize>>::Error: std::fmt::Debug,
+    {
+        let mut protocol = Aby3::<PartyTestNetwork>::new(net);
+        protocol.set_adder_strategy(strategy);
+        let mut iris = IrisProtocol::new(protocol).unwrap();
+        let id = iris.get_id();
+
+        iris.preprocessing().await.unwrap();
+
+        let mut iris_rng = R::from_seed(iris_seed);
+        let mut rng = R::from_seed(seed);
+
+        let db = create_database(DB_SIZE, &mut iris_rng);
+        let code = db[0].get_similar_iris(&mut iris_rng);
+
+        let mut db_t = Vec::with_capacity(db.len());
+        let mut masks = Vec::with_capacity(db.len());
+        for iris in db {
+            db_t.push(share_iris_code(&iris, id, &mut rng));
+            masks.push(iris.mask);
+        }
+        let code_ = share_iris_code(&code, id, &mut rng);
+
+        let res = iris
+            .iris_in_db(&code_, &db_t, &code.mask, &masks, CHUNK_SIZE)
+            .await
+            .unwrap();
+
+        let mut stats = Vec::new();
+        iris.print_connection_stats(&mut stats).unwrap();
+        iris.finish().await.unwrap();
+        (res, String::from_utf8(stats).unwrap())
+    }
+
+    async fn dry_run_party<T: Sharable, R: Rng + SeedableRng>(
+        id: usize,
+        iris_seed: R::Seed,
+        strategy: AdderStrategy,
+    ) -> (bool, String)
+    where
+        <T as std::convert::TryFrom<usize>>::Error: std::fmt::Debug,
+    {
+        let mut protocol = DryRun::new(id).unwrap();
+        protocol.set_adder_strategy(strategy);
+        let mut iris: IrisProtocol<T, T, Bit, DryRun> = IrisProtocol::new(protocol).unwrap();
+
+        iris.preprocessing().await.unwrap();
+
+        let mut iris_rng = R::from_seed(iris_seed);
+        let db = create_database(DB_SIZE, &mut iris_rng);
+        let code = db[0].get_similar_iris(&mut iris_rng);
+
+        let mut db_t = Vec::with_capacity(db.len());
+        let mut masks = Vec::with_capacity(db.len());
+        for iris in db {
+            db_t.push(iris_code_plain_type(&iris));
+            masks.push(iris.mask);
+        }
+        let code_ = iris_code_plain_type(&code);
+
+        let res = iris
+            .iris_in_db(&code_, &db_t, &code.mask, &masks, CHUNK_SIZE)
+            .await
+            .unwrap();
+
+        let mut stats = Vec::new();
+        iris.print_connection_stats(&mut stats).unwrap();
+        let stats = String::from_utf8(stats).unwrap();
+
+        let report = iris.get_mpc_ref().report();
+        assert!(report.to_string().starts_with(&stats));
+        assert_eq!(report.mul_gates, DB_SIZE * IrisCode::IRIS_CODE_SIZE);
+
+        (res, stats)
+    }
+
+    async fn iris_in_db_cost_test_impl<T: Sharable>(strategy: AdderStrategy)
+    where
+        Standard: Distribution<T>,
+        Standard: Distribution<T::Share>,
+        Share<T>: Mul<Output = Share<T>>,
+        Share<T>: Mul<T::Share, Output = Share<T>>,
+        <T as std::convert::TryFrom<usize>>::Error: std::fmt::Debug,
+    {
+        let mut tasks = Vec::with_capacity(NUM_PARTIES);
+
+        let mut rng = ChaCha12Rng::from_entropy();
+        let iris_seed = rng.gen::<<ChaCha12Rng as SeedableRng>::Seed>();
+        let seed = rng.gen::<<ChaCha12Rng as SeedableRng>::Seed>();
+
+        let network = TestNetwork3p::new();
+        let net = network.get_party_networks();
+
+        for n in net {
+            let t = tokio::spawn(async move {
+                aby3_party::<T, ChaCha12Rng>(n, seed, iris_seed, strategy).await
+            });
+            tasks.push(t);
+        }
+
+        for (id, t) in tasks.into_iter().enumerate() {
+            let measured = t.await.expect("Task exited normally");
+            let dry_run = dry_run_party::<T, ChaCha12Rng>(id, iris_seed, strategy).await;
+            assert_eq!(measured, dry_run);
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+    async fn iris_in_db_cost_test() {
+        for strategy in STRATEGIES {
+            iris_in_db_cost_test_impl::<u16>(strategy).await;
+        }
+    }
+}
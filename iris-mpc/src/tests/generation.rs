@@ -0,0 +1,286 @@
+mod generation_test {
+    use crate::{
+        prelude::{
+            generate_random_codes, has_spdzwise_party_mac_key, input_codes, read_aby3_party_db,
+            read_spdzwise_party_db, read_spdzwise_party_mac_key, read_swift3_party_db,
+            write_aby3_party_db, write_spdzwise_party_db, write_spdzwise_party_mac_key,
+            write_swift3_party_db, Aby3, Aby3Share, Bit, MpcTrait, PartyTestNetwork, SpdzWise,
+            Swift3, Swift3Share, TestNetwork3p,
+        },
+        spdzwise::protocol::TShare,
+        tests::iris_config::iris_config::create_database,
+    };
+    use plain_reference::{IrisCode, IrisCodeArray};
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha12Rng;
+    use rusqlite::Connection;
+
+    const NUM_PARTIES: usize = PartyTestNetwork::NUM_PARTIES;
+    const DB_SIZE: usize = 2;
+    const OWNER: usize = 1;
+
+    fn code_from_bits(bits: &[u16]) -> IrisCodeArray {
+        assert_eq!(bits.len(), IrisCode::IRIS_CODE_SIZE);
+        let mut res = IrisCodeArray::ZERO;
+        for (i, bit) in bits.iter().enumerate() {
+            assert!(*bit <= 1);
+            res.set_bit(i, *bit == 1);
+        }
+        res
+    }
+
+    fn check_codes(db: &[IrisCode], results: Vec<(Vec<IrisCodeArray>, Vec<IrisCodeArray>)>) {
+        for (codes, masks) in results {
+            assert_eq!(codes.len(), db.len());
+            for ((code, mask), expected) in codes.iter().zip(masks.iter()).zip(db.iter()) {
+                assert_eq!(code, &expected.code);
+                assert_eq!(mask, &expected.mask);
+            }
+        }
+    }
+
+    async fn run<F, Fut>(party: F) -> Vec<(Vec<IrisCodeArray>, Vec<IrisCodeArray>)>
+    where
+        F: Fn(PartyTestNetwork) -> Fut,
+        Fut:
+            std::future::Future<Output = (Vec<IrisCodeArray>, Vec<IrisCodeArray>)> + Send + 'static,
+    {
+        let network = TestNetwork3p::new();
+        let mut tasks = Vec::with_capacity(NUM_PARTIES);
+        for n in network.get_party_networks() {
+            tasks.push(tokio::spawn(party(n)));
+        }
+        let mut results = Vec::with_capacity(NUM_PARTIES);
+        for t in tasks {
+            results.push(t.await.expect("Task exited normally"));
+        }
+        results
+    }
+
+    async fn aby3_input_party(
+        net: PartyTestNetwork,
+        db: Vec<IrisCode>,
+    ) -> (Vec<IrisCodeArray>, Vec<IrisCodeArray>) {
+        let mut protocol = Aby3::<PartyTestNetwork>::new(net);
+        MpcTrait::<u16, Aby3Share<u16>, Aby3Share<Bit>>::preprocess(&mut protocol)
+            .await
+            .unwrap();
+        let id = MpcTrait::<u16, Aby3Share<u16>, Aby3Share<Bit>>::get_id(&protocol);
+
+        let codes = (id == OWNER).then_some(&db[..]);
+        let shares = input_codes(&mut protocol, codes, DB_SIZE, OWNER)
+            .await
+            .unwrap();
+
+        let mut conn = Connection::open_in_memory().unwrap();
+        write_aby3_party_db(&mut conn, id, &shares).unwrap();
+        let read = read_aby3_party_db::<u16>(&conn, id).unwrap();
+        assert_eq!(read.shares, shares.shares);
+
+        let mut codes = Vec::with_capacity(DB_SIZE);
+        for code in read.shares {
+            let bits = protocol.open_many(code).await.unwrap();
+            codes.push(code_from_bits(&bits));
+        }
+        MpcTrait::<u16, Aby3Share<u16>, Aby3Share<Bit>>::finish(protocol)
+            .await
+            .unwrap();
+        (codes, read.masks)
+    }
+
+    #[tokio::test]
+    async fn aby3_input_test() {
+        let mut rng = ChaCha12Rng::from_entropy();
+        let db = create_database(DB_SIZE, &mut rng);
+        let db_ = db.to_owned();
+        let results = run(move |n| aby3_input_party(n, db_.to_owned())).await;
+        check_codes(&db, results);
+    }
+
+    async fn aby3_random_party(
+        net: PartyTestNetwork,
+        mask_seed: u64,
+    ) -> (Vec<IrisCodeArray>, Vec<IrisCodeArray>) {
+        let mut protocol = Aby3::<PartyTestNetwork>::new(net);
+        MpcTrait::<u16, Aby3Share<u16>, Aby3Share<Bit>>::preprocess(&mut protocol)
+            .await
+            .unwrap();
+
+        let mut rng = ChaCha12Rng::from_entropy();
+        let shares = generate_random_codes(&mut protocol, DB_SIZE, mask_seed, &mut rng)
+            .await
+            .unwrap();
+
+        let mut codes = Vec::with_capacity(DB_SIZE);
+        for code in shares.shares {
+            let bits = protocol.open_many(code).await.unwrap();
+            codes.push(code_from_bits(&bits));
+        }
+        MpcTrait::<u16, Aby3Share<u16>, Aby3Share<Bit>>::finish(protocol)
+            .await
+            .unwrap();
+        (codes, shares.masks)
+    }
+
+    #[tokio::test]
+    async fn aby3_random_test() {
+        let mask_seed = ChaCha12Rng::from_entropy().gen();
+        let results = run(move |n| aby3_random_party(n, mask_seed)).await;
+
+        let mut mask_rng = ChaCha12Rng::seed_from_u64(mask_seed);
+        let expected_masks = (0..DB_SIZE)
+            .map(|_| IrisCode::random_rng(&mut mask_rng).mask)
+            .collect::<Vec<_>>();
+        for (codes, masks) in results.iter() {
+            assert_eq!(codes, &results[0].0);
+            assert_eq!(masks, &expected_masks);
+        }
+        // The codes are random
+        assert_ne!(results[0].0[0], results[0].0[1]);
+    }
+
+    async fn swift3_input_party(
+        net: PartyTestNetwork,
+        db: Vec<IrisCode>,
+    ) -> (Vec<IrisCodeArray>, Vec<IrisCodeArray>) {
+        let mut protocol = Swift3::<PartyTestNetwork, u16>::new(net);
+        MpcTrait::<u16, Swift3Share<u16>, Swift3Share<Bit>>::preprocess(&mut protocol)
+            .await
+            .unwrap();
+        let id = MpcTrait::<u16, Swift3Share<u16>, Swift3Share<Bit>>::get_id(&protocol);
+
+        let codes = (id == OWNER).then_some(&db[..]);
+        let shares = input_codes(&mut protocol, codes, DB_SIZE, OWNER)
+            .await
+            .unwrap();
+
+        let mut conn = Connection::open_in_memory().unwrap();
+        write_swift3_party_db(&mut conn, id, &shares).unwrap();
+        let read = read_swift3_party_db::<u16>(&conn, id).unwrap();
+        assert_eq!(read.shares, shares.shares);
+
+        let mut codes = Vec::with_capacity(DB_SIZE);
+        for code in read.shares {
+            let bits = protocol.open_many(code).await.unwrap();
+            codes.push(code_from_bits(&bits));
+        }
+        MpcTrait::<u16, Swift3Share<u16>, Swift3Share<Bit>>::finish(protocol)
+            .await
+            .unwrap();
+        (codes, read.masks)
+    }
+
+    #[tokio::test]
+    async fn swift3_input_test() {
+        let mut rng = ChaCha12Rng::from_entropy();
+        let db = create_database(DB_SIZE, &mut rng);
+        let db_ = db.to_owned();
+        let results = run(move |n| swift3_input_party(n, db_.to_owned())).await;
+        check_codes(&db, results);
+    }
+
+    async fn swift3_random_party(
+        net: PartyTestNetwork,
+        mask_seed: u64,
+    ) -> (Vec<IrisCodeArray>, Vec<IrisCodeArray>) {
+        let mut protocol = Swift3::<PartyTestNetwork, u16>::new(net);
+        MpcTrait::<u16, Swift3Share<u16>, Swift3Share<Bit>>::preprocess(&mut protocol)
+            .await
+            .unwrap();
+
+        let mut rng = ChaCha12Rng::from_entropy();
+        let shares = generate_random_codes(&mut protocol, DB_SIZE, mask_seed, &mut rng)
+            .await
+            .unwrap();
+
+        let mut codes = Vec::with_capacity(DB_SIZE);
+        for code in shares.shares {
+            let bits = protocol.open_many(code).await.unwrap();
+            codes.push(code_from_bits(&bits));
+        }
+        MpcTrait::<u16, Swift3Share<u16>, Swift3Share<Bit>>::finish(protocol)
+            .await
+            .unwrap();
+        (codes, shares.masks)
+    }
+
+    #[tokio::test]
+    async fn swift3_random_test() {
+        let mask_seed = ChaCha12Rng::from_entropy().gen();
+        let results = run(move |n| swift3_random_party(n, mask_seed)).await;
+        for (codes, masks) in results.iter() {
+            assert_eq!(codes, &results[0].0);
+            assert_eq!(masks, &results[0].1);
+        }
+        assert_ne!(results[0].0[0], results[0].0[1]);
+    }
+
+    async fn spdzwise_input_party(
+        net: PartyTestNetwork,
+        db: Vec<IrisCode>,
+    ) -> (Vec<IrisCodeArray>, Vec<IrisCodeArray>) {
+        let mut protocol = SpdzWise::<PartyTestNetwork, u64>::new(net);
+        MpcTrait::<u16, TShare<u16>, Aby3Share<Bit>>::preprocess(&mut protocol)
+            .await
+            .unwrap();
+        MpcTrait::<u16, TShare<u16>, Aby3Share<Bit>>::set_new_mac_key(&mut protocol);
+        let id = MpcTrait::<u16, TShare<u16>, Aby3Share<Bit>>::get_id(&protocol);
+
+        let codes = (id == OWNER).then_some(&db[..]);
+        let shares = input_codes::<u16, TShare<u16>, _, _>(&mut protocol, codes, DB_SIZE, OWNER)
+            .await
+            .unwrap();
+
+        let mut conn = Connection::open_in_memory().unwrap();
+        assert!(!has_spdzwise_party_mac_key(&conn).unwrap());
+        write_spdzwise_party_mac_key::<u16>(&conn, id, &protocol.get_r()).unwrap();
+        assert!(has_spdzwise_party_mac_key(&conn).unwrap());
+        assert!(write_spdzwise_party_mac_key::<u16>(&conn, id, &protocol.get_r()).is_err());
+        write_spdzwise_party_db::<u16>(&mut conn, id, &shares).unwrap();
+
+        let read = read_spdzwise_party_db::<u16>(&conn, id).unwrap();
+        assert_eq!(read.shares, shares.shares);
+        let mac_key = read_spdzwise_party_mac_key::<u16>(&conn, id).unwrap();
+        assert_eq!(mac_key.get_mac(), protocol.get_r());
+
+        // Opening verifies the MACs of the read shares
+        let mut codes = Vec::with_capacity(DB_SIZE);
+        for code in read.shares {
+            let bits = protocol.open_many(code).await.unwrap();
+            codes.push(code_from_bits(&bits));
+        }
+        MpcTrait::<u16, TShare<u16>, Aby3Share<Bit>>::finish(protocol)
+            .await
+            .unwrap();
+        (codes, read.masks)
+    }
+
+    #[tokio::test]
+    async fn spdzwise_input_test() {
+        let mut rng = ChaCha12Rng::from_entropy();
+        let db = create_database(DB_SIZE, &mut rng);
+        let db_ = db.to_owned();
+        let results = run(move |n| spdzwise_input_party(n, db_.to_owned())).await;
+        check_codes(&db, results);
+    }
+
+    #[tokio::test]
+    async fn invalid_owner_test() {
+        let network = TestNetwork3p::new();
+        let mut tasks = Vec::with_capacity(NUM_PARTIES);
+        for n in network.get_party_networks() {
+            tasks.push(tokio::spawn(async move {
+                let mut protocol = Aby3::<PartyTestNetwork>::new(n);
+                MpcTrait::<u16, Aby3Share<u16>, Aby3Share<Bit>>::preprocess(&mut protocol)
+                    .await
+                    .unwrap();
+                input_codes::<u16, Aby3Share<u16>, _, _>(&mut protocol, None, 1, NUM_PARTIES)
+                    .await
+                    .is_err()
+            }));
+        }
+        for t in tasks {
+            assert!(t.await.expect("Task exited normally"));
+        }
+    }
+}
@@ -2,18 +2,33 @@ mod iris_mpc_test {
     use crate::{
         aby3::share::Share,
         iris::protocol::IrisProtocol,
-        prelude::{Aby3, MpcTrait, PartyTestNetwork, Sharable, TestNetwork3p},
+        prelude::{
+            aby3_party_db_chunks, write_aby3_party_db, Aby3, DbShares, Error, IrisAby3,
+            IrisSpdzWise, IrisSwift3, MalAby3, MpcTrait, MsbVariant, NetworkTrait,
+            PartyTestNetwork, Sharable, SpdzWise, Swift3, TestNetwork3p,
+        },
         tests::iris_config::iris_config::create_database,
         traits::mpc_trait::Plain,
         types::bit::Bit,
     };
+    use bytes::{Bytes, BytesMut};
+    use futures::stream;
     use plain_reference::IrisCode;
     use rand::{
         distributions::{Distribution, Standard},
         Rng, SeedableRng,
     };
     use rand_chacha::ChaCha12Rng;
-    use std::ops::Mul;
+    use rusqlite::Connection;
+    use std::{
+        io,
+        ops::Mul,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+    };
+    use tokio::task::LocalSet;
 
     const NUM_PARTIES: usize = PartyTestNetwork::NUM_PARTIES;
     const DB_SIZE: usize = 128;
@@ -146,11 +161,11 @@ mod iris_mpc_test {
         hwd_test_aby3_impl::<u16>().await
     }
 
-    async fn plain_hwd_test_inner<T: Sharable>()
+    async fn plain_hwd_test_inner<T>()
     where
         Standard: Distribution<T>,
         Standard: Distribution<T::Share>,
-        T: Mul<T::Share, Output = T>,
+        T: Sharable + Mul<T::Share, Output = T>,
         <T as std::convert::TryFrom<usize>>::Error: std::fmt::Debug,
     {
         let mut iris_rng = ChaCha12Rng::from_entropy();
@@ -181,11 +196,11 @@ mod iris_mpc_test {
         plain_hwd_test_inner::<u16>().await
     }
 
-    async fn plain_lt_tester<T: Sharable>(code1: IrisCode, code2: IrisCode) -> bool
+    async fn plain_lt_tester<T>(code1: IrisCode, code2: IrisCode) -> bool
     where
         Standard: Distribution<T>,
         Standard: Distribution<T::Share>,
-        T: Mul<T::Share, Output = T>,
+        T: Sharable + Mul<T::Share, Output = T>,
         <T as std::convert::TryFrom<usize>>::Error: std::fmt::Debug,
     {
         let protocol = Plain::default();
@@ -212,11 +227,11 @@ mod iris_mpc_test {
         cmp
     }
 
-    async fn plain_lt_test_inner<T: Sharable>()
+    async fn plain_lt_test_inner<T>()
     where
         Standard: Distribution<T>,
         Standard: Distribution<T::Share>,
-        T: Mul<T::Share, Output = T>,
+        T: Sharable + Mul<T::Share, Output = T>,
         <T as std::convert::TryFrom<usize>>::Error: std::fmt::Debug,
     {
         let mut iris_rng = ChaCha12Rng::from_entropy();
@@ -283,6 +298,7 @@ mod iris_mpc_test {
         net: PartyTestNetwork,
         seed: R::Seed,
         iris_seed: R::Seed,
+        variant: MsbVariant,
     ) where
         Standard: Distribution<T>,
         Standard: Distribution<T::Share>,
@@ -290,7 +306,8 @@ mod iris_mpc_test {
         Share<T>: Mul<T::Share, Output = Share<T>>,
         <T as std::convert::TryFrom<usize>>::Error: std::fmt::Debug,
     {
-        let protocol = Aby3::<PartyTestNetwork>::new(net);
+        let mut protocol = Aby3::<PartyTestNetwork>::new(net);
+        protocol.set_msb_variant(variant).unwrap();
         let mut iris = IrisProtocol::new(protocol).unwrap();
 
         iris.preprocessing().await.unwrap();
@@ -308,7 +325,7 @@ mod iris_mpc_test {
         iris.finish().await.unwrap();
     }
 
-    async fn lt_test_aby3_impl<T: Sharable>()
+    async fn lt_test_aby3_impl<T: Sharable>(variant: MsbVariant)
     where
         Standard: Distribution<T>,
         Standard: Distribution<T::Share>,
@@ -327,7 +344,7 @@ mod iris_mpc_test {
 
         for n in net {
             let t = tokio::spawn(async move {
-                lt_test_aby3_impl_inner::<T, ChaCha12Rng>(n, seed, iris_seed).await
+                lt_test_aby3_impl_inner::<T, ChaCha12Rng>(n, seed, iris_seed, variant).await
             });
             tasks.push(t);
         }
@@ -339,17 +356,19 @@ mod iris_mpc_test {
 
     #[tokio::test(flavor = "multi_thread", worker_threads = 3)]
     async fn lt_test_aby3() {
-        lt_test_aby3_impl::<u16>().await
+        lt_test_aby3_impl::<u16>(MsbVariant::Adder).await
     }
 
-    async fn plain_cmp_many_iris_tester<T: Sharable>(
-        code1: IrisCode,
-        code2: Vec<IrisCode>,
-    ) -> Vec<bool>
+    #[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+    async fn lt_test_aby3_fss() {
+        lt_test_aby3_impl::<u16>(MsbVariant::Fss).await
+    }
+
+    async fn plain_cmp_many_iris_tester<T>(code1: IrisCode, code2: Vec<IrisCode>) -> Vec<bool>
     where
         Standard: Distribution<T>,
         Standard: Distribution<T::Share>,
-        T: Mul<T::Share, Output = T>,
+        T: Sharable + Mul<T::Share, Output = T>,
         <T as std::convert::TryFrom<usize>>::Error: std::fmt::Debug,
     {
         let protocol = Plain::default();
@@ -368,21 +387,23 @@ mod iris_mpc_test {
             mask2.push(code.mask);
         }
 
-        let cmp = Bit::convert_vec(
-            iris.compare_iris_many(&inp1, &inp2s, &code1.mask, &mask2)
-                .await
-                .unwrap(),
-        );
+        let cmp = iris
+            .compare_iris_many(&inp1, &inp2s, &code1.mask, &mask2)
+            .await
+            .unwrap()
+            .iter()
+            .map(Bit::convert)
+            .collect::<Vec<_>>();
 
         assert_eq!(cmp, cmp_);
         cmp
     }
 
-    async fn plain_cmp_iris_tester<T: Sharable>(code1: IrisCode, code2: IrisCode) -> bool
+    async fn plain_cmp_iris_tester<T>(code1: IrisCode, code2: IrisCode) -> bool
     where
         Standard: Distribution<T>,
         Standard: Distribution<T::Share>,
-        T: Mul<T::Share, Output = T>,
+        T: Sharable + Mul<T::Share, Output = T>,
         <T as std::convert::TryFrom<usize>>::Error: std::fmt::Debug,
     {
         let protocol = Plain::default();
@@ -402,11 +423,11 @@ mod iris_mpc_test {
         cmp
     }
 
-    async fn plain_cmp_iris_test_inner<T: Sharable>()
+    async fn plain_cmp_iris_test_inner<T>()
     where
         Standard: Distribution<T>,
         Standard: Distribution<T::Share>,
-        T: Mul<T::Share, Output = T>,
+        T: Sharable + Mul<T::Share, Output = T>,
         <T as std::convert::TryFrom<usize>>::Error: std::fmt::Debug,
     {
         let mut iris_rng = ChaCha12Rng::from_entropy();
@@ -595,11 +616,11 @@ mod iris_mpc_test {
         cmp_iris_test_aby3_impl::<u16>().await
     }
 
-    async fn plain_full_test_inner<T: Sharable>()
+    async fn plain_full_test_inner<T>()
     where
         Standard: Distribution<T>,
         Standard: Distribution<T::Share>,
-        T: Mul<T::Share, Output = T>,
+        T: Sharable + Mul<T::Share, Output = T>,
         <T as std::convert::TryFrom<usize>>::Error: std::fmt::Debug,
     {
         let mut rng = ChaCha12Rng::from_entropy();
@@ -656,6 +677,8 @@ mod iris_mpc_test {
         net: PartyTestNetwork,
         seed: R::Seed,
         iris_seed: R::Seed,
+        sessions: usize,
+        threads: usize,
     ) where
         Standard: Distribution<T>,
         Standard: Distribution<T::Share>,
@@ -663,8 +686,10 @@ mod iris_mpc_test {
         Share<T>: Mul<T::Share, Output = Share<T>>,
         <T as std::convert::TryFrom<usize>>::Error: std::fmt::Debug,
     {
-        let protocol = Aby3::<PartyTestNetwork>::new(net);
+        let mut protocol = Aby3::<PartyTestNetwork>::new(net);
+        protocol.set_threads(threads).unwrap();
         let mut iris = IrisProtocol::new(protocol).unwrap();
+        iris.set_sessions(sessions).unwrap();
         let id = iris.get_id();
 
         iris.preprocessing().await.unwrap();
@@ -702,8 +727,10 @@ mod iris_mpc_test {
             .await
             .unwrap();
 
+        // The records may also be borrowed, as from a mapped share file
+        let db_ref = db_t.iter().map(Vec::as_slice).collect::<Vec<_>>();
         let res2 = iris
-            .iris_in_db(&iris2_, &db_t, &iris2.mask, &masks, CHUNK_SIZE)
+            .iris_in_db(&iris2_, &db_ref, &iris2.mask, &masks, CHUNK_SIZE)
             .await
             .unwrap();
 
@@ -714,7 +741,7 @@ mod iris_mpc_test {
         assert!(res2);
     }
 
-    async fn full_test_aby3_impl<T: Sharable>()
+    async fn full_test_aby3_impl<T: Sharable>(sessions: usize, threads: usize)
     where
         Standard: Distribution<T>,
         Standard: Distribution<T::Share>,
@@ -733,7 +760,8 @@ mod iris_mpc_test {
 
         for n in net {
             let t = tokio::spawn(async move {
-                full_test_aby3_impl_inner::<T, ChaCha12Rng>(n, seed, iris_seed).await
+                full_test_aby3_impl_inner::<T, ChaCha12Rng>(n, seed, iris_seed, sessions, threads)
+                    .await
             });
             tasks.push(t);
         }
@@ -745,6 +773,452 @@ mod iris_mpc_test {
 
     #[tokio::test(flavor = "multi_thread", worker_threads = 3)]
     async fn full_test_aby3() {
-        full_test_aby3_impl::<u16>().await
+        full_test_aby3_impl::<u16>(1, 1).await;
+        full_test_aby3_impl::<u16>(2, 1).await;
+        full_test_aby3_impl::<u16>(3, 1).await;
+        full_test_aby3_impl::<u16>(1, 4).await;
+        full_test_aby3_impl::<u16>(2, 4).await;
+    }
+
+    async fn stream_test_aby3_impl_inner<T: Sharable, R: Rng + SeedableRng>(
+        net: PartyTestNetwork,
+        seed: R::Seed,
+        iris_seed: R::Seed,
+        sessions: usize,
+        read_size: usize,
+    ) where
+        Standard: Distribution<T>,
+        Standard: Distribution<T::Share>,
+        Share<T>: Mul<Output = Share<T>>,
+        Share<T>: Mul<T::Share, Output = Share<T>>,
+        <T as std::convert::TryFrom<usize>>::Error: std::fmt::Debug,
+    {
+        let protocol = Aby3::<PartyTestNetwork>::new(net);
+        let mut iris = IrisProtocol::new(protocol).unwrap();
+        iris.set_sessions(sessions).unwrap();
+        let id = iris.get_id();
+
+        iris.preprocessing().await.unwrap();
+
+        let mut iris_rng = R::from_seed(iris_seed);
+        let mut rng = R::from_seed(seed);
+
+        // gen db and iris
+        let db = create_database(DB_SIZE, &mut iris_rng);
+        let iris1 = IrisCode::random_rng(&mut rng);
+        let iris2 = db[DB_SIZE - 1].get_similar_iris(&mut rng);
+
+        let mut db_t = Vec::with_capacity(db.len());
+        let mut is_in1 = false;
+        let mut is_in2 = false;
+
+        // get plain result and share database
+        for iris in db.iter() {
+            is_in1 |= iris1.is_close(iris);
+            is_in2 |= iris2.is_close(iris);
+            db_t.push(share_iris_code(iris, id, &mut rng));
+        }
+        let masks = db.iter().map(|iris| iris.mask).collect::<Vec<_>>();
+
+        // The database is streamed in items of read_size codes
+        let items = || {
+            let items = db_t
+                .chunks(read_size)
+                .zip(masks.chunks(read_size))
+                .map(|(shares, masks)| {
+                    Ok(DbShares {
+                        shares: shares.to_vec(),
+                        masks: masks.to_vec(),
+                    })
+                })
+                .collect::<Vec<_>>();
+            stream::iter(items)
+        };
+
+        // share iris1 and iris2
+        let iris1_ = share_iris_code(&iris1, id, &mut rng);
+        let iris2_ = share_iris_code(&iris2, id, &mut rng);
+        // calculate
+        let res1 = iris
+            .iris_in_db_stream(&iris1_, &iris1.mask, items(), CHUNK_SIZE)
+            .await
+            .unwrap();
+
+        let res2 = iris
+            .iris_in_db_stream(&iris2_, &iris2.mask, items(), CHUNK_SIZE)
+            .await
+            .unwrap();
+
+        // An empty database is rejected
+        let empty = stream::iter(Vec::<Result<DbShares<Vec<Share<T>>>, Error>>::new());
+        assert!(iris
+            .iris_in_db_stream(&iris1_, &iris1.mask, empty, CHUNK_SIZE)
+            .await
+            .is_err());
+
+        iris.finish().await.unwrap();
+
+        assert_eq!(res1, is_in1);
+        assert_eq!(res2, is_in2);
+        assert!(res2);
+    }
+
+    async fn stream_test_aby3_impl<T: Sharable>(sessions: usize, read_size: usize)
+    where
+        Standard: Distribution<T>,
+        Standard: Distribution<T::Share>,
+        Share<T>: Mul<Output = Share<T>>,
+        Share<T>: Mul<T::Share, Output = Share<T>>,
+        <T as std::convert::TryFrom<usize>>::Error: std::fmt::Debug,
+    {
+        let mut tasks = Vec::with_capacity(NUM_PARTIES);
+
+        let mut rng = ChaCha12Rng::from_entropy();
+        let iris_seed = rng.gen::<<ChaCha12Rng as SeedableRng>::Seed>();
+        let seed = rng.gen::<<ChaCha12Rng as SeedableRng>::Seed>();
+
+        let network = TestNetwork3p::new();
+        let net = network.get_party_networks();
+
+        for n in net {
+            let t = tokio::spawn(async move {
+                stream_test_aby3_impl_inner::<T, ChaCha12Rng>(
+                    n, seed, iris_seed, sessions, read_size,
+                )
+                .await
+            });
+            tasks.push(t);
+        }
+
+        for t in tasks {
+            t.await.expect("Task exited normally");
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+    async fn stream_test_aby3() {
+        stream_test_aby3_impl::<u16>(1, CHUNK_SIZE).await;
+        stream_test_aby3_impl::<u16>(2, 2 * CHUNK_SIZE).await;
+        // Items which are no multiple of the chunk size
+        stream_test_aby3_impl::<u16>(2, CHUNK_SIZE + 8).await;
+        stream_test_aby3_impl::<u16>(1, DB_SIZE).await;
+    }
+
+    /// Supports a limited number of forks and counts the shut down sessions
+    struct ForkLimitNetwork {
+        net: PartyTestNetwork,
+        forks: usize,
+        shutdowns: Arc<AtomicUsize>,
+    }
+
+    impl NetworkTrait for ForkLimitNetwork {
+        fn get_id(&self) -> usize {
+            self.net.get_id()
+        }
+
+        fn get_num_parties(&self) -> usize {
+            self.net.get_num_parties()
+        }
+
+        fn print_connection_stats(&self, out: &mut impl std::io::Write) -> io::Result<()> {
+            self.net.print_connection_stats(out)
+        }
+
+        async fn shutdown(self) -> io::Result<()> {
+            self.shutdowns.fetch_add(1, Ordering::SeqCst);
+            self.net.shutdown().await
+        }
+
+        async fn send(&mut self, id: usize, data: Bytes) -> io::Result<()> {
+            self.net.send(id, data).await
+        }
+
+        async fn send_next_id(&mut self, data: Bytes) -> io::Result<()> {
+            self.net.send_next_id(data).await
+        }
+
+        async fn send_prev_id(&mut self, data: Bytes) -> io::Result<()> {
+            self.net.send_prev_id(data).await
+        }
+
+        async fn receive(&mut self, id: usize) -> io::Result<BytesMut> {
+            self.net.receive(id).await
+        }
+
+        async fn receive_prev_id(&mut self) -> io::Result<BytesMut> {
+            self.net.receive_prev_id().await
+        }
+
+        async fn receive_next_id(&mut self) -> io::Result<BytesMut> {
+            self.net.receive_next_id().await
+        }
+
+        async fn broadcast(&mut self, data: Bytes) -> io::Result<Vec<BytesMut>> {
+            self.net.broadcast(data).await
+        }
+
+        async fn fork(&mut self) -> io::Result<Self> {
+            if self.forks == 0 {
+                return Err(io::Error::new(io::ErrorKind::Unsupported, "No more forks"));
+            }
+            self.forks -= 1;
+            Ok(Self {
+                net: self.net.fork().await?,
+                forks: 0,
+                shutdowns: self.shutdowns.to_owned(),
+            })
+        }
+    }
+
+    async fn fork_fallback_test_inner(
+        net: PartyTestNetwork,
+        seed: <ChaCha12Rng as SeedableRng>::Seed,
+        iris_seed: <ChaCha12Rng as SeedableRng>::Seed,
+    ) {
+        let shutdowns = Arc::new(AtomicUsize::new(0));
+        let net = ForkLimitNetwork {
+            net,
+            forks: 1,
+            shutdowns: shutdowns.to_owned(),
+        };
+        let protocol = Aby3::new(net);
+        let mut iris = IrisProtocol::new(protocol).unwrap();
+        // The second fork fails, so the chunks are processed sequentially
+        iris.set_sessions(3).unwrap();
+        let id = iris.get_id();
+
+        iris.preprocessing().await.unwrap();
+
+        let mut iris_rng = ChaCha12Rng::from_seed(iris_seed);
+        let mut rng = ChaCha12Rng::from_seed(seed);
+        let db = create_database(DB_SIZE, &mut iris_rng);
+        let iris1 = db[DB_SIZE / 2].get_similar_iris(&mut rng);
+        let db_t = db
+            .iter()
+            .map(|iris| share_iris_code::<u16, _>(iris, id, &mut rng))
+            .collect::<Vec<_>>();
+        let masks = db.iter().map(|iris| iris.mask).collect::<Vec<_>>();
+        let iris1_ = share_iris_code(&iris1, id, &mut rng);
+
+        let res = iris
+            .iris_in_db(&iris1_, &db_t, &iris1.mask, &masks, CHUNK_SIZE)
+            .await
+            .unwrap();
+        assert!(res);
+        // The first fork was shut down
+        assert_eq!(shutdowns.load(Ordering::SeqCst), 1);
+
+        iris.finish().await.unwrap();
+        assert_eq!(shutdowns.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn sessions_unsupported_test() {
+        let [n0, n1, n2] = TestNetwork3p::new().get_party_networks();
+        let [n3, ..] = TestNetwork3p::new().get_party_networks();
+
+        let mut aby3 = IrisAby3::<u16, _>::new(Aby3::new(n0)).unwrap();
+        aby3.set_sessions(2).unwrap();
+
+        // The other protocols can not fork, so they must not silently stay sequential
+        let mut mal_aby3 = IrisAby3::<u16, _>::new(MalAby3::new(n1)).unwrap();
+        assert!(matches!(mal_aby3.set_sessions(2), Err(Error::ConfigError)));
+        mal_aby3.set_sessions(1).unwrap();
+        let mut swift3 = IrisSwift3::<u16, _>::new(Swift3::new(n2)).unwrap();
+        assert!(matches!(swift3.set_sessions(2), Err(Error::ConfigError)));
+        let mut spdzwise = IrisSpdzWise::<u16, _>::new(SpdzWise::new(n3)).unwrap();
+        assert!(matches!(spdzwise.set_sessions(2), Err(Error::ConfigError)));
+        assert_eq!(spdzwise.get_sessions(), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+    async fn fork_fallback_test() {
+        let mut rng = ChaCha12Rng::from_entropy();
+        let iris_seed = rng.gen::<<ChaCha12Rng as SeedableRng>::Seed>();
+        let seed = rng.gen::<<ChaCha12Rng as SeedableRng>::Seed>();
+
+        let network = TestNetwork3p::new();
+        let net = network.get_party_networks();
+
+        let mut tasks = Vec::with_capacity(NUM_PARTIES);
+        for n in net {
+            let t = tokio::spawn(async move { fork_fallback_test_inner(n, seed, iris_seed).await });
+            tasks.push(t);
+        }
+
+        for t in tasks {
+            t.await.expect("Task exited normally");
+        }
+    }
+
+    async fn party_db_stream_test_inner(
+        net: PartyTestNetwork,
+        seed: <ChaCha12Rng as SeedableRng>::Seed,
+        iris_seed: <ChaCha12Rng as SeedableRng>::Seed,
+        sessions: usize,
+        read_size: usize,
+    ) {
+        let protocol = Aby3::<PartyTestNetwork>::new(net);
+        let mut iris = IrisProtocol::new(protocol).unwrap();
+        iris.set_sessions(sessions).unwrap();
+        let id = iris.get_id();
+
+        iris.preprocessing().await.unwrap();
+
+        let mut iris_rng = ChaCha12Rng::from_seed(iris_seed);
+        let mut rng = ChaCha12Rng::from_seed(seed);
+        let mut db = create_database(DB_SIZE, &mut iris_rng);
+        let mut shares = DbShares::default();
+        for code in db.iter() {
+            shares
+                .shares
+                .push(share_iris_code::<u16, _>(code, id, &mut rng));
+            shares.masks.push(code.mask);
+        }
+        let mut conn = Connection::open_in_memory().unwrap();
+        write_aby3_party_db(&mut conn, id, &shares).unwrap();
+
+        // Deleting a record of the first item moves the boundaries of all following items
+        conn.execute(
+            "DELETE FROM iris_codes WHERE id = (SELECT id FROM iris_codes ORDER BY id LIMIT 1 OFFSET 1);",
+            [],
+        )
+        .unwrap();
+        db.remove(1);
+
+        // The first query matches the last code of the first item, the second one the last code
+        let queries = [
+            db[read_size - 1].get_similar_iris(&mut rng),
+            db[db.len() - 1].get_similar_iris(&mut rng),
+            IrisCode::random_rng(&mut rng),
+        ];
+        for query in queries {
+            let expected = db.iter().any(|code| query.is_close(code));
+            let query_ = share_iris_code(&query, id, &mut rng);
+            let chunks = aby3_party_db_chunks::<u16>(&conn, id, read_size).unwrap();
+            let res = iris
+                .iris_in_db_stream(&query_, &query.mask, stream::iter(chunks), CHUNK_SIZE)
+                .await
+                .unwrap();
+            assert_eq!(res, expected);
+        }
+
+        iris.finish().await.unwrap();
+    }
+
+    // The connection is not Sync, so the parties run on a LocalSet
+    async fn party_db_stream_test_impl(sessions: usize, read_size: usize) {
+        let mut rng = ChaCha12Rng::from_entropy();
+        let iris_seed = rng.gen::<<ChaCha12Rng as SeedableRng>::Seed>();
+        let seed = rng.gen::<<ChaCha12Rng as SeedableRng>::Seed>();
+
+        let network = TestNetwork3p::new();
+        let net = network.get_party_networks();
+
+        let local = LocalSet::new();
+        let mut tasks = Vec::with_capacity(NUM_PARTIES);
+        for n in net {
+            tasks.push(local.spawn_local(party_db_stream_test_inner(
+                n, seed, iris_seed, sessions, read_size,
+            )));
+        }
+        local
+            .run_until(async {
+                for t in tasks {
+                    t.await.expect("Task exited normally");
+                }
+            })
+            .await;
+    }
+
+    #[tokio::test]
+    async fn party_db_stream_test() {
+        // Item sizes which are no multiple of the chunk size
+        party_db_stream_test_impl(1, CHUNK_SIZE - 5).await;
+        party_db_stream_test_impl(2, CHUNK_SIZE + 3).await;
+        party_db_stream_test_impl(3, 2 * CHUNK_SIZE + 7).await;
+    }
+
+    async fn stream_fork_cleanup_test_inner(
+        net: PartyTestNetwork,
+        seed: <ChaCha12Rng as SeedableRng>::Seed,
+        iris_seed: <ChaCha12Rng as SeedableRng>::Seed,
+    ) {
+        let shutdowns = Arc::new(AtomicUsize::new(0));
+        let net = ForkLimitNetwork {
+            net,
+            forks: 2,
+            shutdowns: shutdowns.to_owned(),
+        };
+        let protocol = Aby3::new(net);
+        let mut iris = IrisProtocol::new(protocol).unwrap();
+        iris.set_sessions(2).unwrap();
+        let id = iris.get_id();
+
+        iris.preprocessing().await.unwrap();
+
+        let mut iris_rng = ChaCha12Rng::from_seed(iris_seed);
+        let mut rng = ChaCha12Rng::from_seed(seed);
+        let db = create_database(DB_SIZE, &mut iris_rng);
+        let iris1 = db[DB_SIZE / 2].get_similar_iris(&mut rng);
+        let db_t = db
+            .iter()
+            .map(|iris| share_iris_code::<u16, _>(iris, id, &mut rng))
+            .collect::<Vec<_>>();
+        let masks = db.iter().map(|iris| iris.mask).collect::<Vec<_>>();
+        let iris1_ = share_iris_code(&iris1, id, &mut rng);
+
+        // An error in the middle of the stream still finishes the fork
+        let items = vec![
+            Ok(DbShares {
+                shares: db_t[..CHUNK_SIZE].to_vec(),
+                masks: masks[..CHUNK_SIZE].to_vec(),
+            }),
+            Err(Error::InvalidSizeError),
+        ];
+        assert!(iris
+            .iris_in_db_stream(&iris1_, &iris1.mask, stream::iter(items), CHUNK_SIZE)
+            .await
+            .is_err());
+        assert_eq!(shutdowns.load(Ordering::SeqCst), 1);
+
+        // The second fork fails, which is an error for streams. The first fork is finished
+        iris.set_sessions(3).unwrap();
+        let items = vec![Ok(DbShares {
+            shares: db_t.to_owned(),
+            masks: masks.to_owned(),
+        })];
+        assert!(matches!(
+            iris.iris_in_db_stream(&iris1_, &iris1.mask, stream::iter(items), CHUNK_SIZE)
+                .await,
+            Err(Error::ConfigError)
+        ));
+        assert_eq!(shutdowns.load(Ordering::SeqCst), 2);
+
+        iris.finish().await.unwrap();
+        assert_eq!(shutdowns.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+    async fn stream_fork_cleanup_test() {
+        let mut rng = ChaCha12Rng::from_entropy();
+        let iris_seed = rng.gen::<<ChaCha12Rng as SeedableRng>::Seed>();
+        let seed = rng.gen::<<ChaCha12Rng as SeedableRng>::Seed>();
+
+        let network = TestNetwork3p::new();
+        let net = network.get_party_networks();
+
+        let mut tasks = Vec::with_capacity(NUM_PARTIES);
+        for n in net {
+            let t =
+                tokio::spawn(
+                    async move { stream_fork_cleanup_test_inner(n, seed, iris_seed).await },
+                );
+            tasks.push(t);
+        }
+
+        for t in tasks {
+            t.await.expect("Task exited normally");
+        }
     }
 }
@@ -179,11 +179,11 @@ mod iris_mpc_test {
         hwd_test_aby3_mal_impl::<u16>().await
     }
 
-    async fn plain_hwd_test_inner<T: Sharable>()
+    async fn plain_hwd_test_inner<T>()
     where
         Standard: Distribution<T>,
         Standard: Distribution<T::Share>,
-        T: Mul<T::Share, Output = T>,
+        T: Sharable + Mul<T::Share, Output = T>,
         <T as std::convert::TryFrom<usize>>::Error: std::fmt::Debug,
     {
         let mut iris_rng = ChaCha12Rng::from_entropy();
@@ -214,11 +214,11 @@ mod iris_mpc_test {
         plain_hwd_test_inner::<u16>().await
     }
 
-    async fn plain_lt_tester<T: Sharable>(code1: IrisCode, code2: IrisCode) -> bool
+    async fn plain_lt_tester<T>(code1: IrisCode, code2: IrisCode) -> bool
     where
         Standard: Distribution<T>,
         Standard: Distribution<T::Share>,
-        T: Mul<T::Share, Output = T>,
+        T: Sharable + Mul<T::Share, Output = T>,
         <T as std::convert::TryFrom<usize>>::Error: std::fmt::Debug,
     {
         let protocol = Plain::default();
@@ -245,11 +245,11 @@ mod iris_mpc_test {
         cmp
     }
 
-    async fn plain_lt_test_inner<T: Sharable>()
+    async fn plain_lt_test_inner<T>()
     where
         Standard: Distribution<T>,
         Standard: Distribution<T::Share>,
-        T: Mul<T::Share, Output = T>,
+        T: Sharable + Mul<T::Share, Output = T>,
         <T as std::convert::TryFrom<usize>>::Error: std::fmt::Debug,
     {
         let mut iris_rng = ChaCha12Rng::from_entropy();
@@ -408,14 +408,11 @@ mod iris_mpc_test {
         lt_test_aby3_mal_impl::<u16>().await
     }
 
-    async fn plain_cmp_many_iris_tester<T: Sharable>(
-        code1: IrisCode,
-        code2: Vec<IrisCode>,
-    ) -> Vec<bool>
+    async fn plain_cmp_many_iris_tester<T>(code1: IrisCode, code2: Vec<IrisCode>) -> Vec<bool>
     where
         Standard: Distribution<T>,
         Standard: Distribution<T::Share>,
-        T: Mul<T::Share, Output = T>,
+        T: Sharable + Mul<T::Share, Output = T>,
         <T as std::convert::TryFrom<usize>>::Error: std::fmt::Debug,
     {
         let protocol = Plain::default();
@@ -434,21 +431,23 @@ mod iris_mpc_test {
             mask2.push(code.mask);
         }
 
-        let cmp = Bit::convert_vec(
-            iris.compare_iris_many(&inp1, &inp2s, &code1.mask, &mask2)
-                .await
-                .unwrap(),
-        );
+        let cmp = iris
+            .compare_iris_many(&inp1, &inp2s, &code1.mask, &mask2)
+            .await
+            .unwrap()
+            .iter()
+            .map(Bit::convert)
+            .collect::<Vec<_>>();
 
         assert_eq!(cmp, cmp_);
         cmp
     }
 
-    async fn plain_cmp_iris_tester<T: Sharable>(code1: IrisCode, code2: IrisCode) -> bool
+    async fn plain_cmp_iris_tester<T>(code1: IrisCode, code2: IrisCode) -> bool
     where
         Standard: Distribution<T>,
         Standard: Distribution<T::Share>,
-        T: Mul<T::Share, Output = T>,
+        T: Sharable + Mul<T::Share, Output = T>,
         <T as std::convert::TryFrom<usize>>::Error: std::fmt::Debug,
     {
         let protocol = Plain::default();
@@ -468,11 +467,11 @@ mod iris_mpc_test {
         cmp
     }
 
-    async fn plain_cmp_iris_test_inner<T: Sharable>()
+    async fn plain_cmp_iris_test_inner<T>()
     where
         Standard: Distribution<T>,
         Standard: Distribution<T::Share>,
-        T: Mul<T::Share, Output = T>,
+        T: Sharable + Mul<T::Share, Output = T>,
         <T as std::convert::TryFrom<usize>>::Error: std::fmt::Debug,
     {
         let mut iris_rng = ChaCha12Rng::from_entropy();
@@ -705,11 +704,11 @@ mod iris_mpc_test {
         cmp_iris_test_aby3_mal_impl::<u16>().await
     }
 
-    async fn plain_full_test_inner<T: Sharable>()
+    async fn plain_full_test_inner<T>()
     where
         Standard: Distribution<T>,
         Standard: Distribution<T::Share>,
-        T: Mul<T::Share, Output = T>,
+        T: Sharable + Mul<T::Share, Output = T>,
         <T as std::convert::TryFrom<usize>>::Error: std::fmt::Debug,
     {
         let mut rng = ChaCha12Rng::from_entropy();
@@ -1,13 +1,16 @@
 mod iris_mpc_test {
     use crate::{
         iris::protocol::{IrisProtocol, IrisSpdzWise},
-        prelude::{Aby3Share, Bit, MpcTrait, PartyTestNetwork, Sharable, TestNetwork3p},
+        prelude::{
+            Aby3Share, BinaryVerification, Bit, CutAndChoose, Error, MpcTrait, PartyTestNetwork,
+            Sharable, TestNetwork3p, TripleStore,
+        },
         spdzwise::{
             protocol::{SpdzWise, TShare, UShare, VecTShare},
             vecshare::VecShare,
         },
         tests::iris_config::iris_config::create_database,
-        traits::{mpc_trait::Plain, share_trait::VecShareTrait},
+        traits::{binary_trait::BinaryMpcTrait, mpc_trait::Plain, share_trait::VecShareTrait},
     };
     use plain_reference::IrisCode;
     use rand::{
@@ -15,7 +18,7 @@ mod iris_mpc_test {
         Rng, SeedableRng,
     };
     use rand_chacha::ChaCha12Rng;
-    use std::ops::Mul;
+    use std::{ops::Mul, path::PathBuf};
 
     const NUM_PARTIES: usize = PartyTestNetwork::NUM_PARTIES;
     const DB_SIZE: usize = 128;
@@ -154,11 +157,11 @@ mod iris_mpc_test {
         hwd_test_spdzwise_impl::<u16>().await
     }
 
-    async fn plain_hwd_test_inner<T: Sharable>()
+    async fn plain_hwd_test_inner<T>()
     where
         Standard: Distribution<T>,
         Standard: Distribution<T::Share>,
-        T: Mul<T::Share, Output = T>,
+        T: Sharable + Mul<T::Share, Output = T>,
         <T as std::convert::TryFrom<usize>>::Error: std::fmt::Debug,
     {
         let mut iris_rng = ChaCha12Rng::from_entropy();
@@ -189,11 +192,11 @@ mod iris_mpc_test {
         plain_hwd_test_inner::<u16>().await
     }
 
-    async fn plain_lt_tester<T: Sharable>(code1: IrisCode, code2: IrisCode) -> bool
+    async fn plain_lt_tester<T>(code1: IrisCode, code2: IrisCode) -> bool
     where
         Standard: Distribution<T>,
         Standard: Distribution<T::Share>,
-        T: Mul<T::Share, Output = T>,
+        T: Sharable + Mul<T::Share, Output = T>,
         <T as std::convert::TryFrom<usize>>::Error: std::fmt::Debug,
     {
         let protocol = Plain::default();
@@ -220,11 +223,11 @@ mod iris_mpc_test {
         cmp
     }
 
-    async fn plain_lt_test_inner<T: Sharable>()
+    async fn plain_lt_test_inner<T>()
     where
         Standard: Distribution<T>,
         Standard: Distribution<T::Share>,
-        T: Mul<T::Share, Output = T>,
+        T: Sharable + Mul<T::Share, Output = T>,
         <T as std::convert::TryFrom<usize>>::Error: std::fmt::Debug,
     {
         let mut iris_rng = ChaCha12Rng::from_entropy();
@@ -363,14 +366,11 @@ mod iris_mpc_test {
         lt_test_spdzwise_impl::<u16>().await
     }
 
-    async fn plain_cmp_many_iris_tester<T: Sharable>(
-        code1: IrisCode,
-        code2: Vec<IrisCode>,
-    ) -> Vec<bool>
+    async fn plain_cmp_many_iris_tester<T>(code1: IrisCode, code2: Vec<IrisCode>) -> Vec<bool>
     where
         Standard: Distribution<T>,
         Standard: Distribution<T::Share>,
-        T: Mul<T::Share, Output = T>,
+        T: Sharable + Mul<T::Share, Output = T>,
         <T as std::convert::TryFrom<usize>>::Error: std::fmt::Debug,
     {
         let protocol = Plain::default();
@@ -389,21 +389,23 @@ mod iris_mpc_test {
             mask2.push(code.mask);
         }
 
-        let cmp = Bit::convert_vec(
-            iris.compare_iris_many(&inp1, &inp2s, &code1.mask, &mask2)
-                .await
-                .unwrap(),
-        );
+        let cmp = iris
+            .compare_iris_many(&inp1, &inp2s, &code1.mask, &mask2)
+            .await
+            .unwrap()
+            .iter()
+            .map(Bit::convert)
+            .collect::<Vec<_>>();
 
         assert_eq!(cmp, cmp_);
         cmp
     }
 
-    async fn plain_cmp_iris_tester<T: Sharable>(code1: IrisCode, code2: IrisCode) -> bool
+    async fn plain_cmp_iris_tester<T>(code1: IrisCode, code2: IrisCode) -> bool
     where
         Standard: Distribution<T>,
         Standard: Distribution<T::Share>,
-        T: Mul<T::Share, Output = T>,
+        T: Sharable + Mul<T::Share, Output = T>,
         <T as std::convert::TryFrom<usize>>::Error: std::fmt::Debug,
     {
         let protocol = Plain::default();
@@ -423,11 +425,11 @@ mod iris_mpc_test {
         cmp
     }
 
-    async fn plain_cmp_iris_test_inner<T: Sharable>()
+    async fn plain_cmp_iris_test_inner<T>()
     where
         Standard: Distribution<T>,
         Standard: Distribution<T::Share>,
-        T: Mul<T::Share, Output = T>,
+        T: Sharable + Mul<T::Share, Output = T>,
         <T as std::convert::TryFrom<usize>>::Error: std::fmt::Debug,
     {
         let mut iris_rng = ChaCha12Rng::from_entropy();
@@ -639,11 +641,252 @@ mod iris_mpc_test {
         cmp_iris_test_spdzwise_impl::<u16>().await
     }
 
-    async fn plain_full_test_inner<T: Sharable>()
+    async fn stored_triples_test_spdzwise_impl_inner<T: Sharable, R: Rng + SeedableRng>(
+        net: PartyTestNetwork,
+        seed: R::Seed,
+        iris_seed: R::Seed,
+        dir: PathBuf,
+    ) where
+        Standard: Distribution<UShare<T>>,
+        Standard: Distribution<T::Share>,
+        Aby3Share<T::VerificationShare>: Mul<Output = Aby3Share<T::VerificationShare>>,
+        Aby3Share<T::VerificationShare>: Mul<UShare<T>, Output = Aby3Share<T::VerificationShare>>,
+        Aby3Share<T>: Mul<T::Share, Output = Aby3Share<T>>,
+        <T as std::convert::TryFrom<usize>>::Error: std::fmt::Debug,
+    {
+        let protocol = SpdzWise::<PartyTestNetwork, T::VerificationShare>::new(net);
+        let mut iris = IrisSpdzWise::<T, _>::new(protocol).unwrap();
+        let id = iris.get_id();
+
+        iris.preprocessing().await.unwrap();
+        iris.set_new_mac_key();
+        let r = iris.open_mac_key().await.unwrap();
+
+        // Offline phase
+        let store = TripleStore::new(dir, id).unwrap();
+        let num_and_triples = iris.msb_and_gates(2, CHUNK_SIZE);
+        let batch_id = iris
+            .get_mpc_mut()
+            .store_triples(num_and_triples, &store)
+            .await
+            .unwrap();
+        assert_eq!(store.list().unwrap(), vec![batch_id]);
+
+        // Online phase
+        iris.get_mpc_mut()
+            .set_triple_store(store.to_owned())
+            .unwrap();
+        iris.precompute_and_triples(num_and_triples).await.unwrap();
+        assert!(store.list().unwrap().is_empty());
+
+        let mut iris_rng = R::from_seed(iris_seed);
+        let mut rng = R::from_seed(seed);
+        let code1 = IrisCode::random_rng(&mut iris_rng);
+        let code2 = IrisCode::random_rng(&mut iris_rng);
+        let code3 = code1.get_similar_iris(&mut iris_rng);
+        let c = cmp_many_iris_tester_spdzwise::<T, _, _>(
+            &mut iris,
+            &mut rng,
+            code1,
+            vec![code2, code3],
+            r,
+        )
+        .await;
+        assert!(c[1]);
+
+        iris.finish().await.unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+    async fn stored_triples_test_spdzwise() {
+        let mut tasks = Vec::with_capacity(NUM_PARTIES);
+
+        let mut rng = ChaCha12Rng::from_entropy();
+        let iris_seed = rng.gen::<<ChaCha12Rng as SeedableRng>::Seed>();
+        let seed = rng.gen::<<ChaCha12Rng as SeedableRng>::Seed>();
+        let dir = std::env::temp_dir().join(format!("iris-mpc-triples-{}", rng.gen::<u64>()));
+
+        let network = TestNetwork3p::new();
+        let net = network.get_party_networks();
+
+        for n in net {
+            let dir = dir.to_owned();
+            let t = tokio::spawn(async move {
+                stored_triples_test_spdzwise_impl_inner::<u16, ChaCha12Rng>(n, seed, iris_seed, dir)
+                    .await
+            });
+            tasks.push(t);
+        }
+
+        for t in tasks {
+            t.await.expect("Task exited normally");
+        }
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn cut_and_choose_security_test() {
+        // B=2 with 2^20 triples was the previously hardcoded setting for 40 bit security
+        let params = CutAndChoose::default();
+        assert!(params.security_level(1 << 20) >= 40.);
+        let min = params.min_batch_size();
+        assert!(min <= 1 << 20);
+        assert!(params.security_level(min) >= 40.);
+        assert!(params.security_level(min - 1) < 40.);
+
+        // Larger buckets require smaller batches
+        let params = CutAndChoose::new(3, 3, 40).unwrap();
+        assert!(params.min_batch_size() < min);
+        let params = CutAndChoose::new(2, 2, 80).unwrap();
+        assert!(params.min_batch_size() > min);
+
+        assert!(CutAndChoose::new(1, 128, 40).is_err());
+        assert!(CutAndChoose::new(3, 2, 40).is_err());
+        assert!(CutAndChoose::new(2, 128, 0).is_err());
+    }
+
+    async fn cut_and_choose_test_spdzwise_impl_inner<T: Sharable, R: Rng + SeedableRng>(
+        net: PartyTestNetwork,
+        seed: R::Seed,
+        iris_seed: R::Seed,
+    ) where
+        Standard: Distribution<UShare<T>>,
+        Standard: Distribution<T::Share>,
+        Aby3Share<T::VerificationShare>: Mul<Output = Aby3Share<T::VerificationShare>>,
+        Aby3Share<T::VerificationShare>: Mul<UShare<T>, Output = Aby3Share<T::VerificationShare>>,
+        Aby3Share<T>: Mul<T::Share, Output = Aby3Share<T>>,
+        <T as std::convert::TryFrom<usize>>::Error: std::fmt::Debug,
+    {
+        let mut protocol = SpdzWise::<PartyTestNetwork, T::VerificationShare>::new(net);
+        protocol.set_cut_and_choose(CutAndChoose::new(3, 256, 40).unwrap());
+        let mut iris = IrisSpdzWise::<T, _>::new(protocol).unwrap();
+
+        iris.preprocessing().await.unwrap();
+        iris.set_new_mac_key();
+        let r = iris.open_mac_key().await.unwrap();
+
+        let num_and_triples = iris.msb_and_gates(2, CHUNK_SIZE);
+        iris.precompute_and_triples(num_and_triples).await.unwrap();
+
+        let mut iris_rng = R::from_seed(iris_seed);
+        let mut rng = R::from_seed(seed);
+        let code1 = IrisCode::random_rng(&mut iris_rng);
+        let code2 = IrisCode::random_rng(&mut iris_rng);
+        let code3 = code1.get_similar_iris(&mut iris_rng);
+        let c = cmp_many_iris_tester_spdzwise::<T, _, _>(
+            &mut iris,
+            &mut rng,
+            code1,
+            vec![code2, code3],
+            r,
+        )
+        .await;
+        assert!(c[1]);
+
+        iris.finish().await.unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+    async fn cut_and_choose_test_spdzwise() {
+        let mut tasks = Vec::with_capacity(NUM_PARTIES);
+
+        let mut rng = ChaCha12Rng::from_entropy();
+        let iris_seed = rng.gen::<<ChaCha12Rng as SeedableRng>::Seed>();
+        let seed = rng.gen::<<ChaCha12Rng as SeedableRng>::Seed>();
+
+        let network = TestNetwork3p::new();
+        let net = network.get_party_networks();
+
+        for n in net {
+            let t = tokio::spawn(async move {
+                cut_and_choose_test_spdzwise_impl_inner::<u16, ChaCha12Rng>(n, seed, iris_seed)
+                    .await
+            });
+            tasks.push(t);
+        }
+
+        for t in tasks {
+            t.await.expect("Task exited normally");
+        }
+    }
+
+    async fn gf2p64_mac_test_spdzwise_impl_inner<T: Sharable, R: Rng + SeedableRng>(
+        net: PartyTestNetwork,
+        seed: R::Seed,
+        iris_seed: R::Seed,
+    ) where
+        Standard: Distribution<UShare<T>>,
+        Standard: Distribution<T::Share>,
+        Aby3Share<T::VerificationShare>: Mul<Output = Aby3Share<T::VerificationShare>>,
+        Aby3Share<T::VerificationShare>: Mul<UShare<T>, Output = Aby3Share<T::VerificationShare>>,
+        Aby3Share<T>: Mul<T::Share, Output = Aby3Share<T>>,
+        <T as std::convert::TryFrom<usize>>::Error: std::fmt::Debug,
+    {
+        let protocol = SpdzWise::<PartyTestNetwork, T::VerificationShare>::with_binary_verification(
+            net,
+            BinaryVerification::Gf2p64Mac,
+        );
+        let mut iris = IrisSpdzWise::<T, _>::new(protocol).unwrap();
+
+        iris.preprocessing().await.unwrap();
+        iris.set_new_mac_key();
+        let r = iris.open_mac_key().await.unwrap();
+
+        let mut iris_rng = R::from_seed(iris_seed);
+        let mut rng = R::from_seed(seed);
+        let code1 = IrisCode::random_rng(&mut iris_rng);
+        let code2 = IrisCode::random_rng(&mut iris_rng);
+        let code3 = code1.get_similar_iris(&mut iris_rng);
+        let c = cmp_many_iris_tester_spdzwise::<T, _, _>(
+            &mut iris,
+            &mut rng,
+            code1,
+            vec![code2, code3],
+            r,
+        )
+        .await;
+        assert!(!c[0]);
+        assert!(c[1]);
+
+        // An additive error on an AND gate is detected
+        let mpc = iris.get_mpc_mut();
+        let zeros = vec![Aby3Share::<u128>::default(); 2];
+        <_ as BinaryMpcTrait<u128, _>>::and_many(mpc, &zeros, &zeros)
+            .await
+            .unwrap();
+        mpc.tamper_and_triple();
+        let res = <_ as MpcTrait<T, TShare<T>, Aby3Share<Bit>>>::verify(mpc).await;
+        assert!(matches!(res, Err(Error::VerifyError)));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+    async fn gf2p64_mac_test_spdzwise() {
+        let mut tasks = Vec::with_capacity(NUM_PARTIES);
+
+        let mut rng = ChaCha12Rng::from_entropy();
+        let iris_seed = rng.gen::<<ChaCha12Rng as SeedableRng>::Seed>();
+        let seed = rng.gen::<<ChaCha12Rng as SeedableRng>::Seed>();
+
+        let network = TestNetwork3p::new();
+        let net = network.get_party_networks();
+
+        for n in net {
+            let t = tokio::spawn(async move {
+                gf2p64_mac_test_spdzwise_impl_inner::<u16, ChaCha12Rng>(n, seed, iris_seed).await
+            });
+            tasks.push(t);
+        }
+
+        for t in tasks {
+            t.await.expect("Task exited normally");
+        }
+    }
+
+    async fn plain_full_test_inner<T>()
     where
         Standard: Distribution<T>,
         Standard: Distribution<T::Share>,
-        T: Mul<T::Share, Output = T>,
+        T: Sharable + Mul<T::Share, Output = T>,
         <T as std::convert::TryFrom<usize>>::Error: std::fmt::Debug,
     {
         let mut rng = ChaCha12Rng::from_entropy();
@@ -700,6 +943,7 @@ mod iris_mpc_test {
         net: PartyTestNetwork,
         seed: R::Seed,
         iris_seed: R::Seed,
+        threads: usize,
     ) where
         Standard: Distribution<UShare<T>>,
         Standard: Distribution<T::Share>,
@@ -708,7 +952,8 @@ mod iris_mpc_test {
         Aby3Share<T>: Mul<T::Share, Output = Aby3Share<T>>,
         <T as std::convert::TryFrom<usize>>::Error: std::fmt::Debug,
     {
-        let protocol = SpdzWise::<PartyTestNetwork, T::VerificationShare>::new(net);
+        let mut protocol = SpdzWise::<PartyTestNetwork, T::VerificationShare>::new(net);
+        protocol.set_threads(threads).unwrap();
         let mut iris = IrisSpdzWise::<T, _>::new(protocol).unwrap();
         let id = iris.get_id();
 
@@ -761,7 +1006,7 @@ mod iris_mpc_test {
         assert!(res2);
     }
 
-    async fn full_test_spdzwise_impl<T: Sharable>()
+    async fn full_test_spdzwise_impl<T: Sharable>(threads: usize)
     where
         Standard: Distribution<UShare<T>>,
         Standard: Distribution<T::Share>,
@@ -781,7 +1026,7 @@ mod iris_mpc_test {
 
         for n in net {
             let t = tokio::spawn(async move {
-                full_test_spdzwise_impl_inner::<T, ChaCha12Rng>(n, seed, iris_seed).await
+                full_test_spdzwise_impl_inner::<T, ChaCha12Rng>(n, seed, iris_seed, threads).await
             });
             tasks.push(t);
         }
@@ -793,6 +1038,7 @@ mod iris_mpc_test {
 
     #[tokio::test(flavor = "multi_thread", worker_threads = 3)]
     async fn full_test_spdzwise() {
-        full_test_spdzwise_impl::<u16>().await
+        full_test_spdzwise_impl::<u16>(1).await;
+        full_test_spdzwise_impl::<u16>(4).await;
     }
 }
@@ -144,11 +144,11 @@ mod iris_swift3_test {
         hwd_test_swift3_impl::<u16>().await
     }
 
-    async fn plain_hwd_test_inner<T: Sharable>()
+    async fn plain_hwd_test_inner<T>()
     where
         Standard: Distribution<T>,
         Standard: Distribution<T::Share>,
-        T: Mul<T::Share, Output = T>,
+        T: Sharable + Mul<T::Share, Output = T>,
         <T as std::convert::TryFrom<usize>>::Error: std::fmt::Debug,
     {
         let mut iris_rng = ChaCha12Rng::from_entropy();
@@ -179,11 +179,11 @@ mod iris_swift3_test {
         plain_hwd_test_inner::<u16>().await
     }
 
-    async fn plain_lt_tester<T: Sharable>(code1: IrisCode, code2: IrisCode) -> bool
+    async fn plain_lt_tester<T>(code1: IrisCode, code2: IrisCode) -> bool
     where
         Standard: Distribution<T>,
         Standard: Distribution<T::Share>,
-        T: Mul<T::Share, Output = T>,
+        T: Sharable + Mul<T::Share, Output = T>,
         <T as std::convert::TryFrom<usize>>::Error: std::fmt::Debug,
     {
         let protocol = Plain::default();
@@ -210,11 +210,11 @@ mod iris_swift3_test {
         cmp
     }
 
-    async fn plain_lt_test_inner<T: Sharable>()
+    async fn plain_lt_test_inner<T>()
     where
         Standard: Distribution<T>,
         Standard: Distribution<T::Share>,
-        T: Mul<T::Share, Output = T>,
+        T: Sharable + Mul<T::Share, Output = T>,
         <T as std::convert::TryFrom<usize>>::Error: std::fmt::Debug,
     {
         let mut iris_rng = ChaCha12Rng::from_entropy();
@@ -339,14 +339,11 @@ mod iris_swift3_test {
         lt_test_swift3_impl::<u16>().await
     }
 
-    async fn plain_cmp_many_iris_tester<T: Sharable>(
-        code1: IrisCode,
-        code2: Vec<IrisCode>,
-    ) -> Vec<bool>
+    async fn plain_cmp_many_iris_tester<T>(code1: IrisCode, code2: Vec<IrisCode>) -> Vec<bool>
     where
         Standard: Distribution<T>,
         Standard: Distribution<T::Share>,
-        T: Mul<T::Share, Output = T>,
+        T: Sharable + Mul<T::Share, Output = T>,
         <T as std::convert::TryFrom<usize>>::Error: std::fmt::Debug,
     {
         let protocol = Plain::default();
@@ -365,21 +362,23 @@ mod iris_swift3_test {
             mask2.push(code.mask);
         }
 
-        let cmp = Bit::convert_vec(
-            iris.compare_iris_many(&inp1, &inp2s, &code1.mask, &mask2)
-                .await
-                .unwrap(),
-        );
+        let cmp = iris
+            .compare_iris_many(&inp1, &inp2s, &code1.mask, &mask2)
+            .await
+            .unwrap()
+            .iter()
+            .map(Bit::convert)
+            .collect::<Vec<_>>();
 
         assert_eq!(cmp, cmp_);
         cmp
     }
 
-    async fn plain_cmp_iris_tester<T: Sharable>(code1: IrisCode, code2: IrisCode) -> bool
+    async fn plain_cmp_iris_tester<T>(code1: IrisCode, code2: IrisCode) -> bool
     where
         Standard: Distribution<T>,
         Standard: Distribution<T::Share>,
-        T: Mul<T::Share, Output = T>,
+        T: Sharable + Mul<T::Share, Output = T>,
         <T as std::convert::TryFrom<usize>>::Error: std::fmt::Debug,
     {
         let protocol = Plain::default();
@@ -399,11 +398,11 @@ mod iris_swift3_test {
         cmp
     }
 
-    async fn plain_cmp_iris_test_inner<T: Sharable>()
+    async fn plain_cmp_iris_test_inner<T>()
     where
         Standard: Distribution<T>,
         Standard: Distribution<T::Share>,
-        T: Mul<T::Share, Output = T>,
+        T: Sharable + Mul<T::Share, Output = T>,
         <T as std::convert::TryFrom<usize>>::Error: std::fmt::Debug,
     {
         let mut iris_rng = ChaCha12Rng::from_entropy();
@@ -589,11 +588,11 @@ mod iris_swift3_test {
         cmp_iris_test_swift3_impl::<u16>().await
     }
 
-    async fn plain_full_test_inner<T: Sharable>()
+    async fn plain_full_test_inner<T>()
     where
         Standard: Distribution<T>,
         Standard: Distribution<T::Share>,
-        T: Mul<T::Share, Output = T>,
+        T: Sharable + Mul<T::Share, Output = T>,
         <T as std::convert::TryFrom<usize>>::Error: std::fmt::Debug,
     {
         let mut rng = ChaCha12Rng::from_entropy();
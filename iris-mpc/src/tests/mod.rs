@@ -1,3 +1,10 @@
+pub mod blame;
+pub mod bristol;
+pub mod compressed_db;
+pub mod db_management;
+pub mod db_roots;
+pub mod dry_run;
+pub mod generation;
 pub mod iris_aby3;
 pub mod iris_aby3_mal;
 pub mod iris_config;
@@ -7,3 +14,7 @@ pub mod network_aby3;
 pub mod network_aby3_mal;
 pub mod network_spdzwise;
 pub mod network_swift3;
+pub mod packed_bits;
+pub mod party_db;
+pub mod sharding;
+pub mod share_file;
@@ -1,9 +1,9 @@
 mod aby3_test {
     use crate::{
         aby3::{protocol::Aby3, share::Share},
-        prelude::{PartyTestNetwork, TestNetwork3p},
+        prelude::{AdderStrategy, MsbVariant, PartyTestNetwork, TestNetwork3p},
         traits::mpc_trait::{MpcTrait, Plain},
-        types::{bit::Bit, int_ring::IntRing2k, sharable::Sharable},
+        types::{bit::Bit, int_ring::IntRing2k, ring_element::RingImpl, sharable::Sharable},
     };
     use num_traits::Zero;
     use rand::{
@@ -434,6 +434,83 @@ mod aby3_test {
         assert_eq!(r0, &prod);
     }
 
+    async fn fork_test_party<T: Sharable>(net: PartyTestNetwork) -> (T, Vec<T>)
+    where
+        Standard: Distribution<T>,
+        Standard: Distribution<T::Share>,
+        Share<T>: Mul<Output = Share<T>>,
+        Share<T>: Mul<T::Share, Output = Share<T>>,
+    {
+        let mut protocol = Aby3::<PartyTestNetwork>::new(net);
+        protocol.preprocess().await.unwrap();
+
+        let mut rng = ChaCha12Rng::from_entropy();
+        let input = rng.gen::<T>();
+        let shares = protocol.input_all(input).await.unwrap();
+
+        let mut session1 = MpcTrait::<T, Share<T>, Share<Bit>>::fork(&mut protocol)
+            .await
+            .unwrap()
+            .unwrap();
+        let mut session2 = MpcTrait::<T, Share<T>, Share<Bit>>::fork(&mut protocol)
+            .await
+            .unwrap()
+            .unwrap();
+
+        // All sessions communicate concurrently
+        let (r0, r1, r2) = tokio::join!(
+            protocol.mul(shares[0].to_owned(), shares[1].to_owned()),
+            session1.mul(shares[1].to_owned(), shares[2].to_owned()),
+            session2.mul(shares[0].to_owned(), shares[2].to_owned()),
+        );
+        let (r0, r1, r2) = (r0.unwrap(), r1.unwrap(), r2.unwrap());
+        let (o0, o12) = tokio::join!(protocol.open(r0), session1.open_many(vec![r1, r2]));
+
+        let mut open = vec![o0.unwrap()];
+        open.extend(o12.unwrap());
+
+        MpcTrait::<T, Share<T>, Share<Bit>>::finish(session1)
+            .await
+            .unwrap();
+        MpcTrait::<T, Share<T>, Share<Bit>>::finish(session2)
+            .await
+            .unwrap();
+        MpcTrait::<T, Share<T>, Share<Bit>>::finish(protocol)
+            .await
+            .unwrap();
+        (input, open)
+    }
+
+    #[tokio::test]
+    async fn fork_test() {
+        let mut tasks = Vec::with_capacity(NUM_PARTIES);
+
+        let network = TestNetwork3p::new();
+        let net = network.get_party_networks();
+
+        for n in net {
+            let t = tokio::spawn(async move { fork_test_party::<u16>(n).await });
+            tasks.push(t);
+        }
+
+        let mut inputs = Vec::with_capacity(NUM_PARTIES);
+        let mut results = Vec::with_capacity(NUM_PARTIES);
+        for t in tasks {
+            let (inp, outp) = t.await.expect("Task exited normally");
+            inputs.push(inp);
+            results.push(outp);
+        }
+
+        let r0 = &results[0];
+        for r in results.iter().skip(1) {
+            assert_eq!(r0, r);
+        }
+        let expected = [(0, 1), (1, 2), (0, 2)]
+            .map(|(i, j)| inputs[i].wrapping_mul(inputs[j]))
+            .to_vec();
+        assert_eq!(r0, &expected);
+    }
+
     async fn mul_const_test_party<T: Sharable, R: Rng + SeedableRng>(
         net: PartyTestNetwork,
         seed: R::Seed,
@@ -578,11 +655,125 @@ mod aby3_test {
             assert_eq!(r0, r);
         }
         let mut plain = Plain::default();
-        let res = plain
+        let res: u16 = plain
             .dot(inputs[0].to_owned(), inputs[1].to_owned())
             .await
             .unwrap();
         assert_eq!(inputs.len(), 2);
         assert_eq!(r0, &res);
     }
+
+    async fn msb_test_party<T: Sharable, R: Rng + SeedableRng>(
+        net: PartyTestNetwork,
+        seed: R::Seed,
+        variant: MsbVariant,
+        strategy: AdderStrategy,
+    ) -> Vec<(bool, bool)>
+    where
+        Standard: Distribution<T>,
+        Standard: Distribution<T::Share>,
+        Share<T>: Mul<Output = Share<T>>,
+        Share<T>: Mul<T::Share, Output = Share<T>>,
+    {
+        let mut protocol = Aby3::<PartyTestNetwork>::new(net);
+        protocol.preprocess().await.unwrap();
+        protocol.set_msb_variant(variant).unwrap();
+        protocol.set_adder_strategy(strategy);
+        let id = protocol.get_id();
+
+        let mut rng = R::from_seed(seed);
+        let mut inputs = Vec::with_capacity(DOT_SIZE);
+        let mut shares = Vec::with_capacity(DOT_SIZE);
+        for _ in 0..DOT_SIZE {
+            let input = rng.gen::<T>();
+            let share =
+                Aby3::<PartyTestNetwork>::share(input, T::VerificationShare::default(), &mut rng)
+                    [id]
+                    .to_owned();
+            inputs.push(input);
+            shares.push(share);
+        }
+
+        if variant == MsbVariant::Fss {
+            // The remaining keys are generated inline
+            protocol
+                .precompute_dcf_keys::<T>(DOT_SIZE / 2)
+                .await
+                .unwrap();
+        }
+        if variant == MsbVariant::EdaBits {
+            // The remaining edaBits are generated inline
+            protocol
+                .precompute_edabits::<T>(DOT_SIZE / 2)
+                .await
+                .unwrap();
+        }
+        let single = shares.pop().unwrap();
+        let mut msbs = protocol.get_msb_many(shares).await.unwrap();
+        msbs.push(protocol.get_msb(single).await.unwrap());
+        protocol.verify().await.unwrap();
+        let open = protocol.open_bit_many(msbs).await.unwrap();
+
+        MpcTrait::<T, Share<T>, Share<Bit>>::finish(protocol)
+            .await
+            .unwrap();
+        inputs
+            .into_iter()
+            .zip(open)
+            .map(|(i, o)| (i.to_sharetype().get_msb().convert().convert(), o))
+            .collect()
+    }
+
+    async fn msb_test_impl(variant: MsbVariant, strategy: AdderStrategy) {
+        let mut tasks = Vec::with_capacity(NUM_PARTIES);
+
+        let mut rng = ChaCha12Rng::from_entropy();
+        let seed = rng.gen::<<ChaCha12Rng as SeedableRng>::Seed>();
+
+        let network = TestNetwork3p::new();
+        let net = network.get_party_networks();
+
+        for n in net {
+            let t = tokio::spawn(async move {
+                msb_test_party::<u16, ChaCha12Rng>(n, seed, variant, strategy).await
+            });
+            tasks.push(t);
+        }
+
+        for t in tasks {
+            for (plain, mpc) in t.await.expect("Task exited normally") {
+                assert_eq!(plain, mpc);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn msb_test() {
+        msb_test_impl(MsbVariant::Adder, AdderStrategy::RippleCarry).await
+    }
+
+    #[tokio::test]
+    async fn msb_kogge_stone_test() {
+        msb_test_impl(MsbVariant::Adder, AdderStrategy::KoggeStone).await
+    }
+
+    #[tokio::test]
+    async fn msb_brent_kung_test() {
+        msb_test_impl(MsbVariant::Adder, AdderStrategy::BrentKung).await
+    }
+
+    #[tokio::test]
+    async fn msb_sklansky_test() {
+        msb_test_impl(MsbVariant::Adder, AdderStrategy::Sklansky).await
+    }
+
+    #[tokio::test]
+    async fn msb_fss_test() {
+        msb_test_impl(MsbVariant::Fss, AdderStrategy::default()).await
+    }
+
+    #[tokio::test]
+    async fn msb_edabits_test() {
+        msb_test_impl(MsbVariant::EdaBits, AdderStrategy::default()).await
+    }
 }
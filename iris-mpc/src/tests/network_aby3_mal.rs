@@ -1,9 +1,15 @@
 mod aby3_test {
     use crate::{
         aby3::share::Share,
-        prelude::{MalAby3, PartyTestNetwork, TestNetwork3p},
+        prelude::{MalAby3, MsbVariant, PartyTestNetwork, TestNetwork3p},
         traits::mpc_trait::{MpcTrait, Plain},
-        types::{bit::Bit, int_ring::IntRing2k, sharable::Sharable},
+        types::{
+            bit::Bit,
+            int_ring::IntRing2k,
+            packed_bits::PackedBits,
+            ring_element::{RingElement, RingImpl},
+            sharable::Sharable,
+        },
     };
     use num_traits::Zero;
     use rand::{
@@ -15,6 +21,9 @@ mod aby3_test {
 
     const NUM_PARTIES: usize = PartyTestNetwork::NUM_PARTIES;
     const DOT_SIZE: usize = 1000;
+    // Spans three words, the last one with padding
+    const OR_SIZE: usize = 300;
+    const OR_SET_BITS: [usize; 3] = [0, 200, OR_SIZE - 1];
 
     async fn share_test_party<T: Sharable, R: Rng + SeedableRng>(
         net: PartyTestNetwork,
@@ -677,11 +686,176 @@ mod aby3_test {
             assert_eq!(r0, r);
         }
         let mut plain = Plain::default();
-        let res = plain
+        let res: u16 = plain
             .dot(inputs[0].to_owned(), inputs[1].to_owned())
             .await
             .unwrap();
         assert_eq!(inputs.len(), 2);
         assert_eq!(r0, &res);
     }
+
+    async fn msb_edabits_test_party<T: Sharable, R: Rng + SeedableRng>(
+        net: PartyTestNetwork,
+        seed: R::Seed,
+    ) -> Vec<(bool, bool)>
+    where
+        Standard: Distribution<T>,
+        Standard: Distribution<T::Share>,
+        Standard: Distribution<<T::VerificationShare as Sharable>::Share>,
+        Share<T>: Mul<Output = Share<T>>,
+        Share<T>: Mul<T::Share, Output = Share<T>>,
+        Share<T::VerificationShare>:
+            for<'a> MulAssign<&'a <T::VerificationShare as Sharable>::Share>,
+        Share<T>: BitAnd<Output = Share<T>>,
+        Share<T>: BitAnd<T::Share, Output = Share<T>>,
+        Share<T::VerificationShare>: for<'a> Mul<
+            &'a <T::VerificationShare as Sharable>::Share,
+            Output = Share<T::VerificationShare>,
+        >,
+        Share<T::VerificationShare>:
+            Mul<<T::VerificationShare as Sharable>::Share, Output = Share<T::VerificationShare>>,
+    {
+        let mut protocol = MalAby3::<PartyTestNetwork>::new(net);
+        protocol.preprocess().await.unwrap();
+        assert!(protocol.set_msb_variant(MsbVariant::Fss).is_err());
+        protocol.set_msb_variant(MsbVariant::EdaBits).unwrap();
+        let id = protocol.get_id();
+
+        let mut rng = R::from_seed(seed);
+        let mut inputs = Vec::with_capacity(DOT_SIZE);
+        let mut shares = Vec::with_capacity(DOT_SIZE);
+        for _ in 0..DOT_SIZE {
+            let input = rng.gen::<T>();
+            let share = MalAby3::<PartyTestNetwork>::share(
+                input,
+                T::VerificationShare::default(),
+                &mut rng,
+            )[id]
+                .to_owned();
+            inputs.push(input);
+            shares.push(share);
+        }
+
+        // The remaining edaBits are generated inline
+        protocol
+            .precompute_edabits::<T>(DOT_SIZE / 2)
+            .await
+            .unwrap();
+        let single = shares.pop().unwrap();
+        let mut msbs = protocol.get_msb_many(shares).await.unwrap();
+        msbs.push(protocol.get_msb(single).await.unwrap());
+        protocol.verify().await.unwrap();
+        let open = protocol.open_bit_many(msbs).await.unwrap();
+
+        MpcTrait::<T, Share<T>, Share<Bit>>::finish(protocol)
+            .await
+            .unwrap();
+        inputs
+            .into_iter()
+            .zip(open)
+            .map(|(i, o)| (i.to_sharetype().get_msb().convert().convert(), o))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn msb_edabits_test() {
+        let mut tasks = Vec::with_capacity(NUM_PARTIES);
+
+        let mut rng = ChaCha12Rng::from_entropy();
+        let seed = rng.gen::<<ChaCha12Rng as SeedableRng>::Seed>();
+
+        let network = TestNetwork3p::new();
+        let net = network.get_party_networks();
+
+        for n in net {
+            let t =
+                tokio::spawn(
+                    async move { msb_edabits_test_party::<u16, ChaCha12Rng>(n, seed).await },
+                );
+            tasks.push(t);
+        }
+
+        for t in tasks {
+            for (plain, mpc) in t.await.expect("Task exited normally") {
+                assert_eq!(plain, mpc);
+            }
+        }
+    }
+
+    async fn reduce_binary_or_test_party<R: Rng + SeedableRng>(
+        net: PartyTestNetwork,
+        seed: R::Seed,
+    ) -> Vec<bool> {
+        let mut protocol = MalAby3::<PartyTestNetwork>::new(net);
+        MpcTrait::<u16, Share<u16>, Share<Bit>>::preprocess(&mut protocol)
+            .await
+            .unwrap();
+        let id = MpcTrait::<u16, Share<u16>, Share<Bit>>::get_id(&protocol);
+
+        let mut rng = R::from_seed(seed);
+        let mut results = Vec::with_capacity(OR_SET_BITS.len() + 1);
+        for set in OR_SET_BITS.iter().copied().map(Some).chain([None]) {
+            let mut words = vec![0u128; OR_SIZE.div_ceil(128)];
+            if let Some(i) = set {
+                words[i / 128] |= 1 << (i % 128);
+            }
+            // Binary sharing with the same layout as MalAby3::share
+            let shares = words
+                .into_iter()
+                .map(|word| {
+                    let a = rng.gen::<u128>();
+                    let b = rng.gen::<u128>();
+                    let components = [a, b, word ^ a ^ b];
+                    Share::new(
+                        RingElement(components[id]),
+                        RingElement(components[(id + 2) % 3]),
+                    )
+                })
+                .collect();
+            let bits = PackedBits::from_words(shares, OR_SIZE);
+
+            let res =
+                MpcTrait::<u16, Share<u16>, Share<Bit>>::reduce_binary_or(&mut protocol, bits, 1)
+                    .await
+                    .unwrap();
+            MpcTrait::<u16, Share<u16>, Share<Bit>>::verify(&mut protocol)
+                .await
+                .unwrap();
+            let open = MpcTrait::<u16, Share<u16>, Share<Bit>>::open_bit(&mut protocol, res)
+                .await
+                .unwrap();
+            results.push(open);
+        }
+
+        MpcTrait::<u16, Share<u16>, Share<Bit>>::finish(protocol)
+            .await
+            .unwrap();
+        results
+    }
+
+    #[tokio::test]
+    async fn reduce_binary_or_test() {
+        let mut tasks = Vec::with_capacity(NUM_PARTIES);
+
+        let mut rng = ChaCha12Rng::from_entropy();
+        let seed = rng.gen::<<ChaCha12Rng as SeedableRng>::Seed>();
+
+        let network = TestNetwork3p::new();
+        let net = network.get_party_networks();
+
+        for n in net {
+            let t =
+                tokio::spawn(
+                    async move { reduce_binary_or_test_party::<ChaCha12Rng>(n, seed).await },
+                );
+            tasks.push(t);
+        }
+
+        // Only the last input has no bit set
+        let mut expected = vec![true; OR_SET_BITS.len()];
+        expected.push(false);
+        for t in tasks {
+            assert_eq!(t.await.expect("Task exited normally"), expected);
+        }
+    }
 }
@@ -1,6 +1,8 @@
 mod spdzwise_test {
     use crate::{
-        prelude::{Aby3Share, Bit, PartyTestNetwork, TestNetwork3p},
+        prelude::{
+            Aby3Share, BinaryVerification, Bit, MsbVariant, PartyTestNetwork, TestNetwork3p,
+        },
         spdzwise::{
             protocol::{SpdzWise, TShare, UShare},
             share::Share,
@@ -10,7 +12,7 @@ mod spdzwise_test {
             mpc_trait::{MpcTrait, Plain},
             share_trait::VecShareTrait,
         },
-        types::{int_ring::IntRing2k, sharable::Sharable},
+        types::{int_ring::IntRing2k, ring_element::RingImpl, sharable::Sharable},
     };
     use num_traits::Zero;
     use rand::{
@@ -657,11 +659,128 @@ mod spdzwise_test {
             assert_eq!(r0, r);
         }
         let mut plain = Plain::default();
-        let res = plain
+        let res: u16 = plain
             .dot(inputs[0].to_owned(), inputs[1].to_owned())
             .await
             .unwrap();
         assert_eq!(inputs.len(), 2);
         assert_eq!(r0, &res);
     }
+
+    async fn msb_edabits_test_party<T: Sharable, R: Rng + SeedableRng>(
+        net: PartyTestNetwork,
+        seed: R::Seed,
+        mode: BinaryVerification,
+    ) -> Vec<(bool, bool)>
+    where
+        Standard: Distribution<T>,
+        Standard: Distribution<UShare<T>>,
+        Standard: Distribution<T::Share>,
+        Aby3Share<T::VerificationShare>: Mul<Output = Aby3Share<T::VerificationShare>>,
+        Aby3Share<T::VerificationShare>: Mul<UShare<T>, Output = Aby3Share<T::VerificationShare>>,
+        Aby3Share<T>: Mul<T::Share, Output = Aby3Share<T>>,
+    {
+        let mut protocol =
+            SpdzWise::<PartyTestNetwork, T::VerificationShare>::with_binary_verification(net, mode);
+        <_ as MpcTrait<T, TShare<T>, Aby3Share<Bit>>>::preprocess(&mut protocol)
+            .await
+            .unwrap();
+        <_ as MpcTrait<T, TShare<T>, Aby3Share<Bit>>>::set_new_mac_key(&mut protocol);
+        assert!(protocol.set_msb_variant(MsbVariant::Fss).is_err());
+        protocol.set_msb_variant(MsbVariant::EdaBits).unwrap();
+
+        let r = <_ as MpcTrait<T, TShare<T>, Aby3Share<Bit>>>::open_mac_key(&mut protocol)
+            .await
+            .unwrap();
+        let id = <_ as MpcTrait<T, TShare<T>, Aby3Share<Bit>>>::get_id(&protocol);
+
+        let mut rng = R::from_seed(seed);
+        let mut inputs = Vec::with_capacity(DOT_SIZE);
+        let mut shares = Vec::with_capacity(DOT_SIZE);
+        for _ in 0..DOT_SIZE {
+            let input = rng.gen::<T>();
+            let share = SpdzWise::<PartyTestNetwork, T::VerificationShare>::share(
+                input,
+                r.to_owned(),
+                &mut rng,
+            )[id]
+                .to_owned();
+            inputs.push(input);
+            shares.push(share);
+        }
+
+        // The remaining edaBits are generated inline
+        protocol
+            .precompute_edabits::<T>(DOT_SIZE / 2)
+            .await
+            .unwrap();
+        // AND gates of the online comparison and the inline edaBit generation
+        let num_and_triples =
+            <_ as MpcTrait<T, TShare<T>, Aby3Share<Bit>>>::msb_and_gates(&protocol, DOT_SIZE);
+        <_ as MpcTrait<T, TShare<T>, Aby3Share<Bit>>>::precompute_and_triples(
+            &mut protocol,
+            num_and_triples,
+        )
+        .await
+        .unwrap();
+        let single = shares.pop().unwrap();
+        let mut msbs =
+            <_ as MpcTrait<T, TShare<T>, Aby3Share<Bit>>>::get_msb_many(&mut protocol, shares)
+                .await
+                .unwrap();
+        msbs.push(
+            <_ as MpcTrait<T, TShare<T>, Aby3Share<Bit>>>::get_msb(&mut protocol, single)
+                .await
+                .unwrap(),
+        );
+        <_ as MpcTrait<T, TShare<T>, Aby3Share<Bit>>>::verify(&mut protocol)
+            .await
+            .unwrap();
+        let open =
+            <_ as MpcTrait<T, TShare<T>, Aby3Share<Bit>>>::open_bit_many(&mut protocol, msbs)
+                .await
+                .unwrap();
+
+        MpcTrait::<T, TShare<T>, Aby3Share<Bit>>::finish(protocol)
+            .await
+            .unwrap();
+        inputs
+            .into_iter()
+            .zip(open)
+            .map(|(i, o)| (i.to_sharetype().get_msb().convert().convert(), o))
+            .collect()
+    }
+
+    async fn msb_edabits_test_impl(mode: BinaryVerification) {
+        let mut tasks = Vec::with_capacity(NUM_PARTIES);
+
+        let mut rng = ChaCha12Rng::from_entropy();
+        let seed = rng.gen::<<ChaCha12Rng as SeedableRng>::Seed>();
+
+        let network = TestNetwork3p::new();
+        let net = network.get_party_networks();
+
+        for n in net {
+            let t = tokio::spawn(async move {
+                msb_edabits_test_party::<u16, ChaCha12Rng>(n, seed, mode).await
+            });
+            tasks.push(t);
+        }
+
+        for t in tasks {
+            for (plain, mpc) in t.await.expect("Task exited normally") {
+                assert_eq!(plain, mpc);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn msb_edabits_test() {
+        msb_edabits_test_impl(BinaryVerification::CutAndChoose).await
+    }
+
+    #[tokio::test]
+    async fn msb_edabits_gf2p64_mac_test() {
+        msb_edabits_test_impl(BinaryVerification::Gf2p64Mac).await
+    }
 }
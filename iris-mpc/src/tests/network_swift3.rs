@@ -1,9 +1,9 @@
 mod swift3_test {
     use crate::{
-        prelude::{PartyTestNetwork, TestNetwork3p},
+        prelude::{AdderStrategy, PartyTestNetwork, TestNetwork3p},
         swift3::{protocol::Swift3, share::Share},
         traits::mpc_trait::{MpcTrait, Plain},
-        types::{bit::Bit, int_ring::IntRing2k, sharable::Sharable},
+        types::{bit::Bit, int_ring::IntRing2k, ring_element::RingImpl, sharable::Sharable},
     };
     use num_traits::Zero;
     use rand::{
@@ -15,6 +15,7 @@ mod swift3_test {
 
     const NUM_PARTIES: usize = PartyTestNetwork::NUM_PARTIES;
     const DOT_SIZE: usize = 1000;
+    const MSB_SIZE: usize = 4;
 
     async fn share_test_party<T: Sharable, R: Rng + SeedableRng>(
         net: PartyTestNetwork,
@@ -125,6 +126,65 @@ mod swift3_test {
         assert_eq!(&inputs, r0);
     }
 
+    async fn input_many_test_party<T: Sharable>(net: PartyTestNetwork) -> (Vec<T>, Vec<T>)
+    where
+        Standard: Distribution<T>,
+        Standard: Distribution<T::Share>,
+        Share<T>: Mul<T::Share, Output = Share<T>>,
+    {
+        let mut protocol = Swift3::<PartyTestNetwork, _>::new(net);
+        protocol.preprocess().await.unwrap();
+        let id = protocol.get_id();
+
+        let mut rng = ChaCha12Rng::from_entropy();
+        let input = (0..DOT_SIZE).map(|_| rng.gen::<T>()).collect::<Vec<_>>();
+
+        let mut shares = Vec::with_capacity(NUM_PARTIES * (DOT_SIZE + 1));
+        for i in 0..NUM_PARTIES {
+            let inp = (i == id).then(|| input.to_owned());
+            shares.extend(protocol.input_many(inp, DOT_SIZE, i).await.unwrap());
+            // Single inputs still work after a batch
+            let inp = (i == id).then(|| input[0].to_owned());
+            shares.push(protocol.input(inp, i).await.unwrap());
+        }
+        assert!(protocol.input_many(None, 1, NUM_PARTIES).await.is_err());
+        protocol.verify().await.unwrap();
+        let open = protocol.open_many(shares).await.unwrap();
+
+        MpcTrait::<T, Share<T>, Share<Bit>>::finish(protocol)
+            .await
+            .unwrap();
+        (input, open)
+    }
+
+    #[tokio::test]
+    async fn input_many_test() {
+        let mut tasks = Vec::with_capacity(NUM_PARTIES);
+
+        let network = TestNetwork3p::new();
+        let net = network.get_party_networks();
+
+        for n in net {
+            let t = tokio::spawn(async move { input_many_test_party::<u16>(n).await });
+            tasks.push(t);
+        }
+
+        let mut expected = Vec::with_capacity(NUM_PARTIES * (DOT_SIZE + 1));
+        let mut results = Vec::with_capacity(NUM_PARTIES);
+        for t in tasks {
+            let (inp, outp) = t.await.expect("Task exited normally");
+            expected.extend(inp.iter().copied());
+            expected.push(inp[0]);
+            results.push(outp);
+        }
+
+        let r0 = &results[0];
+        for r in results.iter().skip(1) {
+            assert_eq!(r0, r);
+        }
+        assert_eq!(&expected, r0);
+    }
+
     async fn add_test_party<T: Sharable>(net: PartyTestNetwork) -> (T, T)
     where
         Standard: Distribution<T>,
@@ -570,11 +630,88 @@ mod swift3_test {
             assert_eq!(r0, r);
         }
         let mut plain = Plain::default();
-        let res = plain
+        let res: u16 = plain
             .dot(inputs[0].to_owned(), inputs[1].to_owned())
             .await
             .unwrap();
         assert_eq!(inputs.len(), 2);
         assert_eq!(r0, &res);
     }
+
+    async fn msb_test_party<T: Sharable, R: Rng + SeedableRng>(
+        net: PartyTestNetwork,
+        seed: R::Seed,
+        strategy: AdderStrategy,
+    ) -> Vec<(bool, bool)>
+    where
+        Standard: Distribution<T>,
+        Standard: Distribution<T::Share>,
+        Share<T>: Mul<T::Share, Output = Share<T>>,
+    {
+        let mut protocol = Swift3::<PartyTestNetwork, _>::new(net);
+        protocol.preprocess().await.unwrap();
+        protocol.set_adder_strategy(strategy);
+        let id = protocol.get_id();
+
+        let mut rng = R::from_seed(seed);
+        let mut inputs = Vec::with_capacity(MSB_SIZE);
+        let mut shares = Vec::with_capacity(MSB_SIZE);
+        for _ in 0..MSB_SIZE {
+            let input = rng.gen::<T>();
+            let share = Swift3::<PartyTestNetwork, _>::share(
+                input,
+                T::VerificationShare::default(),
+                &mut rng,
+            )[id]
+                .to_owned();
+            inputs.push(input);
+            shares.push(share);
+        }
+
+        let single = shares.pop().unwrap();
+        let mut msbs = protocol.get_msb_many(shares).await.unwrap();
+        msbs.push(protocol.get_msb(single).await.unwrap());
+        protocol.verify().await.unwrap();
+        let open = protocol.open_bit_many(msbs).await.unwrap();
+
+        MpcTrait::<T, Share<T>, Share<Bit>>::finish(protocol)
+            .await
+            .unwrap();
+        inputs
+            .into_iter()
+            .zip(open)
+            .map(|(i, o)| (i.to_sharetype().get_msb().convert().convert(), o))
+            .collect()
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+    async fn msb_test() {
+        for strategy in [
+            AdderStrategy::RippleCarry,
+            AdderStrategy::KoggeStone,
+            AdderStrategy::BrentKung,
+            AdderStrategy::Sklansky,
+        ] {
+            let mut tasks = Vec::with_capacity(NUM_PARTIES);
+
+            let mut rng = ChaCha12Rng::from_entropy();
+            let seed = rng.gen::<<ChaCha12Rng as SeedableRng>::Seed>();
+
+            let network = TestNetwork3p::new();
+            let net = network.get_party_networks();
+
+            for n in net {
+                let t = tokio::spawn(async move {
+                    msb_test_party::<u16, ChaCha12Rng>(n, seed, strategy).await
+                });
+                tasks.push(t);
+            }
+
+            for t in tasks {
+                for (plain, mpc) in t.await.expect("Task exited normally") {
+                    assert_eq!(plain, mpc);
+                }
+            }
+        }
+    }
 }
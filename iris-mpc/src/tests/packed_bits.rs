@@ -0,0 +1,173 @@
+mod packed_bits_test {
+    use crate::{
+        aby3::share::Share,
+        prelude::{DryRun, MpcTrait, PackedBits, VecShareTrait},
+        types::{bit::Bit, ring_element::RingElement},
+    };
+    use plain_reference::IrisCodeArray;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha12Rng;
+
+    const SIZE: usize = 1000;
+
+    fn random_bits<R: Rng>(len: usize, rng: &mut R) -> Vec<Bit> {
+        (0..len).map(|_| Bit::new(rng.gen())).collect()
+    }
+
+    fn unpack(bits: &PackedBits<Bit>) -> Vec<Bit> {
+        bits.iter().collect()
+    }
+
+    // The padding of the last word has to stay zero
+    fn check_padding(bits: &PackedBits<Bit>) {
+        assert_eq!(bits.words().len(), bits.len().div_ceil(128));
+        if let Some(last) = bits.words().last() {
+            let used = bits.len() % 128;
+            if used != 0 {
+                assert_eq!(last >> used, 0);
+            }
+        }
+    }
+
+    #[test]
+    fn push_get_set_test() {
+        let mut rng = ChaCha12Rng::from_entropy();
+        let bits = random_bits(SIZE, &mut rng);
+
+        let mut packed = PackedBits::with_capacity(SIZE);
+        for b in bits.iter() {
+            packed.push(*b);
+        }
+        assert_eq!(packed.len(), SIZE);
+        assert_eq!(unpack(&packed), bits);
+        check_padding(&packed);
+
+        let collected = bits.iter().copied().collect::<PackedBits<Bit>>();
+        assert_eq!(collected.into_iter().collect::<Vec<_>>(), bits);
+
+        let mut flipped = bits.to_owned();
+        for (i, f) in flipped.iter_mut().enumerate().step_by(3) {
+            *f = !*f;
+            packed.set_at(i, *f);
+        }
+        assert_eq!(unpack(&packed), flipped);
+        check_padding(&packed);
+    }
+
+    #[test]
+    fn split_extend_chunks_test() {
+        let mut rng = ChaCha12Rng::from_entropy();
+        let bits = random_bits(SIZE, &mut rng);
+        let packed = bits.iter().copied().collect::<PackedBits<Bit>>();
+
+        // Aligned and unaligned
+        for mid in [0, 100, 128, 256, 999, SIZE] {
+            let (a, b) = packed.split_at(mid);
+            assert_eq!(unpack(&a), bits[..mid]);
+            assert_eq!(unpack(&b), bits[mid..]);
+            check_padding(&a);
+            check_padding(&b);
+
+            let mut joined = a;
+            joined.extend(b);
+            assert_eq!(unpack(&joined), bits);
+            check_padding(&joined);
+        }
+
+        for chunk_size in [1, 100, 128, 300, SIZE, 2 * SIZE] {
+            let chunks = packed.to_owned().chunks(chunk_size);
+            assert_eq!(chunks.len(), SIZE.div_ceil(chunk_size));
+            for (c, b) in chunks.iter().zip(bits[..].chunks(chunk_size)) {
+                assert_eq!(unpack(c), b);
+                check_padding(c);
+            }
+        }
+    }
+
+    #[test]
+    fn xor_shl_test() {
+        let mut rng = ChaCha12Rng::from_entropy();
+        let a = random_bits(SIZE, &mut rng);
+        let b = random_bits(SIZE, &mut rng);
+        let a_packed = a.iter().copied().collect::<PackedBits<Bit>>();
+        let b_packed = b.iter().copied().collect::<PackedBits<Bit>>();
+
+        let xor = a_packed.to_owned().xor_many(b_packed).unwrap();
+        let expected = a.iter().zip(b.iter()).map(|(a_, b_)| *a_ ^ *b_);
+        assert!(xor.iter().eq(expected));
+
+        assert!(a_packed
+            .to_owned()
+            .xor_many(PackedBits::with_capacity(0))
+            .is_err());
+
+        let mut shifted = a_packed.to_owned();
+        shifted.shl_assign_many(0);
+        assert_eq!(unpack(&shifted), a);
+        shifted.shl_assign_many(1);
+        assert!(shifted.iter().all(|b| b == Bit::new(false)));
+    }
+
+    #[test]
+    fn filter_reduce_add_twice_test() {
+        let mut rng = ChaCha12Rng::from_entropy();
+        let len = IrisCodeArray::IRIS_CODE_SIZE;
+        let a = random_bits(len, &mut rng);
+        let b = random_bits(len, &mut rng);
+        let mask = IrisCodeArray::random_rng(&mut rng);
+
+        let (sum_a, sum_b) = PackedBits::filter_reduce_add_twice(
+            &a.iter().copied().collect(),
+            &b.iter().copied().collect(),
+            &mask,
+        )
+        .unwrap();
+        let (sum_a_, sum_b_) = Vec::filter_reduce_add_twice(&a, &b, &mask).unwrap();
+        assert_eq!(sum_a, sum_a_);
+        assert_eq!(sum_b, sum_b_);
+    }
+
+    #[test]
+    fn aby3_share_test() {
+        let mut rng = ChaCha12Rng::from_entropy();
+        let a = random_bits(SIZE, &mut rng);
+        let b = random_bits(SIZE, &mut rng);
+        let shares = a
+            .iter()
+            .zip(b.iter())
+            .map(|(a_, b_)| Share::new(RingElement(*a_), RingElement(*b_)))
+            .collect::<Vec<_>>();
+
+        let packed = shares.iter().cloned().collect::<PackedBits<Share<Bit>>>();
+        assert_eq!(packed.words().len(), SIZE.div_ceil(128));
+        assert!(packed.iter().eq(shares.iter().cloned()));
+
+        let (_, tail) = packed.split_at(SIZE - 1);
+        assert_eq!(tail.get_at(0), shares[SIZE - 1]);
+        let last = tail.words()[0].to_owned();
+        assert_eq!(last.a.0 >> 1, 0);
+        assert_eq!(last.b.0 >> 1, 0);
+    }
+
+    #[tokio::test]
+    async fn open_bandwidth_test() {
+        let mut rng = ChaCha12Rng::from_entropy();
+        let bits = random_bits(SIZE, &mut rng);
+
+        let mut protocol = DryRun::new(0).unwrap();
+        let before = protocol.report();
+        let open =
+            MpcTrait::<u16, u16, Bit>::open_bit_many(&mut protocol, bits.iter().copied().collect())
+                .await
+                .unwrap();
+        let after = protocol.report();
+        assert_eq!(open, Bit::convert_vec(bits));
+
+        // One bit per bit instead of one byte
+        assert_eq!(after.rounds - before.rounds, 1);
+        assert_eq!(
+            after.bytes_sent_next - before.bytes_sent_next,
+            SIZE.div_ceil(128) * 16
+        );
+    }
+}
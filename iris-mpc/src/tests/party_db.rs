@@ -0,0 +1,282 @@
+mod party_db_test {
+    use crate::{
+        prelude::{
+            aby3_party_db_chunks, party_db_len, party_db_path, read_aby3_party_db,
+            read_aby3_party_db_range, read_spdzwise_party_db, read_spdzwise_party_mac_key,
+            read_swift3_party_db, read_truth_code, read_truth_db, read_truth_mac_key,
+            truth_db_path, write_aby3_party_db, write_party_info, Aby3, Aby3Network, Aby3Share,
+            DbShares, MpcTrait, PartyTestNetwork, SpdzWise,
+        },
+        swift3::share::Share as Swift3Share,
+        tests::iris_config::iris_config::create_database,
+        types::ring_element::RingElement,
+    };
+    use plain_reference::IrisCode;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha12Rng;
+    use rusqlite::Connection;
+    use std::path::{Path, PathBuf};
+
+    const NUM_PARTIES: usize = PartyTestNetwork::NUM_PARTIES;
+    const DB_SIZE: usize = 4;
+
+    fn random_component<R: Rng>(rng: &mut R) -> Vec<RingElement<u16>> {
+        (0..IrisCode::IRIS_CODE_SIZE)
+            .map(|_| RingElement(rng.gen()))
+            .collect()
+    }
+
+    #[test]
+    fn path_test() {
+        let db = Path::new("data/db0.sqlite");
+        assert_eq!(
+            party_db_path(db, 1),
+            PathBuf::from("data/db0.party1.sqlite")
+        );
+        assert_eq!(truth_db_path(db), PathBuf::from("data/db0.truth.sqlite"));
+        assert_eq!(
+            party_db_path(Path::new("db"), 2),
+            PathBuf::from("db.party2")
+        );
+    }
+
+    #[test]
+    fn swift3_party_db_test() {
+        let mut rng = ChaCha12Rng::from_entropy();
+        let db = create_database(DB_SIZE, &mut rng);
+        let components = (0..DB_SIZE)
+            .map(|_| {
+                (0..4)
+                    .map(|_| random_component(&mut rng))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        for party in 0..NUM_PARTIES {
+            let prev = (party + NUM_PARTIES - 1) % NUM_PARTIES;
+            let conn = Connection::open_in_memory().unwrap();
+            write_party_info(&conn, party, "swift3").unwrap();
+            conn.execute(
+                "CREATE TABLE iris_codes (
+                    id INTEGER PRIMARY KEY,
+                    mask BLOB NOT NULL,
+                    share_a BLOB NOT NULL,
+                    share_b BLOB NOT NULL,
+                    share_c BLOB NOT NULL
+                )",
+                [],
+            )
+            .unwrap();
+            for (code, component) in db.iter().zip(components.iter()) {
+                conn.execute(
+                    "INSERT INTO iris_codes (mask, share_a, share_b, share_c) VALUES (?1, ?2, ?3, ?4)",
+                    rusqlite::params![
+                        code.mask.as_raw_slice(),
+                        bincode::serialize(&component[party]).unwrap(),
+                        bincode::serialize(&component[prev]).unwrap(),
+                        bincode::serialize(&component[3]).unwrap(),
+                    ],
+                )
+                .unwrap();
+            }
+
+            let read = read_swift3_party_db::<u16>(&conn, party).unwrap();
+            assert_eq!(read.shares.len(), DB_SIZE);
+            for ((shares, mask), (code, component)) in read
+                .shares
+                .iter()
+                .zip(read.masks.iter())
+                .zip(db.iter().zip(components.iter()))
+            {
+                assert_eq!(mask, &code.mask);
+                for (j, share) in shares.iter().enumerate() {
+                    let expected = Swift3Share::new(
+                        component[party][j].to_owned(),
+                        component[prev][j].to_owned(),
+                        component[3][j].to_owned(),
+                    );
+                    assert_eq!(share, &expected);
+                }
+            }
+
+            // Wrong protocol or party
+            assert!(read_aby3_party_db::<u16>(&conn, party).is_err());
+            assert!(read_swift3_party_db::<u16>(&conn, (party + 1) % NUM_PARTIES).is_err());
+        }
+    }
+
+    #[test]
+    fn aby3_party_db_range_test() {
+        let mut rng = ChaCha12Rng::from_entropy();
+        let db = create_database(DB_SIZE, &mut rng);
+        let mut shares = DbShares::default();
+        for code in db.iter() {
+            let share = random_component(&mut rng)
+                .into_iter()
+                .zip(random_component(&mut rng))
+                .map(|(a, b)| Aby3Share::new(a, b))
+                .collect::<Vec<_>>();
+            shares.shares.push(share);
+            shares.masks.push(code.mask);
+        }
+
+        let mut conn = Connection::open_in_memory().unwrap();
+        write_aby3_party_db(&mut conn, 1, &shares).unwrap();
+        assert_eq!(party_db_len(&conn).unwrap(), DB_SIZE);
+
+        for range in [0..DB_SIZE, 1..3, 3..DB_SIZE, 2..2, DB_SIZE..DB_SIZE + 1] {
+            let read = read_aby3_party_db_range::<u16>(&conn, 1, range.to_owned()).unwrap();
+            let expected = range.start..range.end.min(DB_SIZE);
+            assert_eq!(read.shares, shares.shares[expected.to_owned()]);
+            assert_eq!(read.masks, shares.masks[expected]);
+        }
+        assert!(read_aby3_party_db_range::<u16>(&conn, 0, 0..1).is_err());
+
+        for chunk_size in [1, 3, DB_SIZE, DB_SIZE + 1] {
+            let chunks = aby3_party_db_chunks::<u16>(&conn, 1, chunk_size)
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            assert_eq!(chunks.len(), DB_SIZE.div_ceil(chunk_size));
+            for (chunk, (shares, masks)) in chunks.iter().zip(
+                shares
+                    .shares
+                    .chunks(chunk_size)
+                    .zip(shares.masks.chunks(chunk_size)),
+            ) {
+                assert_eq!(chunk.shares, shares);
+                assert_eq!(chunk.masks, masks);
+            }
+        }
+        assert!(aby3_party_db_chunks::<u16>(&conn, 1, 0).is_err());
+        assert!(aby3_party_db_chunks::<u16>(&conn, 0, 1).is_err());
+    }
+
+    #[test]
+    fn spdzwise_party_db_test() {
+        let mut rng = ChaCha12Rng::from_entropy();
+        let db = create_database(DB_SIZE, &mut rng);
+        let mac_key = rng.gen::<u64>();
+
+        // Ground truth
+        let truth = Connection::open_in_memory().unwrap();
+        truth
+            .execute(
+                "CREATE TABLE iris_codes (id INTEGER PRIMARY KEY, code BLOB NOT NULL, mask BLOB NOT NULL)",
+                [],
+            )
+            .unwrap();
+        truth
+            .execute(
+                "CREATE TABLE mac_key (id INTEGER PRIMARY KEY, key BLOB NOT NULL)",
+                [],
+            )
+            .unwrap();
+        truth
+            .execute(
+                "INSERT INTO mac_key (key) VALUES (?1)",
+                [bincode::serialize(&mac_key).unwrap()],
+            )
+            .unwrap();
+        for code in db.iter() {
+            truth
+                .execute(
+                    "INSERT INTO iris_codes (code, mask) VALUES (?1, ?2)",
+                    [code.code.as_raw_slice(), code.mask.as_raw_slice()],
+                )
+                .unwrap();
+        }
+        assert_eq!(read_truth_mac_key::<u16>(&truth).unwrap(), mac_key);
+        let read = read_truth_db(&truth).unwrap();
+        assert_eq!(read.len(), DB_SIZE);
+        for (a, b) in read.iter().zip(db.iter()) {
+            assert_eq!(a.code, b.code);
+            assert_eq!(a.mask, b.mask);
+        }
+        let code = read_truth_code(&truth, 2).unwrap();
+        assert_eq!(code.code, db[1].code);
+        assert_eq!(code.mask, db[1].mask);
+        assert!(read_truth_code(&truth, DB_SIZE as u64 + 1).is_err());
+
+        // Shares
+        let key_shares = Aby3::<Aby3Network>::share(mac_key, 0, &mut rng);
+        let code_shares = db
+            .iter()
+            .map(|code| {
+                code.code
+                    .bits()
+                    .map(|bit| SpdzWise::<Aby3Network, _>::share(u16::from(bit), mac_key, &mut rng))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        for party in 0..NUM_PARTIES {
+            let prev = (party + NUM_PARTIES - 1) % NUM_PARTIES;
+            let conn = Connection::open_in_memory().unwrap();
+            write_party_info(&conn, party, "spdzwise").unwrap();
+            conn.execute(
+                "CREATE TABLE mac_key (id INTEGER PRIMARY KEY, share_a BLOB NOT NULL, share_b BLOB NOT NULL)",
+                [],
+            )
+            .unwrap();
+            conn.execute(
+                "INSERT INTO mac_key (share_a, share_b) VALUES (?1, ?2)",
+                [
+                    bincode::serialize(&key_shares[party].to_owned().get_a()).unwrap(),
+                    bincode::serialize(&key_shares[prev].to_owned().get_a()).unwrap(),
+                ],
+            )
+            .unwrap();
+            conn.execute(
+                "CREATE TABLE iris_codes (
+                    id INTEGER PRIMARY KEY,
+                    mask BLOB NOT NULL,
+                    share_a BLOB NOT NULL,
+                    mac_a BLOB NOT NULL,
+                    share_b BLOB NOT NULL,
+                    mac_b BLOB NOT NULL
+                )",
+                [],
+            )
+            .unwrap();
+            for (code, shares) in db.iter().zip(code_shares.iter()) {
+                let component = |id: usize| {
+                    let (values, macs): (Vec<_>, Vec<_>) = shares
+                        .iter()
+                        .map(|s| {
+                            let (v, m) = s[id].to_owned().get();
+                            (v.get_a(), m.get_a())
+                        })
+                        .unzip();
+                    (
+                        bincode::serialize(&values).unwrap(),
+                        bincode::serialize(&macs).unwrap(),
+                    )
+                };
+                let (share_a, mac_a) = component(party);
+                let (share_b, mac_b) = component(prev);
+                conn.execute(
+                    "INSERT INTO iris_codes (mask, share_a, mac_a, share_b, mac_b) VALUES (?1, ?2, ?3, ?4, ?5)",
+                    rusqlite::params![code.mask.as_raw_slice(), share_a, mac_a, share_b, mac_b],
+                )
+                .unwrap();
+            }
+
+            let key_share = read_spdzwise_party_mac_key::<u16>(&conn, party).unwrap();
+            let (_, mac) = key_share.get();
+            assert_eq!(mac, key_shares[party]);
+
+            let read = read_spdzwise_party_db::<u16>(&conn, party).unwrap();
+            assert_eq!(read.masks.len(), DB_SIZE);
+            for (vecshare, shares) in read.shares.into_iter().zip(code_shares.iter()) {
+                let (values, macs) = vecshare.get();
+                for ((v, m), s) in values.into_iter().zip(macs).zip(shares.iter()) {
+                    let (v_, m_) = s[party].to_owned().get();
+                    assert_eq!(v, v_);
+                    assert_eq!(m, m_);
+                }
+            }
+            assert!(read_aby3_party_db::<u16>(&conn, party).is_err());
+        }
+    }
+}
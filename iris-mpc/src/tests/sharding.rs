@@ -0,0 +1,157 @@
+mod sharding_test {
+    use crate::{
+        prelude::{
+            combine_shard_results, match_shard, shard_ranges, Aby3, Aby3Share, Bit, IrisAby3,
+            MpcTrait, PartyShardTestNetwork, PartyTestNetwork, ShardTestNetwork, TestNetwork3p,
+        },
+        tests::iris_config::iris_config::create_database,
+    };
+    use plain_reference::IrisCode;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha12Rng;
+
+    const NUM_PARTIES: usize = PartyTestNetwork::NUM_PARTIES;
+    const DB_SIZE: usize = 20;
+    const CHUNK_SIZE: usize = 8;
+
+    type Seed = <ChaCha12Rng as SeedableRng>::Seed;
+
+    // All parties derive the same sharings from the seed and pick their own share
+    fn share_iris_code(code: &IrisCode, id: usize, rng: &mut ChaCha12Rng) -> Vec<Aby3Share<u16>> {
+        let mut shared_code = Vec::with_capacity(IrisCode::IRIS_CODE_SIZE);
+        for i in 0..IrisCode::IRIS_CODE_SIZE {
+            let shares = <Aby3<PartyTestNetwork> as MpcTrait<u16, _, Aby3Share<Bit>>>::share(
+                u16::from(code.code.get_bit(i)),
+                0,
+                rng,
+            );
+            shared_code.push(shares[id].to_owned());
+        }
+        shared_code
+    }
+
+    async fn worker(
+        net: PartyTestNetwork,
+        mut shard_net: PartyShardTestNetwork,
+        db: Vec<IrisCode>,
+        iris: IrisCode,
+        shard: usize,
+        num_shards: usize,
+        seed: Seed,
+    ) {
+        let protocol = Aby3::<PartyTestNetwork>::new(net);
+        let mut iris_protocol = IrisAby3::<u16, _>::new(protocol).unwrap();
+        let id = iris_protocol.get_id();
+        iris_protocol.preprocessing().await.unwrap();
+
+        let mut rng = ChaCha12Rng::from_seed(seed);
+        let iris_share = share_iris_code(&iris, id, &mut rng);
+        let db_shares = db
+            .iter()
+            .map(|code| share_iris_code(code, id, &mut rng))
+            .collect::<Vec<_>>();
+        let masks = db.iter().map(|code| code.mask).collect::<Vec<_>>();
+
+        let range = shard_ranges(db.len(), num_shards)[shard].to_owned();
+        match_shard(
+            &mut iris_protocol,
+            &mut shard_net,
+            &iris_share,
+            &db_shares[range.to_owned()],
+            &iris.mask,
+            &masks[range],
+            CHUNK_SIZE,
+        )
+        .await
+        .unwrap();
+        iris_protocol.finish().await.unwrap();
+    }
+
+    async fn coordinator(net: PartyTestNetwork, mut shard_net: PartyShardTestNetwork) -> bool {
+        let protocol = Aby3::<PartyTestNetwork>::new(net);
+        let mut iris_protocol = IrisAby3::<u16, _>::new(protocol).unwrap();
+        iris_protocol.preprocessing().await.unwrap();
+
+        let res = combine_shard_results(&mut iris_protocol, &mut shard_net)
+            .await
+            .unwrap();
+        iris_protocol.finish().await.unwrap();
+        res
+    }
+
+    async fn sharded_test_impl(db_size: usize, num_shards: usize, matching: Option<usize>) {
+        let mut rng = ChaCha12Rng::from_entropy();
+        let seed = rng.gen::<Seed>();
+        let db = create_database(db_size, &mut rng);
+        let iris = match matching {
+            Some(index) => db[index].get_similar_iris(&mut rng),
+            None => IrisCode::random_rng(&mut rng),
+        };
+        let expected = db.iter().any(|code| iris.is_close(code));
+
+        // shard_nets[party][node], where node 0 is the coordinator
+        let mut shard_nets = (0..NUM_PARTIES)
+            .map(|_| {
+                ShardTestNetwork::new(num_shards)
+                    .get_party_networks()
+                    .into_iter()
+                    .map(Some)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let mut workers = Vec::with_capacity(num_shards * NUM_PARTIES);
+        for shard in 0..num_shards {
+            let network = TestNetwork3p::new();
+            for (party, n) in network.get_party_networks().into_iter().enumerate() {
+                let shard_net = shard_nets[party][shard + 1].take().unwrap();
+                let db = db.to_owned();
+                let iris = iris.to_owned();
+                workers.push(tokio::spawn(async move {
+                    worker(n, shard_net, db, iris, shard, num_shards, seed).await
+                }));
+            }
+        }
+
+        let mut coordinators = Vec::with_capacity(NUM_PARTIES);
+        let network = TestNetwork3p::new();
+        for (party, n) in network.get_party_networks().into_iter().enumerate() {
+            let shard_net = shard_nets[party][0].take().unwrap();
+            coordinators.push(tokio::spawn(coordinator(n, shard_net)));
+        }
+
+        for t in workers {
+            t.await.expect("Task exited normally");
+        }
+        for t in coordinators {
+            let res = t.await.expect("Task exited normally");
+            assert_eq!(res, expected);
+        }
+    }
+
+    #[test]
+    fn shard_ranges_test() {
+        let ranges = shard_ranges(10, 3);
+        assert_eq!(ranges, vec![0..4, 4..7, 7..10]);
+
+        let ranges = shard_ranges(2, 4);
+        assert_eq!(ranges, vec![0..1, 1..2, 2..2, 2..2]);
+
+        assert!(shard_ranges(10, 0).is_empty());
+    }
+
+    #[tokio::test]
+    async fn sharded_match_test() {
+        sharded_test_impl(DB_SIZE, 3, Some(DB_SIZE - 1)).await
+    }
+
+    #[tokio::test]
+    async fn sharded_no_match_test() {
+        sharded_test_impl(DB_SIZE, 3, None).await
+    }
+
+    #[tokio::test]
+    async fn sharded_empty_shard_test() {
+        sharded_test_impl(2, 3, Some(0)).await
+    }
+}
@@ -0,0 +1,229 @@
+mod share_file_test {
+    use crate::{
+        aby3::share::Share as Aby3Share,
+        prelude::{
+            aby3_share_file_from_sqlite, swift3_share_file_from_sqlite, ShareFile, ShareFileWriter,
+            ShareProtocol,
+        },
+        swift3::share::Share as Swift3Share,
+        tests::iris_config::iris_config::create_database,
+        types::ring_element::RingElement,
+    };
+    use plain_reference::{IrisCode, IrisCodeArray};
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha12Rng;
+    use rusqlite::Connection;
+    use std::path::PathBuf;
+
+    const DB_SIZE: usize = 5;
+
+    fn temp_path<R: Rng>(rng: &mut R) -> PathBuf {
+        std::env::temp_dir().join(format!("iris_mpc_{:016x}.shares", rng.gen::<u64>()))
+    }
+
+    fn random_aby3<R: Rng>(rng: &mut R) -> Vec<Aby3Share<u16>> {
+        (0..IrisCode::IRIS_CODE_SIZE)
+            .map(|_| Aby3Share::new(rng.gen(), rng.gen()))
+            .collect()
+    }
+
+    fn random_swift3<R: Rng>(rng: &mut R) -> Vec<Swift3Share<u32>> {
+        (0..IrisCode::IRIS_CODE_SIZE)
+            .map(|_| Swift3Share::new(rng.gen(), rng.gen(), rng.gen()))
+            .collect()
+    }
+
+    #[test]
+    fn aby3_roundtrip_test() {
+        let mut rng = ChaCha12Rng::from_entropy();
+        let path = temp_path(&mut rng);
+        let shares = (0..DB_SIZE)
+            .map(|_| random_aby3(&mut rng))
+            .collect::<Vec<_>>();
+        let masks = (0..DB_SIZE)
+            .map(|_| IrisCodeArray::random_rng(&mut rng))
+            .collect::<Vec<_>>();
+
+        let mut writer = ShareFileWriter::create(&path, 2).unwrap();
+        for (share, mask) in shares.iter().zip(masks.iter()) {
+            writer.push(mask, share).unwrap();
+        }
+        let header = writer.finish().unwrap();
+        assert_eq!(header.num_records, DB_SIZE as u64);
+        assert_eq!(header.protocol, ShareProtocol::Aby3);
+        assert_eq!(header.ring_size, 2);
+
+        let file = ShareFile::<Aby3Share<u16>>::open(&path).unwrap();
+        assert_eq!(file.header(), &header);
+        assert_eq!(file.get_party(), 2);
+        assert_eq!(file.len(), DB_SIZE);
+        file.verify_checksum().unwrap();
+        for (i, (share, mask)) in shares.iter().zip(masks.iter()).enumerate() {
+            assert_eq!(file.get_shares(i), share.as_slice());
+            assert_eq!(&file.get_mask(i), mask);
+        }
+        let records = file.records();
+        assert_eq!(records.shares, shares);
+        assert_eq!(records.masks, masks);
+        let chunks = file.chunks(2).collect::<Vec<_>>();
+        assert_eq!(chunks.len(), DB_SIZE.div_ceil(2));
+        for (chunk, (shares, masks)) in chunks.iter().zip(shares.chunks(2).zip(masks.chunks(2))) {
+            assert_eq!(chunk.shares, shares);
+            assert_eq!(chunk.masks, masks);
+        }
+
+        // Wrong share types are rejected
+        assert!(ShareFile::<Aby3Share<u32>>::open(&path).is_err());
+        assert!(ShareFile::<Swift3Share<u16>>::open(&path).is_err());
+        drop(file);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn swift3_roundtrip_test() {
+        let mut rng = ChaCha12Rng::from_entropy();
+        let path = temp_path(&mut rng);
+        let shares = (0..DB_SIZE)
+            .map(|_| random_swift3(&mut rng))
+            .collect::<Vec<_>>();
+        let mask = IrisCodeArray::random_rng(&mut rng);
+
+        let mut writer = ShareFileWriter::create(&path, 0).unwrap();
+        for share in shares.iter() {
+            writer.push(&mask, share).unwrap();
+        }
+        assert!(writer.push(&mask, &shares[0][1..]).is_err());
+        writer.finish().unwrap();
+
+        let file = ShareFile::<Swift3Share<u32>>::open(&path).unwrap();
+        assert_eq!(file.header().protocol, ShareProtocol::Swift3);
+        file.verify_checksum().unwrap();
+        for ((mask_, share_), share) in file.iter().zip(shares.iter()) {
+            assert_eq!(mask_, mask);
+            assert_eq!(share_, share.as_slice());
+        }
+        drop(file);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn corrupted_file_test() {
+        let mut rng = ChaCha12Rng::from_entropy();
+        let path = temp_path(&mut rng);
+        let mut writer = ShareFileWriter::create(&path, 1).unwrap();
+        writer
+            .push(&IrisCodeArray::random_rng(&mut rng), &random_aby3(&mut rng))
+            .unwrap();
+        writer.finish().unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+
+        // Flipped share bit
+        let mut corrupted = bytes.to_owned();
+        *corrupted.last_mut().unwrap() ^= 1;
+        std::fs::write(&path, &corrupted).unwrap();
+        assert!(ShareFile::<Aby3Share<u16>>::open(&path).is_err());
+
+        // Truncated
+        std::fs::write(&path, &bytes[..bytes.len() - 2]).unwrap();
+        assert!(ShareFile::<Aby3Share<u16>>::open(&path).is_err());
+
+        // Wrong magic and version
+        let mut corrupted = bytes.to_owned();
+        corrupted[0] ^= 1;
+        std::fs::write(&path, &corrupted).unwrap();
+        assert!(ShareFile::<Aby3Share<u16>>::open(&path).is_err());
+        let mut corrupted = bytes.to_owned();
+        corrupted[8] = 2;
+        std::fs::write(&path, &corrupted).unwrap();
+        assert!(ShareFile::<Aby3Share<u16>>::open(&path).is_err());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn sqlite_conversion_test() {
+        let mut rng = ChaCha12Rng::from_entropy();
+        let db = create_database(DB_SIZE, &mut rng);
+
+        // The layout of create_sample_data
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute(
+            "CREATE TABLE iris_codes (
+                id INTEGER PRIMARY KEY,
+                code BLOB NOT NULL,
+                mask BLOB NOT NULL,
+                share_a BLOB NOT NULL,
+                share_b BLOB NOT NULL,
+                share_c BLOB NOT NULL,
+                share_d BLOB NOT NULL
+            )",
+            [],
+        )
+        .unwrap();
+        let mut components = Vec::with_capacity(DB_SIZE);
+        for code in db.iter() {
+            let component = (0..4)
+                .map(|_| {
+                    (0..IrisCode::IRIS_CODE_SIZE)
+                        .map(|_| RingElement(rng.gen::<u16>()))
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+            let data = component
+                .iter()
+                .map(|c| bincode::serialize(c).unwrap())
+                .collect::<Vec<_>>();
+            conn.execute(
+                "INSERT INTO iris_codes (code, mask, share_a, share_b, share_c, share_d) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                rusqlite::params![
+                    code.code.as_raw_slice(),
+                    code.mask.as_raw_slice(),
+                    &data[0],
+                    &data[1],
+                    &data[2],
+                    &data[3]
+                ],
+            )
+            .unwrap();
+            components.push(component);
+        }
+
+        for party in 0..3 {
+            let prev = (party + 2) % 3;
+
+            let path = temp_path(&mut rng);
+            aby3_share_file_from_sqlite::<u16, _>(&conn, party, &path).unwrap();
+            let file = ShareFile::<Aby3Share<u16>>::open(&path).unwrap();
+            assert_eq!(file.len(), DB_SIZE);
+            for (i, (mask, shares)) in file.iter().enumerate() {
+                assert_eq!(mask, db[i].mask);
+                for (j, share) in shares.iter().enumerate() {
+                    let expected = Aby3Share::new(
+                        components[i][party][j].to_owned(),
+                        components[i][prev][j].to_owned(),
+                    );
+                    assert_eq!(share, &expected);
+                }
+            }
+            drop(file);
+            std::fs::remove_file(&path).unwrap();
+
+            let path = temp_path(&mut rng);
+            swift3_share_file_from_sqlite::<u16, _>(&conn, party, &path).unwrap();
+            let file = ShareFile::<Swift3Share<u16>>::open(&path).unwrap();
+            assert_eq!(file.get_party(), party);
+            for (i, (_, shares)) in file.iter().enumerate() {
+                for (j, share) in shares.iter().enumerate() {
+                    let expected = Swift3Share::new(
+                        components[i][party][j].to_owned(),
+                        components[i][prev][j].to_owned(),
+                        components[i][3][j].to_owned(),
+                    );
+                    assert_eq!(share, &expected);
+                }
+            }
+            drop(file);
+            std::fs::remove_file(&path).unwrap();
+        }
+    }
+}
@@ -4,6 +4,7 @@ use crate::prelude::{Error, Sharable};
 use crate::types::ring_element::RingImpl;
 use std::ops::{BitXor, BitXorAssign};
 
+#[allow(async_fn_in_trait)]
 pub trait BinaryMpcTrait<T: Sharable, Bshare: ShareTrait>
 where
     Bshare: Clone
@@ -23,20 +24,6 @@ where
         a.xor_many(b)
     }
 
-    fn xor_assign(a: &mut Bshare, b: Bshare) {
-        *a ^= b;
-    }
-
-    fn xor_assign_many(a: &mut Vec<Bshare>, b: Vec<Bshare>) -> Result<(), Error> {
-        if a.len() != b.len() {
-            return Err(Error::InvalidSizeError);
-        }
-        for (a_, b_) in a.iter_mut().zip(b) {
-            Self::xor_assign(a_, b_);
-        }
-        Ok(())
-    }
-
     async fn and(&mut self, a: Bshare, b: Bshare) -> Result<Bshare, Error>;
 
     async fn and_many(
@@ -63,39 +50,11 @@ where
         y.xor_many(a.xor_many(b)?)
     }
 
-    async fn binary_add_3(&mut self, x1: Bshare, x2: Bshare, x3: Bshare) -> Result<Bshare, Error> {
-        let logk = ceil_log2(T::Share::K);
-
-        // Full Adder
-        let x2x3 = Self::xor(x2, x3.to_owned());
-        let s = Self::xor(x1.to_owned(), x2x3.to_owned());
-        let x1x3 = Self::xor(x1, x3.to_owned());
-        let mut c = self.and(x1x3, x2x3).await?;
-        Self::xor_assign(&mut c, x3);
-
-        // Add 2c + s via a packed Kogge-Stone adder
-        c <<= 1;
-        let mut p = Self::xor(s.to_owned(), c.to_owned());
-        let mut g = self.and(s, c).await?;
-        let s_ = p.to_owned();
-        for i in 0..logk {
-            let p_ = p.to_owned() << (1 << i);
-            let g_ = g.to_owned() << (1 << i);
-            // TODO Maybe work with Bits in the inner loop to have less communication?
-            let mut and_inp1 = Bshare::VecShare::with_capacity(2);
-            let mut and_inp2 = Bshare::VecShare::with_capacity(2);
-            and_inp1.push(p.to_owned());
-            and_inp1.push(p);
-            and_inp2.push(g_);
-            and_inp2.push(p_);
-            let res = self.and_many(&and_inp1, &and_inp2).await?;
-            p = res.get_at(1); // p = p & p_
-            Self::xor_assign(&mut g, res.get_at(0)); // g = g ^ (p & g_)
-        }
-        g <<= 1;
-        Ok(Self::xor(s_, g))
-    }
-
+    /// Adds three packed binary shares via a full adder and a packed Kogge-Stone adder. This is
+    /// the arithmetic-to-binary conversion and [`AdderStrategy::KoggeStone`] of the MSB
+    /// extraction.
+    ///
+    /// [`AdderStrategy::KoggeStone`]: crate::prelude::AdderStrategy::KoggeStone
     async fn binary_add_3_many(
         &mut self,
         x1: Bshare::VecShare,
@@ -146,7 +105,6 @@ where
         s_.xor_many(g)
     }
 
-    async fn arithmetic_to_binary(&mut self, x: Bshare) -> Result<Bshare, Error>;
     async fn arithmetic_to_binary_many(
         &mut self,
         x: Vec<Bshare>,
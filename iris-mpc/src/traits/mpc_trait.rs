@@ -1,14 +1,21 @@
-use super::share_trait::{ShareTrait, VecShareTrait};
+use super::{
+    binary_trait::BinaryMpcTrait,
+    share_trait::{BitShareTrait, ShareTrait, VecShareTrait, VecSlice},
+};
 use crate::{
     error::Error,
-    prelude::Bit,
+    prelude::{Bit, DbRoots, PackedBits},
     types::{ring_element::RingImpl, sharable::Sharable},
 };
 use plain_reference::IrisCodeArray;
 use rand::Rng;
+use std::{
+    borrow::Borrow,
+    ops::{Shl, ShlAssign},
+};
 
 #[allow(async_fn_in_trait)]
-pub trait MpcTrait<T: Sharable, Ashare: ShareTrait, Bshare: ShareTrait> {
+pub trait MpcTrait<T: Sharable, Ashare: ShareTrait, Bshare: BitShareTrait> {
     fn get_id(&self) -> usize;
     async fn preprocess(&mut self) -> Result<(), Error>;
     async fn precompute_and_triples(&mut self, _amount: usize) -> Result<(), Error> {
@@ -23,8 +30,52 @@ pub trait MpcTrait<T: Sharable, Ashare: ShareTrait, Bshare: ShareTrait> {
 
     fn print_connection_stats(&self, out: &mut impl std::io::Write) -> Result<(), Error>;
 
+    /// Creates an independent session of the protocol with its own network session and
+    /// randomness, which can run concurrently to this one. All parties have to fork in the same
+    /// order. Returns `None` if the protocol does not support sessions. Preprocessed material is
+    /// not shared with the new session, i.e., it has to preprocess again or generates the material
+    /// on demand.
+    async fn fork(&mut self) -> Result<Option<Self>, Error>
+    where
+        Self: Sized,
+    {
+        Ok(None)
+    }
+
+    /// Whether [`Self::fork`] creates sessions, provided that the network supports them.
+    fn supports_sessions(&self) -> bool {
+        false
+    }
+
+    /// Cross-checks the roots of the database of this party with the neighbours which store the
+    /// same share components (see [`DbRoots`]). Fails if the protocol does not replicate shares.
+    async fn verify_db_roots(&mut self, _roots: &DbRoots) -> Result<(), Error> {
+        Err(Error::Other(
+            "Protocol does not support database commitments".to_string(),
+        ))
+    }
+
     async fn input(&mut self, input: Option<T>, id: usize) -> Result<Ashare, Error>;
 
+    /// Inputs `len` values of party `id` at once. Only party `id` has to provide the values.
+    async fn input_many(
+        &mut self,
+        input: Option<Vec<T>>,
+        len: usize,
+        id: usize,
+    ) -> Result<Ashare::VecShare, Error> {
+        if input.as_ref().is_some_and(|input| input.len() != len) {
+            return Err(Error::InvalidSizeError);
+        }
+        let mut input = input.map(Vec::into_iter);
+        let mut res = Ashare::VecShare::with_capacity(len);
+        for _ in 0..len {
+            let value = input.as_mut().and_then(Iterator::next);
+            res.push(self.input(value, id).await?);
+        }
+        Ok(res)
+    }
+
     // Each party inputs an arithmetic share
     #[cfg(test)]
     async fn input_all(&mut self, input: T) -> Result<Vec<Ashare>, Error>;
@@ -33,7 +84,7 @@ pub trait MpcTrait<T: Sharable, Ashare: ShareTrait, Bshare: ShareTrait> {
     async fn open(&mut self, share: Ashare) -> Result<T, Error>;
     async fn open_many(&mut self, shares: Ashare::VecShare) -> Result<Vec<T>, Error>;
     async fn open_bit(&mut self, share: Bshare) -> Result<bool, Error>;
-    async fn open_bit_many(&mut self, shares: Bshare::VecShare) -> Result<Vec<bool>, Error>;
+    async fn open_bit_many(&mut self, shares: PackedBits<Bshare>) -> Result<Vec<bool>, Error>;
 
     fn add(&self, a: Ashare, b: Ashare) -> Ashare;
     fn add_const(&self, a: Ashare, b: T) -> Ashare;
@@ -48,18 +99,49 @@ pub trait MpcTrait<T: Sharable, Ashare: ShareTrait, Bshare: ShareTrait> {
         a: &[Ashare::VecShare],
         b: &[Ashare::VecShare],
     ) -> Result<Vec<Ashare>, Error>;
-    async fn masked_dot_many(
+    /// Multiplies `a` and `b` element-wise in one round. The default computes dot products of
+    /// length one, backends with a cheaper vector multiplication override it.
+    async fn mul_many(
+        &mut self,
+        a: Ashare::VecShare,
+        b: Ashare::VecShare,
+    ) -> Result<Ashare::VecShare, Error> {
+        if a.len() != b.len() {
+            return Err(Error::InvalidSizeError);
+        }
+        let singletons = |v: &Ashare::VecShare| {
+            (0..v.len())
+                .map(|i| {
+                    let mut res = Ashare::VecShare::with_capacity(1);
+                    res.push(v.get_at(i));
+                    res
+                })
+                .collect::<Vec<_>>()
+        };
+        let products = self.dot_many(&singletons(&a), &singletons(&b)).await?;
+
+        let mut res = Ashare::VecShare::with_capacity(products.len());
+        for product in products {
+            res.push(product);
+        }
+        Ok(res)
+    }
+    /// The entries of `b` may be borrowed, e.g., from a mapped share file.
+    async fn masked_dot_many<D>(
         &mut self,
         a: &Ashare::VecShare,
-        b: &[Ashare::VecShare],
+        b: &[D],
         masks: &[IrisCodeArray],
-    ) -> Result<Vec<Ashare>, Error> {
+    ) -> Result<Vec<Ashare>, Error>
+    where
+        D: Borrow<VecSlice<Ashare>> + Sync,
+    {
         let mut a_vec = Vec::with_capacity(b.len());
         let mut b_vec = Vec::with_capacity(b.len());
 
         for (b_, mask) in b.iter().zip(masks.iter()) {
             let mut code1 = a.to_owned();
-            let mut code2 = b_.to_owned();
+            let mut code2 = b_.borrow().to_owned();
             if code1.len() != IrisCodeArray::IRIS_CODE_SIZE
                 || code2.len() != IrisCodeArray::IRIS_CODE_SIZE
             {
@@ -80,11 +162,13 @@ pub trait MpcTrait<T: Sharable, Ashare: ShareTrait, Bshare: ShareTrait> {
     }
 
     async fn get_msb(&mut self, a: Ashare) -> Result<Bshare, Error>;
-    async fn get_msb_many(&mut self, a: Vec<Ashare>) -> Result<Bshare::VecShare, Error>;
+    async fn get_msb_many(&mut self, a: Vec<Ashare>) -> Result<PackedBits<Bshare>, Error>;
+    /// Returns the number of AND gates which [`Self::get_msb_many`] requires for `amount` values.
+    fn msb_and_gates(&self, amount: usize) -> usize;
     async fn binary_or(&mut self, a: Bshare, b: Bshare) -> Result<Bshare, Error>;
     async fn reduce_binary_or(
         &mut self,
-        a: Bshare::VecShare,
+        a: PackedBits<Bshare>,
         chunk_size: usize,
     ) -> Result<Bshare, Error>;
 
@@ -100,6 +184,7 @@ impl<T: Sharable> ShareTrait for T {
 
 impl<T: Sharable> VecShareTrait for Vec<T> {
     type Share = T;
+    type Slice = [T];
 
     fn len(&self) -> usize {
         Vec::len(self)
@@ -107,7 +192,7 @@ impl<T: Sharable> VecShareTrait for Vec<T> {
 
     fn filter_reduce_add_twice(
         a: &Self,
-        b: &Self,
+        b: &Self::Slice,
         mask: &plain_reference::IrisCodeArray,
     ) -> Result<(Self::Share, Self::Share), Error> {
         if a.is_empty() || a.len() != b.len() || a.len() != IrisCodeArray::IRIS_CODE_SIZE {
@@ -244,8 +329,8 @@ impl<T: Sharable> MpcTrait<T, T, Bit> for Plain {
         Ok(share.convert())
     }
 
-    async fn open_bit_many(&mut self, shares: Vec<Bit>) -> Result<Vec<bool>, Error> {
-        Ok(Bit::convert_vec(shares))
+    async fn open_bit_many(&mut self, shares: PackedBits<Bit>) -> Result<Vec<bool>, Error> {
+        Ok(shares.iter().map(Bit::convert).collect())
     }
 
     fn add(&self, a: T, b: T) -> T {
@@ -277,7 +362,7 @@ impl<T: Sharable> MpcTrait<T, T, Bit> for Plain {
             return Err(Error::InvalidSizeError);
         }
         let mut res = T::zero();
-        for (a, b) in a.into_iter().zip(b.into_iter()) {
+        for (a, b) in a.into_iter().zip(b) {
             res = res.wrapping_add(&a.wrapping_mul(&b));
         }
         Ok(res)
@@ -297,11 +382,21 @@ impl<T: Sharable> MpcTrait<T, T, Bit> for Plain {
         Ok(res)
     }
 
+    async fn mul_many(&mut self, a: Vec<T>, b: Vec<T>) -> Result<Vec<T>, Error> {
+        if a.len() != b.len() {
+            return Err(Error::InvalidSizeError);
+        }
+        Ok(a.into_iter()
+            .zip(b)
+            .map(|(a, b)| a.wrapping_mul(&b))
+            .collect())
+    }
+
     async fn get_msb(&mut self, a: T) -> Result<Bit, Error> {
         Ok(a.to_sharetype().get_msb().convert())
     }
 
-    async fn get_msb_many(&mut self, a: Vec<T>) -> Result<Vec<Bit>, Error> {
+    async fn get_msb_many(&mut self, a: Vec<T>) -> Result<PackedBits<Bit>, Error> {
         let res = a
             .into_iter()
             .map(|a_| a_.to_sharetype().get_msb().convert())
@@ -309,11 +404,15 @@ impl<T: Sharable> MpcTrait<T, T, Bit> for Plain {
         Ok(res)
     }
 
+    fn msb_and_gates(&self, _amount: usize) -> usize {
+        0
+    }
+
     async fn binary_or(&mut self, a: Bit, b: Bit) -> Result<Bit, Error> {
         Ok(a | b)
     }
 
-    async fn reduce_binary_or(&mut self, a: Vec<Bit>, _: usize) -> Result<Bit, Error> {
+    async fn reduce_binary_or(&mut self, a: PackedBits<Bit>, _: usize) -> Result<Bit, Error> {
         Ok(a.into_iter().fold(Bit::new(false), |a, b| a | b))
     }
 
@@ -321,3 +420,23 @@ impl<T: Sharable> MpcTrait<T, T, Bit> for Plain {
         Ok(())
     }
 }
+
+impl<T: Sharable> BinaryMpcTrait<T, T> for Plain
+where
+    T: ShlAssign<u32> + Shl<u32, Output = T> + 'static,
+{
+    async fn and(&mut self, a: T, b: T) -> Result<T, Error> {
+        Ok(a & b)
+    }
+
+    async fn and_many(&mut self, a: &Vec<T>, b: &Vec<T>) -> Result<Vec<T>, Error> {
+        if a.len() != b.len() {
+            return Err(Error::InvalidSizeError);
+        }
+        Ok(a.iter().zip(b).map(|(a_, b_)| *a_ & *b_).collect())
+    }
+
+    async fn arithmetic_to_binary_many(&mut self, x: Vec<T>) -> Result<Vec<T>, Error> {
+        Ok(x)
+    }
+}
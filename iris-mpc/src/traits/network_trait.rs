@@ -1,5 +1,5 @@
 use bytes::{Bytes, BytesMut};
-use std::io::Error;
+use std::io::{Error, ErrorKind};
 
 #[allow(async_fn_in_trait)]
 pub trait NetworkTrait {
@@ -19,4 +19,18 @@ pub trait NetworkTrait {
     async fn receive_next_id(&mut self) -> Result<BytesMut, Error>;
 
     async fn broadcast(&mut self, data: Bytes) -> Result<Vec<BytesMut>, Error>;
+
+    /// Opens a sub-session over the same connections. Messages of different sessions are kept
+    /// apart, so the sessions can be used concurrently. All parties have to fork their sessions in
+    /// the same order. Returns an error of kind [`ErrorKind::Unsupported`] if the network does not
+    /// support sessions.
+    async fn fork(&mut self) -> Result<Self, Error>
+    where
+        Self: Sized,
+    {
+        Err(Error::new(
+            ErrorKind::Unsupported,
+            "Network does not support sessions",
+        ))
+    }
 }
@@ -1,14 +1,24 @@
+use bytes::Bytes;
 use num_traits::Zero;
 use plain_reference::IrisCodeArray;
+use std::{borrow::Borrow, ops::BitXorAssign};
 
 use crate::prelude::Error;
 
 pub trait ShareTrait: Clone + Sized + Zero {
-    type VecShare: VecShareTrait<Share = Self>;
+    type VecShare: VecShareTrait<Share = Self>
+        + Borrow<<Self::VecShare as VecShareTrait>::Slice>
+        + Sync;
 }
 
+/// The borrowed form of a vector of shares of type `S`.
+pub type VecSlice<S> = <<S as ShareTrait>::VecShare as VecShareTrait>::Slice;
+
 pub trait VecShareTrait: Clone {
     type Share: ShareTrait;
+    /// The borrowed form of the vector, e.g., a slice of shares mapped from a share file, such
+    /// that database entries can be matched without copying them.
+    type Slice: ?Sized + ToOwned<Owned = Self> + Sync;
 
     fn len(&self) -> usize;
     fn is_empty(&self) -> bool {
@@ -17,7 +27,7 @@ pub trait VecShareTrait: Clone {
 
     fn filter_reduce_add_twice(
         a: &Self,
-        b: &Self,
+        b: &Self::Slice,
         mask: &IrisCodeArray,
     ) -> Result<(Self::Share, Self::Share), Error>;
 
@@ -33,4 +43,21 @@ pub trait VecShareTrait: Clone {
     fn chunks(self, chunk_size: usize) -> Vec<Self>;
     fn get_at(&self, index: usize) -> Self::Share;
     fn set_at(&mut self, index: usize, value: Self::Share);
+
+    /// Returns the encoded components of the shares which are also held by the next party and
+    /// those which are also held by the previous party, e.g., to commit to a database. Fails if
+    /// the shares are not replicated.
+    fn replicated_components(_slice: &Self::Slice) -> Result<[Vec<Bytes>; 2], Error> {
+        Err(Error::Other("Shares are not replicated".to_string()))
+    }
+}
+
+/// A share of a single bit, which can be packed into a share of a [`u128`] word. Vectors of such
+/// shares are stored in a [`PackedBits`](crate::prelude::PackedBits) with one bit per bit.
+pub trait BitShareTrait: ShareTrait {
+    /// The share of a word of [`u128::BITS`] packed bits.
+    type Word: ShareTrait + BitXorAssign + Send + Sync;
+
+    fn get_bit(word: &Self::Word, index: usize) -> Self;
+    fn set_bit(word: &mut Self::Word, index: usize, bit: Self);
 }
@@ -2,24 +2,55 @@ use super::network_trait::NetworkTrait;
 use crate::aby3::id::PartyID;
 use bytes::Bytes;
 use bytes::BytesMut;
+use std::collections::HashMap;
 use std::io;
 use std::io::Error as IOError;
-use std::io::ErrorKind as IOErrorKind;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
 
+type Link = (
+    Option<UnboundedSender<Bytes>>,
+    Option<UnboundedReceiver<Bytes>>,
+);
+
+// The channels between the parties, indexed by (session, sender, receiver). The channels of a
+// session are created by the first party opening it and removed once both ends are taken.
+#[derive(Default)]
+struct Links(Mutex<HashMap<(usize, usize, usize), Link>>);
+
+impl Links {
+    fn take<T>(
+        &self,
+        session: usize,
+        from: PartyID,
+        to: PartyID,
+        end: impl FnOnce(&mut Link) -> Option<T>,
+    ) -> T {
+        let mut links = self.0.lock().expect("Lock is not poisoned");
+        let key = (session, usize::from(from), usize::from(to));
+        let link = links.entry(key).or_insert_with(|| {
+            let (sender, receiver) = mpsc::unbounded_channel();
+            (Some(sender), Some(receiver))
+        });
+        let res = end(link).expect("Each end of a channel is only taken once");
+        if link.0.is_none() && link.1.is_none() {
+            links.remove(&key);
+        }
+        res
+    }
+
+    fn sender(&self, session: usize, from: PartyID, to: PartyID) -> UnboundedSender<Bytes> {
+        self.take(session, from, to, |link| link.0.take())
+    }
+
+    fn receiver(&self, session: usize, from: PartyID, to: PartyID) -> UnboundedReceiver<Bytes> {
+        self.take(session, from, to, |link| link.1.take())
+    }
+}
+
 pub struct TestNetwork3p {
-    p1_p2_sender: UnboundedSender<Bytes>,
-    p1_p3_sender: UnboundedSender<Bytes>,
-    p2_p3_sender: UnboundedSender<Bytes>,
-    p2_p1_sender: UnboundedSender<Bytes>,
-    p3_p1_sender: UnboundedSender<Bytes>,
-    p3_p2_sender: UnboundedSender<Bytes>,
-    p1_p2_receiver: UnboundedReceiver<Bytes>,
-    p1_p3_receiver: UnboundedReceiver<Bytes>,
-    p2_p3_receiver: UnboundedReceiver<Bytes>,
-    p2_p1_receiver: UnboundedReceiver<Bytes>,
-    p3_p1_receiver: UnboundedReceiver<Bytes>,
-    p3_p2_receiver: UnboundedReceiver<Bytes>,
+    links: Arc<Links>,
 }
 
 impl Default for TestNetwork3p {
@@ -30,59 +61,22 @@ impl Default for TestNetwork3p {
 
 impl TestNetwork3p {
     pub fn new() -> Self {
-        // AT Most 1 message is buffered before they are read so this should be fine
-        let p1_p2 = mpsc::unbounded_channel();
-        let p1_p3 = mpsc::unbounded_channel();
-        let p2_p3 = mpsc::unbounded_channel();
-        let p2_p1 = mpsc::unbounded_channel();
-        let p3_p1 = mpsc::unbounded_channel();
-        let p3_p2 = mpsc::unbounded_channel();
-
         Self {
-            p1_p2_sender: p1_p2.0,
-            p1_p3_sender: p1_p3.0,
-            p2_p1_sender: p2_p1.0,
-            p2_p3_sender: p2_p3.0,
-            p3_p1_sender: p3_p1.0,
-            p3_p2_sender: p3_p2.0,
-            p1_p2_receiver: p1_p2.1,
-            p1_p3_receiver: p1_p3.1,
-            p2_p1_receiver: p2_p1.1,
-            p2_p3_receiver: p2_p3.1,
-            p3_p1_receiver: p3_p1.1,
-            p3_p2_receiver: p3_p2.1,
+            links: Arc::new(Links::default()),
         }
     }
 
     pub fn get_party_networks(self) -> [PartyTestNetwork; 3] {
-        let party1 = PartyTestNetwork {
-            id: PartyID::ID0,
-            send_prev: self.p1_p3_sender,
-            recv_prev: self.p3_p1_receiver,
-            send_next: self.p1_p2_sender,
-            recv_next: self.p2_p1_receiver,
-            stats: [0; 4],
-        };
-
-        let party2 = PartyTestNetwork {
-            id: PartyID::ID1,
-            send_prev: self.p2_p1_sender,
-            recv_prev: self.p1_p2_receiver,
-            send_next: self.p2_p3_sender,
-            recv_next: self.p3_p2_receiver,
-            stats: [0; 4],
-        };
-
-        let party3 = PartyTestNetwork {
-            id: PartyID::ID2,
-            send_prev: self.p3_p2_sender,
-            recv_prev: self.p2_p3_receiver,
-            send_next: self.p3_p1_sender,
-            recv_next: self.p1_p3_receiver,
-            stats: [0; 4],
-        };
-
-        [party1, party2, party3]
+        [PartyID::ID0, PartyID::ID1, PartyID::ID2].map(|id| PartyTestNetwork {
+            id,
+            send_prev: self.links.sender(0, id, id.prev_id()),
+            send_next: self.links.sender(0, id, id.next_id()),
+            recv_prev: self.links.receiver(0, id.prev_id(), id),
+            recv_next: self.links.receiver(0, id.next_id(), id),
+            links: self.links.to_owned(),
+            sessions: Arc::new(AtomicUsize::new(1)),
+            stats: Arc::default(),
+        })
     }
 }
 
@@ -92,11 +86,17 @@ pub struct PartyTestNetwork {
     send_next: UnboundedSender<Bytes>,
     recv_prev: UnboundedReceiver<Bytes>,
     recv_next: UnboundedReceiver<Bytes>,
-    stats: [usize; 4], // [sent_prev, sent_next, recv_prev, recv_next]
+    links: Arc<Links>,
+    sessions: Arc<AtomicUsize>, // Number of sessions opened by this party
+    stats: Arc<[AtomicUsize; 4]>, // [sent_prev, sent_next, recv_prev, recv_next] of all sessions
 }
 
 impl PartyTestNetwork {
     pub const NUM_PARTIES: usize = 3;
+
+    fn add_stats(&self, index: usize, len: usize) {
+        self.stats[index].fetch_add(len, Ordering::Relaxed);
+    }
 }
 
 impl NetworkTrait for PartyTestNetwork {
@@ -108,12 +108,14 @@ impl NetworkTrait for PartyTestNetwork {
         writeln!(
             out,
             "Connection \"prev\" stats:\n\tSENT: {} bytes\n\tRECV: {} bytes",
-            self.stats[0], self.stats[2]
+            self.stats[0].load(Ordering::Relaxed),
+            self.stats[2].load(Ordering::Relaxed)
         )?;
         writeln!(
             out,
             "Connection \"next\" stats:\n\tSENT: {} bytes\n\tRECV: {} bytes",
-            self.stats[1], self.stats[3]
+            self.stats[1].load(Ordering::Relaxed),
+            self.stats[3].load(Ordering::Relaxed)
         )?;
         Ok(())
     }
@@ -121,17 +123,17 @@ impl NetworkTrait for PartyTestNetwork {
     async fn send(&mut self, id: usize, data: Bytes) -> std::io::Result<()> {
         tracing::trace!("send_id {}->{}: {:?}", self.id, id, data);
         let res = if id == usize::from(self.id.next_id()) {
-            self.stats[1] += data.len();
+            self.add_stats(1, data.len());
             self.send_next
                 .send(data)
-                .map_err(|_| IOError::new(IOErrorKind::Other, "Send failed"))
+                .map_err(|_| IOError::other("Send failed"))
         } else if id == usize::from(self.id.prev_id()) {
-            self.stats[0] += data.len();
+            self.add_stats(0, data.len());
             self.send_prev
                 .send(data)
-                .map_err(|_| IOError::new(IOErrorKind::Other, "Send failed"))
+                .map_err(|_| IOError::other("Send failed"))
         } else {
-            Err(IOError::new(io::ErrorKind::Other, "Invalid ID"))
+            Err(IOError::other("Invalid ID"))
         };
 
         tracing::trace!("send_id {}->{}: done", self.id, id);
@@ -145,19 +147,19 @@ impl NetworkTrait for PartyTestNetwork {
                 .recv_prev
                 .recv()
                 .await
-                .ok_or_else(|| IOError::new(IOErrorKind::Other, "Receive failed"))?;
-            self.stats[2] += data.len();
+                .ok_or_else(|| IOError::other("Receive failed"))?;
+            self.add_stats(2, data.len());
             data
         } else if id == usize::from(self.id.next_id()) {
             let data = self
                 .recv_next
                 .recv()
                 .await
-                .ok_or_else(|| IOError::new(IOErrorKind::Other, "Receive failed"))?;
-            self.stats[3] += data.len();
+                .ok_or_else(|| IOError::other("Receive failed"))?;
+            self.add_stats(3, data.len());
             data
         } else {
-            return Err(io::Error::new(io::ErrorKind::Other, "Invalid ID"));
+            return Err(io::Error::other("Invalid ID"));
         };
         tracing::trace!("recv_id {}<-{}: done", self.id, id);
 
@@ -191,22 +193,22 @@ impl NetworkTrait for PartyTestNetwork {
 
     async fn send_next_id(&mut self, data: Bytes) -> Result<(), IOError> {
         tracing::trace!("send {}->{}: {:?}", self.id, self.id.next_id(), data);
-        self.stats[1] += data.len();
+        self.add_stats(1, data.len());
         let res = self
             .send_next
             .send(data)
-            .map_err(|_| IOError::new(IOErrorKind::Other, "Send failed"));
+            .map_err(|_| IOError::other("Send failed"));
         tracing::trace!("send {}->{}: done", self.id, self.id.next_id());
         res
     }
 
     async fn send_prev_id(&mut self, data: Bytes) -> Result<(), IOError> {
         tracing::trace!("send {}->{}: {:?}", self.id, self.id.prev_id(), data);
-        self.stats[0] += data.len();
+        self.add_stats(0, data.len());
         let res = self
             .send_prev
             .send(data)
-            .map_err(|_| IOError::new(IOErrorKind::Other, "Send failed"));
+            .map_err(|_| IOError::other("Send failed"));
         tracing::trace!("send {}->{}: done", self.id, self.id.prev_id());
         res
     }
@@ -217,8 +219,8 @@ impl NetworkTrait for PartyTestNetwork {
             .recv_prev
             .recv()
             .await
-            .ok_or_else(|| IOError::new(IOErrorKind::Other, "Receive failed"))?;
-        self.stats[2] += buf.len();
+            .ok_or_else(|| IOError::other("Receive failed"))?;
+        self.add_stats(2, buf.len());
 
         tracing::trace!("recv {}<-{}: done", self.id, self.id.prev_id());
         Ok(BytesMut::from(buf.as_ref()))
@@ -230,10 +232,25 @@ impl NetworkTrait for PartyTestNetwork {
             .recv_next
             .recv()
             .await
-            .ok_or_else(|| IOError::new(IOErrorKind::Other, "Receive failed"))?;
-        self.stats[3] += buf.len();
+            .ok_or_else(|| IOError::other("Receive failed"))?;
+        self.add_stats(3, buf.len());
 
         tracing::trace!("recv {}<-{}: done", self.id, self.id.next_id());
         Ok(BytesMut::from(buf.as_ref()))
     }
+
+    async fn fork(&mut self) -> Result<Self, IOError> {
+        let session = self.sessions.fetch_add(1, Ordering::Relaxed);
+        let id = self.id;
+        Ok(Self {
+            id,
+            send_prev: self.links.sender(session, id, id.prev_id()),
+            send_next: self.links.sender(session, id, id.next_id()),
+            recv_prev: self.links.receiver(session, id.prev_id(), id),
+            recv_next: self.links.receiver(session, id.next_id(), id),
+            links: self.links.to_owned(),
+            sessions: self.sessions.to_owned(),
+            stats: self.stats.to_owned(),
+        })
+    }
 }
@@ -1,5 +1,6 @@
 pub(crate) mod bit;
 pub(crate) mod int_ring;
+pub(crate) mod packed_bits;
 pub(crate) mod ring_element;
 pub(crate) mod sharable;
 
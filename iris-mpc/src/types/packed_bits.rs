@@ -0,0 +1,262 @@
+use super::{bit::Bit, ring_element::RingElement};
+use crate::{
+    prelude::Error,
+    traits::share_trait::{BitShareTrait, VecShareTrait},
+};
+use num_traits::Zero;
+use plain_reference::IrisCodeArray;
+
+const BITS: usize = u128::BITS as usize;
+
+/// A vector of bit shares, packed into shares of [`u128`] words. Bit `i` is stored in bit
+/// `i % 128` of word `i / 128`. The bits of the last word beyond the length are shares of zero,
+/// such that the words can be used directly in bitsliced computations like an OR-tree.
+#[derive(Clone, Debug)]
+pub struct PackedBits<B: BitShareTrait> {
+    words: Vec<B::Word>,
+    len: usize,
+}
+
+impl<B: BitShareTrait> PackedBits<B> {
+    /// Wraps the words of a bitsliced computation. The padding bits of the last word have to be
+    /// shares of zero.
+    pub(crate) fn from_words(words: Vec<B::Word>, len: usize) -> Self {
+        assert_eq!(words.len(), len.div_ceil(BITS), "Wrong number of words");
+        Self { words, len }
+    }
+
+    pub(crate) fn words(&self) -> &[B::Word] {
+        &self.words
+    }
+
+    pub(crate) fn into_words(self) -> Vec<B::Word> {
+        self.words
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn push(&mut self, value: B) {
+        if self.len % BITS == 0 {
+            self.words.push(B::Word::zero());
+        }
+        let last = self.words.last_mut().expect("Pushed above");
+        B::set_bit(last, self.len % BITS, value);
+        self.len += 1;
+    }
+
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = B> + '_ {
+        (0..self.len).map(|i| self.get_at(i))
+    }
+
+    // Copies the bits start..end
+    fn slice(&self, start: usize, end: usize) -> Self {
+        debug_assert!(start <= end && end <= self.len);
+        if start % BITS != 0 {
+            return (start..end).map(|i| self.get_at(i)).collect();
+        }
+        let mut res = Self {
+            words: self.words[start / BITS..end.div_ceil(BITS)].to_vec(),
+            len: end - start,
+        };
+        // Clear the padding
+        let used = res.len % BITS;
+        if used != 0 {
+            let last = res.words.last_mut().expect("Not empty");
+            for i in used..BITS {
+                B::set_bit(last, i, B::zero());
+            }
+        }
+        res
+    }
+}
+
+impl PackedBits<Bit> {
+    /// Unpacks opened words into `len` bits.
+    pub(crate) fn open_words(words: Vec<u128>, len: usize) -> Vec<bool> {
+        Self::from_words(words, len)
+            .iter()
+            .map(Bit::convert)
+            .collect()
+    }
+}
+
+impl<B: BitShareTrait> VecShareTrait for PackedBits<B> {
+    type Share = B;
+    type Slice = Self;
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn filter_reduce_add_twice(
+        a: &Self,
+        b: &Self::Slice,
+        mask: &IrisCodeArray,
+    ) -> Result<(Self::Share, Self::Share), Error> {
+        if a.is_empty() || a.len() != b.len() {
+            return Err(Error::InvalidCodeSizeError);
+        }
+
+        let sum_a = a
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| mask.get_bit(*i))
+            .fold(B::zero(), |a, (_, b)| a + b);
+        let sum_b = b
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| mask.get_bit(*i))
+            .fold(B::zero(), |a, (_, b)| a + b);
+        Ok((sum_a, sum_b))
+    }
+
+    fn xor_many(mut self, b: Self) -> Result<Self, Error> {
+        self.xor_assign_many(b)?;
+        Ok(self)
+    }
+
+    fn xor_assign_many(&mut self, b: Self) -> Result<Self, Error> {
+        if self.len != b.len {
+            return Err(Error::InvalidSizeError);
+        }
+
+        for (a_, b_) in self.words.iter_mut().zip(b.words) {
+            *a_ ^= b_;
+        }
+        Ok(self.to_owned())
+    }
+
+    fn shl_assign_many(&mut self, shift: u32) -> Self {
+        // Shifting a single bit by a nonzero amount clears it
+        if shift != 0 {
+            for word in self.words.iter_mut() {
+                *word = B::Word::zero();
+            }
+        }
+        self.to_owned()
+    }
+
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            words: Vec::with_capacity(capacity.div_ceil(BITS)),
+            len: 0,
+        }
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        let words = (self.len + additional).div_ceil(BITS);
+        self.words.reserve(words - self.words.len());
+    }
+
+    fn push(&mut self, value: Self::Share) {
+        PackedBits::push(self, value);
+    }
+
+    fn extend(&mut self, other: Self) {
+        if self.len % BITS == 0 {
+            self.words.extend(other.words);
+            self.len += other.len;
+        } else {
+            self.reserve(other.len);
+            for bit in other.iter() {
+                self.push(bit);
+            }
+        }
+    }
+
+    fn split_at(&self, mid: usize) -> (Self, Self) {
+        assert!(mid <= self.len, "Index out of bounds");
+        (self.slice(0, mid), self.slice(mid, self.len))
+    }
+
+    fn chunks(self, chunk_size: usize) -> Vec<Self> {
+        assert!(chunk_size != 0, "Chunk size must be non-zero");
+        (0..self.len)
+            .step_by(chunk_size)
+            .map(|start| self.slice(start, (start + chunk_size).min(self.len)))
+            .collect()
+    }
+
+    fn get_at(&self, index: usize) -> Self::Share {
+        assert!(index < self.len, "Index out of bounds");
+        B::get_bit(&self.words[index / BITS], index % BITS)
+    }
+
+    fn set_at(&mut self, index: usize, value: Self::Share) {
+        assert!(index < self.len, "Index out of bounds");
+        B::set_bit(&mut self.words[index / BITS], index % BITS, value);
+    }
+}
+
+impl<B: BitShareTrait> FromIterator<B> for PackedBits<B> {
+    fn from_iter<I: IntoIterator<Item = B>>(iter: I) -> Self {
+        let iter = iter.into_iter();
+        let mut res = Self::with_capacity(iter.size_hint().0);
+        for bit in iter {
+            res.push(bit);
+        }
+        res
+    }
+}
+
+impl<B: BitShareTrait> IntoIterator for PackedBits<B> {
+    type Item = B;
+    type IntoIter = IntoIter<B>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            bits: self,
+            index: 0,
+        }
+    }
+}
+
+pub struct IntoIter<B: BitShareTrait> {
+    bits: PackedBits<B>,
+    index: usize,
+}
+
+impl<B: BitShareTrait> Iterator for IntoIter<B> {
+    type Item = B;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index == self.bits.len {
+            return None;
+        }
+        self.index += 1;
+        Some(self.bits.get_at(self.index - 1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.bits.len - self.index;
+        (len, Some(len))
+    }
+}
+
+impl<B: BitShareTrait> ExactSizeIterator for IntoIter<B> {}
+
+impl BitShareTrait for Bit {
+    type Word = u128;
+
+    fn get_bit(word: &u128, index: usize) -> Self {
+        Bit::new((word >> index) & 1 == 1)
+    }
+
+    fn set_bit(word: &mut u128, index: usize, bit: Self) {
+        *word = (*word & !(1 << index)) | (u128::from(bit.convert()) << index);
+    }
+}
+
+pub(crate) fn get_ring_bit(word: &RingElement<u128>, index: usize) -> RingElement<Bit> {
+    RingElement(Bit::get_bit(&word.0, index))
+}
+
+pub(crate) fn set_ring_bit(word: &mut RingElement<u128>, index: usize, bit: RingElement<Bit>) {
+    Bit::set_bit(&mut word.0, index, bit.0);
+}
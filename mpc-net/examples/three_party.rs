@@ -37,7 +37,7 @@ async fn main() -> Result<()> {
         key_path: args.key_file,
     };
 
-    let mut network = MpcNetworkHandler::establish(config).await?;
+    let network = MpcNetworkHandler::establish(config).await?;
 
     let mut channels = network.get_byte_channels().await?;
 
@@ -38,7 +38,7 @@ async fn main() -> Result<()> {
         key_path: args.key_file,
     };
 
-    let mut network = MpcNetworkHandler::establish(config).await?;
+    let network = MpcNetworkHandler::establish(config).await?;
 
     let mut channels = network.get_serde_bincode_channels().await?;
 
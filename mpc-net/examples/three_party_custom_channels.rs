@@ -39,7 +39,7 @@ async fn main() -> Result<()> {
         key_path: args.key_file,
     };
 
-    let mut network = MpcNetworkHandler::establish(config).await?;
+    let network = MpcNetworkHandler::establish(config).await?;
 
     let codec = MessageCodec;
     let mut channels = network.get_custom_channels(codec).await?;
@@ -37,7 +37,7 @@ async fn main() -> Result<()> {
         key_path: args.key_file,
     };
 
-    let mut network = MpcNetworkHandler::establish(config).await?;
+    let network = MpcNetworkHandler::establish(config).await?;
 
     let channels = network.get_byte_channels().await?;
     let mut managed_channels = channels
@@ -38,10 +38,7 @@ where
         // reserve a bit to avoid reallocations, but this does not work well for nested types
         let mut buf = BytesMut::with_capacity(std::mem::size_of::<M>() + 16).writer();
         bincode::serialize_into(&mut buf, &item).map_err(|e| {
-            io::Error::new(
-                io::ErrorKind::Other,
-                format!("Failed to serialize message with bincode: {}", e),
-            )
+            io::Error::other(format!("Failed to serialize message with bincode: {}", e))
         })?;
         let buf = buf.into_inner().freeze();
         self.inner.encode(buf, dst)
@@ -65,10 +62,7 @@ where
         let reader = buf.reader();
 
         let result = bincode::deserialize_from::<_, M>(reader).map_err(|e| {
-            io::Error::new(
-                io::ErrorKind::Other,
-                format!("Failed to deserialize message with bincode: {}", e),
-            )
+            io::Error::other(format!("Failed to deserialize message with bincode: {}", e))
         })?;
         Ok(Some(result))
     }
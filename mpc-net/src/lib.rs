@@ -170,14 +170,17 @@ impl MpcNetworkHandler {
         }
         Ok(())
     }
+    /// Opens a new bidirectional stream to each party. The streams of consecutive calls are
+    /// independent of each other, so this can be used to multiplex sessions over the same
+    /// connections. All parties have to call this in the same order.
     pub async fn get_byte_channels(
-        &mut self,
+        &self,
     ) -> std::io::Result<HashMap<usize, BytesChannel<RecvStream, SendStream>>> {
         self.get_custom_channels(LengthDelimitedCodec::new()).await
     }
 
     pub async fn get_serde_bincode_channels<M: Serialize + DeserializeOwned + 'static>(
-        &mut self,
+        &self,
     ) -> std::io::Result<HashMap<usize, Channel<RecvStream, SendStream, BincodeCodec<M>>>> {
         let bincodec = BincodeCodec::<M>::new();
         self.get_custom_channels(bincodec).await
@@ -191,11 +194,11 @@ impl MpcNetworkHandler {
             + 'static
             + Clone,
     >(
-        &mut self,
+        &self,
         codec: C,
     ) -> std::io::Result<HashMap<usize, Channel<RecvStream, SendStream, C>>> {
         let mut channels = HashMap::with_capacity(self.connections.len() - 1);
-        for (&id, conn) in &mut self.connections {
+        for (&id, conn) in &self.connections {
             if id < self.my_id {
                 // we are the client, so we are the receiver
                 let (mut send_stream, mut recv_stream) = conn.open_bi().await?;
@@ -0,0 +1,51 @@
+"""Writes the open-iris template fixtures of the template tests.
+
+The templates are encoded like `IrisTemplate.serialize()` of open-iris: the codes of the two
+filters, each of shape (rows, cols, 2), are stacked to an array of shape (rows, cols, 2, 2), which
+is packed with `np.packbits` (the first bit is the most significant bit of the first byte) and
+base64 encoded. Only the standard library is used, such that no numpy is required.
+"""
+
+import base64
+import json
+
+ROWS = 16
+
+
+def code_bit(row, col, f, c):
+    return (row * 7 + col * 3 + f * 2 + c) % 5 == 0
+
+
+def mask_bit(row, col, f, c):
+    return row < 14 and col % 50 != 0
+
+
+def packbits(bits):
+    out = bytearray()
+    for i in range(0, len(bits), 8):
+        byte = 0
+        for b in bits[i : i + 8]:
+            byte = (byte << 1) | int(b)
+        out.append(byte)
+    return bytes(out)
+
+
+def encode(cols, fn):
+    bits = [
+        fn(row, col, f, c)
+        for row in range(ROWS)
+        for col in range(cols)
+        for f in range(2)
+        for c in range(2)
+    ]
+    return base64.b64encode(packbits(bits)).decode("utf-8")
+
+
+for cols in [200, 256]:
+    template = {
+        "iris_codes": encode(cols, code_bit),
+        "mask_codes": encode(cols, mask_bit),
+        "iris_code_version": "v2.1",
+    }
+    with open(f"open_iris_{cols}.json", "w") as f:
+        json.dump(template, f)
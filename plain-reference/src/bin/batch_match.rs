@@ -0,0 +1,142 @@
+use std::{path::PathBuf, time::Instant};
+
+use clap::Parser;
+use color_eyre::eyre::{Report, Result};
+use plain_reference::{
+    db::read_array,
+    matcher::{BatchMatcher, IrisStore, MatcherConfig},
+    template::{read_template_file, BitOrder, TemplateFormat},
+    IrisCode, MATCH_THRESHOLD_RATIO,
+};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+use rusqlite::Connection;
+
+#[derive(Parser)]
+#[command(author, version, about, long_about = None)]
+struct Opts {
+    /// path to the database file to store stuff in
+    #[arg(short, long, value_name = "FILE", required = true)]
+    database: PathBuf,
+
+    /// a template file whose templates are matched against the database, instead of generated
+    /// irises
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["should_match", "num_probes"])]
+    probe: Option<PathBuf>,
+
+    /// the format of the probe file
+    #[arg(long, value_enum, default_value_t = TemplateFormat::OpenIris)]
+    template_format: TemplateFormat,
+
+    /// the order of the bits within a byte of packed template files
+    #[arg(long, value_enum, default_value_t = BitOrder::Lsb0)]
+    bit_order: BitOrder,
+
+    /// number of generated probes
+    #[arg(short, long, value_name = "NUM", default_value = "1")]
+    num_probes: usize,
+
+    /// Set to true if the generated probes should match random elements in the database
+    #[arg(short, long, default_value = "false")]
+    should_match: bool,
+
+    /// a pair matches if the fraction of differing bits is smaller than this threshold
+    #[arg(long, default_value_t = MATCH_THRESHOLD_RATIO)]
+    threshold: f64,
+
+    /// compare rotations of the probe by up to this many columns in both directions
+    #[arg(long, value_name = "NUM", default_value = "0")]
+    max_rotation: usize,
+
+    /// number of threads, all available cores if 0
+    #[arg(short, long, value_name = "NUM", default_value = "0")]
+    threads: usize,
+
+    /// find all matches of a probe instead of stopping at the first one
+    #[arg(long, default_value = "false")]
+    all_matches: bool,
+}
+
+fn read_store(conn: &Connection) -> Result<IrisStore> {
+    let len = conn.query_row("SELECT COUNT(*) from iris_codes;", [], |row| {
+        row.get::<_, usize>(0)
+    })?;
+    let mut store = IrisStore::with_capacity(len);
+    let mut stmt = conn.prepare("SELECT code, mask from iris_codes;")?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        let mut code = IrisCode::default();
+        read_array(row, 0, &mut code.code)?;
+        read_array(row, 1, &mut code.mask)?;
+        store.push(&code);
+    }
+    Ok(store)
+}
+
+fn main() -> Result<()> {
+    let opts: Opts = Opts::parse();
+
+    let start = Instant::now();
+    let store = read_store(&Connection::open(&opts.database)?)?;
+    println!(
+        "Loading {} irises took: {} sec",
+        store.len(),
+        start.elapsed().as_secs_f64()
+    );
+    if store.is_empty() {
+        return Err(Report::msg("The database is empty"));
+    }
+
+    let mut rng = ChaCha12Rng::from_entropy();
+    let probes = match &opts.probe {
+        Some(probe) => read_template_file(probe, opts.template_format, opts.bit_order)?,
+        None if opts.should_match => (0..opts.num_probes)
+            .map(|_| {
+                let index = rng.gen_range(0..store.len());
+                let code = store.get(index).expect("index is in range");
+                code.get_similar_iris(&mut rng)
+            })
+            .collect(),
+        None => (0..opts.num_probes)
+            .map(|_| IrisCode::random_rng(&mut rng))
+            .collect(),
+    };
+
+    let matcher = BatchMatcher::new(MatcherConfig {
+        threshold: opts.threshold,
+        max_rotation: opts.max_rotation,
+        threads: opts.threads,
+        early_exit: !opts.all_matches,
+    });
+    // The probes are matched in parallel, so the throughput is based on the time of the batch
+    let start = Instant::now();
+    let results = matcher.match_batch(&store, &probes);
+    let duration = start.elapsed().as_secs_f64();
+
+    let mut records = 0;
+    let mut comparisons = 0;
+    for (i, res) in results.iter().enumerate() {
+        if res.is_match() {
+            println!("Probe {i}: found a match at {:?}", res.matches);
+        } else {
+            println!("Probe {i}: no match found");
+        }
+        records += res.records;
+        comparisons += res.comparisons;
+    }
+
+    println!(
+        "Matching {} probes on {} threads took: {duration} sec",
+        probes.len(),
+        matcher.threads()
+    );
+    if duration > 0. {
+        println!(
+            "Throughput: {:.0} records/sec, {:.0} comparisons/sec",
+            records as f64 / duration,
+            comparisons as f64 / duration
+        );
+    }
+
+    Ok(())
+}
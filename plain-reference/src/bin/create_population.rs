@@ -0,0 +1,107 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use color_eyre::eyre::Result;
+use plain_reference::population::{mean_distances, PopulationConfig, PopulationGenerator};
+use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+use rusqlite::Connection;
+
+// The mean distances are computed over all pairs of the first samples only
+const MAX_REPORTED_SAMPLES: usize = 500;
+
+#[derive(Parser)]
+#[command(author, version, about, long_about = None)]
+struct Opts {
+    /// path to the database file to store stuff in
+    #[arg(short, long, value_name = "FILE", required = true)]
+    database: PathBuf,
+
+    /// number of identities to generate
+    #[arg(short, long, value_name = "NUM", required = true)]
+    identities: usize,
+
+    /// number of samples of each identity
+    #[arg(long, value_name = "NUM", default_value = "2")]
+    samples: usize,
+
+    /// a seed for the random number generation, for reproducible test data
+    #[arg(short, long, value_name = "SEED")]
+    seed: Option<u64>,
+
+    /// mean fractional Hamming distance between samples of the same identity
+    #[arg(long, default_value_t = PopulationConfig::default().genuine_distance)]
+    genuine_distance: f64,
+
+    /// mean fractional Hamming distance between samples of different identities
+    #[arg(long, default_value_t = PopulationConfig::default().impostor_distance)]
+    impostor_distance: f64,
+
+    /// relative spread of the genuine distance between identities
+    #[arg(long, default_value_t = PopulationConfig::default().identity_noise_spread)]
+    identity_noise_spread: f64,
+
+    /// number of neighbouring columns whose bits are correlated
+    #[arg(long, default_value_t = PopulationConfig::default().block_size)]
+    block_size: usize,
+
+    /// maximum rotation of a sample in columns
+    #[arg(long, default_value_t = PopulationConfig::default().max_rotation)]
+    max_rotation: usize,
+
+    /// probability that a cell is dropped out of the mask outside of the eyelids
+    #[arg(long, default_value_t = PopulationConfig::default().dropout)]
+    dropout: f64,
+}
+
+fn main() -> Result<()> {
+    let opts: Opts = Opts::parse();
+
+    let generator = PopulationGenerator::new(PopulationConfig {
+        genuine_distance: opts.genuine_distance,
+        impostor_distance: opts.impostor_distance,
+        identity_noise_spread: opts.identity_noise_spread,
+        block_size: opts.block_size,
+        max_rotation: opts.max_rotation,
+        dropout: opts.dropout,
+        ..Default::default()
+    })?;
+    let mut rng = match opts.seed {
+        Some(seed) => ChaCha12Rng::seed_from_u64(seed),
+        None => ChaCha12Rng::from_entropy(),
+    };
+    let samples = generator.generate(opts.identities, opts.samples, &mut rng);
+
+    let mut conn = Connection::open(&opts.database)?;
+
+    // The table extends the one of create_sample_data_plain by the identity of each sample
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS iris_codes (
+            id INTEGER PRIMARY KEY,
+            identity INTEGER NOT NULL,
+            code BLOB NOT NULL,
+            mask BLOB NOT NULL
+        )",
+        rusqlite::params![],
+    )?;
+
+    let transaction = conn.transaction()?;
+    let mut stmt =
+        transaction.prepare("INSERT INTO iris_codes (identity, code, mask) VALUES (?1, ?2, ?3)")?;
+    for sample in samples.iter() {
+        stmt.execute(rusqlite::params![
+            sample.identity as u64,
+            sample.code.code.as_raw_slice(),
+            sample.code.mask.as_raw_slice()
+        ])?;
+    }
+    drop(stmt);
+    transaction.commit()?;
+
+    let reported = samples.len().min(MAX_REPORTED_SAMPLES);
+    let (genuine, impostor) = mean_distances(&samples[..reported]);
+    println!("Mean distances of the first {reported} samples at their true alignment:");
+    println!("genuine: {genuine:.4}, impostor: {impostor:.4}");
+
+    Ok(())
+}
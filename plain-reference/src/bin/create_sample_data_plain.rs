@@ -1,8 +1,11 @@
 use std::path::PathBuf;
 
 use clap::Parser;
-use color_eyre::eyre::Result;
-use plain_reference::IrisCode;
+use color_eyre::eyre::{Report, Result};
+use plain_reference::{
+    template::{read_template_file, write_template_file, BitOrder, TemplateFormat},
+    IrisCode,
+};
 use rusqlite::Connection;
 
 #[derive(Parser)]
@@ -15,12 +18,28 @@ struct Opts {
     /// number of items to generate
     #[arg(short, long, value_name = "NUM", required = true)]
     items: u32,
+
+    /// import the irises from this template file instead of generating random ones
+    #[arg(long, value_name = "FILE")]
+    templates: Option<PathBuf>,
+
+    /// write the irises of the database to this template file
+    #[arg(long, value_name = "FILE")]
+    export_templates: Option<PathBuf>,
+
+    /// the format of the template files
+    #[arg(long, value_enum, default_value_t = TemplateFormat::OpenIris)]
+    template_format: TemplateFormat,
+
+    /// the order of the bits within a byte of packed template files
+    #[arg(long, value_enum, default_value_t = BitOrder::Lsb0)]
+    bit_order: BitOrder,
 }
 
 fn main() -> Result<()> {
     let opts: Opts = Opts::parse();
 
-    let database_file = opts.database;
+    let database_file = opts.database.to_owned();
     let num_items = opts.items;
 
     fn open_database(database_file: &PathBuf) -> Result<Connection> {
@@ -41,10 +60,26 @@ fn main() -> Result<()> {
         rusqlite::params![],
     )?;
 
-    let mut codes = Vec::with_capacity(num_items as usize);
-    for _ in 0..num_items {
-        let code = IrisCode::random();
-        codes.push(code);
+    let codes = match &opts.templates {
+        Some(templates) => {
+            let mut codes = read_template_file(templates, opts.template_format, opts.bit_order)?;
+            if codes.len() < num_items as usize {
+                return Err(Report::msg("The template file contains not enough irises"));
+            }
+            codes.truncate(num_items as usize);
+            codes
+        }
+        None => {
+            let mut codes = Vec::with_capacity(num_items as usize);
+            for _ in 0..num_items {
+                let code = IrisCode::random();
+                codes.push(code);
+            }
+            codes
+        }
+    };
+    if let Some(export) = &opts.export_templates {
+        write_template_file(export, &codes, opts.template_format, opts.bit_order)?;
     }
 
     // Insert the codes into the database
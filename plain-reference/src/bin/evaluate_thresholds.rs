@@ -0,0 +1,88 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::PathBuf,
+    time::Instant,
+};
+
+use clap::Parser;
+use color_eyre::eyre::{Report, Result};
+use plain_reference::{db::read_array, evaluation::Distances, IrisCode, MATCH_THRESHOLD_RATIO};
+use rusqlite::Connection;
+
+#[derive(Parser)]
+#[command(author, version, about, long_about = None)]
+struct Opts {
+    /// path to the database with the labelled irises, e.g., created by create_population
+    #[arg(short, long, value_name = "FILE", required = true)]
+    database: PathBuf,
+
+    /// the rotations of up to this many columns in both directions are compensated
+    #[arg(long, value_name = "NUM", default_value = "0")]
+    max_rotation: usize,
+
+    /// the target false match rates for which thresholds are recommended
+    #[arg(long, value_name = "FMR", value_delimiter = ',', default_values_t = [1e-2, 1e-3, 1e-4, 1e-5])]
+    target_fmr: Vec<f64>,
+
+    /// number of equidistant steps of the thresholds of the curve
+    #[arg(long, value_name = "NUM", default_value = "1000")]
+    steps: usize,
+
+    /// write the threshold, FMR and FNMR of the ROC/DET curve to this CSV file
+    #[arg(long, value_name = "FILE")]
+    curve: Option<PathBuf>,
+
+    /// write the report to this JSON file instead of printing it
+    #[arg(long, value_name = "FILE")]
+    report: Option<PathBuf>,
+}
+
+fn read_records(conn: &Connection) -> Result<Vec<(u64, IrisCode)>> {
+    let mut stmt = conn.prepare("SELECT identity, code, mask from iris_codes ORDER BY id;")?;
+    let mut rows = stmt.query([])?;
+    let mut records = Vec::new();
+    while let Some(row) = rows.next()? {
+        let mut code = IrisCode::default();
+        read_array(row, 1, &mut code.code)?;
+        read_array(row, 2, &mut code.mask)?;
+        records.push((row.get::<_, u64>(0)?, code));
+    }
+    Ok(records)
+}
+
+fn main() -> Result<()> {
+    let opts: Opts = Opts::parse();
+
+    let conn = Connection::open(&opts.database)?;
+    let records = read_records(&conn)?;
+    if records.len() < 2 {
+        return Err(Report::msg("The dataset needs at least two records"));
+    }
+
+    let start = Instant::now();
+    let distances = Distances::from_records(&records, opts.max_rotation);
+    eprintln!(
+        "Computed {} genuine and {} impostor distances in {} sec",
+        distances.genuine().len(),
+        distances.impostor().len(),
+        start.elapsed().as_secs_f64()
+    );
+
+    if let Some(curve) = &opts.curve {
+        let mut writer = BufWriter::new(File::create(curve)?);
+        writeln!(writer, "threshold,fmr,fnmr")?;
+        for point in distances.curve(opts.steps) {
+            writeln!(writer, "{},{},{}", point.threshold, point.fmr, point.fnmr)?;
+        }
+        writer.flush()?;
+    }
+
+    let report = distances.report(&opts.target_fmr, MATCH_THRESHOLD_RATIO, opts.steps);
+    match &opts.report {
+        Some(path) => serde_json::to_writer_pretty(BufWriter::new(File::create(path)?), &report)?,
+        None => println!("{}", serde_json::to_string_pretty(&report)?),
+    }
+
+    Ok(())
+}
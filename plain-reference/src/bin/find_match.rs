@@ -1,8 +1,11 @@
 use std::{path::PathBuf, time::Instant};
 
 use clap::Parser;
-use color_eyre::eyre::Result;
-use plain_reference::IrisCode;
+use color_eyre::eyre::{Report, Result};
+use plain_reference::{
+    template::{read_template_file, write_template_file, BitOrder, TemplateFormat},
+    IrisCode,
+};
 use rand::distributions::{Bernoulli, Distribution};
 use rusqlite::Connection;
 
@@ -16,6 +19,23 @@ struct Opts {
     /// Set to true if a image should be generated that matches an element in the database
     #[arg(short, long, default_value = "false")]
     should_match: bool,
+
+    /// a template file whose first template is matched against the database, instead of a
+    /// generated iris
+    #[arg(long, value_name = "FILE", conflicts_with = "should_match")]
+    probe: Option<PathBuf>,
+
+    /// write the matched template to this file
+    #[arg(long, value_name = "FILE")]
+    export_probe: Option<PathBuf>,
+
+    /// the format of the template files
+    #[arg(long, value_enum, default_value_t = TemplateFormat::OpenIris)]
+    template_format: TemplateFormat,
+
+    /// the order of the bits within a byte of packed template files
+    #[arg(long, value_enum, default_value_t = BitOrder::Lsb0)]
+    bit_order: BitOrder,
 }
 
 fn main() -> Result<()> {
@@ -31,7 +51,12 @@ fn main() -> Result<()> {
 
     let conn = open_database(&database_file)?;
 
-    let code_to_compare_to = if opts.should_match {
+    let code_to_compare_to = if let Some(probe) = &opts.probe {
+        read_template_file(probe, opts.template_format, opts.bit_order)?
+            .into_iter()
+            .next()
+            .ok_or(Report::msg("The probe file contains no template"))?
+    } else if opts.should_match {
         // read the codes from the database using rusqlite and iterate over them
         conn.query_row("SELECT code, mask from iris_codes LIMIT 1;", [], |row| {
             let mut res = IrisCode::default();
@@ -57,6 +82,14 @@ fn main() -> Result<()> {
     } else {
         IrisCode::random()
     };
+    if let Some(export) = &opts.export_probe {
+        write_template_file(
+            export,
+            &[code_to_compare_to.to_owned()],
+            opts.template_format,
+            opts.bit_order,
+        )?;
+    }
 
     // read the codes from the database using rusqlite and iterate over them
     let mut stmt = conn.prepare("SELECT code, mask from iris_codes;")?;
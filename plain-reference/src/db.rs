@@ -0,0 +1,21 @@
+//! Helpers for reading the plaintext iris databases, e.g., created by create_population.
+
+use crate::IrisCodeArray;
+use color_eyre::eyre::{Report, Result};
+use rusqlite::Row;
+
+/// Reads the blob in column `index` of `row` into `array`. Fails if the blob does not have the
+/// size of an iris code array.
+pub fn read_array(row: &Row, index: usize, array: &mut IrisCodeArray) -> Result<()> {
+    let blob = row.get::<_, Vec<u8>>(index)?;
+    let raw = array.as_raw_mut_slice();
+    if blob.len() != raw.len() {
+        return Err(Report::msg(format!(
+            "The database contains a blob of {} bytes instead of {}",
+            blob.len(),
+            raw.len()
+        )));
+    }
+    raw.copy_from_slice(&blob);
+    Ok(())
+}
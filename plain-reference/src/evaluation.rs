@@ -0,0 +1,227 @@
+//! Evaluation of the matching accuracy over a labelled dataset.
+//!
+//! The fractional Hamming distances of all pairs of records are split into genuine pairs (same
+//! identity) and impostor pairs (different identities). A pair matches at a threshold `t`, if its
+//! distance is smaller than `t`, as in `IrisCode::is_close`. The false match rate (FMR) is the
+//! fraction of impostor pairs which match, the false non-match rate (FNMR) the fraction of genuine
+//! pairs which do not match.
+
+use crate::IrisCode;
+use serde::Serialize;
+
+/// The sorted distances of the genuine and the impostor pairs.
+#[derive(Clone, Debug, Default)]
+pub struct Distances {
+    genuine: Vec<f64>,
+    impostor: Vec<f64>,
+}
+
+/// The error rates at a threshold.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct OperatingPoint {
+    pub threshold: f64,
+    pub fmr: f64,
+    pub fnmr: f64,
+}
+
+/// The threshold with the largest FMR not exceeding the target FMR.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct Recommendation {
+    pub target_fmr: f64,
+    #[serde(flatten)]
+    pub point: OperatingPoint,
+}
+
+/// A summary of the evaluation.
+#[derive(Clone, Debug, Serialize)]
+pub struct Report {
+    pub genuine_pairs: usize,
+    pub impostor_pairs: usize,
+    pub genuine_mean: f64,
+    pub impostor_mean: f64,
+    pub equal_error_rate: OperatingPoint,
+    pub current_threshold: OperatingPoint,
+    pub recommendations: Vec<Recommendation>,
+}
+
+/// The smallest distance of `a` rotated by up to `max_rotation` columns in both directions to `b`,
+/// or `None` if the masks have no common unmasked bits in any rotation.
+pub fn min_rotated_distance(a: &IrisCode, b: &IrisCode, max_rotation: usize) -> Option<f64> {
+    let max_rotation = max_rotation as isize;
+    (-max_rotation..=max_rotation)
+        .filter_map(|shift| a.rotated(shift).fractional_distance(b))
+        .min_by(f64::total_cmp)
+}
+
+impl Distances {
+    pub fn new(mut genuine: Vec<f64>, mut impostor: Vec<f64>) -> Self {
+        genuine.sort_by(f64::total_cmp);
+        impostor.sort_by(f64::total_cmp);
+        Self { genuine, impostor }
+    }
+
+    /// Computes the distances of all pairs of records, compensating rotations of up to
+    /// `max_rotation` columns. Pairs without common unmasked bits are skipped.
+    pub fn from_records(records: &[(u64, IrisCode)], max_rotation: usize) -> Self {
+        let mut genuine = Vec::new();
+        let mut impostor = Vec::new();
+        for (i, (identity_a, a)) in records.iter().enumerate() {
+            let rotations = (-(max_rotation as isize)..=max_rotation as isize)
+                .map(|shift| a.rotated(shift))
+                .collect::<Vec<_>>();
+            for (identity_b, b) in records.iter().skip(i + 1) {
+                let distance = rotations
+                    .iter()
+                    .filter_map(|a| a.fractional_distance(b))
+                    .min_by(f64::total_cmp);
+                match distance {
+                    Some(d) if identity_a == identity_b => genuine.push(d),
+                    Some(d) => impostor.push(d),
+                    None => {}
+                }
+            }
+        }
+        Self::new(genuine, impostor)
+    }
+
+    pub fn genuine(&self) -> &[f64] {
+        &self.genuine
+    }
+
+    pub fn impostor(&self) -> &[f64] {
+        &self.impostor
+    }
+
+    pub fn operating_point(&self, threshold: f64) -> OperatingPoint {
+        // The fraction of the sorted distances below the threshold
+        let below = |distances: &[f64]| {
+            if distances.is_empty() {
+                return 0.;
+            }
+            distances.partition_point(|d| *d < threshold) as f64 / distances.len() as f64
+        };
+        OperatingPoint {
+            threshold,
+            fmr: below(&self.impostor),
+            fnmr: if self.genuine.is_empty() {
+                0.
+            } else {
+                1. - below(&self.genuine)
+            },
+        }
+    }
+
+    /// The operating points at `steps + 1` equidistant thresholds from 0 to 1, i.e., the data of
+    /// the ROC and DET curves.
+    pub fn curve(&self, steps: usize) -> Vec<OperatingPoint> {
+        let steps = steps.max(1);
+        (0..=steps)
+            .map(|i| self.operating_point(i as f64 / steps as f64))
+            .collect()
+    }
+
+    /// The largest threshold whose FMR does not exceed `target_fmr`.
+    pub fn threshold_for_fmr(&self, target_fmr: f64) -> Recommendation {
+        // At most k impostor pairs may match, i.e., the threshold is the (k+1)-th smallest impostor
+        // distance
+        let k = (target_fmr * self.impostor.len() as f64).floor() as usize;
+        let threshold = self.impostor.get(k).copied().unwrap_or(1.);
+        Recommendation {
+            target_fmr,
+            point: self.operating_point(threshold),
+        }
+    }
+
+    /// The operating point of the curve with the smallest difference between FMR and FNMR.
+    pub fn equal_error_rate(&self, steps: usize) -> OperatingPoint {
+        self.curve(steps)
+            .into_iter()
+            .min_by(|a, b| (a.fmr - a.fnmr).abs().total_cmp(&(b.fmr - b.fnmr).abs()))
+            .expect("curve is not empty")
+    }
+
+    pub fn report(&self, target_fmrs: &[f64], current_threshold: f64, steps: usize) -> Report {
+        let mean =
+            |distances: &[f64]| distances.iter().sum::<f64>() / distances.len().max(1) as f64;
+        Report {
+            genuine_pairs: self.genuine.len(),
+            impostor_pairs: self.impostor.len(),
+            genuine_mean: mean(&self.genuine),
+            impostor_mean: mean(&self.impostor),
+            equal_error_rate: self.equal_error_rate(steps),
+            current_threshold: self.operating_point(current_threshold),
+            recommendations: target_fmrs
+                .iter()
+                .map(|target| self.threshold_for_fmr(*target))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::population::{PopulationConfig, PopulationGenerator};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
+
+    #[test]
+    fn error_rates() {
+        let distances = Distances::new(vec![0.3, 0.1, 0.2, 0.4], vec![0.5, 0.35, 0.45, 0.25]);
+        assert_eq!(distances.genuine(), [0.1, 0.2, 0.3, 0.4]);
+
+        let point = distances.operating_point(0.3);
+        assert_eq!(point.fmr, 0.25);
+        assert_eq!(point.fnmr, 0.5);
+        let point = distances.operating_point(0.);
+        assert_eq!((point.fmr, point.fnmr), (0., 1.));
+        let point = distances.operating_point(1.);
+        assert_eq!((point.fmr, point.fnmr), (1., 0.));
+
+        let curve = distances.curve(100);
+        assert_eq!(curve.len(), 101);
+        assert!(curve
+            .windows(2)
+            .all(|w| w[0].fmr <= w[1].fmr && w[0].fnmr >= w[1].fnmr));
+
+        let eer = distances.equal_error_rate(100);
+        assert_eq!(eer.fmr, eer.fnmr);
+    }
+
+    #[test]
+    fn recommended_thresholds() {
+        let impostor = (0..100).map(|i| 0.3 + i as f64 / 1000.).collect();
+        let distances = Distances::new(vec![0.1], impostor);
+        for target in [0., 0.01, 0.05, 0.5] {
+            let recommendation = distances.threshold_for_fmr(target);
+            assert!(recommendation.point.fmr <= target);
+            // A slightly larger threshold exceeds the target
+            let larger = distances.operating_point(recommendation.point.threshold + 1e-9);
+            assert!(larger.fmr > target);
+        }
+        assert_eq!(distances.threshold_for_fmr(1.).point.fmr, 1.);
+    }
+
+    #[test]
+    fn rotations_are_compensated() {
+        let mut rng = ChaCha12Rng::seed_from_u64(5);
+        let generator = PopulationGenerator::new(PopulationConfig {
+            max_rotation: 4,
+            ..Default::default()
+        })
+        .unwrap();
+        let samples = generator.generate(10, 2, &mut rng);
+        let records = samples
+            .iter()
+            .map(|sample| (sample.identity as u64, sample.code.to_owned()))
+            .collect::<Vec<_>>();
+
+        let distances = Distances::from_records(&records, 0);
+        let compensated = Distances::from_records(&records, 8);
+        assert_eq!(compensated.genuine().len(), 10);
+        assert_eq!(compensated.impostor().len(), 180);
+        let mean = |d: &[f64]| d.iter().sum::<f64>() / d.len() as f64;
+        assert!(mean(compensated.genuine()) < mean(distances.genuine()));
+        assert!((mean(compensated.genuine()) - 0.25).abs() < 0.03);
+    }
+}
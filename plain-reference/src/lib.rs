@@ -1,3 +1,9 @@
+pub mod db;
+pub mod evaluation;
+pub mod matcher;
+pub mod population;
+pub mod template;
+
 use core::panic;
 use rand::distributions::{Bernoulli, Distribution};
 use rand::Rng;
@@ -20,10 +26,19 @@ impl Default for IrisCodeArray {
 
 impl IrisCodeArray {
     pub const IRIS_CODE_SIZE: usize = 12800;
-    pub const IRIS_CODE_SIZE_BYTES: usize = (Self::IRIS_CODE_SIZE + 7) / 8;
-    pub const IRIS_CODE_SIZE_U64: usize = (Self::IRIS_CODE_SIZE + 63) / 64;
+    pub const IRIS_CODE_SIZE_BYTES: usize = Self::IRIS_CODE_SIZE.div_ceil(8);
+    pub const IRIS_CODE_SIZE_U64: usize = Self::IRIS_CODE_SIZE.div_ceil(64);
     pub const ZERO: Self = IrisCodeArray([0; Self::IRIS_CODE_SIZE_U64]);
     pub const ONES: Self = IrisCodeArray([u64::MAX; Self::IRIS_CODE_SIZE_U64]);
+    // The bits are laid out as ROWS rows (from the pupil to the outer boundary of the iris) of COLS
+    // columns (the angle) with BITS_PER_CELL bits each, i.e., rotating the iris rotates the columns
+    pub const ROWS: usize = 16;
+    pub const COLS: usize = 200;
+    pub const BITS_PER_CELL: usize = 4;
+    #[inline]
+    pub fn bit_index(row: usize, col: usize, k: usize) -> usize {
+        (row * Self::COLS + col) * Self::BITS_PER_CELL + k
+    }
     #[inline]
     pub fn set_bit(&mut self, i: usize, val: bool) {
         let word = i / 64;
@@ -61,6 +76,22 @@ impl IrisCodeArray {
         code
     }
 
+    /// Rotates the columns by `shift`, i.e., column `col` is moved to column `col + shift`.
+    pub fn rotated(&self, shift: isize) -> Self {
+        let shift = shift.rem_euclid(Self::COLS as isize) as usize;
+        let mut res = Self::ZERO;
+        for row in 0..Self::ROWS {
+            for col in 0..Self::COLS {
+                let dest = (col + shift) % Self::COLS;
+                for k in 0..Self::BITS_PER_CELL {
+                    let bit = self.get_bit(Self::bit_index(row, col, k));
+                    res.set_bit(Self::bit_index(row, dest, k), bit);
+                }
+            }
+        }
+        res
+    }
+
     pub fn count_ones(&self) -> usize {
         self.0.iter().map(|c| c.count_ones() as usize).sum()
     }
@@ -166,6 +197,25 @@ impl IrisCode {
         code_distance < match_threshold
     }
 
+    /// The fraction of differing bits among the bits which are unmasked in both codes, or `None`
+    /// if there are no such bits.
+    pub fn fractional_distance(&self, other: &Self) -> Option<f64> {
+        let combined_mask = self.mask & other.mask;
+        let combined_mask_len = combined_mask.count_ones();
+        if combined_mask_len == 0 {
+            return None;
+        }
+        let code_distance = ((self.code ^ other.code) & combined_mask).count_ones();
+        Some(code_distance as f64 / combined_mask_len as f64)
+    }
+
+    pub fn rotated(&self, shift: isize) -> Self {
+        IrisCode {
+            code: self.code.rotated(shift),
+            mask: self.mask.rotated(shift),
+        }
+    }
+
     pub fn get_similar_iris<R: Rng>(&self, rng: &mut R) -> IrisCode {
         let mut res = self.clone();
         // flip a few bits in mask and code (like 5%)
@@ -225,4 +275,23 @@ mod tests {
             assert_eq!(iris.code.get_bit(i), bit);
         }
     }
+
+    #[test]
+    fn rotation() {
+        use super::IrisCodeArray;
+        let iris = super::IrisCode::random();
+        let rotated = iris.rotated(3);
+        for k in 0..IrisCodeArray::BITS_PER_CELL {
+            let bit = |code: &IrisCodeArray, col| code.get_bit(IrisCodeArray::bit_index(5, col, k));
+            assert_eq!(bit(&rotated.code, 3), bit(&iris.code, 0));
+            assert_eq!(
+                bit(&rotated.mask, 1),
+                bit(&iris.mask, IrisCodeArray::COLS - 2)
+            );
+        }
+        let restored = rotated.rotated(-3);
+        assert_eq!(restored.code, iris.code);
+        assert_eq!(restored.mask, iris.mask);
+        assert_eq!(iris.code.rotated(IrisCodeArray::COLS as isize), iris.code);
+    }
 }
@@ -0,0 +1,434 @@
+//! A batch matcher over an in-memory store of iris codes, serving as the plaintext baseline for the
+//! MPC protocols.
+//!
+//! The codes and masks of the database are stored contiguously, and a probe is compared word by
+//! word, counting the common unmasked bits and the differing bits in the same pass. The rotations
+//! of the probe are computed once per probe. The database is split into one contiguous range per
+//! thread. With early exit, each thread stops at its first match or as soon as a match with a
+//! lower index is found, such that the lowest matching index is returned. A batch of probes is
+//! distributed over the threads instead, each of which matches its probes against the whole
+//! database.
+
+use crate::{IrisCode, IrisCodeArray, MASK_THRESHOLD, MATCH_THRESHOLD_RATIO};
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
+
+// Number of records after which a thread checks whether another one found a lower match
+const EARLY_EXIT_INTERVAL: usize = 64;
+
+/// A contiguous store of the codes and masks of a database.
+#[derive(Clone, Debug, Default)]
+pub struct IrisStore {
+    codes: Vec<IrisCodeArray>,
+    masks: Vec<IrisCodeArray>,
+}
+
+impl IrisStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            codes: Vec::with_capacity(capacity),
+            masks: Vec::with_capacity(capacity),
+        }
+    }
+
+    pub fn push(&mut self, code: &IrisCode) {
+        self.codes.push(code.code);
+        self.masks.push(code.mask);
+    }
+
+    pub fn len(&self) -> usize {
+        self.codes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.codes.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<IrisCode> {
+        Some(IrisCode {
+            code: *self.codes.get(index)?,
+            mask: *self.masks.get(index)?,
+        })
+    }
+}
+
+impl<'a> FromIterator<&'a IrisCode> for IrisStore {
+    fn from_iter<I: IntoIterator<Item = &'a IrisCode>>(iter: I) -> Self {
+        let mut store = IrisStore::new();
+        iter.into_iter().for_each(|code| store.push(code));
+        store
+    }
+}
+
+/// The parameters of the matching.
+#[derive(Clone, Debug)]
+pub struct MatcherConfig {
+    /// A pair matches if the fraction of differing bits among the common unmasked bits is smaller
+    pub threshold: f64,
+    /// Rotations of the probe by up to this many columns in both directions are compared
+    pub max_rotation: usize,
+    /// Number of threads, all available cores if 0
+    pub threads: usize,
+    /// Stop at the first match instead of finding all matches
+    pub early_exit: bool,
+}
+
+impl Default for MatcherConfig {
+    fn default() -> Self {
+        Self {
+            threshold: MATCH_THRESHOLD_RATIO,
+            max_rotation: 0,
+            threads: 0,
+            early_exit: true,
+        }
+    }
+}
+
+/// The result of matching a probe against the store.
+#[derive(Clone, Debug, Default)]
+pub struct MatchResult {
+    /// The indices of the matching records in increasing order. With early exit, this contains at
+    /// most the lowest matching index
+    pub matches: Vec<usize>,
+    /// Number of compared records
+    pub records: usize,
+    /// Number of compared pairs, i.e., records times rotations
+    pub comparisons: usize,
+    pub duration: Duration,
+}
+
+impl MatchResult {
+    pub fn is_match(&self) -> bool {
+        !self.matches.is_empty()
+    }
+
+    /// Compared records per second, 0 if no time was measured
+    pub fn throughput(&self) -> f64 {
+        let secs = self.duration.as_secs_f64();
+        if secs == 0. {
+            return 0.;
+        }
+        self.records as f64 / secs
+    }
+}
+
+pub struct BatchMatcher {
+    config: MatcherConfig,
+    threads: usize,
+}
+
+// The counts of the differing and of the common unmasked bits. Like `IrisCode::is_close`, a pair
+// does not match if there are too few common unmasked bits.
+#[inline]
+fn is_close(
+    probe_code: &IrisCodeArray,
+    probe_mask: &IrisCodeArray,
+    code: &IrisCodeArray,
+    mask: &IrisCodeArray,
+    threshold: f64,
+) -> bool {
+    let mut mask_len = 0;
+    let mut distance = 0;
+    for (((pc, pm), c), m) in probe_code
+        .0
+        .iter()
+        .zip(probe_mask.0.iter())
+        .zip(code.0.iter())
+        .zip(mask.0.iter())
+    {
+        let combined_mask = pm & m;
+        mask_len += combined_mask.count_ones();
+        distance += ((pc ^ c) & combined_mask).count_ones();
+    }
+    let mask_len = mask_len as usize;
+    mask_len >= MASK_THRESHOLD && (distance as usize) < (mask_len as f64 * threshold) as usize
+}
+
+impl BatchMatcher {
+    pub fn new(config: MatcherConfig) -> Self {
+        let threads = match config.threads {
+            0 => thread::available_parallelism().map_or(1, |n| n.get()),
+            n => n,
+        };
+        Self { config, threads }
+    }
+
+    pub fn config(&self) -> &MatcherConfig {
+        &self.config
+    }
+
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    // The matches and the number of compared records in the range of one thread. With early exit,
+    // `best` is the lowest match found by any thread so far.
+    fn match_range(
+        &self,
+        store: &IrisStore,
+        rotations: &[IrisCode],
+        start: usize,
+        end: usize,
+        best: &AtomicUsize,
+    ) -> (Vec<usize>, usize) {
+        let mut matches = Vec::new();
+        for i in start..end {
+            if self.config.early_exit
+                && (i - start) % EARLY_EXIT_INTERVAL == 0
+                && best.load(Ordering::Relaxed) < i
+            {
+                return (matches, i - start);
+            }
+            let (code, mask) = (&store.codes[i], &store.masks[i]);
+            if rotations
+                .iter()
+                .any(|probe| is_close(&probe.code, &probe.mask, code, mask, self.config.threshold))
+            {
+                matches.push(i);
+                if self.config.early_exit {
+                    best.fetch_min(i, Ordering::Relaxed);
+                    return (matches, i - start + 1);
+                }
+            }
+        }
+        (matches, end - start)
+    }
+
+    pub fn match_probe(&self, store: &IrisStore, probe: &IrisCode) -> MatchResult {
+        self.match_probe_on(store, probe, self.threads)
+    }
+
+    fn match_probe_on(&self, store: &IrisStore, probe: &IrisCode, threads: usize) -> MatchResult {
+        let start = Instant::now();
+        let max_rotation = self.config.max_rotation as isize;
+        let rotations = (-max_rotation..=max_rotation)
+            .map(|shift| probe.rotated(shift))
+            .collect::<Vec<_>>();
+
+        let best = AtomicUsize::new(usize::MAX);
+        let results = if threads <= 1 {
+            vec![self.match_range(store, &rotations, 0, store.len(), &best)]
+        } else {
+            let chunk_size = store.len().div_ceil(threads).max(1);
+            thread::scope(|scope| {
+                let handles = (0..store.len())
+                    .step_by(chunk_size)
+                    .map(|begin| {
+                        let end = (begin + chunk_size).min(store.len());
+                        let rotations = &rotations;
+                        let best = &best;
+                        scope.spawn(move || self.match_range(store, rotations, begin, end, best))
+                    })
+                    .collect::<Vec<_>>();
+                handles
+                    .into_iter()
+                    .map(|handle| handle.join().expect("matching thread panicked"))
+                    .collect::<Vec<_>>()
+            })
+        };
+
+        // The ranges are in increasing order, so the first match is the lowest one
+        let mut res = MatchResult::default();
+        for (matches, records) in results {
+            res.matches.extend(matches);
+            res.records += records;
+        }
+        if self.config.early_exit {
+            res.matches.truncate(1);
+        }
+        res.comparisons = res.records * rotations.len();
+        res.duration = start.elapsed();
+        res
+    }
+
+    /// Matches all probes, distributing them over the threads. If there are fewer probes than
+    /// threads, the remaining threads split the database of each probe.
+    pub fn match_batch(&self, store: &IrisStore, probes: &[IrisCode]) -> Vec<MatchResult> {
+        let workers = self.threads.min(probes.len());
+        if workers <= 1 {
+            return probes
+                .iter()
+                .map(|probe| self.match_probe(store, probe))
+                .collect();
+        }
+        let threads_per_probe = self.threads / workers;
+
+        let next = AtomicUsize::new(0);
+        let results = thread::scope(|scope| {
+            let handles = (0..workers)
+                .map(|_| {
+                    let next = &next;
+                    scope.spawn(move || {
+                        let mut results = Vec::new();
+                        loop {
+                            let index = next.fetch_add(1, Ordering::Relaxed);
+                            let Some(probe) = probes.get(index) else {
+                                break;
+                            };
+                            results.push((
+                                index,
+                                self.match_probe_on(store, probe, threads_per_probe),
+                            ));
+                        }
+                        results
+                    })
+                })
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().expect("matching thread panicked"))
+                .collect::<Vec<_>>()
+        });
+
+        let mut res = vec![MatchResult::default(); probes.len()];
+        for (index, result) in results {
+            res[index] = result;
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
+
+    const DB_SIZE: usize = 500;
+
+    fn store<R: rand::Rng>(rng: &mut R) -> (Vec<IrisCode>, IrisStore) {
+        let codes = (0..DB_SIZE)
+            .map(|_| IrisCode::random_rng(rng))
+            .collect::<Vec<_>>();
+        let store = codes.iter().collect();
+        (codes, store)
+    }
+
+    #[test]
+    fn matches_is_close() {
+        let mut rng = ChaCha12Rng::seed_from_u64(1);
+        let (codes, store) = store(&mut rng);
+        assert_eq!(store.len(), DB_SIZE);
+        assert_eq!(store.get(7).unwrap().code, codes[7].code);
+
+        let mut probes = vec![
+            codes[3].get_similar_iris(&mut rng),
+            IrisCode::random_rng(&mut rng),
+        ];
+        // A probe matching several records
+        probes.push(codes[100].to_owned());
+        probes[2].mask = codes[100].mask & codes[200].mask;
+        let mut mixed = codes[200].code;
+        for i in 0..IrisCode::IRIS_CODE_SIZE / 2 {
+            mixed.set_bit(i, codes[100].code.get_bit(i));
+        }
+        probes[2].code = mixed;
+
+        for threads in [1, 3] {
+            let matcher = BatchMatcher::new(MatcherConfig {
+                threads,
+                early_exit: false,
+                ..Default::default()
+            });
+            for probe in probes.iter() {
+                let expected = codes
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, code)| code.is_close(probe))
+                    .map(|(i, _)| i)
+                    .collect::<Vec<_>>();
+                let res = matcher.match_probe(&store, probe);
+                assert_eq!(res.matches, expected);
+                assert_eq!(res.records, DB_SIZE);
+                assert_eq!(res.comparisons, DB_SIZE);
+            }
+        }
+    }
+
+    #[test]
+    fn early_exit() {
+        let mut rng = ChaCha12Rng::seed_from_u64(2);
+        let (codes, store) = store(&mut rng);
+        let matcher = BatchMatcher::new(MatcherConfig {
+            threads: 1,
+            ..Default::default()
+        });
+        let res = matcher.match_probe(&store, &codes[10].get_similar_iris(&mut rng));
+        assert_eq!(res.matches, vec![10]);
+        assert_eq!(res.records, 11);
+
+        let res = matcher.match_probe(&store, &IrisCode::random_rng(&mut rng));
+        assert!(!res.is_match());
+        assert_eq!(res.records, DB_SIZE);
+    }
+
+    #[test]
+    fn early_exit_lowest_match() {
+        let mut rng = ChaCha12Rng::seed_from_u64(4);
+        let (mut codes, _) = store(&mut rng);
+        // A second match at the start of the next range, which is found before the lower one
+        codes[260] = codes[240].to_owned();
+        let store = codes.iter().collect::<IrisStore>();
+        let probe = codes[240].get_similar_iris(&mut rng);
+
+        for threads in [1, 2, 4, 7] {
+            let matcher = BatchMatcher::new(MatcherConfig {
+                threads,
+                ..Default::default()
+            });
+            let res = matcher.match_probe(&store, &probe);
+            assert_eq!(res.matches, vec![240]);
+        }
+    }
+
+    #[test]
+    fn batch() {
+        let mut rng = ChaCha12Rng::seed_from_u64(5);
+        let (codes, store) = store(&mut rng);
+        let probes = (0..5)
+            .map(|i| match i % 2 {
+                0 => codes[i * 50].get_similar_iris(&mut rng),
+                _ => IrisCode::random_rng(&mut rng),
+            })
+            .collect::<Vec<_>>();
+
+        for threads in [1, 2, 3, 8] {
+            let matcher = BatchMatcher::new(MatcherConfig {
+                threads,
+                ..Default::default()
+            });
+            let results = matcher.match_batch(&store, &probes);
+            assert_eq!(results.len(), probes.len());
+            for (probe, res) in probes.iter().zip(results) {
+                assert_eq!(res.matches, matcher.match_probe(&store, probe).matches);
+            }
+        }
+        assert_eq!(MatchResult::default().throughput(), 0.);
+    }
+
+    #[test]
+    fn rotations() {
+        let mut rng = ChaCha12Rng::seed_from_u64(3);
+        let (codes, store) = store(&mut rng);
+        let probe = codes[42].get_similar_iris(&mut rng).rotated(-5);
+
+        let matcher = BatchMatcher::new(MatcherConfig::default());
+        assert!(!matcher.match_probe(&store, &probe).is_match());
+
+        let matcher = BatchMatcher::new(MatcherConfig {
+            max_rotation: 5,
+            early_exit: false,
+            ..Default::default()
+        });
+        let res = matcher.match_probe(&store, &probe);
+        assert_eq!(res.matches, vec![42]);
+        assert_eq!(res.comparisons, 11 * DB_SIZE);
+    }
+}
@@ -0,0 +1,446 @@
+//! A generator for synthetic iris populations, whose genuine and impostor distance distributions
+//! resemble the ones of real iris codes.
+//!
+//! Each identity has a template, in which the bits of blocks of neighbouring columns are equal,
+//! modelling the local correlation of the iris texture. The bits are biased such that two
+//! independent templates have the target impostor distance on average. A sample of an identity
+//! flips blocks of its template with an identity specific probability, such that two samples have
+//! the target genuine distance on average, and is rotated by a random number of columns. Its mask
+//! occludes eyelid shaped regions at the top and bottom of the iris and randomly dropped out bits,
+//! e.g., reflections and eyelashes.
+//!
+//! The target distances refer to samples compared at their true alignment, i.e., after undoing
+//! the rotation of both samples.
+
+use crate::{IrisCode, IrisCodeArray};
+use rand::{
+    distributions::{Bernoulli, Distribution},
+    Rng,
+};
+use thiserror::Error;
+
+/// An Error enum capturing invalid parameters of the population.
+#[derive(Error, Debug)]
+pub enum PopulationError {
+    /// The blocks of correlated columns are empty
+    #[error("Block size has to be positive")]
+    InvalidBlockSize,
+    /// The rotation is not smaller than the number of columns
+    #[error(
+        "Rotation of {0} columns has to be smaller than {}",
+        IrisCodeArray::COLS
+    )]
+    InvalidRotation(usize),
+    /// A target distance is outside of [0, 0.5]
+    #[error("Distance {0} has to be in [0, 0.5]")]
+    InvalidDistance(f64),
+    /// The spread of the genuine distance is outside of [0, 1]
+    #[error("Identity noise spread {0} has to be in [0, 1]")]
+    InvalidNoiseSpread(f64),
+    /// The impostor distance is below the largest genuine distance of an identity
+    #[error("Impostor distance {0} has to be at least the largest genuine distance {1}")]
+    ImpostorBelowGenuine(f64, f64),
+}
+
+/// The parameters of the generated population.
+#[derive(Clone, Debug)]
+pub struct PopulationConfig {
+    /// Mean fractional Hamming distance between samples of the same identity, at most 0.5
+    pub genuine_distance: f64,
+    /// Mean fractional Hamming distance between samples of different identities, at most 0.5 and
+    /// at least the largest genuine distance of an identity
+    pub impostor_distance: f64,
+    /// The genuine distance of an identity is drawn uniformly from `genuine_distance` times
+    /// `1 ± identity_noise_spread`, modelling identities which are harder to capture. The spread
+    /// is in [0, 1]
+    pub identity_noise_spread: f64,
+    /// Number of neighbouring columns whose bits are correlated
+    pub block_size: usize,
+    /// Maximum rotation of a sample in columns, in both directions
+    pub max_rotation: usize,
+    /// Maximum fraction of the rows occluded by the upper eyelid at the top of the iris
+    pub upper_eyelid: f64,
+    /// Maximum fraction of the rows occluded by the lower eyelid at the bottom of the iris
+    pub lower_eyelid: f64,
+    /// Half of the angular width of an eyelid occlusion, in columns
+    pub eyelid_width: usize,
+    /// Probability that a cell is dropped out of the mask outside of the eyelids
+    pub dropout: f64,
+}
+
+impl Default for PopulationConfig {
+    fn default() -> Self {
+        Self {
+            genuine_distance: 0.25,
+            impostor_distance: 0.48,
+            identity_noise_spread: 0.3,
+            block_size: 4,
+            max_rotation: 15,
+            upper_eyelid: 0.4,
+            lower_eyelid: 0.25,
+            eyelid_width: 40,
+            dropout: 0.02,
+        }
+    }
+}
+
+/// A generated sample of an identity.
+#[derive(Clone, Debug)]
+pub struct Sample {
+    pub identity: usize,
+    /// The rotation of the sample in columns, `code.rotated(-rotation)` is at the true alignment
+    pub rotation: isize,
+    pub code: IrisCode,
+}
+
+/// An identity of the population, from which samples are drawn.
+#[derive(Clone, Debug)]
+pub struct Identity {
+    pub id: usize,
+    template: IrisCodeArray,
+    // Probability that a block is flipped in a sample
+    noise: f64,
+}
+
+// The probability p, such that two independent bits which are 1 with probability p differ with
+// probability d, i.e., 2p(1-p) = d
+fn bias_for_distance(d: f64) -> f64 {
+    (1. - (1. - 2. * d.clamp(0., 0.5)).sqrt()) / 2.
+}
+
+pub struct PopulationGenerator {
+    config: PopulationConfig,
+}
+
+impl PopulationGenerator {
+    pub fn new(config: PopulationConfig) -> Result<Self, PopulationError> {
+        if config.block_size == 0 {
+            return Err(PopulationError::InvalidBlockSize);
+        }
+        if config.max_rotation >= IrisCodeArray::COLS {
+            return Err(PopulationError::InvalidRotation(config.max_rotation));
+        }
+        for distance in [config.genuine_distance, config.impostor_distance] {
+            if !(0. ..=0.5).contains(&distance) {
+                return Err(PopulationError::InvalidDistance(distance));
+            }
+        }
+        let spread = config.identity_noise_spread;
+        if !(0. ..=1.).contains(&spread) {
+            return Err(PopulationError::InvalidNoiseSpread(spread));
+        }
+        let max_genuine = config.genuine_distance * (1. + spread);
+        if config.impostor_distance < max_genuine {
+            return Err(PopulationError::ImpostorBelowGenuine(
+                config.impostor_distance,
+                max_genuine,
+            ));
+        }
+        Ok(Self { config })
+    }
+
+    pub fn config(&self) -> &PopulationConfig {
+        &self.config
+    }
+
+    // Calls f for each block with the indices of its bits
+    fn for_each_block<F: FnMut(&[usize])>(&self, mut f: F) {
+        let mut block = Vec::with_capacity(self.config.block_size);
+        for row in 0..IrisCodeArray::ROWS {
+            for k in 0..IrisCodeArray::BITS_PER_CELL {
+                for start in (0..IrisCodeArray::COLS).step_by(self.config.block_size) {
+                    let end = (start + self.config.block_size).min(IrisCodeArray::COLS);
+                    block.clear();
+                    block.extend((start..end).map(|col| IrisCodeArray::bit_index(row, col, k)));
+                    f(&block);
+                }
+            }
+        }
+    }
+
+    pub fn identity<R: Rng>(&self, id: usize, rng: &mut R) -> Identity {
+        let spread = self.config.identity_noise_spread;
+        let factor = rng.gen_range(1. - spread..=1. + spread);
+        let noise = bias_for_distance(self.config.genuine_distance * factor);
+
+        // The bits of the samples have to be 1 with the probability given by the impostor
+        // distance. The noise moves the bias p of the template to p + noise - 2 * p * noise, thus
+        // the impostor distance can not be lower than the one caused by the noise alone.
+        // With noise 0.5, the samples are uniform regardless of the template.
+        let sample_bias = bias_for_distance(self.config.impostor_distance);
+        let bias = if noise < 0.5 {
+            ((sample_bias - noise) / (1. - 2. * noise)).clamp(0., 0.5)
+        } else {
+            0.
+        };
+        let bias = Bernoulli::new(bias).unwrap();
+        let mut template = IrisCodeArray::ZERO;
+        self.for_each_block(|block| {
+            if bias.sample(rng) {
+                block.iter().for_each(|i| template.set_bit(*i, true));
+            }
+        });
+        Identity {
+            id,
+            template,
+            noise,
+        }
+    }
+
+    pub fn sample<R: Rng>(&self, identity: &Identity, rng: &mut R) -> Sample {
+        let noise = Bernoulli::new(identity.noise).unwrap();
+        let mut code = identity.template;
+        self.for_each_block(|block| {
+            if noise.sample(rng) {
+                block.iter().for_each(|i| code.flip_bit(*i));
+            }
+        });
+
+        let max_rotation = self.config.max_rotation as isize;
+        let rotation = rng.gen_range(-max_rotation..=max_rotation);
+        Sample {
+            identity: identity.id,
+            rotation,
+            code: IrisCode {
+                code: code.rotated(rotation),
+                mask: self.occlusion_mask(rng),
+            },
+        }
+    }
+
+    // The eyelids occlude the outer rows around the top and the bottom of the iris, the occluded
+    // depth decreasing quadratically with the angular distance to the center of the eyelid
+    fn occlusion_mask<R: Rng>(&self, rng: &mut R) -> IrisCodeArray {
+        let upper = rng.gen_range(0. ..=self.config.upper_eyelid.clamp(0., 1.));
+        let lower = rng.gen_range(0. ..=self.config.lower_eyelid.clamp(0., 1.));
+        let dropout = Bernoulli::new(self.config.dropout.clamp(0., 1.)).unwrap();
+        let width = self.config.eyelid_width.max(1) as f64;
+        let rows = IrisCodeArray::ROWS as f64;
+        let cols = IrisCodeArray::COLS;
+
+        let mut mask = IrisCodeArray::ONES;
+        for col in 0..cols {
+            let depth = |center: usize, eyelid: f64| {
+                let dist = (col as isize - center as isize).unsigned_abs();
+                let dist = dist.min(cols - dist) as f64 / width;
+                (eyelid * (1. - dist * dist)).max(0.) * rows
+            };
+            let occluded = depth(cols / 4, upper).max(depth(3 * cols / 4, lower));
+            for row in 0..IrisCodeArray::ROWS {
+                // The rows go from the pupil to the outer boundary of the iris
+                let valid = ((IrisCodeArray::ROWS - row) as f64) > occluded && !dropout.sample(rng);
+                if !valid {
+                    for k in 0..IrisCodeArray::BITS_PER_CELL {
+                        mask.set_bit(IrisCodeArray::bit_index(row, col, k), false);
+                    }
+                }
+            }
+        }
+        mask
+    }
+
+    /// Generates `samples_per_identity` samples for each of `num_identities` identities, ordered
+    /// by identity.
+    pub fn generate<R: Rng>(
+        &self,
+        num_identities: usize,
+        samples_per_identity: usize,
+        rng: &mut R,
+    ) -> Vec<Sample> {
+        let mut res = Vec::with_capacity(num_identities * samples_per_identity);
+        for id in 0..num_identities {
+            let identity = self.identity(id, rng);
+            for _ in 0..samples_per_identity {
+                res.push(self.sample(&identity, rng));
+            }
+        }
+        res
+    }
+
+    /// A pair of samples of the same identity.
+    pub fn genuine_pair<R: Rng>(&self, rng: &mut R) -> (Sample, Sample) {
+        let identity = self.identity(0, rng);
+        (self.sample(&identity, rng), self.sample(&identity, rng))
+    }
+
+    /// A pair of samples of two different identities.
+    pub fn impostor_pair<R: Rng>(&self, rng: &mut R) -> (Sample, Sample) {
+        let a = self.identity(0, rng);
+        let b = self.identity(1, rng);
+        (self.sample(&a, rng), self.sample(&b, rng))
+    }
+}
+
+impl Sample {
+    /// The code and mask of the sample at its true alignment.
+    pub fn aligned(&self) -> IrisCode {
+        self.code.rotated(-self.rotation)
+    }
+}
+
+/// The mean fractional Hamming distances (genuine, impostor) of all pairs of samples at their
+/// true alignment. Pairs without common unmasked bits are skipped.
+pub fn mean_distances(samples: &[Sample]) -> (f64, f64) {
+    let aligned = samples.iter().map(Sample::aligned).collect::<Vec<_>>();
+    let mut sums = [0.; 2];
+    let mut counts = [0usize; 2];
+    for (i, a) in aligned.iter().enumerate() {
+        for (j, b) in aligned.iter().enumerate().skip(i + 1) {
+            if let Some(d) = a.fractional_distance(b) {
+                let impostor = usize::from(samples[i].identity != samples[j].identity);
+                sums[impostor] += d;
+                counts[impostor] += 1;
+            }
+        }
+    }
+    let mean = |i: usize| sums[i] / counts[i].max(1) as f64;
+    (mean(0), mean(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
+
+    #[test]
+    fn target_distances() {
+        let mut rng = ChaCha12Rng::seed_from_u64(1);
+        for (genuine, impostor) in [(0.25, 0.48), (0.1, 0.5), (0.3, 0.42)] {
+            let generator = PopulationGenerator::new(PopulationConfig {
+                genuine_distance: genuine,
+                impostor_distance: impostor,
+                ..Default::default()
+            })
+            .unwrap();
+            let samples = generator.generate(40, 3, &mut rng);
+            let (g, i) = mean_distances(&samples);
+            assert!(
+                (g - genuine).abs() < 0.02,
+                "genuine {g}, expected {genuine}"
+            );
+            assert!(
+                (i - impostor).abs() < 0.01,
+                "impostor {i}, expected {impostor}"
+            );
+        }
+    }
+
+    #[test]
+    fn genuine_pairs_match() {
+        let mut rng = ChaCha12Rng::seed_from_u64(2);
+        let generator = PopulationGenerator::new(PopulationConfig {
+            identity_noise_spread: 0.,
+            ..Default::default()
+        })
+        .unwrap();
+        for _ in 0..10 {
+            let (a, b) = generator.genuine_pair(&mut rng);
+            assert!(a.aligned().is_close(&b.aligned()));
+            let (a, b) = generator.impostor_pair(&mut rng);
+            assert!(!a.aligned().is_close(&b.aligned()));
+        }
+    }
+
+    #[test]
+    fn eyelid_masks() {
+        let mut rng = ChaCha12Rng::seed_from_u64(3);
+        let generator = PopulationGenerator::new(PopulationConfig {
+            dropout: 0.,
+            ..Default::default()
+        })
+        .unwrap();
+        let mask = generator.occlusion_mask(&mut rng);
+        // The inner rows and the sides of the iris are never occluded
+        for col in 0..IrisCodeArray::COLS {
+            assert!(mask.get_bit(IrisCodeArray::bit_index(0, col, 0)));
+        }
+        for row in 0..IrisCodeArray::ROWS {
+            assert!(mask.get_bit(IrisCodeArray::bit_index(row, 0, 0)));
+            assert!(mask.get_bit(IrisCodeArray::bit_index(row, IrisCodeArray::COLS / 2, 0)));
+        }
+        // The occluded depth is largest at the center of the eyelid
+        let occluded = |col: usize| {
+            (0..IrisCodeArray::ROWS)
+                .filter(|row| !mask.get_bit(IrisCodeArray::bit_index(*row, col, 0)))
+                .count()
+        };
+        let center = IrisCodeArray::COLS / 4;
+        assert!(occluded(center) >= occluded(center + 20));
+        assert!(occluded(center + 20) >= occluded(center + 39));
+    }
+
+    #[test]
+    fn rotation() {
+        let mut rng = ChaCha12Rng::seed_from_u64(4);
+        let generator = PopulationGenerator::new(PopulationConfig {
+            genuine_distance: 0.,
+            identity_noise_spread: 0.,
+            ..Default::default()
+        })
+        .unwrap();
+        let identity = generator.identity(0, &mut rng);
+        let samples = (0..5)
+            .map(|_| generator.sample(&identity, &mut rng))
+            .collect::<Vec<_>>();
+        for sample in samples.iter() {
+            assert!(sample.rotation.unsigned_abs() <= generator.config().max_rotation);
+            assert_eq!(
+                sample.aligned().code & sample.aligned().mask,
+                identity.template & sample.aligned().mask
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_config() {
+        let block_size = PopulationGenerator::new(PopulationConfig {
+            block_size: 0,
+            ..Default::default()
+        });
+        assert!(matches!(block_size, Err(PopulationError::InvalidBlockSize)));
+        let rotation = PopulationGenerator::new(PopulationConfig {
+            max_rotation: IrisCodeArray::COLS,
+            ..Default::default()
+        });
+        assert!(matches!(rotation, Err(PopulationError::InvalidRotation(_))));
+        let distance = PopulationGenerator::new(PopulationConfig {
+            impostor_distance: 0.6,
+            ..Default::default()
+        });
+        assert!(matches!(distance, Err(PopulationError::InvalidDistance(_))));
+        let spread = PopulationGenerator::new(PopulationConfig {
+            identity_noise_spread: -0.1,
+            ..Default::default()
+        });
+        assert!(matches!(
+            spread,
+            Err(PopulationError::InvalidNoiseSpread(_))
+        ));
+        // The largest genuine distance 0.45 * 1.3 would exceed 0.5
+        let genuine = PopulationGenerator::new(PopulationConfig {
+            genuine_distance: 0.45,
+            impostor_distance: 0.5,
+            ..Default::default()
+        });
+        assert!(matches!(
+            genuine,
+            Err(PopulationError::ImpostorBelowGenuine(..))
+        ));
+    }
+
+    #[test]
+    fn maximal_genuine_distance() {
+        // The largest genuine distance reaches 0.5, which makes the noise 0.5
+        let mut rng = ChaCha12Rng::seed_from_u64(5);
+        let generator = PopulationGenerator::new(PopulationConfig {
+            genuine_distance: 0.5,
+            impostor_distance: 0.5,
+            identity_noise_spread: 0.,
+            ..Default::default()
+        })
+        .unwrap();
+        let (a, b) = generator.genuine_pair(&mut rng);
+        assert!(!a.aligned().is_close(&b.aligned()));
+    }
+}
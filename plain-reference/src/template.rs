@@ -0,0 +1,346 @@
+//! Import and export of iris templates in external encodings.
+//!
+//! Two encodings are supported:
+//! - The JSON format of open-iris (`IrisTemplate.serialize()`), which stores the iris codes and
+//!   the mask codes as base64 encoded, packed bits. open-iris packs the bits with numpy, i.e., the
+//!   first bit is the most significant bit of the first byte. A file contains either a single
+//!   template object or a list of them.
+//! - Raw packed bit files, which contain the packed code followed by the packed mask for each
+//!   template, without any header. The order of the bits within a byte is configurable.
+//!
+//! In both encodings, bit `i` of the code or mask is bit `i` of the flattened template. The
+//! template has the layout of [`IrisCodeArray`]: 16 rows of 200 columns with 2 filters and 2 bits
+//! (real and imaginary part) each, in this order. This is the layout of open-iris, but with 200
+//! instead of its default 256 columns, i.e., templates of the default open-iris pipeline
+//! (16 x 256 x 2 x 2 = 16384 bits) are not supported and have to be produced with 200 columns.
+
+use crate::{IrisCode, IrisCodeArray};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+use thiserror::Error;
+
+/// The version written into exported open-iris templates
+pub const OPEN_IRIS_CODE_VERSION: &str = "v2.1";
+
+/// An Error enum capturing the errors of the template import and export.
+#[derive(Error, Debug)]
+pub enum TemplateError {
+    /// IO error
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    /// JSON error
+    #[error(transparent)]
+    JsonError(#[from] serde_json::Error),
+    /// Base64 error
+    #[error(transparent)]
+    Base64Error(#[from] base64::DecodeError),
+    /// The packed bits do not have the size of an iris code
+    #[error(
+        "Packed iris code has {0} bytes, expected {} (16 rows x 200 columns x 2 filters x 2 bits), \
+         templates with 256 columns are not supported",
+        IrisCodeArray::IRIS_CODE_SIZE_BYTES
+    )]
+    InvalidSize(usize),
+}
+
+/// The order of the bits within a byte of a packed iris code.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum BitOrder {
+    /// The first bit is the least significant bit of the byte
+    #[default]
+    Lsb0,
+    /// The first bit is the most significant bit of the byte (numpy's `packbits`)
+    Msb0,
+}
+
+/// The encoding of a template file.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum TemplateFormat {
+    /// The JSON format of open-iris, always packed with `BitOrder::Msb0`
+    #[default]
+    OpenIris,
+    /// Raw packed bits, the code followed by the mask for each template
+    Packed,
+}
+
+/// An iris template as serialized by open-iris.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OpenIrisTemplate {
+    pub iris_codes: String,
+    pub mask_codes: String,
+    #[serde(default)]
+    pub iris_code_version: String,
+}
+
+// A file contains either a single template or a list of them
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OpenIrisTemplates {
+    Single(OpenIrisTemplate),
+    List(Vec<OpenIrisTemplate>),
+}
+
+impl IrisCodeArray {
+    pub fn from_packed(bytes: &[u8], order: BitOrder) -> Result<Self, TemplateError> {
+        if bytes.len() != Self::IRIS_CODE_SIZE_BYTES {
+            return Err(TemplateError::InvalidSize(bytes.len()));
+        }
+        // Bit i is bit i % 64 of word i / 64, i.e., the words are little-endian in Lsb0 order
+        let mut res = Self::ZERO;
+        for (word, chunk) in res.0.iter_mut().zip(bytes.chunks(8)) {
+            let mut le = [0u8; 8];
+            le[..chunk.len()].copy_from_slice(chunk);
+            if order == BitOrder::Msb0 {
+                le.iter_mut().for_each(|b| *b = b.reverse_bits());
+            }
+            *word = u64::from_le_bytes(le);
+        }
+        Ok(res)
+    }
+
+    pub fn to_packed(&self, order: BitOrder) -> Vec<u8> {
+        let mut res = self
+            .0
+            .iter()
+            .flat_map(|word| word.to_le_bytes())
+            .take(Self::IRIS_CODE_SIZE_BYTES)
+            .collect::<Vec<_>>();
+        if order == BitOrder::Msb0 {
+            res.iter_mut().for_each(|b| *b = b.reverse_bits());
+        }
+        res
+    }
+}
+
+impl IrisCode {
+    /// Reads a template from its packed code followed by its packed mask.
+    pub fn from_packed(bytes: &[u8], order: BitOrder) -> Result<Self, TemplateError> {
+        if bytes.len() != 2 * IrisCodeArray::IRIS_CODE_SIZE_BYTES {
+            return Err(TemplateError::InvalidSize(bytes.len()));
+        }
+        let (code, mask) = bytes.split_at(IrisCodeArray::IRIS_CODE_SIZE_BYTES);
+        Ok(IrisCode {
+            code: IrisCodeArray::from_packed(code, order)?,
+            mask: IrisCodeArray::from_packed(mask, order)?,
+        })
+    }
+
+    pub fn to_packed(&self, order: BitOrder) -> Vec<u8> {
+        let mut res = self.code.to_packed(order);
+        res.extend(self.mask.to_packed(order));
+        res
+    }
+
+    pub fn from_open_iris(template: &OpenIrisTemplate) -> Result<Self, TemplateError> {
+        Ok(IrisCode {
+            code: IrisCodeArray::from_packed(
+                &STANDARD.decode(&template.iris_codes)?,
+                BitOrder::Msb0,
+            )?,
+            mask: IrisCodeArray::from_packed(
+                &STANDARD.decode(&template.mask_codes)?,
+                BitOrder::Msb0,
+            )?,
+        })
+    }
+
+    pub fn to_open_iris(&self) -> OpenIrisTemplate {
+        OpenIrisTemplate {
+            iris_codes: STANDARD.encode(self.code.to_packed(BitOrder::Msb0)),
+            mask_codes: STANDARD.encode(self.mask.to_packed(BitOrder::Msb0)),
+            iris_code_version: OPEN_IRIS_CODE_VERSION.to_owned(),
+        }
+    }
+}
+
+/// Reads all templates from `reader`. The bit order is only used for `TemplateFormat::Packed`.
+pub fn read_templates<R: Read>(
+    mut reader: R,
+    format: TemplateFormat,
+    order: BitOrder,
+) -> Result<Vec<IrisCode>, TemplateError> {
+    match format {
+        TemplateFormat::OpenIris => match serde_json::from_reader(reader)? {
+            OpenIrisTemplates::Single(template) => Ok(vec![IrisCode::from_open_iris(&template)?]),
+            OpenIrisTemplates::List(templates) => {
+                templates.iter().map(IrisCode::from_open_iris).collect()
+            }
+        },
+        TemplateFormat::Packed => {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes)?;
+            let record_size = 2 * IrisCodeArray::IRIS_CODE_SIZE_BYTES;
+            if bytes.len() % record_size != 0 {
+                return Err(TemplateError::InvalidSize(bytes.len() % record_size));
+            }
+            bytes
+                .chunks(record_size)
+                .map(|record| IrisCode::from_packed(record, order))
+                .collect()
+        }
+    }
+}
+
+/// Writes the templates to `writer`. The open-iris format is written as a list of templates.
+pub fn write_templates<W: Write>(
+    mut writer: W,
+    codes: &[IrisCode],
+    format: TemplateFormat,
+    order: BitOrder,
+) -> Result<(), TemplateError> {
+    match format {
+        TemplateFormat::OpenIris => {
+            let templates = codes.iter().map(IrisCode::to_open_iris).collect::<Vec<_>>();
+            serde_json::to_writer_pretty(&mut writer, &templates)?;
+        }
+        TemplateFormat::Packed => {
+            for code in codes {
+                writer.write_all(&code.to_packed(order))?;
+            }
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+pub fn read_template_file<P: AsRef<Path>>(
+    path: P,
+    format: TemplateFormat,
+    order: BitOrder,
+) -> Result<Vec<IrisCode>, TemplateError> {
+    read_templates(BufReader::new(File::open(path)?), format, order)
+}
+
+pub fn write_template_file<P: AsRef<Path>>(
+    path: P,
+    codes: &[IrisCode],
+    format: TemplateFormat,
+    order: BitOrder,
+) -> Result<(), TemplateError> {
+    write_templates(BufWriter::new(File::create(path)?), codes, format, order)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bit_order() {
+        let mut code = IrisCodeArray::ZERO;
+        code.set_bit(0, true);
+        code.set_bit(9, true);
+        assert_eq!(
+            code.to_packed(BitOrder::Lsb0)[..2],
+            [0b0000_0001, 0b0000_0010]
+        );
+        assert_eq!(
+            code.to_packed(BitOrder::Msb0)[..2],
+            [0b1000_0000, 0b0100_0000]
+        );
+        for order in [BitOrder::Lsb0, BitOrder::Msb0] {
+            let packed = code.to_packed(order);
+            assert_eq!(packed.len(), IrisCodeArray::IRIS_CODE_SIZE_BYTES);
+            assert_eq!(IrisCodeArray::from_packed(&packed, order).unwrap(), code);
+        }
+    }
+
+    #[test]
+    fn roundtrip() {
+        let codes = (0..3).map(|_| IrisCode::random()).collect::<Vec<_>>();
+        for (format, order) in [
+            (TemplateFormat::OpenIris, BitOrder::Lsb0),
+            (TemplateFormat::Packed, BitOrder::Lsb0),
+            (TemplateFormat::Packed, BitOrder::Msb0),
+        ] {
+            let mut buf = Vec::new();
+            write_templates(&mut buf, &codes, format, order).unwrap();
+            let read = read_templates(buf.as_slice(), format, order).unwrap();
+            assert_eq!(read.len(), codes.len());
+            for (a, b) in read.iter().zip(codes.iter()) {
+                assert_eq!(a.code, b.code);
+                assert_eq!(a.mask, b.mask);
+            }
+        }
+
+        // The bit order of packed files matters
+        let mut buf = Vec::new();
+        write_templates(&mut buf, &codes, TemplateFormat::Packed, BitOrder::Msb0).unwrap();
+        let read = read_templates(buf.as_slice(), TemplateFormat::Packed, BitOrder::Lsb0).unwrap();
+        assert_ne!(read[0].code, codes[0].code);
+    }
+
+    #[test]
+    fn open_iris_single_template() {
+        let code = IrisCode::random();
+        let json = serde_json::to_string(&code.to_open_iris()).unwrap();
+        let read =
+            read_templates(json.as_bytes(), TemplateFormat::OpenIris, BitOrder::Msb0).unwrap();
+        assert_eq!(read.len(), 1);
+        assert_eq!(read[0].code, code.code);
+        assert_eq!(read[0].mask, code.mask);
+    }
+
+    // Encoded like open-iris, see fixtures/gen_open_iris_fixtures.py
+    const OPEN_IRIS_200: &str = include_str!("../fixtures/open_iris_200.json");
+    const OPEN_IRIS_256: &str = include_str!("../fixtures/open_iris_256.json");
+
+    #[test]
+    fn open_iris_fixture() {
+        let read = read_templates(
+            OPEN_IRIS_200.as_bytes(),
+            TemplateFormat::OpenIris,
+            BitOrder::Msb0,
+        )
+        .unwrap();
+        assert_eq!(read.len(), 1);
+        for row in 0..IrisCodeArray::ROWS {
+            for col in 0..IrisCodeArray::COLS {
+                for k in 0..IrisCodeArray::BITS_PER_CELL {
+                    // k is the filter followed by the real or imaginary part
+                    let (f, c) = (k / 2, k % 2);
+                    let i = IrisCodeArray::bit_index(row, col, k);
+                    assert_eq!(
+                        read[0].code.get_bit(i),
+                        (row * 7 + col * 3 + f * 2 + c) % 5 == 0
+                    );
+                    assert_eq!(read[0].mask.get_bit(i), row < 14 && col % 50 != 0);
+                }
+            }
+        }
+        // Exporting gives the same encoding
+        let template: OpenIrisTemplate = serde_json::from_str(OPEN_IRIS_200).unwrap();
+        assert_eq!(read[0].to_open_iris(), template);
+    }
+
+    #[test]
+    fn open_iris_256_columns() {
+        let res = read_templates(
+            OPEN_IRIS_256.as_bytes(),
+            TemplateFormat::OpenIris,
+            BitOrder::Msb0,
+        );
+        let err = res.unwrap_err();
+        assert!(matches!(err, TemplateError::InvalidSize(2048)));
+        assert!(err.to_string().contains("256 columns"));
+    }
+
+    #[test]
+    fn invalid_size() {
+        let template = OpenIrisTemplate {
+            iris_codes: STANDARD.encode([0u8; 10]),
+            mask_codes: STANDARD.encode([0u8; IrisCodeArray::IRIS_CODE_SIZE_BYTES]),
+            iris_code_version: OPEN_IRIS_CODE_VERSION.to_owned(),
+        };
+        assert!(matches!(
+            IrisCode::from_open_iris(&template),
+            Err(TemplateError::InvalidSize(10))
+        ));
+        let bytes = vec![0u8; 2 * IrisCodeArray::IRIS_CODE_SIZE_BYTES + 1];
+        assert!(read_templates(bytes.as_slice(), TemplateFormat::Packed, BitOrder::Lsb0).is_err());
+    }
+}